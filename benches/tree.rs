@@ -0,0 +1,124 @@
+//! Widget tree operations on synthetic trees of 1k, 10k and 100k nodes, fully expanded.
+//!
+//! Run with `cargo bench --bench tree`; `cargo bench --bench tree -- search` runs one group.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use flutter_tui_tools::app_state::AppState;
+use flutter_tui_tools::synthetic_tree::TreeShape;
+use flutter_tui_tools::ui;
+use flutter_tui_tools::vm_service::RemoteDiagnosticsNode;
+use ratatui::{backend::TestBackend, Terminal};
+use std::collections::HashSet;
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+fn collect_ids(node: &RemoteDiagnosticsNode, ids: &mut HashSet<String>) {
+    ids.extend(AppState::get_node_id(node));
+    for child in node.children.iter().flatten() {
+        collect_ids(child, ids);
+    }
+}
+
+/// An inspector showing the tree of `nodes` nodes with every node expanded.
+fn expanded_state(nodes: usize) -> AppState {
+    let root = TreeShape::new(nodes).generate();
+    let mut state = AppState::new(std::env::temp_dir());
+    collect_ids(&root, &mut state.expanded_ids);
+    state.set_root_node(root);
+    state
+}
+
+fn flatten(c: &mut Criterion) {
+    let mut group = c.benchmark_group("flatten");
+    for nodes in SIZES {
+        let state = expanded_state(nodes);
+        group.bench_with_input(
+            BenchmarkId::new("visible_count", nodes),
+            &state,
+            |b, state| b.iter(|| state.visible_count()),
+        );
+        // Walked once per change of the tree or its expansion, not per frame
+        group.bench_with_input(BenchmarkId::new("rows", nodes), &state, |b, state| {
+            b.iter(|| {
+                ui::tree::TreeRows::new(state.root_node.as_ref(), &state.expanded_ids, None).len()
+            })
+        });
+
+        // One frame of the tree pane scrolled to the middle
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        let scroll_offset = state.visible_count() / 2;
+        group.bench_with_input(BenchmarkId::new("draw", nodes), &state, |b, state| {
+            b.iter(|| {
+                terminal
+                    .draw(|f| {
+                        ui::tree::draw(
+                            f,
+                            f.area(),
+                            state.root_node.as_ref(),
+                            state.tree_rows(),
+                            scroll_offset,
+                            None,
+                            None,
+                            None,
+                            scroll_offset,
+                            0,
+                            "Widget Tree",
+                            true,
+                        );
+                    })
+                    .unwrap();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    for nodes in SIZES {
+        let mut state = expanded_state(nodes);
+        state.search_query = "Label 42".to_string();
+        group.bench_function(BenchmarkId::from_parameter(nodes), |b| {
+            b.iter(|| state.perform_search())
+        });
+    }
+    group.finish();
+}
+
+fn selection(c: &mut Criterion) {
+    let mut group = c.benchmark_group("selection");
+    for nodes in SIZES {
+        let mut state = expanded_state(nodes);
+        state.selected_index = state.visible_count() - 1;
+        group.bench_function(BenchmarkId::new("selected_node", nodes), |b| {
+            b.iter(|| state.get_selected_node().is_some())
+        });
+
+        let last_id = AppState::get_node_id(state.get_selected_node().unwrap()).unwrap();
+        group.bench_function(BenchmarkId::new("select_by_id", nodes), |b| {
+            b.iter(|| state.select_node_by_id(&last_id))
+        });
+    }
+    group.finish();
+}
+
+/// A refreshed tree replacing the shown one, with the selection deep in it restored.
+fn set_root_node(c: &mut Criterion) {
+    let mut group = c.benchmark_group("set_root_node");
+    group.sample_size(10);
+    for nodes in SIZES {
+        let mut state = expanded_state(nodes);
+        state.selected_index = state.visible_count() - 1;
+        group.bench_function(BenchmarkId::from_parameter(nodes), |b| {
+            b.iter_batched(
+                || TreeShape::new(nodes).generate(),
+                |root| state.set_root_node(root),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, flatten, search, selection, set_root_node);
+criterion_main!(benches);
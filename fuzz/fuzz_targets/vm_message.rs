@@ -0,0 +1,11 @@
+#![no_main]
+
+use flutter_tui_tools::vm_service::VmMessage;
+use libfuzzer_sys::fuzz_target;
+
+// Frames arrive as text, anything that is valid UTF-8 can come off the socket
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        let _ = VmMessage::decode(text);
+    }
+});
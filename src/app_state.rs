@@ -1,7 +1,24 @@
-use crate::vm_service::RemoteDiagnosticsNode;
+use crate::breakpoints::BreakpointEntry;
+use crate::config::Snippet;
+use crate::layout_warnings::LayoutWarning;
+use crate::logger::{LogEntry, LogSource};
+use crate::network::{DiffLine, HttpProfileRequest};
+use crate::persistence::{PersistedState, WidgetFingerprint};
+use crate::profiler::{CallNode, CpuProfile, PROFILE_WINDOWS};
+use crate::rebuilds::RebuildTracker;
+use crate::reload_scope::{ReloadScope, ScriptSnapshot};
+use crate::screenshot::Screenshot;
+use crate::snapshots::SnapshotHistory;
+use crate::supervisor::TaskStatus;
+use crate::timeline::{FrameTiming, TimelineCapture, TimelineRecorder, CAPTURE_WINDOWS};
+use crate::tree_filter::TreeFilter;
+use crate::ui::tree::TreeRows;
+use crate::vm_service::{ClassHeapStats, MemoryUsage, RemoteDiagnosticsNode};
 use ratatui::layout::Rect;
-use std::cell::RefCell;
-use std::collections::HashSet;
+use std::cell::{OnceCell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::RangeInclusive;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Focus {
@@ -10,57 +27,949 @@ pub enum Focus {
     Logs,
     IsolateSelection,
     Search,
+    Filter,
     DebuggerFiles,
     DebuggerSource,
     DebuggerSearch,
+    DebuggerBreakpoints,
     DebuggerStack,
+    DebuggerLocals,
+    LogpointInput,
+    ConditionInput,
+    IsolateLabelInput,
+    TextSearch,
+    DeviceInput,
+    DepthInput,
+    DetailsSearch,
+    Memory,
+    Profiler,
+    Timeline,
+    TimelineFilter,
+    Network,
+    NetworkDetails,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Tab {
     Inspector,
     Debugger,
+    /// The log pane promoted to a full-screen tab.
+    Logs,
+    Memory,
+    Profiler,
+    Performance,
+    Network,
+}
+
+/// The buttons of the app bar, in the order they are laid out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppBarButton {
+    Inspector,
+    Debugger,
+    HotReload,
+    HotRestart,
+    AutoReload,
+    Refresh,
+    Logs,
+    Pause,
+    /// Opens the Toggles popup, the service extensions don't get a button each.
+    Toggles,
+    Quit,
+    /// Drawn in place of the buttons that don't fit, opens a menu of them.
+    More,
+}
+
+impl AppBarButton {
+    pub const ALL: [AppBarButton; 10] = [
+        AppBarButton::Inspector,
+        AppBarButton::Debugger,
+        AppBarButton::HotReload,
+        AppBarButton::HotRestart,
+        AppBarButton::AutoReload,
+        AppBarButton::Refresh,
+        AppBarButton::Logs,
+        AppBarButton::Pause,
+        AppBarButton::Toggles,
+        AppBarButton::Quit,
+    ];
+}
+
+/// Debug flags without a service extension, also offered in the Toggles popup and set by
+/// evaluating in their library: (library, variable, label).
+pub const DEBUG_PRINT_TOGGLES: [(&str, &str, &str); 2] = [
+    (
+        "package:flutter/src/gestures/debug.dart",
+        "debugPrintGestureArenaDiagnostics",
+        "Print gesture arena",
+    ),
+    (
+        "package:flutter/src/gestures/debug.dart",
+        "debugPrintHitTestResults",
+        "Print hit test results",
+    ),
+];
+
+/// The connection to the VM service, as the status bar shows it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConnectionStatus {
+    Connecting,
+    /// Connecting again after the session ended, `attempt` times so far.
+    Reconnecting {
+        attempt: u32,
+    },
+    Connected,
+    /// Waiting `retry_in` seconds before reconnecting.
+    Disconnected {
+        retry_in: u64,
+    },
+}
+
+impl ConnectionStatus {
+    pub fn label(self) -> String {
+        match self {
+            ConnectionStatus::Connecting => crate::t!("connection.connecting").to_string(),
+            ConnectionStatus::Reconnecting { attempt } => {
+                crate::t!("connection.reconnecting", attempt = attempt)
+            }
+            ConnectionStatus::Connected => crate::t!("connection.connected").to_string(),
+            ConnectionStatus::Disconnected { retry_in } => {
+                crate::t!("connection.disconnected", seconds = retry_in)
+            }
+        }
+    }
+}
+
+/// Source tab of the Logs pane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogSourceFilter {
+    All,
+    App,
+    Daemon,
+    Tool,
+}
+
+impl LogSourceFilter {
+    pub const ALL: [LogSourceFilter; 4] = [
+        LogSourceFilter::All,
+        LogSourceFilter::App,
+        LogSourceFilter::Daemon,
+        LogSourceFilter::Tool,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LogSourceFilter::All => crate::t!("logs.source_all"),
+            LogSourceFilter::App => crate::t!("logs.source_app"),
+            LogSourceFilter::Daemon => crate::t!("logs.source_daemon"),
+            LogSourceFilter::Tool => crate::t!("logs.source_tool"),
+        }
+    }
+
+    pub fn matches(self, source: LogSource) -> bool {
+        match self {
+            LogSourceFilter::All => true,
+            LogSourceFilter::App => source == LogSource::App,
+            LogSourceFilter::Daemon => source == LogSource::Daemon,
+            LogSourceFilter::Tool => source == LogSource::Tool,
+        }
+    }
+}
+
+/// Least severe levels the Logs pane can be limited to, in the order they are cycled.
+pub const LOG_LEVEL_FILTERS: [log::LevelFilter; 4] = [
+    log::LevelFilter::Trace,
+    log::LevelFilter::Info,
+    log::LevelFilter::Warn,
+    log::LevelFilter::Error,
+];
+
+/// Column the Rebuild Stats popup is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RebuildStatsSort {
+    Total,
+    Recent,
+    Paints,
+    Location,
+}
+
+impl RebuildStatsSort {
+    pub fn next(self) -> Self {
+        match self {
+            RebuildStatsSort::Total => RebuildStatsSort::Recent,
+            RebuildStatsSort::Recent => RebuildStatsSort::Paints,
+            RebuildStatsSort::Paints => RebuildStatsSort::Location,
+            RebuildStatsSort::Location => RebuildStatsSort::Total,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RebuildStatsSort::Total => crate::t!("sort.total"),
+            RebuildStatsSort::Recent => crate::t!("sort.recent"),
+            RebuildStatsSort::Paints => crate::t!("sort.paints"),
+            RebuildStatsSort::Location => crate::t!("sort.location"),
+        }
+    }
+}
+
+/// Column the Memory tab's class allocation table is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AllocationSort {
+    #[default]
+    Bytes,
+    Instances,
+    Class,
+}
+
+impl AllocationSort {
+    pub fn next(self) -> Self {
+        match self {
+            AllocationSort::Bytes => AllocationSort::Instances,
+            AllocationSort::Instances => AllocationSort::Class,
+            AllocationSort::Class => AllocationSort::Bytes,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AllocationSort::Bytes => crate::t!("sort.bytes"),
+            AllocationSort::Instances => crate::t!("sort.instances"),
+            AllocationSort::Class => crate::t!("sort.class"),
+        }
+    }
+}
+
+/// How the Profiler tab presents the samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProfileView {
+    TopDown,
+    BottomUp,
+    Functions,
+    FlameGraph,
+}
+
+impl ProfileView {
+    pub fn next(self) -> Self {
+        match self {
+            ProfileView::TopDown => ProfileView::BottomUp,
+            ProfileView::BottomUp => ProfileView::Functions,
+            ProfileView::Functions => ProfileView::FlameGraph,
+            ProfileView::FlameGraph => ProfileView::TopDown,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ProfileView::TopDown => crate::t!("profiler.top_down"),
+            ProfileView::BottomUp => crate::t!("profiler.bottom_up"),
+            ProfileView::Functions => crate::t!("profiler.functions"),
+            ProfileView::FlameGraph => crate::t!("profiler.flame_graph"),
+        }
+    }
+}
+
+/// A visible row of a profile call tree.
+pub struct ProfileRow {
+    /// Child indices from the root, see [`CallNode::at`].
+    pub path: Vec<usize>,
+    pub function: usize,
+    pub ticks: u64,
+    pub self_ticks: u64,
+    pub expandable: bool,
+    pub expanded: bool,
+}
+
+/// State of the Memory tab, created when it is first opened.
+#[derive(Default)]
+pub struct MemoryTabState {
+    pub isolates: Vec<IsolateMemory>, // Latest sample per isolate, while the tab is shown
+    pub heap_history: VecDeque<u64>,  // Dart heap of the inspected isolate per sample
+    pub allocations: Option<Result<Vec<ClassHeapStats>, String>>, // Latest allocation profile
+    pub allocation_sort: AllocationSort,
+    pub allocation_selected_index: usize,
+}
+
+impl MemoryTabState {
+    /// The classes of the allocation profile that have instances, in the chosen order.
+    pub fn sorted_allocations(&self) -> Vec<&ClassHeapStats> {
+        let Some(Ok(members)) = &self.allocations else {
+            return Vec::new();
+        };
+        let mut classes: Vec<&ClassHeapStats> = members
+            .iter()
+            .filter(|stats| stats.instances_current > 0)
+            .collect();
+        match self.allocation_sort {
+            AllocationSort::Bytes => {
+                classes.sort_by_key(|stats| std::cmp::Reverse(stats.bytes_current))
+            }
+            AllocationSort::Instances => {
+                classes.sort_by_key(|stats| std::cmp::Reverse(stats.instances_current))
+            }
+            AllocationSort::Class => classes.sort_by(|a, b| a.class.name.cmp(&b.class.name)),
+        }
+        classes
+    }
+
+    pub fn move_allocation_selection(&mut self, delta: isize) {
+        self.allocation_selected_index = self
+            .allocation_selected_index
+            .saturating_add_signed(delta)
+            .min(self.sorted_allocations().len().saturating_sub(1));
+    }
+}
+
+/// State of the Profiler tab, created when it is first opened.
+pub struct ProfilerTabState {
+    pub profile: Option<Result<CpuProfile, String>>, // The last capture
+    pub capturing: bool,                             // While samples are fetched
+    pub window_index: usize,                         // Into PROFILE_WINDOWS
+    pub view: ProfileView,
+    pub selected_index: usize,
+    pub expanded: HashSet<Vec<usize>>, // Paths of the expanded nodes of the shown call tree
+    pub flame_root: Vec<usize>,        // Top-down node the flame graph is zoomed into
+}
+
+impl Default for ProfilerTabState {
+    fn default() -> Self {
+        Self {
+            profile: None,
+            capturing: false,
+            window_index: 2,
+            view: ProfileView::TopDown,
+            selected_index: 0,
+            expanded: HashSet::new(),
+            flame_root: Vec::new(),
+        }
+    }
+}
+
+impl ProfilerTabState {
+    pub fn window_secs(&self) -> u64 {
+        PROFILE_WINDOWS[self.window_index]
+    }
+
+    pub fn change_window(&mut self, delta: isize) {
+        self.window_index = self
+            .window_index
+            .saturating_add_signed(delta)
+            .min(PROFILE_WINDOWS.len() - 1);
+    }
+
+    /// Shows a new capture with its hottest call paths expanded.
+    pub fn set_profile(&mut self, profile: Result<CpuProfile, String>) {
+        self.capturing = false;
+        self.selected_index = 0;
+        self.expanded.clear();
+        self.flame_root.clear();
+        self.profile = Some(profile);
+        self.expand_hot_path();
+    }
+
+    /// Expands the call tree down the heaviest path while one node takes most of the time.
+    fn expand_hot_path(&mut self) {
+        let mut expanded = Vec::new();
+        let mut path = Vec::new();
+        let mut node = self.call_tree();
+        while let Some(child) = node.and_then(|node| {
+            node.children
+                .first()
+                .filter(|child| child.ticks * 2 >= node.ticks)
+        }) {
+            path.push(0);
+            expanded.push(path.clone());
+            node = Some(child);
+        }
+        self.expanded.extend(expanded);
+    }
+
+    fn call_tree(&self) -> Option<&CallNode> {
+        let profile = self.profile.as_ref()?.as_ref().ok()?;
+        match self.view {
+            ProfileView::TopDown => Some(&profile.top_down),
+            ProfileView::BottomUp => Some(&profile.bottom_up),
+            ProfileView::Functions | ProfileView::FlameGraph => None,
+        }
+    }
+
+    /// The visible rows of the call tree view, the synthetic root left out.
+    pub fn rows(&self) -> Vec<ProfileRow> {
+        fn walk(
+            node: &CallNode,
+            path: &mut Vec<usize>,
+            expanded: &HashSet<Vec<usize>>,
+            rows: &mut Vec<ProfileRow>,
+        ) {
+            for (index, child) in node.children.iter().enumerate() {
+                path.push(index);
+                let is_expanded = expanded.contains(path);
+                rows.push(ProfileRow {
+                    path: path.clone(),
+                    function: child.function,
+                    ticks: child.ticks,
+                    self_ticks: child.self_ticks,
+                    expandable: !child.children.is_empty(),
+                    expanded: is_expanded,
+                });
+                if is_expanded {
+                    walk(child, path, expanded, rows);
+                }
+                path.pop();
+            }
+        }
+        let mut rows = Vec::new();
+        if let Some(root) = self.call_tree() {
+            walk(root, &mut Vec::new(), &self.expanded, &mut rows);
+        }
+        rows
+    }
+
+    fn row_count(&self) -> usize {
+        match self.view {
+            ProfileView::TopDown | ProfileView::BottomUp => self.rows().len(),
+            ProfileView::Functions => self
+                .profile
+                .as_ref()
+                .and_then(|profile| profile.as_ref().ok())
+                .map_or(0, |profile| profile.functions.len()),
+            ProfileView::FlameGraph => 0,
+        }
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.selected_index = self
+            .selected_index
+            .saturating_add_signed(delta)
+            .min(self.row_count().saturating_sub(1));
+    }
+
+    /// Expands or collapses the selected call tree node.
+    pub fn set_row_expanded(&mut self, expand: bool) {
+        let rows = self.rows();
+        let Some(row) = rows.get(self.selected_index) else {
+            return;
+        };
+        if expand && row.expandable {
+            self.expanded.insert(row.path.clone());
+        } else if !expand && row.expanded {
+            self.expanded.remove(&row.path);
+        } else if !expand && row.path.len() > 1 {
+            // Already collapsed: go to the caller
+            let parent = &row.path[..row.path.len() - 1];
+            if let Some(index) = rows.iter().position(|r| r.path == parent) {
+                self.selected_index = index;
+            }
+        }
+    }
+
+    pub fn toggle_row(&mut self) {
+        let expanded = self
+            .rows()
+            .get(self.selected_index)
+            .is_some_and(|row| row.expanded);
+        self.set_row_expanded(!expanded);
+    }
+
+    /// Switches to the next view. The flame graph zooms into the node selected in the
+    /// top-down tree.
+    pub fn cycle_view(&mut self) {
+        if self.view == ProfileView::TopDown {
+            self.flame_root = self
+                .rows()
+                .get(self.selected_index)
+                .map(|row| row.path.clone())
+                .unwrap_or_default();
+        }
+        self.view = self.view.next();
+        self.selected_index = 0;
+        // Top-down and bottom-up paths don't mean the same nodes
+        self.expanded.clear();
+        self.expand_hot_path();
+    }
+}
+
+/// Memory samples the heap chart goes back, 4 minutes at the 2s poll interval.
+const MAX_HEAP_HISTORY: usize = 120;
+/// Frames the Performance tab keeps, wider than any terminal draws them.
+const MAX_FRAME_HISTORY: usize = 1000;
+/// Timeline events listed per category, the longest ones.
+const MAX_LISTED_SPANS: usize = 200;
+
+/// A row of the timeline event list: a category heading with the number of its events
+/// passing the filter, or an event of a category.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimelineRow {
+    Category { category: usize, matched: usize },
+    Span { category: usize, span: usize },
+}
+
+/// State of the Network tab, created when it is first opened.
+#[derive(Default)]
+pub struct NetworkTabState {
+    pub requests: Vec<HttpProfileRequest>, // Newest first
+    pub selected_index: usize,
+    pub details: Option<HttpDetails>, // The request drilled into
+    pub details_scroll: usize,
+    pub details_max_scroll: RefCell<usize>, // As far as the details scroll, set when drawn
+}
+
+impl NetworkTabState {
+    pub fn set_requests(&mut self, mut requests: Vec<HttpProfileRequest>) {
+        requests.sort_by_key(|request| std::cmp::Reverse(request.start_time));
+        // Keep the same request selected as new ones come in on top
+        let selected_id = self.selected_request().map(|request| request.id.clone());
+        self.requests = requests;
+        self.selected_index = selected_id
+            .and_then(|id| self.requests.iter().position(|request| request.id == id))
+            .unwrap_or(0);
+    }
+
+    pub fn selected_request(&self) -> Option<&HttpProfileRequest> {
+        self.requests.get(self.selected_index)
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.selected_index = self
+            .selected_index
+            .saturating_add_signed(delta)
+            .min(self.requests.len().saturating_sub(1));
+    }
+}
+
+/// A recorded request fetched with its bodies, `request` is `None` while it loads.
+#[derive(Debug, Clone)]
+pub struct HttpDetails {
+    pub id: String,
+    pub request: Option<Result<HttpProfileRequest, String>>,
+}
+
+/// State of the Performance tab, created when it is first opened.
+pub struct PerformanceTabState {
+    pub frames: VecDeque<FrameTiming>, // Oldest first, since the tab was opened
+    pub timeline: Option<Result<TimelineCapture, String>>, // The last timeline capture
+    pub capturing: bool,               // While the timeline is fetched
+    pub window_index: usize,           // Into CAPTURE_WINDOWS
+    pub category: Option<String>,      // Only events of this category are listed
+    pub filter: String,                // Only events whose name or thread contains this
+    pub rows: Vec<TimelineRow>,        // The event list, updated with the capture and filters
+    pub selected_index: usize,
+}
+
+impl Default for PerformanceTabState {
+    fn default() -> Self {
+        Self {
+            frames: VecDeque::new(),
+            timeline: None,
+            capturing: false,
+            window_index: 1,
+            category: None,
+            filter: String::new(),
+            rows: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PerformanceTabState {
+    pub fn window_secs(&self) -> u64 {
+        CAPTURE_WINDOWS[self.window_index]
+    }
+
+    pub fn change_window(&mut self, delta: isize) {
+        self.window_index = self
+            .window_index
+            .saturating_add_signed(delta)
+            .min(CAPTURE_WINDOWS.len() - 1);
+    }
+
+    pub fn set_timeline(&mut self, timeline: Result<TimelineCapture, String>) {
+        self.capturing = false;
+        self.timeline = Some(timeline);
+        self.selected_index = 0;
+        self.update_rows();
+    }
+
+    /// Lists only the next category of the capture, or all of them after the last one.
+    pub fn cycle_category(&mut self) {
+        let Some(Ok(capture)) = &self.timeline else {
+            return;
+        };
+        let names: Vec<&String> = capture.categories.iter().map(|c| &c.name).collect();
+        let next = match &self.category {
+            None => 0,
+            Some(current) => names
+                .iter()
+                .position(|n| *n == current)
+                .map_or(0, |i| i + 1),
+        };
+        self.category = names.get(next).map(|name| name.to_string());
+        self.selected_index = 0;
+        self.update_rows();
+    }
+
+    pub fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.selected_index = 0;
+        self.update_rows();
+    }
+
+    fn update_rows(&mut self) {
+        self.rows.clear();
+        let Some(Ok(capture)) = &self.timeline else {
+            return;
+        };
+        let filter = self.filter.to_lowercase();
+        for (index, category) in capture.categories.iter().enumerate() {
+            if self.category.as_ref().is_some_and(|c| *c != category.name) {
+                continue;
+            }
+            let spans: Vec<usize> = category
+                .spans
+                .iter()
+                .enumerate()
+                .filter(|(_, span)| {
+                    filter.is_empty()
+                        || span.name.to_lowercase().contains(&filter)
+                        || span.thread.to_lowercase().contains(&filter)
+                })
+                .map(|(span, _)| span)
+                .collect();
+            if spans.is_empty() {
+                continue;
+            }
+            self.rows.push(TimelineRow::Category {
+                category: index,
+                matched: spans.len(),
+            });
+            self.rows
+                .extend(
+                    spans
+                        .into_iter()
+                        .take(MAX_LISTED_SPANS)
+                        .map(|span| TimelineRow::Span {
+                            category: index,
+                            span,
+                        }),
+                );
+        }
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.selected_index = self
+            .selected_index
+            .saturating_add_signed(delta)
+            .min(self.rows.len().saturating_sub(1));
+    }
+
+    pub fn push_frame(&mut self, frame: FrameTiming) {
+        self.frames.push_back(frame);
+        while self.frames.len() > MAX_FRAME_HISTORY {
+            self.frames.pop_front();
+        }
+    }
+
+    /// Frames that started within the second before the newest one. The app only renders
+    /// when something changes, so an idle app reads close to 0.
+    pub fn fps(&self) -> usize {
+        let Some(latest) = self.frames.back() else {
+            return 0;
+        };
+        self.frames
+            .iter()
+            .rev()
+            .take_while(|frame| latest.start_micros - frame.start_micros < 1_000_000)
+            .count()
+    }
+
+    /// The slowest frame kept, by build plus raster time.
+    pub fn worst_frame(&self) -> Option<&FrameTiming> {
+        self.frames.iter().max_by_key(|frame| frame.work())
+    }
+
+    pub fn janky_frames(&self, budget: Duration) -> usize {
+        self.frames
+            .iter()
+            .filter(|frame| frame.work() > budget)
+            .count()
+    }
+}
+
+/// Lowest the bottom log panel gets, in rows including its borders. At most it takes all
+/// rows below the app bar.
+pub const MIN_LOG_PANEL_HEIGHT: u16 = 3;
+/// Fixed heights the log panel cycles through before half and all of the screen.
+pub const LOG_PANEL_PRESETS: [u16; 2] = [10, 20];
+/// Narrowest a resizable pane gets, in percent of its split.
+pub const MIN_PANE_PERCENT: u16 = 10;
+/// Percent a pane grows or shrinks by with Ctrl+arrows.
+pub const PANE_RESIZE_STEP: u16 = 5;
+
+/// Whether `split` can be used as pane sizes: percentages adding up to 100, none of them
+/// narrower than [`MIN_PANE_PERCENT`]. Guards what's restored from older or edited state.
+fn is_valid_split(split: &[u16]) -> bool {
+    split.iter().sum::<u16>() == 100 && split.iter().all(|&p| p >= MIN_PANE_PERCENT)
+}
+
+/// Grows pane `pane` of `split` by `delta` percent (shrinks it when negative), taking the
+/// room from its neighbour on the right, or on the left for the last pane.
+fn resize_pane(split: &mut [u16], pane: usize, delta: i16) {
+    let neighbour = if pane + 1 < split.len() {
+        pane + 1
+    } else {
+        pane - 1
+    };
+    let total = split[pane] + split[neighbour];
+    let size = split[pane]
+        .saturating_add_signed(delta)
+        .clamp(MIN_PANE_PERCENT, total - MIN_PANE_PERCENT);
+    split[pane] = size;
+    split[neighbour] = total - size;
+}
+
+/// Moves the border after pane `border` of `split` to `percent` of the whole width,
+/// keeping both panes next to it at least [`MIN_PANE_PERCENT`] wide.
+fn move_split_border(split: &mut [u16], border: usize, percent: u16) {
+    let start: u16 = split[..border].iter().sum();
+    let total = split[border] + split[border + 1];
+    let size = percent
+        .saturating_sub(start)
+        .clamp(MIN_PANE_PERCENT, total - MIN_PANE_PERCENT);
+    split[border] = size;
+    split[border + 1] = total - size;
+}
+
+/// Boolean service extensions offered in the Toggles popup, with their labels.
+pub const SERVICE_TOGGLES: [(&str, &str); 12] = [
+    ("ext.flutter.debugPaint", "Debug paint"),
+    ("ext.flutter.repaintRainbow", "Repaint rainbow"),
+    ("ext.flutter.showPerformanceOverlay", "Performance overlay"),
+    ("ext.flutter.debugPaintBaselinesEnabled", "Paint baselines"),
+    ("ext.flutter.debugAllowBanner", "Debug banner"),
+    (
+        "ext.flutter.invertOversizedImages",
+        "Invert oversized images",
+    ),
+    ("ext.flutter.debugDisableClipLayers", "Disable clip layers"),
+    (
+        "ext.flutter.debugDisableOpacityLayers",
+        "Disable opacity layers",
+    ),
+    (
+        "ext.flutter.debugDisablePhysicalShapeLayers",
+        "Disable physical shape layers",
+    ),
+    ("ext.flutter.profileWidgetBuilds", "Trace widget builds"),
+    ("ext.flutter.profileRenderObjectLayouts", "Trace layouts"),
+    ("ext.flutter.profileRenderObjectPaints", "Trace paints"),
+];
+
+/// Service extensions with more than two states, offered in the Toggles popup after the
+/// debug flags, each flip picking the next value: (method, parameter, label, values).
+pub const CHOICE_TOGGLES: [(&str, &str, &str, &[&str]); 3] = [
+    (
+        "ext.flutter.platformOverride",
+        "value",
+        "Platform",
+        &[
+            "default", "android", "iOS", "fuchsia", "linux", "macOS", "windows",
+        ],
+    ),
+    (
+        "ext.flutter.brightnessOverride",
+        "value",
+        "Brightness",
+        &["default", "Brightness.light", "Brightness.dark"],
+    ),
+    (
+        "ext.flutter.timeDilation",
+        "timeDilation",
+        "Slow animations",
+        &["1.0", "5.0"],
+    ),
+];
+
+/// Rows of the Toggles popup, each flipped by its own key. Skips the keys that close it.
+pub const TOGGLE_KEYS: [char; 24] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'p', 'r', 's', 't', 'u',
+    'v', 'w', 'x', 'y', 'z',
+];
+
+pub const TOGGLE_COUNT: usize =
+    SERVICE_TOGGLES.len() + DEBUG_PRINT_TOGGLES.len() + CHOICE_TOGGLES.len();
+
+/// What the app reported for a toggle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToggleState {
+    Enabled(bool),
+    Value(String),
+}
+
+/// How a row of the Toggles popup gets flipped.
+#[derive(Debug, Clone, Copy)]
+pub enum ToggleKind {
+    Extension,
+    /// Set by evaluating in the library.
+    DebugFlag {
+        library: &'static str,
+    },
+    Choice {
+        parameter: &'static str,
+        values: &'static [&'static str],
+    },
+}
+
+/// A row of the Toggles popup. `method` names the variable of debug flags.
+#[derive(Debug, Clone, Copy)]
+pub struct ToggleRow {
+    pub method: &'static str,
+    pub label: &'static str,
+    pub kind: ToggleKind,
+}
+
+/// Rows of the Toggles popup, in order: service extensions, debug flags, then choices.
+pub fn toggle_rows() -> impl Iterator<Item = ToggleRow> {
+    let extensions = SERVICE_TOGGLES.iter().map(|&(method, label)| ToggleRow {
+        method,
+        label,
+        kind: ToggleKind::Extension,
+    });
+    let debug_flags = DEBUG_PRINT_TOGGLES
+        .iter()
+        .map(|&(library, method, label)| ToggleRow {
+            method,
+            label,
+            kind: ToggleKind::DebugFlag { library },
+        });
+    let choices = CHOICE_TOGGLES
+        .iter()
+        .map(|&(method, parameter, label, values)| ToggleRow {
+            method,
+            label,
+            kind: ToggleKind::Choice { parameter, values },
+        });
+    extensions.chain(debug_flags).chain(choices)
 }
 
 pub struct AppState {
     pub current_tab: Tab,
     pub root_node: Option<RemoteDiagnosticsNode>,
     pub selected_node_details: Option<RemoteDiagnosticsNode>,
-    pub connection_status: String,
+    pub pending_details_request: Option<(String, std::time::Instant)>, // (node id, fire at)
+    pub widget_select_mode: bool, // Tapping a widget on the device selects it here and back
+    pub device_selection: Option<String>, // Last node id the device and the TUI agreed on
+    pub pending_device_selection: Option<String>, // Selected on the device, not in the tree yet
+    pub details_cache: HashMap<String, serde_json::Value>, // node id -> raw details, per tree
+    pub expired_details_id: Option<String>, // Node whose details fetch hit a Sentinel
+    pub details_raw_json: Option<String>, // pretty-printed getDetailsSubtree response
+    pub show_raw_json: bool,
+    pub raw_json_scroll: usize,
+    pub details_properties: Option<PropertyNode>,
+    pub details_search_query: String,
+    pub details_search_root: Option<PropertyNode>, // Properties pruned to the search matches
+    pub details_search_matches: usize,
+    pub details_expanded_ids: HashSet<String>,
+    pub details_selected_index: usize,
+    pub details_scroll_offset: usize,
+    pub connection_status: ConnectionStatus,
+    pub subsystems: std::collections::BTreeMap<&'static str, TaskStatus>,
 
     // Isolate Selection State
     pub available_isolates: Vec<crate::vm_service::IsolateRef>,
     pub show_isolate_selection: bool,
+    pub inspected_isolate: Option<crate::vm_service::IsolateRef>, // The isolate the tree belongs to
+    pub inspector_isolates: HashSet<String>, // Ids of the isolates running a Flutter UI
+    pub show_app_info: bool,
+    pub show_isolate_metrics: bool,
+    pub isolate_metrics: Option<Result<Vec<IsolateGroupMetrics>, String>>, // None while fetched
+    pub isolate_metrics_at: Option<String>,                                // When they were fetched
+    pub show_toggles: bool,
+    pub toggles_selected_index: usize,
+    pub toggle_states: HashMap<String, ToggleState>, // Extension method -> state, once known
+    pub snippets: Vec<Snippet>,                      // Named evaluate expressions from the config
+    pub keymap: crate::keymap::KeyMap,
+    pub vim_keys: bool,      // Vim navigation, from the config
+    pub vim_pending_g: bool, // The first g of gg was typed
+    pub text_search_input: String,
+    pub text_search_query: String, // What n/N look for in the logs and the source view
+    pub text_search_return_focus: Focus,
+    pub show_command_palette: bool,
+    pub palette_query: String,
+    pub palette_selected_index: usize,
+    pub eval_result: Option<EvalResult>, // Shown in a popup until dismissed
+    pub show_screenshot: bool,
+    pub http_replay: Option<HttpReplay>,
+    pub http_replay_scroll: usize,
+    pub screenshot: Option<Result<Screenshot, String>>, // None while it is taken
+    pub eval_result_scroll: usize,
+    pub app_info: AppInfo,
+    pub display_refresh_rate: Option<f64>, // Hz, as reported by the engine
+    pub refresh_rate_override: Option<f64>, // Hz, from --refresh-rate
     pub selected_isolate_index: usize,
 
     // Tree State
     pub selected_index: usize,
     pub expanded_ids: HashSet<String>,
+    pub children_loading: HashSet<String>, // Nodes whose children are being fetched
+    pub expand_pending: HashMap<String, Option<usize>>, // Node -> levels to expand once its children arrive
+    pub depth_input: String,
+    pub subtree_focus_saved: Option<HashSet<String>>, // Expansion to restore when leaving subtree focus
+    pub summary_tree: bool, // User widgets only, rather than the full widget tree
+    pub pending_expanded_locations: HashSet<String>, // Restored from the last session
+    pub pending_selection: Option<WidgetFingerprint>, // Widget to reselect once it's in the tree
     pub tree_scroll_offset: usize,
     pub tree_horizontal_scroll: usize,
+    pub tree_mark_anchor: Option<String>, // Node the Shift+arrow selection was extended from
+    pub tree_refreshing: bool,
+    pub tree_updated_at: Option<String>, // Local time of the last tree fetch, e.g. "12:03:11"
+    pub last_reload: Option<(String, bool)>, // Local time of the last hot reload, and if it restarted
+    pub script_snapshot: Option<ScriptSnapshot>, // Scripts of the inspected isolate before the next reload
+    pub reload_scope: Option<ReloadScope>,       // Libraries the last hot reload recompiled
+    pub show_reload_scope: bool,
+    pub reload_scope_scroll: usize,
+    pub spinner_tick: usize,
 
     // Logs State
-    pub logs: Vec<String>,
-    pub log_scroll_state: usize, // Index of the first visible log line
+    // Positions below are in `log_view`, the entries the filters let through
+    pub logs: Vec<LogEntry>,
+    pub log_view: Vec<usize>, // Indices into `logs` of the entries shown, in order
+    pub log_source_filter: LogSourceFilter,
+    pub log_level_filter: log::LevelFilter, // Least severe level shown
+    pub log_counts: HashMap<(LogSource, log::Level), usize>,
+    pub log_scroll_state: usize, // Position of the first visible log entry
+    pub log_selected: Option<usize>,
+    pub log_mark_anchor: Option<usize>, // Entry the Shift+arrow selection was extended from
+    pub expanded_logs: HashSet<usize>,  // Entries showing their error and stack trace
+    pub log_view_height: RefCell<usize>,
     pub log_auto_scroll: bool,
     pub show_logs: bool,
+    pub log_panel_height: u16,
+    pub log_panel_limit: RefCell<u16>, // Rows below the app bar, the most the log panel can take
+    pub dragging_log_border: bool,
+    pub logs_only: bool, // `logs` subcommand: only the log pane is shown
 
     // Search State
     pub search_query: String,
-    pub search_results: Vec<String>, // IDs of matching nodes
-    pub current_match_index: usize,  // Index into search_results
+    pub search_results: Vec<String>,     // IDs of matching nodes
+    pub search_matches: HashSet<String>, // The same IDs, looked up while drawing
+    pub current_match_index: usize,      // Index into search_results
+
+    // Filter State
+    pub filter_query: String,
+    pub filter_visible_ids: Option<HashSet<String>>, // None when no filter is active
+    pub filter_match_count: usize,
+    filter_saved_expansion: Option<HashSet<String>>, // Expansion to restore when the filter is cleared
 
     pub focus: Focus,
     pub auto_reload: bool,
 
     pub tx_flutter_command: Option<tokio::sync::mpsc::Sender<String>>,
+    pub registered_services: HashMap<String, String>, // service name -> method alias
     pub vm_service_client: Option<crate::vm_service::VmServiceClient>,
 
     // Debugger State
     pub project_root: std::path::PathBuf,
     pub file_list: Vec<String>,
     pub file_tree: Option<FileNode>,
-    pub selected_file_index: usize,
     pub debugger_selected_index: usize,
     pub debugger_expanded_ids: HashSet<String>,
     pub debugger_tree_scroll_offset: usize,
@@ -69,30 +978,116 @@ pub struct AppState {
     pub open_file_content: Option<Vec<String>>,
     pub source_scroll_offset: usize,
     pub source_selected_line: Option<usize>,
-    pub breakpoints: HashSet<String>, // "path:line"
-    pub debug_state: DebugState,
-    pub stack_trace: Option<serde_json::Value>,
+    pub breakpoints: HashSet<String>,       // "path:line"
+    pub logpoints: HashMap<String, String>, // "path:line" -> message template
+    pub breakpoint_vm_ids: HashMap<String, HashMap<String, String>>, // "path:line" -> isolate id -> VM breakpoint id
+    pub logpoint_input: String,
+    pub isolate_labels: HashMap<String, String>, // isolate_label_key -> label given to it
+    pub isolate_label_input: String,
+    pub disabled_breakpoints: HashSet<String>, // subset of `breakpoints` not registered with the VM
+    pub breakpoint_conditions: HashMap<String, String>, // "path:line" -> Dart condition
+    pub condition_input: String,
+    pub device_input: String, // kept after sending so a step can be repeated
+    pub device_input_return_focus: Focus,
+    pub breakpoints_selected_index: usize,
+    pub breakpoint_mark_anchor: Option<String>, // Breakpoint the Shift+arrow selection started at
+    pub rebuild_tracking: bool,
+    pub rebuilds: RebuildTracker,
+    pub repaints: RebuildTracker,
+    pub memory: Option<MemoryTabState>, // Once the Memory tab was opened
+    pub snapshot_interval: Option<Duration>, // Widget count and memory snapshots, while set
+    pub snapshots: SnapshotHistory,
+    pub timeline: TimelineRecorder,
+    pub profiler: Option<ProfilerTabState>, // Once the Profiler tab was opened
+    pub performance: Option<PerformanceTabState>, // Once the Performance tab was opened
+    pub network: Option<NetworkTabState>,   // Once the Network tab was opened
+    pub show_focus_tree: bool,
+    pub focus_tree: Option<String>, // debugDumpFocusTree output
+    pub focus_tree_scroll: usize,
+    pub show_help: bool,
+    pub help_scroll: usize,
+    pub help_max_scroll: RefCell<usize>, // Lines of the help overlay below its last page
+    pub show_rebuild_stats: bool,
+    pub rebuild_stats_selected_index: usize,
+    pub rebuild_stats_sort: RebuildStatsSort,
+    pub layout_warnings: Vec<LayoutWarning>, // found in the details fetched, per tree
+    pub show_layout_warnings: bool,
+    pub layout_warnings_selected_index: usize,
+    pub pause_requested: bool, // the current pause came from the "Pause app" control
+    pub debug_state: DebugState, // the isolate the Debugger shows
+    pub paused_isolates: HashMap<String, (DebugState, Option<crate::vm_service::Stack>)>,
+    pub stack_trace: Option<crate::vm_service::Stack>,
+    pub stack_selected_index: usize,
+    pub execution_line: Option<(String, usize)>, // (path, 1-based line) of the selected frame
+
+    // Locals State
+    pub locals_root: Option<VariableNode>,
+    pub locals_expanded_ids: HashSet<String>,
+    pub locals_selected_index: usize,
+    pub locals_scroll_offset: usize,
 
     pub debugger_search_query: String,
     pub debugger_search_results: Vec<String>, // Paths of matching nodes
     pub debugger_current_match_index: usize,
+    pub right_panel_split: [u16; 3], // Breakpoints / Call Stack / Locals heights in percent
+    pub inspector_split: [u16; 2],   // Tree / Details widths in percent
+    pub debugger_split: [u16; 3],    // Files / Source / right panel widths in percent
+    pub dragging_split_border: Option<usize>, // Border of the current tab's split being dragged
+    pub show_app_bar_menu: bool,
+    pub app_bar_menu_selected_index: usize,
 
     // UI Areas for Mouse Interaction
     pub inspector_tree_area: RefCell<Rect>,
+    pub breadcrumb_areas: RefCell<Vec<(Rect, String)>>, // Clickable ancestors and their node ids
+    pub app_bar_areas: RefCell<Vec<(Rect, AppBarButton)>>, // Buttons as drawn, More included
+    pub app_bar_overflow: RefCell<Vec<AppBarButton>>,   // Buttons in the More menu
+    pub app_bar_menu_areas: RefCell<Vec<(Rect, AppBarButton)>>, // Rows of the open More menu
     pub debugger_tree_area: RefCell<Rect>,
     pub debugger_source_area: RefCell<Rect>,
-    pub isolate_list_area: RefCell<Rect>,
+    pub split_area: RefCell<Rect>, // What the current tab's resizable split divides
+    pub split_borders: RefCell<Vec<u16>>, // Columns of the borders between its panes
+    pub logs_area: RefCell<Rect>,  // Where the logs were drawn, panel or tab; empty when hidden
 
     pub inspector_visible_count: RefCell<usize>,
+    /// Shown rows of the inspector tree, walked on the first draw after a change.
+    tree_rows: OnceCell<TreeRows>,
     pub debugger_visible_count: RefCell<usize>,
     pub inspector_tree_height: RefCell<usize>,
     pub debugger_tree_height: RefCell<usize>,
+    pub locals_tree_height: RefCell<usize>,
+    pub details_tree_height: RefCell<usize>,
+    pub details_tree_area: RefCell<Rect>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DebugState {
     Running,
-    Paused { isolate_id: String, reason: String },
+    Paused {
+        isolate_id: String,
+        reason: String,
+        breakpoint_id: Option<String>, // VM id of the breakpoint that was hit
+    },
+}
+
+/// What the pause button does in the current debug state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseControl {
+    /// Resumes the isolates waiting at their start, as with `--start-paused`.
+    Start,
+    Resume,
+    Pause,
+}
+
+/// What the VM task reports about the run state of the app's isolates, all of them and
+/// not just the inspected one. One channel keeps a step's resume and pause in order.
+#[derive(Debug)]
+pub enum DebugEvent {
+    /// Always a `DebugState::Paused`, with the isolate's stack.
+    Paused(DebugState, Option<crate::vm_service::Stack>),
+    Resumed(String),
+    /// Runnable, so breakpoints can be set in it.
+    IsolateStarted(crate::vm_service::IsolateRef),
+    IsolateExited(String),
 }
 
 impl AppState {
@@ -100,31 +1095,109 @@ impl AppState {
         Self {
             root_node: None,
             selected_node_details: None,
-            connection_status: "Connecting...".to_string(),
+            pending_details_request: None,
+            widget_select_mode: false,
+            device_selection: None,
+            pending_device_selection: None,
+            details_cache: HashMap::new(),
+            expired_details_id: None,
+            details_raw_json: None,
+            show_raw_json: false,
+            raw_json_scroll: 0,
+            details_properties: None,
+            details_search_query: String::new(),
+            details_search_root: None,
+            details_search_matches: 0,
+            details_expanded_ids: HashSet::new(),
+            details_selected_index: 0,
+            details_scroll_offset: 0,
+            connection_status: ConnectionStatus::Connecting,
+            subsystems: std::collections::BTreeMap::new(),
             available_isolates: Vec::new(),
             show_isolate_selection: false,
+            inspected_isolate: None,
+            inspector_isolates: HashSet::new(),
+            show_app_info: false,
+            show_isolate_metrics: false,
+            isolate_metrics: None,
+            isolate_metrics_at: None,
+            show_toggles: false,
+            toggles_selected_index: 0,
+            toggle_states: HashMap::new(),
+            snippets: Vec::new(),
+            keymap: crate::keymap::KeyMap::default(),
+            vim_keys: false,
+            vim_pending_g: false,
+            text_search_input: String::new(),
+            text_search_query: String::new(),
+            text_search_return_focus: Focus::Logs,
+            show_command_palette: false,
+            palette_query: String::new(),
+            palette_selected_index: 0,
+            eval_result: None,
+            show_screenshot: false,
+            http_replay: None,
+            http_replay_scroll: 0,
+            screenshot: None,
+            eval_result_scroll: 0,
+            app_info: AppInfo::default(),
+            display_refresh_rate: None,
+            refresh_rate_override: None,
             selected_isolate_index: 0,
             selected_index: 0,
             expanded_ids: HashSet::new(),
+            children_loading: HashSet::new(),
+            expand_pending: HashMap::new(),
+            depth_input: String::new(),
+            subtree_focus_saved: None,
+            summary_tree: true,
+            pending_expanded_locations: HashSet::new(),
+            pending_selection: None,
             tree_scroll_offset: 0,
+            tree_mark_anchor: None,
             tree_horizontal_scroll: 0,
+            tree_refreshing: false,
+            tree_updated_at: None,
+            last_reload: None,
+            script_snapshot: None,
+            reload_scope: None,
+            show_reload_scope: false,
+            reload_scope_scroll: 0,
+            spinner_tick: 0,
             logs: Vec::new(),
+            log_view: Vec::new(),
+            log_source_filter: LogSourceFilter::All,
+            log_level_filter: log::LevelFilter::Trace,
+            log_counts: HashMap::new(),
             log_scroll_state: 0,
+            log_selected: None,
+            log_mark_anchor: None,
+            expanded_logs: HashSet::new(),
+            log_view_height: RefCell::new(0),
             log_auto_scroll: true,
             show_logs: true,
+            log_panel_height: 10,
+            log_panel_limit: RefCell::new(0),
+            dragging_log_border: false,
+            logs_only: false,
             search_query: String::new(),
             search_results: Vec::new(),
+            search_matches: HashSet::new(),
             current_match_index: 0,
+            filter_query: String::new(),
+            filter_visible_ids: None,
+            filter_match_count: 0,
+            filter_saved_expansion: None,
             focus: Focus::Tree,
             auto_reload: true,
             tx_flutter_command: None,
+            registered_services: HashMap::new(),
             vm_service_client: None,
             current_tab: Tab::Inspector,
 
             project_root,
             file_list: Vec::new(),
             file_tree: None,
-            selected_file_index: 0,
             debugger_selected_index: 0,
             debugger_expanded_ids: HashSet::new(),
             debugger_tree_scroll_offset: 0,
@@ -134,73 +1207,748 @@ impl AppState {
             source_scroll_offset: 0,
             source_selected_line: None,
             breakpoints: HashSet::new(),
+            logpoints: HashMap::new(),
+            breakpoint_vm_ids: HashMap::new(),
+            logpoint_input: String::new(),
+            isolate_labels: HashMap::new(),
+            isolate_label_input: String::new(),
+            disabled_breakpoints: HashSet::new(),
+            breakpoint_conditions: HashMap::new(),
+            condition_input: String::new(),
+            device_input: String::new(),
+            device_input_return_focus: Focus::Tree,
+            breakpoints_selected_index: 0,
+            breakpoint_mark_anchor: None,
+            rebuild_tracking: false,
+            rebuilds: RebuildTracker::default(),
+            repaints: RebuildTracker::default(),
+            memory: None,
+            snapshot_interval: None,
+            timeline: TimelineRecorder::new(Duration::from_secs(10)),
+            profiler: None,
+            performance: None,
+            network: None,
+            snapshots: SnapshotHistory::default(),
+            show_focus_tree: false,
+            focus_tree: None,
+            focus_tree_scroll: 0,
+            show_help: false,
+            help_scroll: 0,
+            help_max_scroll: RefCell::new(0),
+            show_rebuild_stats: false,
+            rebuild_stats_selected_index: 0,
+            rebuild_stats_sort: RebuildStatsSort::Total,
+            layout_warnings: Vec::new(),
+            show_layout_warnings: false,
+            layout_warnings_selected_index: 0,
+            pause_requested: false,
             debug_state: DebugState::Running,
+            paused_isolates: HashMap::new(),
             stack_trace: None,
+            stack_selected_index: 0,
+            execution_line: None,
+            locals_root: None,
+            locals_expanded_ids: HashSet::new(),
+            locals_selected_index: 0,
+            locals_scroll_offset: 0,
             debugger_search_query: String::new(),
             debugger_search_results: Vec::new(),
             debugger_current_match_index: 0,
+            right_panel_split: [30, 35, 35],
+            inspector_split: [75, 25],
+            debugger_split: [20, 50, 30],
+            dragging_split_border: None,
+            show_app_bar_menu: false,
+            app_bar_menu_selected_index: 0,
             inspector_tree_area: RefCell::new(Rect::default()),
+            breadcrumb_areas: RefCell::new(Vec::new()),
+            app_bar_areas: RefCell::new(Vec::new()),
+            app_bar_overflow: RefCell::new(Vec::new()),
+            app_bar_menu_areas: RefCell::new(Vec::new()),
             debugger_tree_area: RefCell::new(Rect::default()),
             debugger_source_area: RefCell::new(Rect::default()),
-            isolate_list_area: RefCell::new(Rect::default()),
+            split_area: RefCell::new(Rect::default()),
+            split_borders: RefCell::new(Vec::new()),
+            logs_area: RefCell::new(Rect::default()),
             inspector_visible_count: RefCell::new(0),
+            tree_rows: OnceCell::new(),
             debugger_visible_count: RefCell::new(0),
             inspector_tree_height: RefCell::new(0),
             debugger_tree_height: RefCell::new(0),
+            locals_tree_height: RefCell::new(0),
+            details_tree_height: RefCell::new(0),
+            details_tree_area: RefCell::new(Rect::default()),
+        }
+    }
+
+    pub fn set_root_node(&mut self, node: RemoteDiagnosticsNode) {
+        // Capture currently selected node ID
+        let selected_id = self.get_selected_node().and_then(Self::get_node_id);
+
+        // When we get a new tree, we might want to preserve expansion state if possible.
+        // For now, let's just expand the root by default.
+        if let Some(id) = Self::get_node_id(&node) {
+            self.expanded_ids.insert(id);
+        }
+        self.root_node = Some(node);
+        self.children_loading.clear();
+        self.expand_pending.clear();
+        self.details_cache.clear();
+        self.layout_warnings.clear();
+        self.expired_details_id = None;
+        self.expand_restored_locations();
+        self.recompute_filter();
+
+        if let Some(id) = self.pending_device_selection.take() {
+            if self.select_node_by_id(&id) {
+                self.request_details();
+                return;
+            }
+            log::warn!("Widget selected on the device is not in the summary tree");
+        }
+
+        // After a restart ids are new, the widget is found again by its fingerprint instead
+        if self.pending_selection.is_some() {
+            self.selected_index = 0;
+            self.tree_scroll_offset = 0;
+            self.selected_node_details = None;
+            if !self.select_pending_selection() && !self.has_children_to_load() {
+                self.drop_pending_selection();
+            }
+            return;
+        }
+
+        // Try to restore selection
+        if let Some(id) = selected_id {
+            // Ensure path is expanded (in case IDs changed or it's a new tree structure)
+            self.expand_path_to_node(&id);
+
+            if let Some(index) = self.get_visible_index_of_id(&id) {
+                self.selected_index = index;
+                // Update scroll to keep it visible
+                self.ensure_selection_visible_after_restore();
+                return;
+            }
+        }
+
+        // Not found (or nothing selected yet), reset to top
+        self.selected_index = 0;
+        self.tree_scroll_offset = 0;
+        self.selected_node_details = None;
+    }
+
+    /// Remembers the selected widget to select it again in the tree of a restarted app,
+    /// unless a widget from before an earlier restart is still waited for.
+    pub fn remember_selection_for_restart(&mut self) {
+        if self.pending_selection.is_none() {
+            self.pending_selection = self.selected_fingerprint();
+        }
+    }
+
+    /// Id of the node selected in the inspector tree.
+    pub fn selected_node_id(&self) -> Option<String> {
+        self.get_selected_node().and_then(Self::get_node_id)
+    }
+
+    /// Fingerprint of the selected widget, to find it again after a restart.
+    fn selected_fingerprint(&self) -> Option<WidgetFingerprint> {
+        let id = self.get_selected_node().and_then(Self::get_node_id)?;
+        WidgetFingerprint::of(self.root_node.as_ref()?, &id)
+    }
+
+    /// Selects the node matching `pending_selection`, if the tree has one by now.
+    fn select_pending_selection(&mut self) -> bool {
+        let Some(fingerprint) = &self.pending_selection else {
+            return false;
+        };
+        let id = self
+            .root_node
+            .as_ref()
+            .and_then(|root| fingerprint.find(root))
+            .and_then(Self::get_node_id);
+        match id {
+            Some(id) if self.select_node_by_id(&id) => {
+                if let Some(fingerprint) = self.pending_selection.take() {
+                    log::info!("Reselected {} from before the restart", fingerprint.widget);
+                }
+                self.request_details();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn drop_pending_selection(&mut self) {
+        if let Some(fingerprint) = self.pending_selection.take() {
+            log::debug!("{} selected before the restart is gone", fingerprint.widget);
+        }
+    }
+
+    /// Whether expanded nodes still wait for children cut off the summary tree, the
+    /// selected widget of before a restart possibly among them.
+    fn has_children_to_load(&self) -> bool {
+        let mut ids = Vec::new();
+        if let Some(root) = &self.root_node {
+            Self::collect_unloaded_ids(root, &self.expanded_ids, &mut ids);
+        }
+        !ids.is_empty() || !self.children_loading.is_empty()
+    }
+
+    pub fn persisted_state(&self) -> PersistedState {
+        let expanded_locations = match &self.root_node {
+            Some(root) => {
+                let mut locations = HashSet::new();
+                // Not what the filter opened up to show its matches
+                let expanded = self
+                    .filter_saved_expansion
+                    .as_ref()
+                    .unwrap_or(&self.expanded_ids);
+                Self::collect_expanded_locations(root, expanded, &mut locations);
+                locations
+            }
+            // Never connected this session, keep what was restored
+            None => self.pending_expanded_locations.clone(),
+        };
+
+        PersistedState {
+            selected_widget: self
+                .selected_fingerprint()
+                .or_else(|| self.pending_selection.clone()),
+            current_tab: Some(self.current_tab),
+            show_logs: Some(self.show_logs),
+            log_panel_height: Some(self.log_panel_height),
+            expanded_locations,
+            tree_scroll_offset: self.tree_scroll_offset,
+            tree_horizontal_scroll: self.tree_horizontal_scroll,
+            debugger_expanded_ids: self.debugger_expanded_ids.clone(),
+            debugger_tree_scroll_offset: self.debugger_tree_scroll_offset,
+            open_file_path: self.open_file_path.clone(),
+            source_scroll_offset: self.source_scroll_offset,
+            source_selected_line: self.source_selected_line,
+            right_panel_split: Some(self.right_panel_split),
+            inspector_split: Some(self.inspector_split),
+            debugger_split: Some(self.debugger_split),
+            isolate_labels: self.isolate_labels.clone(),
+        }
+    }
+
+    pub fn restore_persisted_state(&mut self, state: PersistedState) {
+        if let Some(tab) = state.current_tab {
+            self.current_tab = tab;
+            self.focus = match tab {
+                Tab::Inspector => Focus::Tree,
+                Tab::Debugger => Focus::DebuggerFiles,
+                Tab::Logs => Focus::Logs,
+                Tab::Memory => Focus::Memory,
+                Tab::Profiler => Focus::Profiler,
+                Tab::Performance => Focus::Timeline,
+                Tab::Network => Focus::Network,
+            };
+        }
+        if let Some(show_logs) = state.show_logs {
+            self.show_logs = show_logs;
+        }
+        if let Some(height) = state.log_panel_height {
+            self.log_panel_height = height.max(MIN_LOG_PANEL_HEIGHT);
+        }
+        if let Some(split) = state.right_panel_split.filter(|s| is_valid_split(s)) {
+            self.right_panel_split = split;
+        }
+        if let Some(split) = state.inspector_split.filter(|s| is_valid_split(s)) {
+            self.inspector_split = split;
+        }
+        if let Some(split) = state.debugger_split.filter(|s| is_valid_split(s)) {
+            self.debugger_split = split;
+        }
+        self.isolate_labels = state.isolate_labels;
+
+        self.pending_expanded_locations = state.expanded_locations;
+        self.pending_selection = state.selected_widget;
+        self.tree_scroll_offset = state.tree_scroll_offset;
+        self.tree_horizontal_scroll = state.tree_horizontal_scroll;
+
+        self.debugger_expanded_ids = state.debugger_expanded_ids;
+        self.debugger_tree_scroll_offset = state.debugger_tree_scroll_offset;
+        if let Some(path) = state.open_file_path {
+            self.open_file(&path);
+            if self.open_file_path.is_some() {
+                self.source_scroll_offset = state.source_scroll_offset;
+                self.source_selected_line = state.source_selected_line;
+            }
+        }
+    }
+
+    fn collect_expanded_locations(
+        node: &RemoteDiagnosticsNode,
+        expanded_ids: &HashSet<String>,
+        locations: &mut HashSet<String>,
+    ) {
+        let is_expanded = Self::get_node_id(node).is_some_and(|id| expanded_ids.contains(&id));
+        if !is_expanded {
+            return;
+        }
+        if let Some(hash) = crate::persistence::location_hash(node) {
+            locations.insert(hash);
+        }
+        if let Some(children) = &node.children {
+            for child in children {
+                Self::collect_expanded_locations(child, expanded_ids, locations);
+            }
+        }
+    }
+
+    /// Applies expansion state restored from the previous session to the first tree
+    /// received in this one.
+    fn expand_restored_locations(&mut self) {
+        if self.pending_expanded_locations.is_empty() {
+            return;
+        }
+        let locations = std::mem::take(&mut self.pending_expanded_locations);
+        if let Some(root) = &self.root_node {
+            let mut ids = Vec::new();
+            Self::collect_ids_for_locations(root, &locations, &mut ids);
+            self.expanded_ids.extend(ids);
+            self.invalidate_tree_rows();
+        }
+    }
+
+    fn collect_ids_for_locations(
+        node: &RemoteDiagnosticsNode,
+        locations: &HashSet<String>,
+        ids: &mut Vec<String>,
+    ) {
+        let matches =
+            crate::persistence::location_hash(node).is_some_and(|h| locations.contains(&h));
+        if matches {
+            if let Some(id) = Self::get_node_id(node) {
+                ids.push(id);
+            }
+        }
+        if let Some(children) = &node.children {
+            for child in children {
+                Self::collect_ids_for_locations(child, locations, ids);
+            }
+        }
+    }
+
+    fn ensure_selection_visible_after_restore(&mut self) {
+        if self.selected_index < self.tree_scroll_offset {
+            self.tree_scroll_offset = self.selected_index;
+        } else {
+            // We don't know the height here, so we can't perfectly scroll to bottom.
+            // But we can try to keep it somewhat centered or just ensure top visibility.
+            // Let's just leave scroll offset alone if it's visible, or move it if it's way off.
+            // Actually, if we just reloaded, the tree structure might be similar.
+            // Let's try to maintain relative position?
+            // For now, let's just ensure it's not above the viewport.
+            // If it's way below, the user will scroll.
+            // Better: use the same logic as jump_to_match
+            if self.selected_index >= 3 {
+                self.tree_scroll_offset = self.selected_index - 3;
+            } else {
+                self.tree_scroll_offset = 0;
+            }
+        }
+    }
+
+    /// Schedules a details fetch for the selected node once the selection has settled,
+    /// so scrolling quickly through the tree doesn't fire a request per step.
+    pub fn request_details(&mut self) {
+        const SETTLE_DELAY: std::time::Duration = std::time::Duration::from_millis(150);
+
+        match self.get_selected_node().and_then(Self::get_node_id) {
+            Some(id) => {
+                if self.apply_cached_details(&id) {
+                    self.pending_details_request = None;
+                    return;
+                }
+                self.pending_details_request = Some((id, std::time::Instant::now() + SETTLE_DELAY));
+            }
+            None => {
+                log::warn!("UI: Selected node has no object_id or value_id");
+                self.pending_details_request = None;
+            }
+        }
+    }
+
+    /// Shows prefetched details for `id` without a round-trip, if there are any.
+    fn apply_cached_details(&mut self, id: &str) -> bool {
+        let Some(raw) = self.details_cache.get(id).cloned() else {
+            return false;
+        };
+        match serde_json::from_value(raw.clone()) {
+            Ok(details) => {
+                self.set_node_details(details, &raw);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Ids of the shown children of the selected node that have no cached details yet, so
+    /// they can be prefetched as soon as the node is expanded.
+    pub fn details_prefetch_ids(&self) -> Vec<String> {
+        const PREFETCH_LIMIT: usize = 32;
+
+        let Some(node) = self.get_selected_node() else {
+            return Vec::new();
+        };
+        if !Self::get_node_id(node).is_some_and(|id| self.expanded_ids.contains(&id)) {
+            return Vec::new();
+        }
+        node.children
+            .iter()
+            .flatten()
+            .filter(|child| self.is_node_shown(child))
+            .filter_map(Self::get_node_id)
+            .filter(|id| !self.details_cache.contains_key(id))
+            .take(PREFETCH_LIMIT)
+            .collect()
+    }
+
+    /// The selected node id if it should be highlighted on the device, i.e. select mode
+    /// is on and the selection changed since the last sync in either direction.
+    pub fn take_device_selection_sync(&mut self) -> Option<String> {
+        if !self.widget_select_mode {
+            return None;
+        }
+        let id = self.get_selected_node().and_then(Self::get_node_id)?;
+        if self.device_selection.as_ref() == Some(&id) {
+            return None;
+        }
+        self.device_selection = Some(id.clone());
+        Some(id)
+    }
+
+    /// Selects the widget picked on the device. Returns false if it isn't in the current
+    /// tree, in which case it is selected once the next tree arrives.
+    pub fn select_device_widget(&mut self, id: String) -> bool {
+        self.device_selection = Some(id.clone());
+        self.pending_selection = None;
+        if self.select_node_by_id(&id) {
+            self.request_details();
+            true
+        } else {
+            self.pending_device_selection = Some(id);
+            false
+        }
+    }
+
+    pub fn take_due_details_request(&mut self) -> Option<String> {
+        match &self.pending_details_request {
+            Some((_, due)) if std::time::Instant::now() >= *due => {
+                self.pending_details_request.take().map(|(id, _)| id)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn set_node_details(&mut self, details: RemoteDiagnosticsNode, raw: &serde_json::Value) {
+        self.details_raw_json = serde_json::to_string_pretty(raw).ok();
+        self.raw_json_scroll = 0;
+        let root = PropertyNode::root(&details);
+        self.details_expanded_ids.clear();
+        self.details_expanded_ids.insert(root.key.clone());
+        self.details_expanded_ids.insert(RENDER_KEY.to_string());
+        self.details_properties = Some(root);
+        self.details_selected_index = 0;
+        self.details_scroll_offset = 0;
+        self.selected_node_details = Some(details);
+        self.recompute_details_search();
+    }
+
+    /// The properties tree the Details pane shows: pruned to the search matches while
+    /// searching.
+    pub fn shown_details(&self) -> Option<&PropertyNode> {
+        self.details_search_root
+            .as_ref()
+            .or(self.details_properties.as_ref())
+    }
+
+    /// Re-runs the property search over the loaded properties and expands the ancestors
+    /// of every match.
+    fn recompute_details_search(&mut self) {
+        let query = self.details_search_query.to_lowercase();
+        self.details_search_root = None;
+        self.details_search_matches = 0;
+        let Some(root) = self
+            .details_properties
+            .as_ref()
+            .filter(|_| !query.is_empty())
+        else {
+            return;
+        };
+        let mut matches = 0;
+        let mut ancestors = Vec::new();
+        let children = root
+            .children
+            .iter()
+            .flatten()
+            .filter_map(|child| child.pruned(&query, &mut matches, &mut ancestors))
+            .collect();
+        self.details_search_root = Some(root.with_children(children));
+        self.details_expanded_ids.extend(ancestors);
+        self.details_search_matches = matches;
+    }
+
+    pub fn set_details_search(&mut self, query: String) {
+        self.details_search_query = query;
+        self.recompute_details_search();
+        self.details_selected_index = 0;
+        self.details_scroll_offset = 0;
+    }
+
+    fn selected_property(&self) -> Option<&PropertyNode> {
+        let root = self.shown_details()?;
+        let mut current_index = 0;
+        crate::ui::tree::get_node_at_index(
+            root,
+            &self.details_expanded_ids,
+            self.details_selected_index,
+            &mut current_index,
+        )
+    }
+
+    pub fn toggle_raw_json(&mut self) {
+        self.show_raw_json = !self.show_raw_json;
+        self.raw_json_scroll = 0;
+    }
+
+    pub fn move_details_selection(&mut self, delta: isize) {
+        if self.show_raw_json {
+            let line_count = self
+                .details_raw_json
+                .as_ref()
+                .map_or(0, |json| json.lines().count());
+            self.raw_json_scroll = (self.raw_json_scroll as isize + delta)
+                .clamp(0, line_count.saturating_sub(1) as isize)
+                as usize;
+            return;
         }
+        if let Some(root) = self.shown_details() {
+            let count = crate::ui::tree::count_visible_nodes(root, &self.details_expanded_ids);
+            if count == 0 {
+                return;
+            }
+            let new_index = (self.details_selected_index as isize + delta)
+                .max(0)
+                .min(count as isize - 1);
+            self.details_selected_index = new_index as usize;
+
+            let visible_height = self.details_tree_height.borrow().saturating_sub(2);
+            if self.details_selected_index < self.details_scroll_offset {
+                self.details_scroll_offset = self.details_selected_index;
+            } else if visible_height > 0
+                && self.details_selected_index >= self.details_scroll_offset + visible_height
+            {
+                self.details_scroll_offset = self.details_selected_index - visible_height + 1;
+            }
+        }
+    }
+
+    /// The selected line of the focused pane as plain text, for the clipboard: the tree
+    /// node's type, description and id, the property or the log message.
+    pub fn selected_text(&self) -> Option<String> {
+        match self.focus {
+            Focus::Tree => {
+                let rows = self
+                    .marked_tree_rows()
+                    .unwrap_or(self.selected_index..=self.selected_index);
+                let lines: Vec<String> = rows
+                    .filter_map(|index| self.get_node_at_index(index))
+                    .map(|node| {
+                        let mut text = format!(
+                            "{} ({})",
+                            node.widget_runtime_type
+                                .as_deref()
+                                .or(node.node_type.as_deref())
+                                .unwrap_or("Unknown"),
+                            node.description.as_deref().unwrap_or("?")
+                        );
+                        if let Some(id) = Self::get_node_id(node) {
+                            text.push_str(&format!(" {}", id));
+                        }
+                        text
+                    })
+                    .collect();
+                (!lines.is_empty()).then(|| lines.join("\n"))
+            }
+            Focus::Details => {
+                let property = self.selected_property()?;
+                Some(if property.name.is_empty() {
+                    property.description.clone()
+                } else {
+                    format!("{}: {}", property.name, property.description)
+                })
+            }
+            Focus::Logs => {
+                let selected = self.log_selected?;
+                let entries = self.marked_logs().unwrap_or(selected..=selected);
+                let lines: Vec<String> = entries
+                    .filter_map(|position| self.shown_log(position))
+                    .map(|entry| {
+                        let mut text = entry.message.clone();
+                        if let Some(error) = &entry.error {
+                            text.push_str(&format!("\n{}", error));
+                        }
+                        for frame in &entry.stack_trace {
+                            text.push_str(&format!("\n{}", frame));
+                        }
+                        text
+                    })
+                    .collect();
+                Some(lines.join("\n"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Before the cursor of the focused list moves: Shift+arrows (`extend`) keep the
+    /// selection's other end where the cursor was, plain moves drop the selection.
+    pub fn anchor_marks(&mut self, extend: bool) {
+        match self.focus {
+            Focus::Tree if !extend => self.tree_mark_anchor = None,
+            Focus::Tree if self.tree_mark_anchor.is_none() => {
+                self.tree_mark_anchor = self.get_selected_node().and_then(Self::get_node_id);
+            }
+            Focus::Logs if !extend => self.log_mark_anchor = None,
+            Focus::Logs if self.log_mark_anchor.is_none() => {
+                // Before the first move the cursor is on the newest entry
+                self.log_mark_anchor = self
+                    .log_selected
+                    .or_else(|| self.log_view.len().checked_sub(1));
+            }
+            Focus::DebuggerBreakpoints if !extend => self.breakpoint_mark_anchor = None,
+            Focus::DebuggerBreakpoints if self.breakpoint_mark_anchor.is_none() => {
+                self.breakpoint_mark_anchor = self.selected_listed_breakpoint();
+            }
+            _ => {}
+        }
+    }
+
+    /// Shown rows of the inspector tree selected with Shift+arrows, the cursor's included.
+    /// None without such a selection, or once its anchor is collapsed out of view.
+    pub fn marked_tree_rows(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.get_visible_index_of_id(self.tree_mark_anchor.as_deref()?)?;
+        Some(anchor.min(self.selected_index)..=anchor.max(self.selected_index))
+    }
+
+    /// Log entries selected with Shift+arrows, the cursor's included.
+    pub fn marked_logs(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.log_mark_anchor?;
+        let selected = self.log_selected?;
+        Some(anchor.min(selected)..=anchor.max(selected))
+    }
+
+    /// Positions in the Breakpoints pane selected with Shift+arrows, the cursor's included.
+    pub fn marked_breakpoints(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.breakpoint_mark_anchor.as_ref()?;
+        let anchor = self
+            .sorted_breakpoints()
+            .iter()
+            .position(|key| *key == anchor)?;
+        let selected = self.breakpoints_selected_index;
+        Some(anchor.min(selected)..=anchor.max(selected))
+    }
+
+    /// Keys of the breakpoints that d/Del acts on: the Shift+arrow selection, else the one
+    /// under the cursor.
+    pub fn marked_or_selected_breakpoints(&self) -> Vec<String> {
+        let sorted = self.sorted_breakpoints();
+        let range = self
+            .marked_breakpoints()
+            .unwrap_or(self.breakpoints_selected_index..=self.breakpoints_selected_index);
+        range
+            .filter_map(|index| sorted.get(index).map(|key| key.to_string()))
+            .collect()
+    }
+
+    /// Snippets whose name or expression contains the palette query, in config order.
+    pub fn palette_snippets(&self) -> Vec<&Snippet> {
+        let query = self.palette_query.to_lowercase();
+        self.snippets
+            .iter()
+            .filter(|snippet| {
+                snippet.name.to_lowercase().contains(&query)
+                    || snippet.expression.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+
+    pub fn open_help(&mut self) {
+        self.show_help = true;
+        self.help_scroll = 0;
+    }
+
+    pub fn move_help_scroll(&mut self, delta: usize) {
+        self.help_scroll = (self.help_scroll + delta).min(*self.help_max_scroll.borrow());
     }
 
-    pub fn set_root_node(&mut self, node: RemoteDiagnosticsNode) {
-        // Capture currently selected node ID
-        let selected_id = self.get_selected_node().and_then(|n| Self::get_node_id(n));
+    pub fn open_command_palette(&mut self) {
+        self.show_command_palette = true;
+        self.palette_query.clear();
+        self.palette_selected_index = 0;
+    }
 
-        // When we get a new tree, we might want to preserve expansion state if possible.
-        // For now, let's just expand the root by default.
-        if let Some(id) = Self::get_node_id(&node) {
-            self.expanded_ids.insert(id);
+    /// Selects the clicked row of the Details tree, or toggles it when it already was
+    /// selected. Returns what [`Self::toggle_selected_property`] returns.
+    pub fn click_details_row(&mut self, row: usize) -> Option<(String, String)> {
+        let index = self.details_scroll_offset + row;
+        let count = self.shown_details().map_or(0, |root| {
+            crate::ui::tree::count_visible_nodes(root, &self.details_expanded_ids)
+        });
+        if index >= count {
+            return None;
         }
-        self.root_node = Some(node);
+        if index == self.details_selected_index {
+            self.toggle_selected_property()
+        } else {
+            self.details_selected_index = index;
+            None
+        }
+    }
 
-        // Try to restore selection
-        if let Some(id) = selected_id {
-            // Ensure path is expanded (in case IDs changed or it's a new tree structure)
-            self.expand_path_to_node(&id);
+    /// Expands the selected property. Returns `(key, object_id)` when its nested
+    /// properties weren't part of the details subtree and have to be fetched.
+    pub fn expand_selected_property(&mut self) -> Option<(String, String)> {
+        let node = self.selected_property()?;
+        if !node.is_expandable() {
+            return None;
+        }
+        let key = node.key.clone();
+        let pending = match (&node.children, &node.object_id) {
+            (None, Some(object_id)) => Some((key.clone(), object_id.clone())),
+            _ => None,
+        };
+        self.details_expanded_ids.insert(key);
+        pending
+    }
 
-            if let Some(index) = self.get_visible_index_of_id(&id) {
-                self.selected_index = index;
-                // Update scroll to keep it visible
-                self.ensure_selection_visible_after_restore();
-            } else {
-                // Node not found, reset to top
-                self.selected_index = 0;
-                self.tree_scroll_offset = 0;
-                self.selected_node_details = None;
-            }
+    pub fn toggle_selected_property(&mut self) -> Option<(String, String)> {
+        let key = self.selected_property()?.key.clone();
+        if self.details_expanded_ids.remove(&key) {
+            None
         } else {
-            // No previous selection, reset
-            self.selected_index = 0;
-            self.tree_scroll_offset = 0;
-            self.selected_node_details = None;
+            self.expand_selected_property()
         }
     }
 
-    fn ensure_selection_visible_after_restore(&mut self) {
-        if self.selected_index < self.tree_scroll_offset {
-            self.tree_scroll_offset = self.selected_index;
-        } else {
-            // We don't know the height here, so we can't perfectly scroll to bottom.
-            // But we can try to keep it somewhat centered or just ensure top visibility.
-            // Let's just leave scroll offset alone if it's visible, or move it if it's way off.
-            // Actually, if we just reloaded, the tree structure might be similar.
-            // Let's try to maintain relative position?
-            // For now, let's just ensure it's not above the viewport.
-            // If it's way below, the user will scroll.
-            // Better: use the same logic as jump_to_match
-            if self.selected_index >= 3 {
-                self.tree_scroll_offset = self.selected_index - 3;
-            } else {
-                self.tree_scroll_offset = 0;
-            }
+    pub fn collapse_selected_property(&mut self) {
+        if let Some(key) = self.selected_property().map(|n| n.key.clone()) {
+            self.details_expanded_ids.remove(&key);
+        }
+    }
+
+    pub fn set_property_children(&mut self, key: &str, properties: &[RemoteDiagnosticsNode]) {
+        if let Some(node) = self
+            .details_properties
+            .as_mut()
+            .and_then(|r| r.find_mut(key))
+        {
+            node.children = Some(PropertyNode::from_properties(key, properties));
+        }
+        if self.details_search_root.is_some() {
+            self.recompute_details_search();
         }
     }
 
@@ -218,6 +1966,7 @@ impl AppState {
                 } else {
                     self.expanded_ids.insert(id);
                 }
+                self.invalidate_tree_rows();
             }
         }
     }
@@ -233,6 +1982,7 @@ impl AppState {
                     expanded_any = true;
                 }
             }
+            self.invalidate_tree_rows();
             return expanded_any;
         }
         false
@@ -241,7 +1991,7 @@ impl AppState {
     pub fn select_first_child(&mut self) {
         if let Some(node) = self.get_selected_node() {
             if let Some(children) = &node.children {
-                if !children.is_empty() {
+                if children.iter().any(|c| self.is_node_shown(c)) {
                     self.selected_index += 1;
                     self.ensure_selection_visible();
                     self.selected_node_details = None;
@@ -250,6 +2000,281 @@ impl AppState {
         }
     }
 
+    /// Ids of expanded nodes whose children were cut off the summary tree and aren't
+    /// being fetched yet. They count as being fetched from here on.
+    pub fn take_children_requests(&mut self) -> Vec<String> {
+        let mut ids = Vec::new();
+        if let Some(root) = &self.root_node {
+            Self::collect_unloaded_ids(root, &self.expanded_ids, &mut ids);
+        }
+        ids.retain(|id| self.children_loading.insert(id.clone()));
+        ids
+    }
+
+    fn collect_unloaded_ids(
+        node: &RemoteDiagnosticsNode,
+        expanded_ids: &HashSet<String>,
+        ids: &mut Vec<String>,
+    ) {
+        let Some(id) = Self::get_node_id(node) else {
+            return;
+        };
+        if !expanded_ids.contains(&id) {
+            return;
+        }
+        if node.has_unloaded_children() {
+            ids.push(id);
+        }
+        for child in node.children.iter().flatten() {
+            Self::collect_unloaded_ids(child, expanded_ids, ids);
+        }
+    }
+
+    /// Merges lazily fetched children into the tree.
+    pub fn set_node_children(&mut self, id: &str, children: Vec<RemoteDiagnosticsNode>) {
+        self.children_loading.remove(id);
+        let Some(node) = self
+            .root_node
+            .as_mut()
+            .and_then(|root| Self::find_node_mut(root, id))
+        else {
+            return;
+        };
+        if children.is_empty() {
+            // Nothing to show after all, don't ask again
+            node.has_children = Some(false);
+        }
+        node.children = Some(children);
+        // Part of an expand-all that reached the cut-off: carry on into the new children
+        if let Some(levels) = self.expand_pending.remove(id) {
+            let mut ids = Vec::new();
+            let mut pending = Vec::new();
+            for child in node.children.iter().flatten() {
+                Self::collect_expand_ids(child, levels, &mut ids, &mut pending);
+            }
+            self.expanded_ids.extend(ids);
+            self.expand_pending.extend(pending);
+        }
+        self.recompute_filter();
+        if self.pending_selection.is_some()
+            && !self.select_pending_selection()
+            && !self.has_children_to_load()
+        {
+            self.drop_pending_selection();
+        }
+    }
+
+    /// Expands the selected node and everything below it, children that still have to be
+    /// fetched included.
+    pub fn expand_subtree(&mut self) {
+        let Some(node) = self.get_selected_node() else {
+            return;
+        };
+        let mut ids = Vec::new();
+        let mut pending = Vec::new();
+        Self::collect_expand_ids(node, None, &mut ids, &mut pending);
+        self.expanded_ids.extend(ids);
+        self.expand_pending.extend(pending);
+        self.invalidate_tree_rows();
+    }
+
+    /// Collapses the selected node and everything below it.
+    pub fn collapse_subtree(&mut self) {
+        let Some(node) = self.get_selected_node() else {
+            return;
+        };
+        let mut ids = Vec::new();
+        let mut pending = Vec::new();
+        Self::collect_expand_ids(node, None, &mut ids, &mut pending);
+        for id in ids {
+            self.expanded_ids.remove(&id);
+            self.expand_pending.remove(&id);
+        }
+        self.invalidate_tree_rows();
+    }
+
+    /// Collapses everything but the ancestors and the subtree of the selected node, or,
+    /// when that was done before, brings back the expansion from before.
+    pub fn toggle_subtree_focus(&mut self) {
+        let selected_id = self.get_selected_node().and_then(Self::get_node_id);
+        if let Some(saved) = self.subtree_focus_saved.take() {
+            self.expanded_ids = saved;
+        } else {
+            let (Some(root), Some(id)) = (&self.root_node, &selected_id) else {
+                return;
+            };
+            let mut keep = Vec::new();
+            Self::find_path_to_node(root, id, &mut keep);
+            if let Some(node) = Self::find_node(root, id) {
+                Self::collect_subtree_ids(node, &mut keep);
+            }
+            let focused = keep
+                .into_iter()
+                .filter(|id| self.expanded_ids.contains(id))
+                .collect();
+            self.subtree_focus_saved = Some(std::mem::replace(&mut self.expanded_ids, focused));
+        }
+        self.invalidate_tree_rows();
+        if let Some(index) = selected_id.and_then(|id| self.get_visible_index_of_id(&id)) {
+            self.selected_index = index;
+        }
+        self.ensure_selection_visible();
+    }
+
+    /// Node ids and widget names from the root down to the selected node.
+    pub fn selected_breadcrumb(&self) -> Vec<(String, String)> {
+        let (Some(root), Some(selected)) = (&self.root_node, self.get_selected_node()) else {
+            return Vec::new();
+        };
+        let Some(selected_id) = Self::get_node_id(selected) else {
+            return Vec::new();
+        };
+        let mut path = Vec::new();
+        if !Self::find_path_to_node(root, &selected_id, &mut path) {
+            return Vec::new();
+        }
+        path.push(selected_id);
+        path.into_iter()
+            .filter_map(|id| {
+                let node = Self::find_node(root, &id)?;
+                let label = node
+                    .widget_runtime_type
+                    .as_deref()
+                    .or(node.description.as_deref())
+                    .unwrap_or("?")
+                    .to_string();
+                Some((id, label))
+            })
+            .collect()
+    }
+
+    /// The node id of the breadcrumb segment drawn at a screen position.
+    pub fn breadcrumb_at(&self, x: u16, y: u16) -> Option<String> {
+        self.breadcrumb_areas
+            .borrow()
+            .iter()
+            .find(|(area, _)| {
+                x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+            })
+            .map(|(_, id)| id.clone())
+    }
+
+    /// The app bar button, or row of the open More menu, drawn at a screen position.
+    pub fn app_bar_button_at(&self, x: u16, y: u16) -> Option<AppBarButton> {
+        let contains = |area: &Rect| {
+            x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+        };
+        let menu = self.app_bar_menu_areas.borrow();
+        let bar = self.app_bar_areas.borrow();
+        let menu = if self.show_app_bar_menu {
+            &menu[..]
+        } else {
+            &[]
+        };
+        menu.iter()
+            .chain(bar.iter())
+            .find(|(area, _)| contains(area))
+            .map(|(_, button)| *button)
+    }
+
+    pub fn move_app_bar_menu_selection(&mut self, delta: isize) {
+        let len = self.app_bar_overflow.borrow().len();
+        self.app_bar_menu_selected_index = self
+            .app_bar_menu_selected_index
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+    }
+
+    fn find_node<'a>(
+        node: &'a RemoteDiagnosticsNode,
+        id: &str,
+    ) -> Option<&'a RemoteDiagnosticsNode> {
+        if Self::get_node_id(node).as_deref() == Some(id) {
+            return Some(node);
+        }
+        node.children
+            .as_ref()?
+            .iter()
+            .find_map(|child| Self::find_node(child, id))
+    }
+
+    fn collect_subtree_ids(node: &RemoteDiagnosticsNode, ids: &mut Vec<String>) {
+        if let Some(id) = Self::get_node_id(node) {
+            ids.push(id);
+        }
+        for child in node.children.iter().flatten() {
+            Self::collect_subtree_ids(child, ids);
+        }
+    }
+
+    /// Expands the whole tree `depth` levels down from the root and collapses everything
+    /// deeper. The selection stays on the same node, or its closest ancestor still shown.
+    pub fn expand_to_depth(&mut self, depth: usize) {
+        let Some(root) = &self.root_node else {
+            return;
+        };
+        let selected_id = self.get_selected_node().and_then(Self::get_node_id);
+        let mut ids = Vec::new();
+        let mut pending = Vec::new();
+        Self::collect_expand_ids(root, Some(depth), &mut ids, &mut pending);
+        self.expanded_ids = ids.into_iter().collect();
+        self.expand_pending = pending.into_iter().collect();
+        self.invalidate_tree_rows();
+
+        if let Some(id) = selected_id {
+            let mut path = Vec::new();
+            if let Some(root) = &self.root_node {
+                Self::find_path_to_node(root, &id, &mut path);
+            }
+            if let Some(index) = std::iter::once(id)
+                .chain(path.into_iter().rev())
+                .find_map(|id| self.get_visible_index_of_id(&id))
+            {
+                self.selected_index = index;
+            }
+        }
+        self.ensure_selection_visible();
+    }
+
+    /// Ids of the nodes with children from `node` down, `levels` deep (unlimited with None),
+    /// and which of them have children yet to fetch along with the levels left below them.
+    fn collect_expand_ids(
+        node: &RemoteDiagnosticsNode,
+        levels: Option<usize>,
+        ids: &mut Vec<String>,
+        pending: &mut Vec<(String, Option<usize>)>,
+    ) {
+        if levels == Some(0) || node.has_children == Some(false) {
+            return;
+        }
+        let Some(id) = Self::get_node_id(node) else {
+            return;
+        };
+        let below = levels.map(|levels| levels - 1);
+        if node.has_unloaded_children() {
+            pending.push((id.clone(), below));
+        } else if node.children.as_ref().is_none_or(|c| c.is_empty()) {
+            return;
+        }
+        ids.push(id);
+        for child in node.children.iter().flatten() {
+            Self::collect_expand_ids(child, below, ids, pending);
+        }
+    }
+
+    fn find_node_mut<'a>(
+        node: &'a mut RemoteDiagnosticsNode,
+        id: &str,
+    ) -> Option<&'a mut RemoteDiagnosticsNode> {
+        if Self::get_node_id(node).as_deref() == Some(id) {
+            return Some(node);
+        }
+        node.children
+            .as_mut()?
+            .iter_mut()
+            .find_map(|child| Self::find_node_mut(child, id))
+    }
+
     fn collect_smart_expand_ids(
         node: &RemoteDiagnosticsNode,
         ids: &mut Vec<String>,
@@ -273,6 +2298,7 @@ impl AppState {
             if let Some(id) = Self::get_node_id(node) {
                 if self.expanded_ids.contains(&id) {
                     self.expanded_ids.remove(&id);
+                    self.invalidate_tree_rows();
                     return true;
                 }
             }
@@ -280,11 +2306,89 @@ impl AppState {
         false
     }
 
+    /// Whether the node passes the active tree filter (always true when no filter is set).
+    pub fn is_node_shown(&self, node: &RemoteDiagnosticsNode) -> bool {
+        match &self.filter_visible_ids {
+            Some(ids) => Self::get_node_id(node).is_some_and(|id| ids.contains(&id)),
+            None => true,
+        }
+    }
+
+    fn shown_root(&self) -> Option<&RemoteDiagnosticsNode> {
+        self.root_node
+            .as_ref()
+            .filter(|root| self.is_node_shown(root))
+    }
+
+    /// Shown rows of the inspector tree, walked again after the tree, its expansion or its
+    /// filter changed.
+    pub fn tree_rows(&self) -> &TreeRows {
+        self.tree_rows.get_or_init(|| {
+            TreeRows::new(
+                self.root_node.as_ref(),
+                &self.expanded_ids,
+                self.filter_visible_ids.as_ref(),
+            )
+        })
+    }
+
+    fn invalidate_tree_rows(&mut self) {
+        self.tree_rows.take();
+    }
+
+    /// Re-evaluates `filter_query` against the current tree and expands the ancestors of
+    /// every match so they show up without manual expansion, until the filter is cleared.
+    fn recompute_filter(&mut self) {
+        self.invalidate_tree_rows();
+        let filter = TreeFilter::parse(&self.filter_query);
+        match &self.root_node {
+            Some(root) if !filter.is_empty() => {
+                let (ids, match_count) = filter.visible_ids(root);
+                if self.filter_saved_expansion.is_none() {
+                    self.filter_saved_expansion = Some(self.expanded_ids.clone());
+                }
+                self.expanded_ids.extend(ids.iter().cloned());
+                self.filter_visible_ids = Some(ids);
+                self.filter_match_count = match_count;
+            }
+            _ => {
+                self.filter_visible_ids = None;
+                self.filter_match_count = 0;
+                if let Some(saved) = self.filter_saved_expansion.take() {
+                    self.expanded_ids = saved;
+                }
+            }
+        }
+    }
+
+    pub fn apply_filter(&mut self) {
+        let selected_id = self.get_selected_node().and_then(Self::get_node_id);
+        self.recompute_filter();
+
+        let index = selected_id.and_then(|id| self.get_visible_index_of_id(&id));
+        self.selected_index = index.unwrap_or(0);
+        if index.is_none() {
+            self.tree_scroll_offset = 0;
+        }
+        self.ensure_selection_visible();
+        self.selected_node_details = None;
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.filter_query.clear();
+        self.apply_filter();
+    }
+
     // Helper to find the node at the current selected index based on visible nodes
     pub fn get_selected_node(&self) -> Option<&RemoteDiagnosticsNode> {
-        if let Some(root) = &self.root_node {
+        self.get_node_at_index(self.selected_index)
+    }
+
+    /// The node shown at row `index` of the inspector tree.
+    fn get_node_at_index(&self, index: usize) -> Option<&RemoteDiagnosticsNode> {
+        if let Some(root) = self.shown_root() {
             let mut current_index = 0;
-            return self.find_node_at_index(root, &mut current_index);
+            return self.find_node_at_index(root, index, &mut current_index);
         }
         None
     }
@@ -292,9 +2396,10 @@ impl AppState {
     fn find_node_at_index<'a>(
         &'a self,
         node: &'a RemoteDiagnosticsNode,
+        index: usize,
         current_index: &mut usize,
     ) -> Option<&'a RemoteDiagnosticsNode> {
-        if *current_index == self.selected_index {
+        if *current_index == index {
             return Some(node);
         }
         *current_index += 1;
@@ -302,8 +2407,8 @@ impl AppState {
         if let Some(id) = Self::get_node_id(node) {
             if self.expanded_ids.contains(&id) {
                 if let Some(children) = &node.children {
-                    for child in children {
-                        if let Some(found) = self.find_node_at_index(child, current_index) {
+                    for child in children.iter().filter(|c| self.is_node_shown(c)) {
+                        if let Some(found) = self.find_node_at_index(child, index, current_index) {
                             return Some(found);
                         }
                     }
@@ -316,7 +2421,7 @@ impl AppState {
     // Helper to get parent of currently selected node (for Left arrow navigation)
     // This is expensive to traverse every time, but tree size is likely manageable for now.
     pub fn select_parent(&mut self) {
-        if let Some(root) = &self.root_node {
+        if let Some(root) = self.shown_root() {
             let mut current_index = 0;
             if let Some(parent_index) = self.find_parent_index(root, &mut current_index, None) {
                 self.selected_index = parent_index;
@@ -342,7 +2447,7 @@ impl AppState {
         if let Some(id) = Self::get_node_id(node) {
             if self.expanded_ids.contains(&id) {
                 if let Some(children) = &node.children {
-                    for child in children {
+                    for child in children.iter().filter(|c| self.is_node_shown(c)) {
                         if let Some(found) =
                             self.find_parent_index(child, current_index, Some(my_index))
                         {
@@ -356,7 +2461,7 @@ impl AppState {
     }
 
     pub fn visible_count(&self) -> usize {
-        if let Some(root) = &self.root_node {
+        if let Some(root) = self.shown_root() {
             let mut count = 0;
             self.count_visible(root, &mut count);
             count
@@ -370,7 +2475,7 @@ impl AppState {
         if let Some(id) = Self::get_node_id(node) {
             if self.expanded_ids.contains(&id) {
                 if let Some(children) = &node.children {
-                    for child in children {
+                    for child in children.iter().filter(|c| self.is_node_shown(c)) {
                         self.count_visible(child, count);
                     }
                 }
@@ -425,15 +2530,292 @@ impl AppState {
         // rendering will handle empty space.
     }
 
-    pub fn scroll_tree_horizontal(&mut self, delta: isize) {
-        let new_offset = self.tree_horizontal_scroll as isize + delta;
-        self.tree_horizontal_scroll = new_offset.max(0) as usize;
+    pub fn scroll_tree_horizontal(&mut self, delta: isize) {
+        let new_offset = self.tree_horizontal_scroll as isize + delta;
+        self.tree_horizontal_scroll = new_offset.max(0) as usize;
+    }
+
+    pub fn add_log(&mut self, entry: LogEntry) {
+        *self
+            .log_counts
+            .entry((entry.source(), entry.level))
+            .or_default() += 1;
+        if self.is_log_shown(&entry) {
+            self.log_view.push(self.logs.len());
+        }
+        self.logs.push(entry);
+        // If auto-scroll is on, we don't strictly need to do anything here
+        // if the UI handles "tailing".
+    }
+
+    fn is_log_shown(&self, entry: &LogEntry) -> bool {
+        self.log_source_filter.matches(entry.source()) && entry.level <= self.log_level_filter
+    }
+
+    /// The entry at `position` of the Logs pane.
+    pub fn shown_log(&self, position: usize) -> Option<&LogEntry> {
+        self.logs.get(*self.log_view.get(position)?)
+    }
+
+    pub fn shown_logs(&self) -> impl Iterator<Item = &LogEntry> {
+        self.log_view.iter().map(|&index| &self.logs[index])
+    }
+
+    /// Entries of each level from the current source tab, whatever the level filter.
+    pub fn log_level_counts(&self) -> [(log::Level, usize); 4] {
+        [
+            log::Level::Error,
+            log::Level::Warn,
+            log::Level::Info,
+            log::Level::Debug,
+        ]
+        .map(|level| {
+            let count = self
+                .log_counts
+                .iter()
+                .filter(|((source, l), _)| *l == level && self.log_source_filter.matches(*source))
+                .map(|(_, count)| count)
+                .sum();
+            (level, count)
+        })
+    }
+
+    /// Switches to the source tab `delta` tabs on, wrapping around.
+    pub fn cycle_log_source(&mut self, delta: isize) {
+        let tabs = LogSourceFilter::ALL;
+        let current = tabs
+            .iter()
+            .position(|tab| *tab == self.log_source_filter)
+            .unwrap_or(0);
+        let next = (current as isize + delta).rem_euclid(tabs.len() as isize) as usize;
+        self.set_log_filters(tabs[next], self.log_level_filter);
+    }
+
+    /// Shows one level less, from everything down to errors only, then everything again.
+    pub fn cycle_log_level(&mut self) {
+        let current = LOG_LEVEL_FILTERS
+            .iter()
+            .position(|level| *level == self.log_level_filter)
+            .unwrap_or(0);
+        let next = LOG_LEVEL_FILTERS[(current + 1) % LOG_LEVEL_FILTERS.len()];
+        self.set_log_filters(self.log_source_filter, next);
+    }
+
+    /// Rebuilds the shown entries for new filters. The cursor and expanded entries stay on
+    /// the same entries where those are still shown.
+    pub fn set_log_filters(&mut self, source: LogSourceFilter, level: log::LevelFilter) {
+        let entry_at = |position: &usize| self.log_view.get(*position).copied();
+        let selected = self.log_selected.as_ref().and_then(entry_at);
+        let expanded: HashSet<usize> = self.expanded_logs.iter().filter_map(entry_at).collect();
+
+        self.log_source_filter = source;
+        self.log_level_filter = level;
+        self.log_view = (0..self.logs.len())
+            .filter(|&index| self.is_log_shown(&self.logs[index]))
+            .collect();
+
+        let position_of = |index: usize| self.log_view.binary_search(&index).ok();
+        self.log_selected = selected.and_then(position_of);
+        self.expanded_logs = expanded.into_iter().filter_map(position_of).collect();
+        self.log_mark_anchor = None;
+        match self.log_selected {
+            Some(_) => self.move_log_selection(0),
+            None => {
+                self.log_auto_scroll = true;
+                self.log_scroll_state = 0;
+            }
+        }
+    }
+
+    /// Number of lines the entry at `position` takes up in the Logs pane.
+    pub fn log_entry_height(&self, position: usize) -> usize {
+        match self.shown_log(position) {
+            Some(entry) if self.expanded_logs.contains(&position) => {
+                1 + entry.error.is_some() as usize + entry.stack_trace.len()
+            }
+            Some(_) => 1,
+            None => 0,
+        }
+    }
+
+    /// Moves the log cursor, starting from the newest entry, and scrolls to keep it shown.
+    pub fn move_log_selection(&mut self, delta: isize) {
+        if self.log_view.is_empty() {
+            return;
+        }
+        let last = self.log_view.len() - 1;
+        let selected = match self.log_selected {
+            Some(selected) => (selected as isize + delta).clamp(0, last as isize) as usize,
+            None => last,
+        };
+        self.log_selected = Some(selected);
+        // Follow new entries again once the cursor is back at the bottom
+        self.log_auto_scroll = selected == last;
+
+        let height = self.log_view_height.borrow().saturating_sub(2).max(1);
+        if selected < self.log_scroll_state {
+            self.log_scroll_state = selected;
+        }
+        while self.log_scroll_state < selected
+            && (self.log_scroll_state..=selected)
+                .map(|i| self.log_entry_height(i))
+                .sum::<usize>()
+                > height
+        {
+            self.log_scroll_state += 1;
+        }
+    }
+
+    pub fn toggle_selected_log(&mut self) {
+        let Some(selected) = self.log_selected else {
+            return;
+        };
+        if self.shown_log(selected).is_some_and(LogEntry::has_details)
+            && !self.expanded_logs.remove(&selected)
+        {
+            self.expanded_logs.insert(selected);
+        }
+    }
+
+    /// Refresh rate frames are measured against: the configured one, else the one the
+    /// engine reports for the display, else 60Hz.
+    pub fn refresh_rate(&self) -> f64 {
+        self.refresh_rate_override
+            .or(self.display_refresh_rate)
+            .filter(|hz| *hz > 0.0)
+            .unwrap_or(60.0)
+    }
+
+    /// Time a frame may take to build and rasterize without janking, e.g. 8.3ms at 120Hz.
+    pub fn frame_budget(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.refresh_rate())
+    }
+
+    /// Rows taken by the bottom log panel, zero while it is hidden or promoted to a tab.
+    pub fn log_panel_rows(&self) -> u16 {
+        if self.show_logs && !self.logs_only && self.current_tab != Tab::Logs {
+            self.log_panel_height.min(self.max_log_panel_height())
+        } else {
+            0
+        }
+    }
+
+    /// The whole screen below the app bar, once known from a draw.
+    fn max_log_panel_height(&self) -> u16 {
+        match *self.log_panel_limit.borrow() {
+            0 => u16::MAX,
+            limit => limit.max(MIN_LOG_PANEL_HEIGHT),
+        }
+    }
+
+    pub fn resize_log_panel(&mut self, delta: i16) {
+        // Grows from the height shown, which a smaller terminal may have capped
+        let height = self.log_panel_height.min(self.max_log_panel_height());
+        self.log_panel_height = height
+            .saturating_add_signed(delta)
+            .clamp(MIN_LOG_PANEL_HEIGHT, self.max_log_panel_height());
+    }
+
+    /// Switches the log panel to the next of 10 rows, 20 rows, half and all of the screen,
+    /// shown again if hidden.
+    pub fn cycle_log_panel_height(&mut self) {
+        let max = self.max_log_panel_height();
+        let mut heights: Vec<u16> = LOG_PANEL_PRESETS
+            .into_iter()
+            .chain([max / 2, max])
+            .map(|height| height.clamp(MIN_LOG_PANEL_HEIGHT, max))
+            .collect();
+        heights.sort();
+        heights.dedup();
+        let current = self.log_panel_rows();
+        self.log_panel_height = heights
+            .iter()
+            .copied()
+            .find(|height| *height > current)
+            .unwrap_or(heights[0]);
+        self.show_logs = true;
+    }
+
+    /// Whether row `y` is the top border of the log panel, which can be dragged.
+    pub fn is_log_border(&self, y: u16) -> bool {
+        let area = *self.logs_area.borrow();
+        self.log_panel_rows() > 0 && area.height > 0 && y == area.y
+    }
+
+    /// Moves the dragged top border of the log panel to row `y`.
+    pub fn drag_log_border(&mut self, y: u16) {
+        let bottom = self.logs_area.borrow().bottom();
+        self.log_panel_height = bottom
+            .saturating_sub(y)
+            .clamp(MIN_LOG_PANEL_HEIGHT, self.max_log_panel_height());
+    }
+
+    /// The current tab's split of panes side by side, if it has one.
+    fn current_split_mut(&mut self) -> Option<&mut [u16]> {
+        match self.current_tab {
+            _ if self.logs_only => None,
+            Tab::Inspector => Some(&mut self.inspector_split),
+            Tab::Debugger => Some(&mut self.debugger_split),
+            _ => None,
+        }
+    }
+
+    /// Widens the focused pane by `delta` steps (narrows it when negative), or for
+    /// `vertical`, makes the focused panel of the Debugger's right column taller. Returns
+    /// whether the focus is on a pane that resizes that way.
+    pub fn resize_focused_pane(&mut self, delta: i16, vertical: bool) -> bool {
+        let delta = delta * PANE_RESIZE_STEP as i16;
+        if vertical {
+            let pane = match self.focus {
+                Focus::DebuggerBreakpoints => 0,
+                Focus::DebuggerStack => 1,
+                Focus::DebuggerLocals => 2,
+                _ => return false,
+            };
+            resize_pane(&mut self.right_panel_split, pane, delta);
+            return true;
+        }
+        let pane = match self.focus {
+            Focus::Tree | Focus::DebuggerFiles => 0,
+            Focus::Details | Focus::DetailsSearch | Focus::DebuggerSource => 1,
+            Focus::DebuggerBreakpoints | Focus::DebuggerStack | Focus::DebuggerLocals => 2,
+            _ => return false,
+        };
+        match self.current_split_mut() {
+            Some(split) if pane < split.len() => {
+                resize_pane(split, pane, delta);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The border of the current tab's split at the given position, as last drawn. Both
+    /// columns of a border, the right edge of one pane and the left of the next, count.
+    pub fn split_border_at(&self, x: u16, y: u16) -> Option<usize> {
+        let area = *self.split_area.borrow();
+        if y < area.y || y >= area.y + area.height {
+            return None;
+        }
+        self.split_borders
+            .borrow()
+            .iter()
+            .position(|&column| x == column || x + 1 == column)
     }
 
-    pub fn add_log(&mut self, message: String) {
-        self.logs.push(message);
-        // If auto-scroll is on, we don't strictly need to do anything here
-        // if the UI handles "tailing".
+    /// Moves the dragged border of the current tab's split to column `x`.
+    pub fn drag_split_border(&mut self, border: usize, x: u16) {
+        let area = *self.split_area.borrow();
+        if area.width == 0 {
+            return;
+        }
+        // The border follows the mouse onto the next pane's left edge
+        let percent = ((x + 1).saturating_sub(area.x) as u32 * 100 / area.width as u32) as u16;
+        if let Some(split) = self.current_split_mut() {
+            if border + 1 < split.len() {
+                move_split_border(split, border, percent);
+            }
+        }
     }
 
     pub fn scroll_logs(&mut self, delta: isize) {
@@ -443,16 +2825,17 @@ impl AppState {
             self.log_scroll_state = new_scroll.max(0) as usize;
         } else {
             let new_scroll = self.log_scroll_state as isize + delta;
-            self.log_scroll_state = (new_scroll as usize).min(self.logs.len().saturating_sub(1));
+            self.log_scroll_state =
+                (new_scroll as usize).min(self.log_view.len().saturating_sub(1));
 
-            if self.log_scroll_state >= self.logs.len().saturating_sub(1) {
+            if self.log_scroll_state >= self.log_view.len().saturating_sub(1) {
                 self.log_auto_scroll = true;
             }
         }
     }
 
     pub fn get_selected_depth(&self) -> usize {
-        if let Some(root) = &self.root_node {
+        if let Some(root) = self.shown_root() {
             let mut current_index = 0;
             return self
                 .find_depth_at_index(root, &mut current_index, 0)
@@ -475,7 +2858,7 @@ impl AppState {
         if let Some(id) = Self::get_node_id(node) {
             if self.expanded_ids.contains(&id) {
                 if let Some(children) = &node.children {
-                    for child in children {
+                    for child in children.iter().filter(|c| self.is_node_shown(c)) {
                         if let Some(found) =
                             self.find_depth_at_index(child, current_index, depth + 1)
                         {
@@ -522,6 +2905,9 @@ impl AppState {
     }
 
     pub fn cycle_focus(&mut self) {
+        if self.logs_only {
+            return;
+        }
         self.focus = match self.current_tab {
             Tab::Inspector => match self.focus {
                 Focus::Tree => Focus::Details,
@@ -531,11 +2917,28 @@ impl AppState {
             },
             Tab::Debugger => match self.focus {
                 Focus::DebuggerFiles => Focus::DebuggerSource,
-                Focus::DebuggerSource => Focus::DebuggerStack,
-                Focus::DebuggerStack => Focus::Logs,
+                Focus::DebuggerSource => Focus::DebuggerBreakpoints,
+                Focus::DebuggerBreakpoints => Focus::DebuggerStack,
+                Focus::DebuggerStack => Focus::DebuggerLocals,
+                Focus::DebuggerLocals => Focus::Logs,
                 Focus::Logs => Focus::DebuggerFiles,
                 _ => Focus::DebuggerFiles,
             },
+            Tab::Logs => Focus::Logs,
+            Tab::Memory => match self.focus {
+                Focus::Memory => Focus::Logs,
+                _ => Focus::Memory,
+            },
+            Tab::Profiler => Focus::Profiler,
+            Tab::Performance => match self.focus {
+                Focus::Timeline => Focus::Logs,
+                _ => Focus::Timeline,
+            },
+            Tab::Network => match self.focus {
+                Focus::Network => Focus::NetworkDetails,
+                Focus::NetworkDetails => Focus::Logs,
+                _ => Focus::Network,
+            },
         };
     }
 
@@ -563,28 +2966,26 @@ impl AppState {
         let mut nodes: Vec<FileNode> = Vec::new();
         self.file_list.clear();
 
-        for result in walker {
-            if let Ok(entry) = result {
-                let path = entry.path();
-                if path == root_path {
-                    continue;
-                }
+        for entry in walker.flatten() {
+            let path = entry.path();
+            if path == root_path {
+                continue;
+            }
 
-                // Populate file_list
-                if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
-                    if let Ok(p) = path.strip_prefix(&root_path) {
-                        self.file_list.push(p.to_string_lossy().to_string());
-                    }
+            // Populate file_list
+            if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
+                if let Ok(p) = path.strip_prefix(&root_path) {
+                    self.file_list.push(p.to_string_lossy().to_string());
                 }
-
-                // We need to insert this path into our tree
-                Self::insert_path_into_tree(
-                    &mut nodes,
-                    path,
-                    &root_path,
-                    entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false),
-                );
             }
+
+            // We need to insert this path into our tree
+            Self::insert_path_into_tree(
+                &mut nodes,
+                path,
+                &root_path,
+                entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false),
+            );
         }
         self.file_list.sort();
 
@@ -639,7 +3040,7 @@ impl AppState {
 
                     let new_node = FileNode {
                         path: node_path,
-                        name: name,
+                        name,
                         children: Vec::new(),
                         is_dir: node_is_dir,
                     };
@@ -673,10 +3074,6 @@ impl AppState {
         }
     }
 
-    pub fn ensure_debugger_horizontal_visibility(&mut self, _visible_width: usize) {
-        // Placeholder for horizontal scrolling if needed
-    }
-
     pub fn toggle_debugger_expand(&mut self) {
         // We need to clone root to avoid borrow checker issues if we used &self.file_tree directly with &mut self
         // But get_node_at_index takes reference.
@@ -737,53 +3134,898 @@ impl AppState {
         }
     }
 
-    pub fn activate_selected_debugger_node(&mut self) {
-        let (is_dir, id) = if let Some(root) = &self.file_tree {
-            let mut current_index = 0;
-            if let Some(node) = crate::ui::tree::get_node_at_index(
-                root,
-                &self.debugger_expanded_ids,
-                self.debugger_selected_index,
-                &mut current_index,
-            ) {
-                (
-                    node.is_dir,
-                    crate::ui::tree::Treeable::id(node).map(|s| s.to_string()),
-                )
-            } else {
-                (false, None)
-            }
-        } else {
-            (false, None)
+    pub fn activate_selected_debugger_node(&mut self) {
+        let (is_dir, id) = if let Some(root) = &self.file_tree {
+            let mut current_index = 0;
+            if let Some(node) = crate::ui::tree::get_node_at_index(
+                root,
+                &self.debugger_expanded_ids,
+                self.debugger_selected_index,
+                &mut current_index,
+            ) {
+                (
+                    node.is_dir,
+                    crate::ui::tree::Treeable::id(node).map(|s| s.to_string()),
+                )
+            } else {
+                (false, None)
+            }
+        } else {
+            (false, None)
+        };
+
+        if is_dir {
+            if let Some(id) = id {
+                if self.debugger_expanded_ids.contains(&id) {
+                    self.debugger_expanded_ids.remove(&id);
+                } else {
+                    self.debugger_expanded_ids.insert(id);
+                }
+            }
+        } else {
+            self.open_selected_debugger_file();
+        }
+    }
+
+    pub fn set_debug_state(&mut self, state: DebugState, stack: Option<crate::vm_service::Stack>) {
+        match &state {
+            DebugState::Paused { isolate_id, .. } => {
+                self.paused_isolates
+                    .insert(isolate_id.clone(), (state.clone(), stack.clone()));
+            }
+            DebugState::Running => self.paused_isolates.clear(),
+        }
+        if let Some(stack) = stack {
+            self.stack_trace = Some(stack);
+            self.select_frame(0);
+        }
+        if state == DebugState::Running {
+            self.locals_root = None;
+            self.execution_line = None;
+            self.pause_requested = false;
+        }
+        self.debug_state = state;
+    }
+
+    /// Id of the isolate the Debugger shows paused.
+    pub fn debugged_isolate_id(&self) -> Option<&str> {
+        match &self.debug_state {
+            DebugState::Paused { isolate_id, .. } => Some(isolate_id),
+            DebugState::Running => None,
+        }
+    }
+
+    /// Shows the pause of `isolate_id` in the Debugger, if it is paused. Returns whether it was.
+    pub fn show_paused_isolate(&mut self, isolate_id: &str) -> bool {
+        if self.debugged_isolate_id() == Some(isolate_id) {
+            return true;
+        }
+        let Some((state, stack)) = self.paused_isolates.get(isolate_id).cloned() else {
+            return false;
+        };
+        self.set_debug_state(state, stack);
+        true
+    }
+
+    /// Another isolate may still be paused; the Debugger moves on to it rather than
+    /// claiming the app runs.
+    pub fn isolate_resumed(&mut self, isolate_id: &str) {
+        self.paused_isolates.remove(isolate_id);
+        if self.debugged_isolate_id() != Some(isolate_id) {
+            return;
+        }
+        let next = self.paused_isolates.keys().next().cloned();
+        match next {
+            Some(next) => {
+                self.show_paused_isolate(&next);
+            }
+            None => self.set_debug_state(DebugState::Running, None),
+        }
+    }
+
+    pub fn isolate_started(&mut self, isolate: crate::vm_service::IsolateRef) {
+        if !self.available_isolates.iter().any(|i| i.id == isolate.id) {
+            self.available_isolates.push(isolate);
+        }
+    }
+
+    /// Forgets an isolate that went away, keeping the selection on the same isolate.
+    pub fn isolate_exited(&mut self, isolate_id: &str) {
+        self.isolate_resumed(isolate_id);
+        for vm_ids in self.breakpoint_vm_ids.values_mut() {
+            vm_ids.remove(isolate_id);
+        }
+        let selected = self
+            .available_isolates
+            .get(self.selected_isolate_index)
+            .map(|isolate| isolate.id.clone());
+        self.available_isolates
+            .retain(|isolate| isolate.id != isolate_id);
+        self.selected_isolate_index = selected
+            .and_then(|id| self.available_isolates.iter().position(|i| i.id == id))
+            .unwrap_or(0);
+    }
+
+    /// Name of an isolate for display, its id while unknown.
+    pub fn isolate_name<'a>(&'a self, isolate_id: &'a str) -> &'a str {
+        self.available_isolates
+            .iter()
+            .find(|isolate| isolate.id == isolate_id)
+            .map_or(isolate_id, |isolate| {
+                self.isolate_label(&isolate.id, &isolate.name)
+            })
+    }
+
+    /// The label given to an isolate, or else its name.
+    pub fn isolate_label<'a>(&'a self, isolate_id: &str, name: &'a str) -> &'a str {
+        self.isolate_labels
+            .get(&self.isolate_label_key(isolate_id, name))
+            .map_or(name, String::as_str)
+    }
+
+    /// Key of an isolate in `isolate_labels`: its name, numbered from the second isolate of
+    /// that name on so each gets its own label.
+    pub fn isolate_label_key(&self, isolate_id: &str, name: &str) -> String {
+        let Some(position) = self
+            .available_isolates
+            .iter()
+            .position(|isolate| isolate.id == isolate_id)
+        else {
+            return name.to_string();
+        };
+        match self.available_isolates[..position]
+            .iter()
+            .filter(|isolate| isolate.name == name)
+            .count()
+        {
+            0 => name.to_string(),
+            before => format!("{}#{}", name, before + 1),
+        }
+    }
+
+    /// Starts editing the label of the isolate selected in the isolate popup.
+    pub fn edit_isolate_label(&mut self) {
+        let Some(isolate) = self.available_isolates.get(self.selected_isolate_index) else {
+            return;
+        };
+        self.isolate_label_input = self
+            .isolate_labels
+            .get(&self.isolate_label_key(&isolate.id, &isolate.name))
+            .cloned()
+            .unwrap_or_default();
+        self.focus = Focus::IsolateLabelInput;
+    }
+
+    /// Labels the selected isolate with the edited label, or removes its label when it was
+    /// cleared.
+    pub fn apply_isolate_label(&mut self) {
+        self.focus = Focus::IsolateSelection;
+        let label = std::mem::take(&mut self.isolate_label_input);
+        let Some(isolate) = self.available_isolates.get(self.selected_isolate_index) else {
+            return;
+        };
+        let key = self.isolate_label_key(&isolate.id, &isolate.name);
+        let label = label.trim();
+        if label.is_empty() {
+            self.isolate_labels.remove(&key);
+        } else {
+            self.isolate_labels.insert(key, label.to_string());
+        }
+    }
+
+    /// Rebuild spark-lines for the widget tree, keyed by node id.
+    pub fn rebuild_sparklines(&self) -> Option<HashMap<String, String>> {
+        if !self.rebuild_tracking {
+            return None;
+        }
+        let mut sparklines = HashMap::new();
+        if let Some(root) = &self.root_node {
+            self.collect_sparklines(root, &mut sparklines);
+        }
+        Some(sparklines)
+    }
+
+    fn collect_sparklines(
+        &self,
+        node: &RemoteDiagnosticsNode,
+        sparklines: &mut HashMap<String, String>,
+    ) {
+        if let (Some(location), Some(id)) = (&node.creation_location, Self::get_node_id(node)) {
+            let key = crate::rebuilds::location_key(&location.file, location.line, location.column);
+            let recent = if crate::ui::accessible() {
+                self.rebuilds
+                    .recent_rebuilds(&key)
+                    .map(|count| format!("{} rebuilds", count))
+            } else {
+                self.rebuilds.sparkline(&key)
+            };
+            // The total stays as a badge once the location went quiet
+            let mut annotation = match (recent, self.rebuilds.total_rebuilds(&key)) {
+                (Some(recent), Some(total)) => Some(format!("{} ({} total)", recent, total)),
+                (None, Some(total)) => Some(format!("{} total", total)),
+                (recent, None) => recent,
+            };
+            if let Some(paints) = self.repaints.total_rebuilds(&key) {
+                let paints = format!("{} paints", paints);
+                annotation = Some(match annotation {
+                    Some(rebuilds) => format!("{}, {}", rebuilds, paints),
+                    None => paints,
+                });
+            }
+            if let Some(annotation) = annotation {
+                sparklines.insert(id, annotation);
+            }
+        }
+        for child in node.children.iter().flatten() {
+            self.collect_sparklines(child, sparklines);
+        }
+    }
+
+    /// Rows of the Rebuild Stats popup, with the widget created at each location when it
+    /// is in the tree, in the chosen order.
+    pub fn rebuild_stats(&self) -> Vec<crate::rebuilds::RebuildStat> {
+        let mut widgets = HashMap::new();
+        if let Some(root) = &self.root_node {
+            Self::collect_locations(root, &mut widgets);
+        }
+        let mut stats = self.rebuilds.stats();
+        for paint in self.repaints.stats() {
+            match stats.iter_mut().find(|stat| stat.key == paint.key) {
+                Some(stat) => stat.paints = paint.total,
+                None => stats.push(crate::rebuilds::RebuildStat {
+                    total: 0,
+                    recent: 0,
+                    paints: paint.total,
+                    ..paint
+                }),
+            }
+        }
+        for stat in &mut stats {
+            if let Some((widget, id)) = widgets.get(&stat.key) {
+                stat.widget = Some(widget.clone());
+                stat.node_id = Some(id.clone());
+            }
+        }
+        match self.rebuild_stats_sort {
+            RebuildStatsSort::Total => stats.sort_by_key(|stat| std::cmp::Reverse(stat.total)),
+            RebuildStatsSort::Recent => {
+                stats.sort_by(|a, b| b.recent.cmp(&a.recent).then(b.total.cmp(&a.total)))
+            }
+            RebuildStatsSort::Paints => stats.sort_by_key(|stat| std::cmp::Reverse(stat.paints)),
+            RebuildStatsSort::Location => stats.sort_by(|a, b| a.key.cmp(&b.key)),
+        }
+        stats
+    }
+
+    /// Location key -> (widget type, node id) of the first node created at each location.
+    fn collect_locations(
+        node: &RemoteDiagnosticsNode,
+        locations: &mut HashMap<String, (String, String)>,
+    ) {
+        if let (Some(location), Some(id)) = (&node.creation_location, Self::get_node_id(node)) {
+            let key = crate::rebuilds::location_key(&location.file, location.line, location.column);
+            let widget = node
+                .widget_runtime_type
+                .clone()
+                .or_else(|| node.description.clone())
+                .unwrap_or_default();
+            locations.entry(key).or_insert((widget, id));
+        }
+        for child in node.children.iter().flatten() {
+            Self::collect_locations(child, locations);
+        }
+    }
+
+    /// Selects the tree node of the chosen Rebuild Stats row and closes the popup.
+    pub fn jump_to_rebuild_stat(&mut self) {
+        let stats = self.rebuild_stats();
+        let Some(stat) = stats.get(self.rebuild_stats_selected_index) else {
+            return;
+        };
+        match &stat.node_id {
+            Some(id) => {
+                let id = id.clone();
+                self.show_rebuild_stats = false;
+                self.current_tab = Tab::Inspector;
+                self.focus = Focus::Tree;
+                self.select_node_by_id(&id);
+            }
+            None => log::info!("{} is not in the widget tree", stat.key),
+        }
+    }
+
+    /// Takes the scripts of an isolate as the baseline of the next reload. Scripts of the
+    /// isolate the baseline already belongs to arrive after a reload, and tell its scope.
+    pub fn set_scripts(&mut self, isolate_id: String, scripts: Vec<crate::vm_service::ScriptRef>) {
+        let snapshot = ScriptSnapshot::new(isolate_id, scripts);
+        if let Some(before) = &self.script_snapshot {
+            if before.isolate_id == snapshot.isolate_id {
+                let time = self
+                    .last_reload
+                    .as_ref()
+                    .map(|(time, _)| time.clone())
+                    .unwrap_or_default();
+                let scope = ReloadScope::between(before, &snapshot, time);
+                match scope.library_count() {
+                    0 => log::warn!("Hot reload changed no libraries"),
+                    count => log::info!(
+                        "Hot reload changed {} libraries in {}",
+                        count,
+                        scope
+                            .packages
+                            .keys()
+                            .cloned()
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                }
+                self.reload_scope = Some(scope);
+                self.reload_scope_scroll = 0;
+            }
+        }
+        self.script_snapshot = Some(snapshot);
+    }
+
+    /// Replaces the layout warnings found in an earlier fetch of the details of `id` with
+    /// those of `details`. Warnings another node's details already reported stay once.
+    pub fn scan_layout_warnings(&mut self, id: &str, details: &RemoteDiagnosticsNode) {
+        self.layout_warnings
+            .retain(|warning| warning.source_id != id);
+        for warning in crate::layout_warnings::scan(id, details) {
+            let known = self
+                .layout_warnings
+                .iter()
+                .any(|known| known.node_id == warning.node_id && known.message == warning.message);
+            if !known {
+                self.layout_warnings.push(warning);
+            }
+        }
+    }
+
+    /// Selects the tree node of the chosen layout warning and closes the popup.
+    pub fn jump_to_layout_warning(&mut self) {
+        let Some(warning) = self
+            .layout_warnings
+            .get(self.layout_warnings_selected_index)
+            .cloned()
+        else {
+            return;
+        };
+        self.show_layout_warnings = false;
+        self.current_tab = Tab::Inspector;
+        self.focus = Focus::Tree;
+        // Render objects and nodes the summary tree leaves out fall back to where they were found
+        if self.select_node_by_id(&warning.node_id) || self.select_node_by_id(&warning.source_id) {
+            self.request_details();
+        } else {
+            log::info!("{} is not in the widget tree", warning.widget);
+        }
+    }
+
+    /// Stores a memory sample of every isolate, keeping each one's previous sample so
+    /// growth shows, in the order of `available_isolates`.
+    pub fn set_isolate_memory(&mut self, samples: Vec<(String, MemoryUsage)>) {
+        let Some(memory) = &mut self.memory else {
+            return;
+        };
+        let previous = std::mem::take(&mut memory.isolates);
+        memory.isolates = samples
+            .into_iter()
+            .filter_map(|(id, usage)| {
+                let name = self
+                    .available_isolates
+                    .iter()
+                    .find(|isolate| isolate.id == id)?
+                    .name
+                    .clone();
+                let previous = previous
+                    .iter()
+                    .find(|memory| memory.isolate_id == id)
+                    .map(|memory| memory.usage);
+                Some(IsolateMemory {
+                    isolate_id: id,
+                    name,
+                    usage,
+                    previous,
+                })
+            })
+            .collect();
+
+        let inspected = self.inspected_isolate.as_ref().and_then(|inspected| {
+            memory
+                .isolates
+                .iter()
+                .find(|sample| sample.isolate_id == inspected.id)
+        });
+        if let Some(sample) = inspected {
+            memory.heap_history.push_back(sample.usage.heap_usage);
+            while memory.heap_history.len() > MAX_HEAP_HISTORY {
+                memory.heap_history.pop_front();
+            }
+        }
+    }
+
+    /// Sets up the state of the shown tab the first time it is opened, so tabs that are
+    /// never looked at cost nothing. Returns whether it just did.
+    pub fn init_current_tab(&mut self) -> bool {
+        match self.current_tab {
+            Tab::Debugger if self.file_tree.is_none() => self.build_file_tree(),
+            Tab::Memory if self.memory.is_none() => self.memory = Some(MemoryTabState::default()),
+            Tab::Profiler if self.profiler.is_none() => {
+                self.profiler = Some(ProfilerTabState::default())
+            }
+            Tab::Performance if self.performance.is_none() => {
+                self.performance = Some(PerformanceTabState::default())
+            }
+            Tab::Network if self.network.is_none() => {
+                self.network = Some(NetworkTabState::default())
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Starts taking leak hunting snapshots from scratch, or stops taking them.
+    pub fn toggle_snapshots(&mut self, interval: Duration) {
+        if self.snapshot_interval.take().is_none() {
+            self.snapshot_interval = Some(interval);
+            self.snapshots.clear();
+        }
+    }
+
+    /// Selects where instances of the fastest growing widget type pile up: the node with
+    /// the most children of that type, or else its first instance.
+    pub fn jump_to_leak_suspect(&mut self) {
+        let Some(widget) = self
+            .snapshots
+            .leak_suspects()
+            .and_then(|suspects| suspects.into_iter().next())
+            .map(|(widget, _)| widget)
+        else {
+            log::info!("No growing widget type to jump to");
+            return;
+        };
+        let Some(root) = &self.root_node else {
+            return;
+        };
+        let is_suspect = |node: &RemoteDiagnosticsNode| {
+            node.widget_runtime_type
+                .as_deref()
+                .or(node.description.as_deref())
+                == Some(widget.as_str())
+        };
+        let mut best: Option<(usize, String)> = None;
+        let mut first: Option<String> = None;
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            let children = node.children.as_deref().unwrap_or_default();
+            let count = children.iter().filter(|child| is_suspect(child)).count();
+            if count > best.as_ref().map_or(1, |(best, _)| *best) {
+                best = Self::get_node_id(node).map(|id| (count, id));
+            }
+            if first.is_none() && is_suspect(node) {
+                first = Self::get_node_id(node);
+            }
+            stack.extend(children.iter().rev());
+        }
+        match best.map(|(_, id)| id).or(first) {
+            Some(id) => {
+                self.current_tab = Tab::Inspector;
+                self.focus = Focus::Tree;
+                self.select_node_by_id(&id);
+            }
+            None => log::info!("No {} in the widget tree", widget),
+        }
+    }
+
+    pub fn failed_subsystems(&self) -> Vec<&'static str> {
+        self.subsystems
+            .iter()
+            .filter(|(_, status)| matches!(status, TaskStatus::Failed(_)))
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        matches!(self.debug_state, DebugState::Paused { .. })
+    }
+
+    /// An isolate waits at its start for breakpoints to be set, as with `--start-paused`.
+    pub fn paused_at_start(&self) -> bool {
+        !self.isolates_paused_at_start().is_empty()
+    }
+
+    /// The isolates that wait at their start, sorted by id.
+    pub fn isolates_paused_at_start(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .paused_isolates
+            .iter()
+            .filter(|(_, (state, _))| {
+                matches!(state, DebugState::Paused { reason, .. } if reason == "PauseStart")
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    pub fn pause_control(&self) -> PauseControl {
+        if self.paused_at_start() {
+            PauseControl::Start
+        } else if self.is_paused() {
+            PauseControl::Resume
+        } else {
+            PauseControl::Pause
+        }
+    }
+
+    pub fn stack_frames(&self) -> &[crate::vm_service::Frame] {
+        self.stack_trace
+            .as_ref()
+            .map(|s| s.frames.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn move_stack_selection(&mut self, delta: isize) {
+        let count = self.stack_frames().len();
+        if count == 0 {
+            return;
+        }
+        self.stack_selected_index =
+            (self.stack_selected_index as isize + delta).clamp(0, count as isize - 1) as usize;
+    }
+
+    /// Makes `index` the active frame: the Locals pane is rebuilt from its variables.
+    pub fn select_frame(&mut self, index: usize) {
+        self.stack_selected_index = index;
+        self.locals_root = self
+            .stack_frames()
+            .get(index)
+            .map(|frame| VariableNode::from_frame(frame, index));
+        self.locals_expanded_ids.clear();
+        if let Some(root) = &self.locals_root {
+            self.locals_expanded_ids.insert(root.key.clone());
+        }
+        self.locals_selected_index = 0;
+        self.locals_scroll_offset = 0;
+    }
+
+    /// Opens the file behind a resolved `file://` URI and centers `line` (1-based) in the
+    /// source pane, marking it as the execution line.
+    pub fn show_source_location(&mut self, file_uri: &str, line: usize) {
+        if let Some(path) = self.reveal_source_location(file_uri, line) {
+            self.execution_line = Some((path, line));
+        }
+    }
+
+    /// Switches to the Debugger with the source cursor on the line the selected widget
+    /// was created at. Creation locations need `--track-widget-creation`, the default for
+    /// debug builds.
+    pub fn open_widget_source(&mut self) {
+        let Some(location) = self
+            .get_selected_node()
+            .and_then(|node| node.creation_location.clone())
+        else {
+            log::warn!("Selected widget has no creation location");
+            return;
+        };
+        if self
+            .reveal_source_location(&location.file, location.line as usize)
+            .is_none()
+        {
+            log::warn!("Could not open {}", location.file);
+            return;
+        }
+        self.current_tab = Tab::Debugger;
+        self.focus = Focus::DebuggerSource;
+    }
+
+    /// Selects the widget created closest above `line` of `file`, e.g. the one under an
+    /// editor's cursor.
+    pub fn select_widget_at(&mut self, file: &std::path::Path, line: u32) -> bool {
+        let Some(root) = &self.root_node else {
+            return false;
+        };
+        let mut best: Option<(u32, String)> = None;
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            if let (Some(location), Some(id)) = (&node.creation_location, Self::get_node_id(node)) {
+                let created_in = std::path::Path::new(location.file.trim_start_matches("file://"));
+                if created_in == file
+                    && location.line <= line
+                    && best.as_ref().is_none_or(|(best, _)| location.line > *best)
+                {
+                    best = Some((location.line, id));
+                }
+            }
+            stack.extend(node.children.iter().flatten().rev());
+        }
+        match best {
+            Some((_, id)) if self.select_node_by_id(&id) => {
+                self.request_details();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Shows a project file in the Debugger with the cursor on `line` (1-based).
+    pub fn open_source_file(&mut self, path: &str, line: usize) {
+        if self.reveal_source_location(path, line).is_some() {
+            self.current_tab = Tab::Debugger;
+            self.focus = Focus::DebuggerSource;
+        }
+    }
+
+    /// Opens the file behind a `file://` URI with `line` centered, returning its path
+    /// relative to the project.
+    fn reveal_source_location(&mut self, file_uri: &str, line: usize) -> Option<String> {
+        let absolute = std::path::PathBuf::from(file_uri.trim_start_matches("file://"));
+        let path = absolute
+            .strip_prefix(&self.project_root)
+            .unwrap_or(&absolute)
+            .to_string_lossy()
+            .to_string();
+
+        if self.open_file_path.as_deref() != Some(path.as_str()) {
+            self.open_file(&path);
+        }
+        if self.open_file_path.as_deref() != Some(path.as_str()) {
+            return None;
+        }
+
+        self.reveal_source_line(line);
+        Some(path)
+    }
+
+    /// Moves the source cursor by `delta` lines, scrolling to keep it shown.
+    pub fn move_source_selection(&mut self, delta: isize) {
+        let (Some(current), Some(content)) = (self.source_selected_line, &self.open_file_content)
+        else {
+            return;
+        };
+        let line = current
+            .saturating_add_signed(delta)
+            .min(content.len().saturating_sub(1));
+        self.source_selected_line = Some(line);
+        let inner_height = self.debugger_source_area.borrow().height.saturating_sub(2) as usize;
+        if line < self.source_scroll_offset {
+            self.source_scroll_offset = line;
+        } else if inner_height > 0 && line >= self.source_scroll_offset + inner_height {
+            self.source_scroll_offset = line + 1 - inner_height;
+        }
+    }
+
+    /// Moves the source cursor to the 1-based `line` of the open file and centers it.
+    fn reveal_source_line(&mut self, line: usize) {
+        let line_idx = line.saturating_sub(1);
+        let visible_height = self.debugger_source_area.borrow().height.saturating_sub(2) as usize;
+        self.source_selected_line = Some(line_idx);
+        self.source_scroll_offset = line_idx.saturating_sub(visible_height / 2);
+    }
+
+    fn selected_local(&self) -> Option<&VariableNode> {
+        let root = self.locals_root.as_ref()?;
+        let mut current_index = 0;
+        crate::ui::tree::get_node_at_index(
+            root,
+            &self.locals_expanded_ids,
+            self.locals_selected_index,
+            &mut current_index,
+        )
+    }
+
+    pub fn move_locals_selection(&mut self, delta: isize) {
+        if let Some(root) = &self.locals_root {
+            let count = crate::ui::tree::count_visible_nodes(root, &self.locals_expanded_ids);
+            if count == 0 {
+                return;
+            }
+            let new_index = (self.locals_selected_index as isize + delta)
+                .max(0)
+                .min(count as isize - 1);
+            self.locals_selected_index = new_index as usize;
+
+            let visible_height = self.locals_tree_height.borrow().saturating_sub(2);
+            if self.locals_selected_index < self.locals_scroll_offset {
+                self.locals_scroll_offset = self.locals_selected_index;
+            } else if visible_height > 0
+                && self.locals_selected_index >= self.locals_scroll_offset + visible_height
+            {
+                self.locals_scroll_offset = self.locals_selected_index - visible_height + 1;
+            }
+        }
+    }
+
+    /// Expands the selected local. Returns `(key, object_id)` when its fields still have
+    /// to be fetched from the VM.
+    pub fn expand_selected_local(&mut self) -> Option<(String, String)> {
+        let node = self.selected_local()?;
+        if !node.expandable {
+            return None;
+        }
+        let key = node.key.clone();
+        let pending = match (&node.children, &node.object_id) {
+            (None, Some(object_id)) => Some((key.clone(), object_id.clone())),
+            _ => None,
         };
+        self.locals_expanded_ids.insert(key);
+        pending
+    }
 
-        if is_dir {
-            if let Some(id) = id {
-                if self.debugger_expanded_ids.contains(&id) {
-                    self.debugger_expanded_ids.remove(&id);
-                } else {
-                    self.debugger_expanded_ids.insert(id);
-                }
-            }
+    pub fn toggle_selected_local(&mut self) -> Option<(String, String)> {
+        let key = self.selected_local()?.key.clone();
+        if self.locals_expanded_ids.remove(&key) {
+            None
         } else {
-            self.open_selected_debugger_file();
+            self.expand_selected_local()
         }
     }
 
-    pub fn toggle_breakpoint(&mut self) {
-        if let Some(path) = &self.open_file_path {
-            if let Some(line_idx) = self.source_selected_line {
-                let line = line_idx + 1;
-                let key = format!("{}:{}", path, line);
-                if self.breakpoints.contains(&key) {
-                    self.breakpoints.remove(&key);
-                    // TODO: Send remove breakpoint request to VM
-                } else {
-                    self.breakpoints.insert(key);
-                    // TODO: Send add breakpoint request to VM
+    pub fn collapse_selected_local(&mut self) {
+        if let Some(key) = self.selected_local().map(|n| n.key.clone()) {
+            self.locals_expanded_ids.remove(&key);
+        }
+    }
+
+    pub fn set_local_children(&mut self, key: &str, children: Vec<VariableNode>) {
+        if let Some(node) = self.locals_root.as_mut().and_then(|r| r.find_mut(key)) {
+            node.children = Some(children);
+        }
+    }
+
+    /// The breakpoint key ("path:line") and 1-based line of the source cursor.
+    pub fn selected_breakpoint_key(&self) -> Option<(String, usize)> {
+        let path = self.open_file_path.as_ref()?;
+        let line = self.source_selected_line? + 1;
+        Some((format!("{}:{}", path, line), line))
+    }
+
+    /// The breakpoint key and line of the innermost frame in this project of the selected
+    /// log entry's stack trace.
+    pub fn selected_log_breakpoint_key(&self) -> Option<(String, usize)> {
+        let entry = self.shown_log(self.log_selected?)?;
+        let package = self
+            .app_info
+            .package_name
+            .as_ref()
+            .map(|name| format!("package:{}/", name));
+        let root = format!("file://{}/", self.project_root.to_string_lossy());
+        entry.frame_locations().find_map(|(uri, line)| {
+            let path = match &package {
+                Some(package) if uri.starts_with(package.as_str()) => {
+                    format!("lib/{}", &uri[package.len()..])
                 }
+                _ => uri.strip_prefix(&root)?.to_string(),
+            };
+            Some((format!("{}:{}", path, line), line))
+        })
+    }
+
+    /// Maps a VM breakpoint id, of any isolate, back to its "path:line" key.
+    pub fn breakpoint_key_for_vm_id(&self, vm_id: &str) -> Option<&str> {
+        self.breakpoint_vm_ids
+            .iter()
+            .find(|(_, ids)| ids.values().any(|id| id == vm_id))
+            .map(|(key, _)| key.as_str())
+    }
+
+    /// Forgets a breakpoint and everything attached to it, returning its VM id per isolate
+    /// it was registered with.
+    pub fn remove_breakpoint(&mut self, key: &str) -> HashMap<String, String> {
+        self.breakpoints.remove(key);
+        self.logpoints.remove(key);
+        self.breakpoint_conditions.remove(key);
+        self.disabled_breakpoints.remove(key);
+        self.breakpoints_selected_index = self
+            .breakpoints_selected_index
+            .min(self.breakpoints.len().saturating_sub(1));
+        self.breakpoint_vm_ids.remove(key).unwrap_or_default()
+    }
+
+    /// Breakpoint keys in the order the Breakpoints pane lists them (by file, then line).
+    pub fn sorted_breakpoints(&self) -> Vec<&String> {
+        let mut keys: Vec<&String> = self.breakpoints.iter().collect();
+        keys.sort_by_key(|key| match key.rsplit_once(':') {
+            Some((file, line)) => (file, line.parse::<usize>().unwrap_or(0)),
+            None => (key.as_str(), 0),
+        });
+        keys
+    }
+
+    pub fn selected_listed_breakpoint(&self) -> Option<String> {
+        self.sorted_breakpoints()
+            .get(self.breakpoints_selected_index)
+            .map(|key| key.to_string())
+    }
+
+    pub fn move_breakpoint_selection(&mut self, delta: isize) {
+        let count = self.breakpoints.len();
+        if count == 0 {
+            return;
+        }
+        self.breakpoints_selected_index = (self.breakpoints_selected_index as isize + delta)
+            .clamp(0, count as isize - 1) as usize;
+    }
+
+    /// Opens the file of the selected entry in the Breakpoints pane at its line.
+    pub fn open_selected_breakpoint(&mut self) {
+        let Some(key) = self.selected_listed_breakpoint() else {
+            return;
+        };
+        let Some((path, line)) = key.rsplit_once(':') else {
+            return;
+        };
+        let Ok(line) = line.parse() else {
+            return;
+        };
+        if self.open_file_path.as_deref() != Some(path) {
+            self.open_file(path);
+        }
+        if self.open_file_path.as_deref() == Some(path) {
+            self.reveal_source_line(line);
+            self.focus = Focus::DebuggerSource;
+        }
+    }
+
+    /// Keys and 1-based lines of the breakpoints that should be registered with the VM.
+    pub fn enabled_breakpoints(&self) -> Vec<(String, usize)> {
+        self.breakpoints
+            .iter()
+            .filter(|key| !self.disabled_breakpoints.contains(*key))
+            .filter_map(|key| {
+                let (_, line) = key.rsplit_once(':')?;
+                Some((key.clone(), line.parse().ok()?))
+            })
+            .collect()
+    }
+
+    pub fn breakpoint_entries(&self) -> Vec<BreakpointEntry> {
+        let mut entries: Vec<BreakpointEntry> = self
+            .breakpoints
+            .iter()
+            .filter_map(|key| {
+                let (file, line) = key.rsplit_once(':')?;
+                Some(BreakpointEntry {
+                    file: file.to_string(),
+                    line: line.parse().ok()?,
+                    enabled: !self.disabled_breakpoints.contains(key),
+                    condition: self.breakpoint_conditions.get(key).cloned(),
+                    log_message: self.logpoints.get(key).cloned(),
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+        entries
+    }
+
+    pub fn restore_breakpoints(&mut self, entries: Vec<BreakpointEntry>) {
+        for entry in entries {
+            let key = format!("{}:{}", entry.file, entry.line);
+            if !entry.enabled {
+                self.disabled_breakpoints.insert(key.clone());
+            }
+            if let Some(condition) = entry.condition {
+                self.breakpoint_conditions.insert(key.clone(), condition);
             }
+            if let Some(message) = entry.log_message {
+                self.logpoints.insert(key.clone(), message);
+            }
+            self.breakpoints.insert(key);
+        }
+    }
+
+    /// Opens the isolate popup with the inspected isolate highlighted.
+    pub fn open_isolate_selection(&mut self) {
+        if let Some(index) = self.inspected_isolate.as_ref().and_then(|inspected| {
+            self.available_isolates
+                .iter()
+                .position(|isolate| isolate.id == inspected.id)
+        }) {
+            self.selected_isolate_index = index;
         }
+        self.show_isolate_selection = true;
+        self.focus = Focus::IsolateSelection;
     }
 
     pub fn move_isolate_selection(&mut self, delta: isize) {
@@ -809,6 +4051,7 @@ impl AppState {
 
     pub fn perform_search(&mut self) {
         self.search_results.clear();
+        self.search_matches.clear();
         self.current_match_index = 0;
 
         if self.search_query.is_empty() {
@@ -822,6 +4065,7 @@ impl AppState {
         if let Some(root) = &self.root_node {
             Self::search_recursive(root, &matcher, &self.search_query, &mut results);
         }
+        self.search_matches = results.iter().cloned().collect();
         self.search_results = results;
 
         // Auto-focus first match
@@ -866,6 +4110,81 @@ impl AppState {
         }
     }
 
+    /// Asks what to look for in the focused logs or source view.
+    pub fn open_text_search(&mut self) {
+        self.text_search_return_focus = self.focus;
+        self.text_search_input = self.text_search_query.clone();
+        self.focus = Focus::TextSearch;
+    }
+
+    /// Searches for what was typed into the text search, from the cursor on.
+    pub fn apply_text_search(&mut self) {
+        self.focus = self.text_search_return_focus;
+        self.text_search_query = self.text_search_input.trim().to_string();
+        if !self.next_text_match(true) {
+            log::info!("No match for {:?}", self.text_search_query);
+        }
+    }
+
+    /// Moves the cursor of the focused logs or source view to the next (or previous) line
+    /// containing the text search, ignoring case and wrapping around. Returns whether
+    /// there was one.
+    pub fn next_text_match(&mut self, forward: bool) -> bool {
+        let query = self.text_search_query.to_lowercase();
+        if query.is_empty() {
+            return false;
+        }
+        let (lines, cursor): (Vec<&str>, usize) = match self.focus {
+            Focus::Logs => (
+                self.shown_logs()
+                    .map(|entry| entry.message.as_str())
+                    .collect(),
+                self.log_selected.unwrap_or(self.log_view.len()),
+            ),
+            Focus::DebuggerSource => match (&self.open_file_content, self.source_selected_line) {
+                (Some(content), Some(line)) => (content.iter().map(String::as_str).collect(), line),
+                _ => return false,
+            },
+            _ => return false,
+        };
+        let count = lines.len();
+        let found = (1..=count)
+            .map(|step| {
+                if forward {
+                    (cursor + step) % count
+                } else {
+                    (cursor + count - step % count) % count
+                }
+            })
+            .find(|&index| lines[index].to_lowercase().contains(&query));
+        let Some(index) = found else {
+            return false;
+        };
+        let delta = index as isize - cursor as isize;
+        match self.focus {
+            Focus::Logs if self.log_selected.is_none() => {
+                // Without a cursor, the first move puts it on the newest entry
+                self.move_log_selection(0);
+                self.move_log_selection(index as isize - (count - 1) as isize);
+            }
+            Focus::Logs => self.move_log_selection(delta),
+            _ => self.move_source_selection(delta),
+        }
+        true
+    }
+
+    /// Rows inside the borders of the pane in focus, as last drawn, for half-page moves.
+    pub fn focused_pane_height(&self) -> usize {
+        let height = match self.focus {
+            Focus::Tree => *self.inspector_tree_height.borrow(),
+            Focus::DebuggerFiles => *self.debugger_tree_height.borrow(),
+            Focus::DebuggerSource => self.debugger_source_area.borrow().height as usize,
+            Focus::Logs => *self.log_view_height.borrow(),
+            _ => 0,
+        };
+        height.saturating_sub(2)
+    }
+
     pub fn next_match(&mut self) {
         if self.search_results.is_empty() {
             return;
@@ -888,32 +4207,40 @@ impl AppState {
 
     fn jump_to_match(&mut self, match_index: usize) {
         if let Some(id) = self.search_results.get(match_index).cloned() {
-            // 1. Expand path to this node
-            self.expand_path_to_node(&id);
+            self.select_node_by_id(&id);
+        }
+    }
 
-            // 2. Find the new visible index of this node
-            if let Some(index) = self.get_visible_index_of_id(&id) {
-                self.selected_index = index;
+    /// Expands the path to the node with `id`, selects it and scrolls it into the middle of
+    /// the view. Returns false if no such node is shown.
+    pub fn select_node_by_id(&mut self, id: &str) -> bool {
+        // 1. Expand path to this node
+        self.expand_path_to_node(id);
 
-                // 3. Scroll to show context
-                self.ensure_selection_visible();
-                // Center it if possible?
-                // ensure_selection_visible just ensures it's in view.
-                // To center, we'd need to set offset = index - height / 2.
-                let height = *self.inspector_tree_height.borrow();
-                if height > 0 {
-                    if index > height / 2 {
-                        self.tree_scroll_offset = index - height / 2;
-                    } else {
-                        self.tree_scroll_offset = 0;
-                    }
-                }
+        // 2. Find the new visible index of this node
+        let Some(index) = self.get_visible_index_of_id(id) else {
+            return false;
+        };
+        self.selected_index = index;
 
-                let depth = self.get_selected_depth();
-                let start_visual_pos = depth * 2;
-                self.tree_horizontal_scroll = start_visual_pos.saturating_sub(6);
+        // 3. Scroll to show context
+        self.ensure_selection_visible();
+        // Center it if possible?
+        // ensure_selection_visible just ensures it's in view.
+        // To center, we'd need to set offset = index - height / 2.
+        let height = *self.inspector_tree_height.borrow();
+        if height > 0 {
+            if index > height / 2 {
+                self.tree_scroll_offset = index - height / 2;
+            } else {
+                self.tree_scroll_offset = 0;
             }
         }
+
+        let depth = self.get_selected_depth();
+        let start_visual_pos = depth * 2;
+        self.tree_horizontal_scroll = start_visual_pos.saturating_sub(6);
+        true
     }
 
     fn expand_path_to_node(&mut self, target_id: &str) {
@@ -925,6 +4252,7 @@ impl AppState {
                 }
             }
         }
+        self.invalidate_tree_rows();
     }
 
     fn find_path_to_node(
@@ -959,7 +4287,7 @@ impl AppState {
     }
 
     fn get_visible_index_of_id(&self, target_id: &str) -> Option<usize> {
-        if let Some(root) = &self.root_node {
+        if let Some(root) = self.shown_root() {
             let mut current_index = 0;
             return self.find_visible_index_recursive(root, target_id, &mut current_index);
         }
@@ -981,7 +4309,7 @@ impl AppState {
 
             if self.expanded_ids.contains(&id) {
                 if let Some(children) = &node.children {
-                    for child in children {
+                    for child in children.iter().filter(|c| self.is_node_shown(c)) {
                         if let Some(found) =
                             self.find_visible_index_recursive(child, target_id, current_index)
                         {
@@ -1125,6 +4453,89 @@ impl AppState {
     }
 }
 
+/// Metadata about the attached app, filled in piecemeal as each source reports back.
+#[derive(Debug, Clone, Default)]
+pub struct AppInfo {
+    pub package_name: Option<String>,
+    pub device: Option<String>,
+    pub build_mode: Option<String>,
+    pub flutter_version: Option<String>,
+    pub engine_revision: Option<String>,
+    pub dart_version: Option<String>,
+    pub vm_target: Option<String>,
+    pub protocol_version: Option<String>,
+}
+
+impl AppInfo {
+    /// Takes every field that is set in `other`.
+    pub fn merge(&mut self, other: AppInfo) {
+        fn take(field: &mut Option<String>, value: Option<String>) {
+            if value.is_some() {
+                *field = value;
+            }
+        }
+        take(&mut self.package_name, other.package_name);
+        take(&mut self.device, other.device);
+        take(&mut self.build_mode, other.build_mode);
+        take(&mut self.flutter_version, other.flutter_version);
+        take(&mut self.engine_revision, other.engine_revision);
+        take(&mut self.dart_version, other.dart_version);
+        take(&mut self.vm_target, other.vm_target);
+        take(&mut self.protocol_version, other.protocol_version);
+    }
+
+    /// Reads the package name from the project's pubspec.yaml.
+    pub fn package_name_from_pubspec(project_root: &std::path::Path) -> Option<String> {
+        let pubspec = std::fs::read_to_string(project_root.join("pubspec.yaml")).ok()?;
+        pubspec.lines().find_map(|line| {
+            line.strip_prefix("name:")
+                .map(|name| name.trim().trim_matches(['"', '\'']).to_string())
+        })
+    }
+}
+
+/// A captured request re-issued from the host, `report` is `None` while it runs.
+#[derive(Debug, Clone)]
+pub struct HttpReplay {
+    pub uri: String,
+    pub report: Option<Result<Vec<DiffLine>, String>>,
+}
+
+/// A snippet run from the command palette, `output` is `None` while it evaluates.
+#[derive(Debug, Clone)]
+pub struct EvalResult {
+    pub name: String,
+    pub expression: String,
+    pub output: Option<Result<String, String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IsolateMemory {
+    pub isolate_id: String,
+    pub name: String,
+    pub usage: MemoryUsage,
+    pub previous: Option<MemoryUsage>,
+}
+
+/// An isolate group of the isolate metrics popup: its shared heap and its isolates.
+#[derive(Debug, Clone)]
+pub struct IsolateGroupMetrics {
+    pub name: String,
+    pub usage: Option<MemoryUsage>,
+    pub isolates: Vec<IsolateMetrics>,
+}
+
+/// What `getIsolate` and `getMemoryUsage` tell about one isolate, `None` where they failed.
+#[derive(Debug, Clone)]
+pub struct IsolateMetrics {
+    pub id: String,
+    pub name: String,
+    pub usage: Option<MemoryUsage>,
+    /// Kind of the last pause event, e.g. `PauseBreakpoint`, or `Resume` while running.
+    pub pause_event: Option<String>,
+    pub live_ports: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct FileNode {
     pub path: std::path::PathBuf,
@@ -1148,15 +4559,413 @@ impl crate::ui::tree::Treeable for FileNode {
 
     fn render(&self, depth: usize, is_expanded: bool) -> String {
         let indent = "  ".repeat(depth);
-        let icon = if self.is_dir {
-            if is_expanded {
-                "▼ 📁 "
-            } else {
-                "▶ 📁 "
+        let icon = crate::ui::expander(self.is_dir, is_expanded);
+        if crate::ui::accessible() {
+            let suffix = if self.is_dir { "/" } else { "" };
+            return format!("{}{}{}{}", indent, icon, self.name, suffix);
+        }
+        let kind = if self.is_dir { "📁 " } else { "📄 " };
+        format!("{}{}{}{}", indent, icon, kind, self.name)
+    }
+}
+
+/// A variable shown in the Locals pane. Object instances are loaded lazily through
+/// `getObject`, so `children` stays `None` until the node is expanded for the first time.
+#[derive(Debug, Clone)]
+pub struct VariableNode {
+    pub key: String, // Unique path, e.g. "frame0.widget.child"
+    pub name: String,
+    pub type_name: String,
+    pub value: String,
+    pub object_id: Option<String>,
+    pub expandable: bool,
+    pub children: Option<Vec<VariableNode>>,
+}
+
+impl VariableNode {
+    // Instance kinds whose value is fully described by `valueAsString`.
+    const PRIMITIVE_KINDS: [&'static str; 6] = ["Null", "Bool", "Double", "Int", "String", "Type"];
+
+    /// Builds the root node for a frame of a `getStack` response, with one child per
+    /// bound variable.
+    pub fn from_frame(frame: &crate::vm_service::Frame, frame_index: usize) -> Self {
+        let key = format!("frame{}", frame_index);
+        let function = frame
+            .function
+            .as_ref()
+            .map_or("<unknown>", |f| f.name.as_str());
+        let children = frame
+            .vars
+            .iter()
+            .map(|var| Self::from_value(&key, &var.name, &var.value))
+            .collect();
+
+        Self {
+            key,
+            name: format!("{}()", function),
+            type_name: String::new(),
+            value: String::new(),
+            object_id: None,
+            expandable: true,
+            children: Some(children),
+        }
+    }
+
+    pub fn from_value(
+        parent_key: &str,
+        name: &str,
+        value: &crate::vm_service::InstanceRefOrSentinel,
+    ) -> Self {
+        use crate::vm_service::InstanceRefOrSentinel;
+        match value {
+            InstanceRefOrSentinel::Instance(instance) => {
+                Self::from_instance_ref(parent_key, name, instance)
+            }
+            InstanceRefOrSentinel::Sentinel(sentinel) => {
+                Self::leaf(parent_key, name, &sentinel.kind, &sentinel.value_as_string)
             }
+            InstanceRefOrSentinel::Other => Self::leaf(parent_key, name, "?", ""),
+        }
+    }
+
+    /// A node that can't be expanded, e.g. a Sentinel or an error placeholder.
+    pub fn leaf(parent_key: &str, name: &str, type_name: &str, value: &str) -> Self {
+        Self {
+            key: format!("{}.{}", parent_key, name),
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            value: value.to_string(),
+            object_id: None,
+            expandable: false,
+            children: None,
+        }
+    }
+
+    fn from_instance_ref(
+        parent_key: &str,
+        name: &str,
+        instance: &crate::vm_service::InstanceRef,
+    ) -> Self {
+        let kind = instance.kind.as_str();
+        let type_name = instance
+            .class
+            .as_ref()
+            .map_or(kind, |c| c.name.as_str())
+            .to_string();
+
+        Self {
+            key: format!("{}.{}", parent_key, name),
+            name: name.to_string(),
+            value: Self::describe_value(instance, &type_name),
+            expandable: instance.id.is_some() && !Self::PRIMITIVE_KINDS.contains(&kind),
+            type_name,
+            object_id: instance.id.clone(),
+            children: None,
+        }
+    }
+
+    fn describe_value(instance: &crate::vm_service::InstanceRef, type_name: &str) -> String {
+        let truncated = instance.value_as_string_is_truncated;
+        match (instance.kind.as_str(), &instance.value_as_string) {
+            ("Null", _) => "null".to_string(),
+            ("String", Some(s)) => format!("\"{}{}\"", s, if truncated { "…" } else { "" }),
+            (_, Some(s)) => s.clone(),
+            _ => match instance.length {
+                Some(length) => format!("{} (length {})", type_name, length),
+                None => type_name.to_string(),
+            },
+        }
+    }
+
+    /// Converts a full object from `getObject` into child nodes: fields for plain
+    /// instances, indexed elements for lists/sets and key/value pairs for maps.
+    pub fn children_from_object(
+        parent_key: &str,
+        object: &crate::vm_service::Instance,
+    ) -> Vec<Self> {
+        use crate::vm_service::InstanceRefOrSentinel;
+        let mut children = Vec::new();
+
+        for field in &object.fields {
+            let name = match &field.name {
+                Some(serde_json::Value::String(s)) => s.clone(),
+                Some(serde_json::Value::Number(n)) => format!("${}", n),
+                _ => field
+                    .decl
+                    .as_ref()
+                    .map_or("?", |d| d.name.as_str())
+                    .to_string(),
+            };
+            children.push(Self::from_value(parent_key, &name, &field.value));
+        }
+
+        for (i, element) in object.elements.iter().enumerate() {
+            children.push(Self::from_value(parent_key, &format!("[{}]", i), element));
+        }
+
+        for association in &object.associations {
+            let key = match &association.key {
+                InstanceRefOrSentinel::Instance(instance) => {
+                    instance.value_as_string.as_deref().unwrap_or("?")
+                }
+                _ => "?",
+            };
+            children.push(Self::from_value(
+                parent_key,
+                &format!("[{}]", key),
+                &association.value,
+            ));
+        }
+
+        children
+    }
+
+    fn find_mut(&mut self, key: &str) -> Option<&mut VariableNode> {
+        if self.key == key {
+            return Some(self);
+        }
+        self.children
+            .as_mut()?
+            .iter_mut()
+            .find_map(|child| child.find_mut(key))
+    }
+}
+
+impl crate::ui::tree::Treeable for VariableNode {
+    fn children(&self) -> Option<&[Self]> {
+        self.children.as_deref()
+    }
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.key)
+    }
+
+    fn render(&self, depth: usize, is_expanded: bool) -> String {
+        let indent = "  ".repeat(depth);
+        let icon = crate::ui::expander(self.expandable, is_expanded);
+
+        if self.type_name.is_empty() {
+            return format!("{}{}{}", indent, icon, self.name);
+        }
+
+        let loading = if is_expanded && self.children.is_none() {
+            " (loading...)"
+        } else {
+            ""
+        };
+        format!(
+            "{}{}{}: {} = {}{}",
+            indent, icon, self.name, self.type_name, self.value, loading
+        )
+    }
+}
+
+/// Key of the render object's layout facts in the Details tree.
+const RENDER_KEY: &str = "render";
+
+/// A property in the Details pane. Properties whose value is itself diagnosable (render
+/// objects, controllers, ...) can be expanded; their nested properties are fetched with
+/// the inspector's getProperties when they weren't part of the details subtree.
+#[derive(Debug, Clone)]
+pub struct PropertyNode {
+    pub key: String,
+    pub name: String,
+    pub description: String,
+    pub object_id: Option<String>,
+    pub is_diagnosable: bool,
+    pub children: Option<Vec<PropertyNode>>,
+}
+
+impl PropertyNode {
+    /// The whole Details pane as one tree: the widget, its identifiers, the layout of its
+    /// render object and then its properties.
+    fn root(details: &RemoteDiagnosticsNode) -> Self {
+        let key = "properties".to_string();
+        let mut children = vec![
+            Self::leaf(
+                "info/description",
+                "Description",
+                details.description.as_deref(),
+            ),
+            Self::leaf("info/object_id", "Object ID", details.object_id.as_deref()),
+            Self::leaf("info/value_id", "Value ID", details.value_id.as_deref()),
+        ];
+        if let Some(level) = &details.level {
+            children.push(Self::leaf("info/level", "Level", Some(level)));
+        }
+        if details.truncated == Some(true) {
+            children.push(Self::leaf("info/truncated", "Truncated", Some("yes")));
+        }
+        if !details.extra.is_empty() {
+            let keys: Vec<&str> = details.extra.keys().map(|k| k.as_str()).collect();
+            children.push(Self::leaf(
+                "info/extra",
+                "Other fields",
+                Some(&format!("{} (J: raw JSON)", keys.join(", "))),
+            ));
+        }
+        // Layout facts first, they are what overflow debugging starts from
+        if let Some((render_object, layout)) = details.render_info() {
+            let mut render = Self::leaf(RENDER_KEY, "Render", Some(render_object));
+            render.children = Some(
+                layout
+                    .into_iter()
+                    .map(|(name, value)| {
+                        Self::leaf(&format!("{}/{}", RENDER_KEY, name), name, Some(value))
+                    })
+                    .collect(),
+            );
+            children.push(render);
+        }
+        children.extend(Self::from_properties(
+            &key,
+            details.properties.as_deref().unwrap_or_default(),
+        ));
+
+        Self {
+            children: Some(children),
+            key,
+            name: details
+                .widget_runtime_type
+                .clone()
+                .unwrap_or_else(|| "Unknown".to_string()),
+            description: String::new(),
+            object_id: None,
+            is_diagnosable: true,
+        }
+    }
+
+    fn leaf(key: &str, name: &str, description: Option<&str>) -> Self {
+        Self {
+            key: key.to_string(),
+            name: name.to_string(),
+            description: description.unwrap_or("-").to_string(),
+            object_id: None,
+            is_diagnosable: false,
+            children: None,
+        }
+    }
+
+    fn from_properties(parent_key: &str, properties: &[RemoteDiagnosticsNode]) -> Vec<Self> {
+        properties
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.name.is_some() || p.description.is_some())
+            .map(|(i, p)| Self::from_diagnostics(&format!("{}/{}", parent_key, i), p))
+            .collect()
+    }
+
+    fn from_diagnostics(key: &str, node: &RemoteDiagnosticsNode) -> Self {
+        let children = node
+            .properties
+            .as_deref()
+            .filter(|props| !props.is_empty())
+            .map(|props| Self::from_properties(key, props));
+
+        let name = if node.show_name == Some(false) {
+            String::new()
+        } else {
+            node.name.clone().unwrap_or_default()
+        };
+        let mut description = node.description.clone().unwrap_or_default();
+        if let Some(level @ ("warning" | "error")) = node.level.as_deref() {
+            description.push_str(&format!(" [{}]", level));
+        }
+
+        Self {
+            key: key.to_string(),
+            name,
+            description,
+            object_id: node.object_id.clone(),
+            is_diagnosable: node.is_diagnosable_value.unwrap_or(false),
+            children,
+        }
+    }
+
+    pub fn is_expandable(&self) -> bool {
+        match &self.children {
+            Some(children) => !children.is_empty(),
+            None => self.is_diagnosable && self.object_id.is_some(),
+        }
+    }
+
+    /// A copy holding only the properties whose name or value contains `query` (lower
+    /// case), with their ancestors. Matches keep all their children so they can still be
+    /// drilled into.
+    fn pruned(
+        &self,
+        query: &str,
+        matches: &mut usize,
+        ancestors: &mut Vec<String>,
+    ) -> Option<PropertyNode> {
+        if self.name.to_lowercase().contains(query)
+            || self.description.to_lowercase().contains(query)
+        {
+            *matches += 1;
+            return Some(self.clone());
+        }
+        let children: Vec<PropertyNode> = self
+            .children
+            .iter()
+            .flatten()
+            .filter_map(|child| child.pruned(query, matches, ancestors))
+            .collect();
+        if children.is_empty() {
+            return None;
+        }
+        ancestors.push(self.key.clone());
+        Some(self.with_children(children))
+    }
+
+    fn with_children(&self, children: Vec<PropertyNode>) -> PropertyNode {
+        PropertyNode {
+            key: self.key.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            object_id: self.object_id.clone(),
+            is_diagnosable: self.is_diagnosable,
+            children: Some(children),
+        }
+    }
+
+    fn find_mut(&mut self, key: &str) -> Option<&mut PropertyNode> {
+        if self.key == key {
+            return Some(self);
+        }
+        self.children
+            .as_mut()?
+            .iter_mut()
+            .find_map(|child| child.find_mut(key))
+    }
+}
+
+impl crate::ui::tree::Treeable for PropertyNode {
+    fn children(&self) -> Option<&[Self]> {
+        self.children.as_deref()
+    }
+
+    fn id(&self) -> Option<&str> {
+        Some(&self.key)
+    }
+
+    fn render(&self, depth: usize, is_expanded: bool) -> String {
+        let indent = "  ".repeat(depth);
+        let icon = crate::ui::expander(self.is_expandable(), is_expanded);
+        let loading = if is_expanded && self.children.is_none() {
+            " (loading...)"
         } else {
-            "  📄 "
+            ""
         };
-        format!("{}{}{}", indent, icon, self.name)
+
+        if self.name.is_empty() {
+            format!("{}{}{}{}", indent, icon, self.description, loading)
+        } else if self.description.is_empty() {
+            format!("{}{}{}{}", indent, icon, self.name, loading)
+        } else {
+            format!(
+                "{}{}{}: {}{}",
+                indent, icon, self.name, self.description, loading
+            )
+        }
     }
 }
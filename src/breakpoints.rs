@@ -0,0 +1,112 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const BREAKPOINTS_DIR: &str = ".flutter_tui";
+const BREAKPOINTS_FILE: &str = "breakpoints.json";
+
+/// A breakpoint as stored in `.flutter_tui/breakpoints.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreakpointEntry {
+    /// Path relative to the project root.
+    pub file: String,
+    /// 1-based line number.
+    pub line: usize,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_message: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+pub fn breakpoints_path(project_root: &Path) -> PathBuf {
+    project_root.join(BREAKPOINTS_DIR).join(BREAKPOINTS_FILE)
+}
+
+pub fn load(project_root: &Path) -> Vec<BreakpointEntry> {
+    let Ok(content) = std::fs::read_to_string(breakpoints_path(project_root)) else {
+        return Vec::new();
+    };
+    match serde_json::from_str(&content) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::error!("Ignoring unreadable breakpoints file: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn save(project_root: &Path, entries: &[BreakpointEntry]) -> Result<()> {
+    let content = serde_json::to_vec_pretty(entries)?;
+    crate::persistence::write_atomic(&breakpoints_path(project_root), &content)
+        .context("Failed to write breakpoints")
+}
+
+/// Parses a `FILE:LINE` breakpoint given on the command line, the file relative to the
+/// project.
+pub fn parse_location(spec: &str) -> std::result::Result<BreakpointEntry, String> {
+    let (file, line) = spec
+        .rsplit_once(':')
+        .ok_or_else(|| format!("expected FILE:LINE, got '{}'", spec))?;
+    let line = line
+        .parse()
+        .ok()
+        .filter(|line| *line > 0)
+        .ok_or_else(|| format!("invalid line number '{}'", line))?;
+    Ok(BreakpointEntry {
+        file: file.trim_start_matches("./").to_string(),
+        line,
+        enabled: true,
+        condition: None,
+        log_message: None,
+    })
+}
+
+/// Turns a logpoint message template into a Dart expression for `evaluateInFrame`.
+///
+/// `{expr}` segments are interpolated, everything else is kept literally, e.g.
+/// `count is {count}` becomes `'count is ${count}'`. Use `{{` / `}}` for literal braces.
+pub fn logpoint_expression(template: &str) -> String {
+    let mut expression = String::from("'");
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                expression.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                expression.push('}');
+            }
+            '{' => {
+                let mut inner = String::new();
+                let mut depth = 0;
+                for c in chars.by_ref() {
+                    match c {
+                        '{' => depth += 1,
+                        '}' if depth == 0 => break,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    inner.push(c);
+                }
+                expression.push_str(&format!("${{{}}}", inner));
+            }
+            '\\' => expression.push_str("\\\\"),
+            '\'' => expression.push_str("\\'"),
+            '$' => expression.push_str("\\$"),
+            '\n' => expression.push_str("\\n"),
+            _ => expression.push(c),
+        }
+    }
+
+    expression.push('\'');
+    expression
+}
@@ -0,0 +1,18 @@
+use anyhow::Result;
+use base64::Engine;
+use std::io::Write;
+
+/// Puts text on the system clipboard with the OSC 52 escape sequence, which the terminal
+/// handles, so copying works over SSH as well.
+pub fn copy(text: &str) -> Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut sequence = format!("\x1b]52;c;{}\x07", encoded);
+    if std::env::var_os("TMUX").is_some() {
+        // tmux only forwards escape sequences wrapped in its passthrough
+        sequence = format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"));
+    }
+    let mut stdout = std::io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
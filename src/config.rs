@@ -0,0 +1,92 @@
+use crate::persistence::STATE_DIR;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// User settings from `~/.config/flutter-tui/config.toml` and the project's
+/// `.flutter-tui/config.toml`, the project's taking precedence.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub snippets: Vec<Snippet>,
+    /// Action name -> key, e.g. `hot_reload = "ctrl+h"` under `[keys]`; see
+    /// [`crate::keymap::Action`].
+    pub keys: HashMap<String, String>,
+    /// hjkl, gg/G, Ctrl+D/U and n/N in the trees, the source view and the logs. Moves
+    /// toggling the logs to Ctrl+L and opening a widget's source to gd.
+    pub vim: Option<bool>,
+    /// `"dark"`, `"light"` or `"high-contrast"`.
+    pub theme: Option<crate::ui::theme::ThemeName>,
+    /// Screen-reader friendly rendering, as with `--accessible`.
+    pub accessible: Option<bool>,
+}
+
+/// A named Dart expression run from the command palette, e.g.
+///
+/// ```toml
+/// [[snippets]]
+/// name = "dump auth state"
+/// expression = "AuthService.instance.debugDescribe()"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    pub expression: String,
+    /// URI of the library to evaluate in, the app's root library by default.
+    #[serde(default)]
+    pub library: Option<String>,
+}
+
+pub fn load(project_root: &Path) -> Config {
+    let mut config = Config::default();
+    let paths = [
+        user_config_dir().map(|dir| dir.join(CONFIG_FILE)),
+        Some(project_root.join(STATE_DIR).join(CONFIG_FILE)),
+    ];
+    for path in paths.into_iter().flatten() {
+        if let Some(layer) = read(&path) {
+            config.merge(layer);
+        }
+    }
+    config
+}
+
+impl Config {
+    fn merge(&mut self, other: Config) {
+        for snippet in other.snippets {
+            self.snippets
+                .retain(|existing| existing.name != snippet.name);
+            self.snippets.push(snippet);
+        }
+        self.keys.extend(other.keys);
+        if other.vim.is_some() {
+            self.vim = other.vim;
+        }
+        if other.theme.is_some() {
+            self.theme = other.theme;
+        }
+        if other.accessible.is_some() {
+            self.accessible = other.accessible;
+        }
+    }
+}
+
+fn read(path: &Path) -> Option<Config> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match toml::from_str(&content) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            log::error!("Ignoring unreadable config {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn user_config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("flutter-tui"))
+}
@@ -0,0 +1,91 @@
+use crate::vm_service::RemoteDiagnosticsNode;
+
+/// Property levels the framework gives values equal to their default, left out of the
+/// snippet like they would be left out of the constructor call.
+const DEFAULT_LEVELS: [&str; 3] = ["fine", "debug", "hidden"];
+/// Property types describing state rather than a constructor argument.
+const SKIPPED_TYPES: [&str; 2] = ["FlagProperty", "ObjectFlagProperty"];
+
+/// An approximate Dart constructor call for a widget from its diagnostics: the widget type,
+/// its non-default properties as named arguments and the loaded children as `child` or
+/// `children`. Meant for issue reports and repros, it won't always compile as is.
+pub fn widget_snippet(node: &RemoteDiagnosticsNode) -> String {
+    let mut snippet = String::new();
+    write_widget(node, 0, &mut snippet);
+    snippet.push(';');
+    snippet
+}
+
+fn write_widget(node: &RemoteDiagnosticsNode, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth + 1);
+    let name = node
+        .widget_runtime_type
+        .as_deref()
+        .or(node.description.as_deref())
+        .unwrap_or("Widget");
+    out.push_str(name);
+    out.push('(');
+
+    let mut arguments = Vec::new();
+    for property in node.properties.iter().flatten() {
+        if let Some(argument) = argument(property) {
+            arguments.push(argument);
+        }
+    }
+    let children: Vec<&RemoteDiagnosticsNode> = node.children.iter().flatten().collect();
+    if arguments.is_empty() && children.is_empty() {
+        out.push(')');
+        return;
+    }
+
+    out.push('\n');
+    for (name, value) in arguments {
+        out.push_str(&format!("{}{}: {},\n", indent, name, value));
+    }
+    match children.as_slice() {
+        [] => {}
+        [child] => {
+            out.push_str(&format!("{}child: ", indent));
+            write_widget(child, depth + 1, out);
+            out.push_str(",\n");
+        }
+        children => {
+            out.push_str(&format!("{}children: [\n", indent));
+            for child in children {
+                out.push_str(&format!("{}  ", indent));
+                write_widget(child, depth + 2, out);
+                out.push_str(",\n");
+            }
+            out.push_str(&format!("{}],\n", indent));
+        }
+    }
+    out.push_str(&"  ".repeat(depth));
+    out.push(')');
+}
+
+/// The named argument for a property, or `None` when it wouldn't be passed explicitly.
+fn argument(property: &RemoteDiagnosticsNode) -> Option<(&str, String)> {
+    let name = property.name.as_deref()?;
+    let description = property.description.as_deref()?;
+    let node_type = property.node_type.as_deref().unwrap_or_default();
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        || description == "null"
+        || property
+            .level
+            .as_deref()
+            .is_some_and(|level| DEFAULT_LEVELS.contains(&level))
+        || SKIPPED_TYPES.contains(&node_type)
+    {
+        return None;
+    }
+
+    // Enums are described by their value alone: `center` -> `MainAxisAlignment.center`
+    let value = match node_type
+        .strip_prefix("EnumProperty<")
+        .and_then(|rest| rest.strip_suffix('>'))
+    {
+        Some(enum_type) if !description.contains('.') => format!("{}.{}", enum_type, description),
+        _ => description.to_string(),
+    };
+    Some((name, value))
+}
@@ -0,0 +1,85 @@
+use anyhow::{bail, Context, Result};
+use tokio::process::Command;
+
+/// A simple input event to send to the device, parsed from the input form.
+///
+/// Accepted forms:
+/// - `tap <x> <y>` taps at the given physical pixel coordinates
+/// - `back` presses the system back button
+/// - `text <text>` types the text into the focused field
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputAction {
+    Tap(u32, u32),
+    Back,
+    Text(String),
+}
+
+impl InputAction {
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        let (command, rest) = input.split_once(' ').unwrap_or((input, ""));
+        match command {
+            "tap" => {
+                let mut coords = rest.split_whitespace().map(str::parse::<u32>);
+                match (coords.next(), coords.next(), coords.next()) {
+                    (Some(Ok(x)), Some(Ok(y)), None) => Ok(Self::Tap(x, y)),
+                    _ => bail!("Usage: tap <x> <y>"),
+                }
+            }
+            "back" if rest.is_empty() => Ok(Self::Back),
+            "text" if !rest.is_empty() => Ok(Self::Text(rest.to_string())),
+            _ => bail!(
+                "Unknown input '{}', expected tap <x> <y>, back or text <text>",
+                input
+            ),
+        }
+    }
+}
+
+/// Sends the action to an Android device with `adb shell input`.
+///
+/// `simctl` has no equivalent for taps or key events, so iOS simulators and devices are
+/// not supported.
+pub async fn send(
+    action: &InputAction,
+    device_id: Option<&str>,
+    target_os: Option<&str>,
+) -> Result<()> {
+    if let Some(os) = target_os {
+        if os != "android" {
+            bail!(
+                "Input injection is only supported on Android devices (target is {})",
+                os
+            );
+        }
+    }
+
+    let mut cmd = Command::new("adb");
+    if let Some(id) = device_id {
+        cmd.arg("-s").arg(id);
+    }
+    cmd.arg("shell").arg("input");
+    match action {
+        InputAction::Tap(x, y) => {
+            cmd.arg("tap").arg(x.to_string()).arg(y.to_string());
+        }
+        InputAction::Back => {
+            cmd.arg("keyevent").arg("KEYCODE_BACK");
+        }
+        InputAction::Text(text) => {
+            // `input text` treats spaces as separators, %s is its escape for a space. The
+            // command line goes through the device shell, so quote it as well.
+            let escaped = text.replace(' ', "%s").replace('\'', "'\\''");
+            cmd.arg("text").arg(format!("'{}'", escaped));
+        }
+    }
+
+    let output = cmd.output().await.context("Failed to run adb")?;
+    if !output.status.success() {
+        bail!(
+            "adb failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
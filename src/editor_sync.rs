@@ -0,0 +1,87 @@
+use crate::persistence::STATE_DIR;
+use crate::vm_service::CreationLocation;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Written by the TUI: `file:line:column` of the selected widget's creation location, empty
+/// when the selection has none.
+const SELECTION_FILE: &str = "selection";
+/// Written by editor plugins: `file:line` or `file:line:column` of the widget to select.
+/// The file may be absolute, a `file://` URI or relative to the project.
+const GOTO_FILE: &str = "goto";
+
+/// Lightweight selection sync with an editor through two files in `.flutter-tui/`, so a
+/// plugin only has to watch and write plain text.
+pub struct EditorSync {
+    project_root: PathBuf,
+    last_selection: Option<String>,
+    goto_modified: Option<SystemTime>,
+}
+
+impl EditorSync {
+    pub fn new(project_root: &Path) -> Self {
+        let goto_modified = std::fs::metadata(project_root.join(STATE_DIR).join(GOTO_FILE))
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        Self {
+            project_root: project_root.to_path_buf(),
+            last_selection: None,
+            goto_modified,
+        }
+    }
+
+    /// Publishes the selected widget's creation location when it changed.
+    pub fn write_selection(&mut self, location: Option<&CreationLocation>) {
+        let selection = location
+            .map(|location| {
+                format!(
+                    "{}:{}:{}",
+                    location.file.trim_start_matches("file://"),
+                    location.line,
+                    location.column
+                )
+            })
+            .unwrap_or_default();
+        if self.last_selection.as_ref() == Some(&selection) {
+            return;
+        }
+        let dir = self.project_root.join(STATE_DIR);
+        let result = std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(dir.join(SELECTION_FILE), format!("{}\n", selection)));
+        if let Err(e) = result {
+            log::error!("Failed to write the editor selection: {}", e);
+        }
+        self.last_selection = Some(selection);
+    }
+
+    /// The absolute path and line an editor asked to select, once per write of the goto
+    /// file.
+    pub fn take_goto(&mut self) -> Option<(PathBuf, u32)> {
+        let path = self.project_root.join(STATE_DIR).join(GOTO_FILE);
+        let modified = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()?;
+        if self.goto_modified == Some(modified) {
+            return None;
+        }
+        self.goto_modified = Some(modified);
+
+        let content = std::fs::read_to_string(&path).ok()?;
+        let target = content.trim().trim_start_matches("file://");
+        let (file, line) = parse_location(target)?;
+        Some((self.project_root.join(file), line))
+    }
+}
+
+/// Splits `file:line[:column]`, dropping the column.
+fn parse_location(location: &str) -> Option<(&str, u32)> {
+    let (rest, last) = location.rsplit_once(':')?;
+    let last = last.parse().ok()?;
+    Some(match rest.rsplit_once(':') {
+        Some((file, line)) => match line.parse() {
+            Ok(line) => (file, line),
+            Err(_) => (rest, last),
+        },
+        None => (rest, last),
+    })
+}
@@ -1,10 +1,23 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use regex::Regex;
+use serde::Deserialize;
 use std::process::Stdio;
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
+use tokio::process::{ChildStderr, Command};
 use tokio::sync::mpsc;
 
+#[derive(Debug, Deserialize)]
+pub struct FlutterVersion {
+    #[serde(rename = "frameworkVersion")]
+    pub framework_version: Option<String>,
+    pub channel: Option<String>,
+    #[serde(rename = "engineRevision")]
+    pub engine_revision: Option<String>,
+    #[serde(rename = "dartSdkVersion")]
+    pub dart_sdk_version: Option<String>,
+}
+
+#[derive(Clone)]
 pub struct FlutterDaemon {
     uri_sender: mpsc::Sender<String>,
 }
@@ -14,11 +27,66 @@ impl FlutterDaemon {
         Self { uri_sender }
     }
 
+    /// Reads the Flutter, engine and Dart versions from `flutter --version --machine`.
+    pub async fn flutter_version(app_dir: &str) -> Result<FlutterVersion> {
+        let output = Command::new("fvm")
+            .arg("flutter")
+            .arg("--version")
+            .arg("--machine")
+            .current_dir(app_dir)
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .context("Failed to run fvm flutter --version")?;
+
+        // Flutter may print notices (e.g. upgrade hints) before the JSON object
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json_start = stdout
+            .find('{')
+            .context("No version information in flutter output")?;
+        let version: FlutterVersion = serde_json::from_str(&stdout[json_start..])
+            .context("Failed to parse flutter version")?;
+        Ok(version)
+    }
+
+    /// Streams `flutter logs` into the app log. Works without a VM service connection, so
+    /// it is usable when attaching fails.
+    pub async fn stream_logs(app_dir: &str, device_id: Option<&str>) -> Result<()> {
+        let mut cmd = Command::new("fvm");
+        cmd.arg("flutter")
+            .arg("logs")
+            .current_dir(app_dir)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::null());
+
+        if let Some(id) = device_id {
+            cmd.arg("-d").arg(id);
+        }
+
+        let mut child = cmd.spawn().context("Failed to spawn fvm flutter logs")?;
+
+        let stdout = child.stdout.take().context("Failed to open stdout")?;
+        let stderr = child.stderr.take().context("Failed to open stderr")?;
+        tokio::spawn(forward_stderr(stderr));
+
+        let mut lines = BufReader::new(stdout).lines();
+        while let Some(line) = lines.next_line().await? {
+            let trimmed = line.trim();
+            if !trimmed.is_empty() {
+                log::info!("Flutter Output: {}", trimmed);
+            }
+        }
+
+        log::info!("flutter logs exited");
+        Ok(())
+    }
+
     pub async fn run(
         &self,
         app_dir: &str,
         device_id: Option<&str>,
-        mut command_rx: mpsc::Receiver<String>,
+        command_rx: &mut mpsc::Receiver<String>,
     ) -> Result<()> {
         let mut cmd = Command::new("fvm");
         cmd.arg("flutter")
@@ -40,27 +108,7 @@ impl FlutterDaemon {
         let stderr = child.stderr.take().context("Failed to open stderr")?;
         let mut stdin = child.stdin.take().context("Failed to open stdin")?;
 
-        // Spawn stderr reader
-        tokio::spawn(async move {
-            let mut reader = BufReader::new(stderr);
-            let mut line = String::new();
-            loop {
-                line.clear();
-                match reader.read_line(&mut line).await {
-                    Ok(0) => break, // EOF
-                    Ok(_) => {
-                        let trimmed = line.trim();
-                        if !trimmed.is_empty() {
-                            log::error!("Flutter Error: {}", trimmed);
-                        }
-                    }
-                    Err(e) => {
-                        log::error!("Error reading stderr: {}", e);
-                        break;
-                    }
-                }
-            }
-        });
+        tokio::spawn(forward_stderr(stderr));
 
         let mut reader = BufReader::new(stdout);
         let mut line = String::new();
@@ -109,6 +157,36 @@ impl FlutterDaemon {
             }
         }
 
+        // A crashed `flutter attach` is reported so the supervisor can restart it
+        let status = child
+            .wait()
+            .await
+            .context("Failed to wait for flutter attach")?;
+        if !status.success() {
+            bail!("flutter attach exited with {}", status);
+        }
         Ok(())
     }
 }
+
+/// Forwards a Flutter process' stderr to the app log.
+async fn forward_stderr(stderr: ChildStderr) {
+    let mut reader = BufReader::new(stderr);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break, // EOF
+            Ok(_) => {
+                let trimmed = line.trim();
+                if !trimmed.is_empty() {
+                    log::error!("Flutter Error: {}", trimmed);
+                }
+            }
+            Err(e) => {
+                log::error!("Error reading stderr: {}", e);
+                break;
+            }
+        }
+    }
+}
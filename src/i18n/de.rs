@@ -0,0 +1,422 @@
+//! German. Key hints in parentheses stay as they are, the keys don't change with the locale.
+
+pub fn message(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "common.error" => "Fehler: {error}",
+        "common.fetching" => "Wird abgerufen...",
+        "common.loading" => "Wird geladen...",
+        "common.not_connected" => "Nicht verbunden",
+        "common.query_matches" => "{query} ({count} Treffer)",
+        "common.unknown" => "unbekannt",
+
+        "app.controls" => "Steuerung",
+        "app.controls_isolate" => "Steuerung - Isolate: {isolate}",
+        "app.controls_isolates" => "Steuerung - Isolate: {isolate} ({previous}/{next}: Wechseln, {list}: Liste)",
+        "app.inspector" => "Inspektor ({key})",
+        "app.debugger" => "Debugger ({key})",
+        "app.hot_reload" => "Hot Reload ({key})",
+        "app.hot_restart" => "Hot Restart ({key})",
+        "app.auto_on" => "Auto ({key}): AN",
+        "app.auto_off" => "Auto ({key}): AUS",
+        "app.refresh" => "Aktualisieren ({key})",
+        "app.logs_on" => "Logs ({key}): AN",
+        "app.logs_off" => "Logs ({key}): AUS",
+        "app.pause" => "Pause ({key})",
+        "app.toggles" => "Schalter ({key})",
+        "app.resume" => "PAUSIERT: Weiter ({key})",
+        "app.start" => "▶ APP STARTEN ({key})",
+        "app.quit" => "Beenden ({key})",
+        "app.more" => "Mehr...",
+
+        "tree.title" => "Widget-Baum",
+        "tree.title_all" => "Widget-Baum (alle Widgets, A: Zusammenfassung)",
+        "tree.filtered" => "(gefiltert)",
+        "tree.subtree_focus" => "[Teilbaum-Fokus, z: zurück]",
+        "tree.subsystem_failed" => "[{name} ausgefallen, i: Details]",
+        "tree.app_paused" => "[APP PAUSIERT]",
+        "tree.tracking_rebuilds" => "[Rebuilds werden gezählt, B: Statistik]",
+        "tree.select_mode" => "[Auswahlmodus: Widget antippen, w: aus]",
+        "tree.refreshing" => "{spinner} wird aktualisiert...",
+        "tree.as_of" => "- Baum von {time}",
+        "tree.waiting" => "Warte auf Daten...",
+        "tree.no_matches" => "Keine Knoten passen zum Filter",
+        "tree.loading" => "(wird geladen...)",
+
+        "search.title" => "Suche",
+        "search.prompt" => {
+            "Suche: {query}\nTreffer: {current}/{total}\n\n(Enter: Nächster, Umschalt+Enter: Vorheriger, Esc: Abbrechen)"
+        }
+        "filter.title" => "Filter (F)",
+        "filter.title_editing" => "Filter (Enter: Anwenden, Esc: Leeren)",
+        "logpoint.title" => "Logpoint",
+        "logpoint.prompt" => {
+            "Nachricht: {message}\n\nMit {expr} werden Werte eingesetzt, z. B. count = {count}\n(Enter: Setzen, Esc: Abbrechen)"
+        }
+        "isolate_label.title" => "Bezeichnung des Isolates",
+        "isolate_label.prompt" => {
+            "Bezeichnung: {label}\n\nWird in diesem Projekt statt \"{name}\" für dieses Isolate gezeigt\n(Enter: Setzen, leer entfernt sie, Esc: Abbrechen)"
+        }
+        "device_input.title" => "Eingabe an das Gerät senden",
+        "device_input.prompt" => {
+            "Eingabe: {input}\n\ntap <x> <y> | back | text <text>  (nur Android)\n(Enter: Senden, Esc: Abbrechen)"
+        }
+        "text_search.title" => "Suchen",
+        "text_search.prompt" => {
+            "Suche: {query}\n\nZeilen, die es enthalten, ohne Groß-/Kleinschreibung, n/N für nächste und vorige\n(Enter: Suchen, Esc: Abbrechen)"
+        }
+        "depth.title" => "Baum bis zur Tiefe aufklappen",
+        "depth.prompt" => {
+            "Tiefe: {depth}\n\nEbenen unter der Wurzel, tiefere werden zugeklappt\n(Enter: Aufklappen, Esc: Abbrechen)"
+        }
+        "condition.title" => "Bedingung des Haltepunkts",
+        "condition.prompt" => {
+            "Bedingung: {condition}\n\nDart-Ausdruck, der Haltepunkt hält nur, wenn er wahr ist\n(Enter: Setzen, leer entfernt sie, Esc: Abbrechen)"
+        }
+
+        "connection.connecting" => "Verbinde...",
+        "connection.reconnecting" => "Verbinde erneut (Versuch {attempt})...",
+        "connection.connected" => "Verbunden",
+        "connection.disconnected" => "Getrennt, neuer Versuch in {seconds}s",
+
+        "status.isolate" => "Isolate: {isolate}",
+        "status.no_isolate" => "Kein Isolate",
+        "status.no_device" => "Kein Gerät",
+        "status.running" => "Läuft",
+        "status.paused" => "Angehalten: {reason}",
+        "status.auto_reload_on" => "Auto-Reload: AN",
+        "status.auto_reload_off" => "Auto-Reload: AUS",
+        "status.reloaded" => "Neu geladen um {time}",
+        "status.restarted" => "Neu gestartet um {time}",
+        "status.not_reloaded" => "Noch nicht neu geladen",
+        "status.reload_scope" => ", {count} Bibliotheken in {packages} Paketen ({key})",
+        "status.reload_unchanged" => ", nichts geändert ({key})",
+        "status.layout_warnings" => "⚠ {count} Layout-Warnungen ({key})",
+
+        "isolates.select_keep" => "Isolate wählen (Enter: Untersuchen, n: Bezeichnen, Esc: Aktuelles behalten)",
+        "isolates.select_first" => "Isolate wählen (Enter: Untersuchen, n: Bezeichnen, Esc: Erstes nehmen)",
+        "isolates.flutter_ui" => "[Flutter-UI]",
+        "isolates.paused" => "[Angehalten: {reason}]",
+        "toggles.title" => "Flutter-Schalter (Taste einer Zeile: Umschalten, Enter: Umschalten, o/Esc: Schließen)",
+        "toggles.unavailable" => "(nicht verfügbar)",
+        "snippets.title" => "Snippets (Tippen filtert, Enter: Ausführen, Esc: Schließen)",
+        "snippets.none" => {
+            r#"Keine Snippets eingerichtet. Sie gehören in .flutter-tui/config.toml oder ~/.config/flutter-tui/config.toml:
+
+[[snippets]]
+name = "dump auth state"
+expression = "AuthService.instance.debugDescribe()""#
+        }
+        "eval.title" => "{name} (y: Kopieren, Esc: Schließen)",
+        "eval.evaluating" => "Wird ausgewertet...",
+        "screenshot.title" => {
+            "{widget} {width}x{height} (s: Als PNG speichern, r: Neu aufnehmen, Esc: Schließen)"
+        }
+        "screenshot.title_empty" => "Screenshot (Esc: Schließen)",
+        "screenshot.taking" => "Screenshot wird aufgenommen...",
+        "replay.title" => "Wiederholung von {uri} (- von der App empfangen, + wiederholt, Esc: Schließen)",
+        "replay.replaying" => "Wird wiederholt...",
+        "focus_tree.title" => "Fokus-Baum (r: Aktualisieren, K/Esc: Schließen)",
+
+        "rebuilds.title" => {
+            "Rebuild-Statistik nach {sort} (s: Sortieren, c: Zurücksetzen, Enter: Widget zeigen, B/Esc: Schließen)"
+        }
+        "rebuilds.none_yet" => "Noch keine Rebuilds, benutze die App",
+        "rebuilds.off" => "Rebuilds werden nicht gezählt (T: Start)",
+        "rebuilds.total" => "Gesamt",
+        "rebuilds.recent" => "Zuletzt",
+        "rebuilds.paints" => "Paints",
+        "rebuilds.widget" => "Widget",
+        "rebuilds.location" => "Ort",
+        "reload_scope.title" => "Neu geladen um {time}: {count} Bibliotheken geändert (U/Esc: Schließen)",
+        "reload_scope.title_empty" => "Umfang des Neuladens (U/Esc: Schließen)",
+        "reload_scope.none" => "Kein Hot Reload, seit das Isolate untersucht wird.",
+        "reload_scope.unchanged" => {
+            "Das Neuladen hat keine Bibliothek neu kompiliert, die App führt denselben Code aus wie vorher. Prüfe, ob die Datei gespeichert ist und zur laufenden App gehört."
+        }
+        "layout_warnings.title" => "Layout-Warnungen: {count} (Enter: Widget zeigen, V/Esc: Schließen)",
+        "layout_warnings.none" => {
+            "Keine Layout-Warnungen in den bisher geladenen Details. Details werden geprüft, sobald Widgets ausgewählt werden."
+        }
+        "layout_warnings.no_directionality" => "Kein Directionality-Widget darüber",
+        "layout_warnings.infinite_size" => "Unendliche Größe {size}",
+        "layout_warnings.unbounded_height" => "Unbegrenzte Höhe, Layout fehlgeschlagen",
+        "layout_warnings.unbounded_width" => "Unbegrenzte Breite, Layout fehlgeschlagen",
+        "sort.total" => "Gesamt",
+        "sort.recent" => "Zuletzt",
+        "sort.paints" => "Paints",
+        "sort.location" => "Ort",
+        "sort.bytes" => "Bytes",
+        "sort.instances" => "Instanzen",
+        "sort.class" => "Klasse",
+
+        "logs.title" => "Logs",
+        "logs.title_focused" => {
+            "Logs (Enter: Aufklappen, b: Am Fehler anhalten, [/]: Quelle, v: Stufe, +/-: Größe, 3: Vollbild)"
+        }
+        "logs.title_full_screen" => "Logs (Enter: Aufklappen, b: Am Fehler anhalten, [/]: Quelle, v: Stufe)",
+        "logs.source_all" => "Alle",
+        "logs.source_app" => "App",
+        "logs.source_daemon" => "Daemon",
+        "logs.source_tool" => "Tool",
+        "logs.level_all" => "Alle Stufen",
+        "logs.level_min" => "Ab {level}",
+
+        "info.title" => "App-Info (r: Ausgefallene neu starten, i/Esc: Schließen)",
+        "info.package" => "Paket",
+        "info.device" => "Gerät",
+        "info.mode" => "Modus",
+        "info.flutter" => "Flutter",
+        "info.engine" => "Engine",
+        "info.dart" => "Dart",
+        "info.target" => "Ziel",
+        "info.vm_service" => "VM-Service",
+        "info.display_label" => "Display",
+        "info.display" => "{rate}Hz ({budget}ms pro Frame)",
+        "info.subsystems" => "Subsysteme",
+
+        "metrics.title" => "Isolates (F5: Aktualisieren, M/Esc: Schließen)",
+        "metrics.title_as_of" => "Isolates von {time} (F5: Aktualisieren, M/Esc: Schließen)",
+        "metrics.isolate" => "Isolate",
+        "metrics.heap" => "Heap",
+        "metrics.capacity" => "Kapazität",
+        "metrics.external" => "Extern",
+        "metrics.state" => "Zustand",
+        "metrics.ports" => "Ports",
+        "metrics.legend" => {
+            "Gruppen teilen sich einen Heap. * untersuchtes Isolate. Offene Ports halten ein Isolate am Leben."
+        }
+
+        "details.title" => "Details",
+        "details.title_raw_json" => "Details (rohes JSON, J: schließen)",
+        "details.search" => "Eigenschaften durchsuchen (/)",
+        "details.search_editing" => "Eigenschaften durchsuchen (Enter: Behalten, Esc: Leeren)",
+        "details.summary" => {
+            "Typ: {widget_type}\nBeschreibung: {description}\nObjekt-ID: {object_id}\nWert-ID: {value_id}\n\n{status}"
+        }
+        "details.expired" => "(Objekt abgelaufen — Strg+R aktualisiert den Baum)",
+        "details.fetching" => "(Details werden abgerufen...)",
+        "details.no_selection" => "Kein Knoten ausgewählt",
+        "details.no_data" => "Keine Daten",
+
+        "debugger.files" => "Dateien",
+        "debugger.search_files" => "Dateien durchsuchen",
+        "debugger.source" => "Quelltext",
+        "debugger.no_file" => "Keine Datei geöffnet",
+        "debugger.breakpoint_condition" => "(wenn: {condition})",
+        "debugger.breakpoint_log" => "(Log: {template})",
+        "debugger.breakpoint_disabled" => "(deaktiviert)",
+        "debugger.breakpoints" => "Haltepunkte",
+        "debugger.breakpoints_focused" => "Haltepunkte (Enter: Öffnen, d/Entf: Entfernen)",
+        "debugger.stack" => "Aufrufstapel",
+        "debugger.stack_paused" => "Aufrufstapel von {isolate} (Angehalten: {reason})",
+        "debugger.stack_others_paused" => " +{count} weitere angehalten",
+        "debugger.paused_at_start" => "Noch lief nichts. Haltepunkte setzen, dann die App mit {key} starten.",
+        "debugger.running" => "Läuft...",
+        "debugger.locals" => "Lokale Variablen",
+        "debugger.not_paused" => "Nicht angehalten",
+
+        "memory.isolates_title" => "Speicher (alle Isolates, alle 2s gemessen)",
+        "memory.sampling" => "Wird gemessen...",
+        "memory.isolate" => "Isolate",
+        "memory.dart_heap" => "Dart-Heap",
+        "memory.capacity" => "Kapazität",
+        "memory.external" => "Extern",
+        "memory.total" => "Gesamt",
+        "memory.all_isolates" => "Alle Isolates",
+        "memory.legend" => {
+            "* untersuchtes Isolate. Externer Speicher liegt außerhalb des Dart-Heaps, z. B. dekodierte Bilder. W: Lecksuche"
+        }
+        "memory.heap_title" => "Heap des untersuchten Isolates: {heap} (Spitze {peak})",
+        "memory.heap_title_empty" => "Heap des untersuchten Isolates",
+        "memory.allocations_title" => {
+            "Allokationen nach {sort} (s: Sortieren, G: Garbage Collection)"
+        }
+        "memory.class" => "Klasse",
+        "memory.instances" => "Instanzen",
+        "memory.bytes" => "Bytes",
+        "memory.allocated" => "Alloziert",
+        "memory.leak_hunt_title" => "Lecksuche (Schnappschuss alle {secs}s, W: Stopp)",
+        "memory.leak_hunt_stopped" => "Lecksuche (gestoppt, W: Neu starten)",
+        "memory.first_snapshot" => "Erster Schnappschuss wird aufgenommen...",
+        "memory.snapshots" => "{count} Schnappschüsse, der letzte vor {secs}s",
+        "memory.widgets" => "Widgets: {count}",
+        "memory.memory" => "Speicher: {bytes}",
+        "memory.probable_leak" => {
+            "Wahrscheinlich ein Leck: die Zahl der Widgets wuchs zuletzt nur. Am meisten gewachsen: {widgets} (j: Zeigen wo)"
+        }
+        "memory.no_leak" => "Kein stetiges Wachstum der Widgets",
+
+        "profiler.title" => {
+            "Profiler: {view} der letzten {secs}s (c: Aufnehmen, [/]: Zeitraum, v: Ansicht)"
+        }
+        "profiler.fetching" => "CPU-Samples werden abgerufen...",
+        "profiler.no_capture" => {
+            "Noch keine Aufnahme. Benutze die App und drücke dann c, um die letzten Sekunden zu profilieren."
+        }
+        "profiler.no_samples" => "Keine Samples in diesem Zeitraum, das Isolate war untätig",
+        "profiler.summary" => {
+            "{count} Samples über {extent}s, eines alle {period}us, aufgenommen aus den letzten {secs}s"
+        }
+        "profiler.total" => "Gesamt",
+        "profiler.self" => "Selbst",
+        "profiler.function" => "Funktion",
+        "profiler.function_callees" => "Funktion (Aufgerufene darunter)",
+        "profiler.function_callers" => "Funktion (Aufrufer darunter)",
+        "profiler.location" => "Ort",
+        "profiler.top_down" => "Top-down",
+        "profiler.bottom_up" => "Bottom-up",
+        "profiler.functions" => "Funktionen",
+        "profiler.flame_graph" => "Flame Graph",
+
+        "performance.frames_title" => {
+            "Performance: Build- und Rasterzeit pro Frame, rot über dem Budget von {budget}ms"
+        }
+        "performance.waiting" => {
+            "Warte auf Frames. Benutze die App; Release-Builds melden keine."
+        }
+        "performance.stats" => {
+            "FPS: {fps}   Ruckler: {janky} von {count}   Durchschnitt: Build {build}ms, Raster {raster}ms\nLangsamster Frame: {worst}"
+        }
+        "performance.worst" => "#{number} {work}ms (Build {build}ms, Raster {raster}ms)",
+        "performance.filter" => "Ereignisse filtern (/)",
+        "performance.filter_editing" => "Ereignisse filtern (Enter: Behalten, Esc: Leeren)",
+        "performance.timeline_title" => {
+            "Timeline-Ereignisse von {category} (c: Letzte {secs}s aufnehmen, [/]: Zeitraum, f: Kategorie)"
+        }
+        "performance.all_categories" => "allen Kategorien",
+        "performance.fetching" => "Timeline wird abgerufen...",
+        "performance.no_capture" => {
+            "Noch keine Aufnahme. Benutze die App und drücke dann c, um die letzten Sekunden der Timeline aufzunehmen."
+        }
+        "performance.no_events" => {
+            "Keine Ereignisse in diesem Zeitraum, wird die Timeline aufgezeichnet?"
+        }
+        "performance.no_matches" => "Keine Ereignisse passen zum Filter",
+        "performance.category" => "{name} - {count} Ereignisse, insgesamt {total}ms",
+        "performance.no_category" => "(keine Kategorie)",
+        "performance.at_offset" => "bei +{secs}s",
+        "performance.captured" => "{count} Ereignisse aus den letzten {secs}s aufgenommen",
+
+        "network.title" => {
+            "HTTP-Anfragen: {count} (Enter: Details, d: GET wiederholen und vergleichen)"
+        }
+        "network.none" => "Noch keine Anfragen. Ab jetzt werden sie aufgezeichnet.",
+        "network.method" => "Methode",
+        "network.code" => "Code",
+        "network.time" => "Zeit",
+        "network.size" => "Größe",
+        "network.url" => "URL",
+        "network.details" => "Details",
+        "network.select" => "Anfrage auswählen und Enter drücken",
+        "network.status" => "Status:",
+        "network.in_millis" => "in {millis}ms",
+        "network.request_headers" => "Anfrage-Header",
+        "network.request_body" => "Anfrage-Body",
+        "network.response_headers" => "Antwort-Header",
+        "network.response_body" => "Antwort-Body",
+        "network.no_headers" => "(keine)",
+        "network.empty_body" => "(leer)",
+        "network.binary_body" => "(binär, {size})",
+        "network.more_lines" => "... {count} weitere Zeilen",
+
+        "help.title" => "Tastenkürzel (↑/↓: Blättern, ?/Esc: Schließen)",
+        "help.current" => "(hier)",
+        "help.global" => "Überall",
+        "help.inspector" => "Widget-Baum",
+        "help.details" => "Details",
+        "help.source" => "Quellcode",
+        "help.debugger" => "Debugger",
+        "help.logs" => "Logs",
+        "help.memory" => "Speicher",
+        "help.profiler" => "Profiler",
+        "help.performance" => "Performance",
+        "help.network" => "Netzwerk",
+        "help.inspector_tab" => "Tab Inspektor",
+        "help.debugger_tab" => "Tab Debugger",
+        "help.logs_tab" => "Tab Logs",
+        "help.memory_tab" => "Tab Speicher",
+        "help.profiler_tab" => "Tab Profiler",
+        "help.performance_tab" => "Tab Performance",
+        "help.network_tab" => "Tab Netzwerk",
+        "help.cycle_focus" => "Nächster Bereich",
+        "help.hot_reload" => "Hot Reload",
+        "help.hot_restart" => "Hot Restart",
+        "help.auto_reload" => "Hot Reload beim Speichern an/aus",
+        "help.reload_scope" => "Vom letzten Neuladen geänderte Bibliotheken",
+        "help.reassemble" => "Alle Widgets neu bauen",
+        "help.refresh" => "Isolates und Baum aktualisieren",
+        "help.pause" => "App anhalten oder fortsetzen",
+        "help.select_isolate" => "Isolate wählen",
+        "help.previous_isolate" => "Vorheriges Isolate",
+        "help.next_isolate" => "Nächstes Isolate",
+        "help.toggle_logs" => "Logs ein- oder ausblenden",
+        "help.grow_logs" => "Logs vergrößern",
+        "help.shrink_logs" => "Logs verkleinern",
+        "help.cycle_log_height" => "Logs mit 10/20 Zeilen, halbem oder ganzem Bildschirm (oder Rand ziehen)",
+        "help.search" => "Suchen",
+        "help.copy" => "Auswahl kopieren",
+        "help.command_palette" => "Snippet-Palette",
+        "help.toggles" => "Debug-Schalter",
+        "help.app_info" => "App-Info",
+        "help.isolate_metrics" => "Speicher der Isolates",
+        "help.device_input" => "Text in die App eingeben",
+        "help.save_timeline" => "Timeline speichern",
+        "help.help" => "Diese Hilfe",
+        "help.quit" => "Beenden",
+        "help.suspend" => "In die Shell wechseln",
+        "help.resize_pane" => "Bereich schmaler/breiter (oder Rand ziehen)",
+        "help.jump_to_edge" => "Zum Anfang/Ende des Bereichs springen",
+        "help.search_text" => "Zeilen durchsuchen",
+        "help.vim" => "Mit vim = true in der Konfiguration: hjkl, gg/G, Strg+D/U",
+        "help.extend_selection" => "Auswahl erweitern (y kopiert alles)",
+        "help.extend_breakpoints" => "Mehrere Haltepunkte wählen (d entfernt alle)",
+        "help.move" => "Bewegen",
+        "help.collapse_expand" => "Zu- / aufklappen",
+        "help.expand_collapse_subtree" => "Teilbaum auf- / zuklappen",
+        "help.focus_subtree" => "Nur den gewählten Teilbaum zeigen",
+        "help.expand_to_depth" => "Bis zu einer Tiefe aufklappen",
+        "help.scroll_to_node" => "Auswahl nach oben scrollen",
+        "help.filter" => "Baum filtern",
+        "help.open_source" => "Quelle des Widgets öffnen",
+        "help.raw_json" => "Details als rohes JSON",
+        "help.screenshot" => "Screenshot des Widgets",
+        "help.copy_as_dart" => "Widget als Dart kopieren",
+        "help.summary_tree" => "Zusammenfassung oder ganzer Baum",
+        "help.select_mode" => "Widget auf dem Gerät wählen",
+        "help.rebuild_tracking" => "Rebuilds verfolgen",
+        "help.rebuild_stats" => "Rebuild-Statistik",
+        "help.layout_warnings" => "Layout-Warnungen",
+        "help.focus_tree" => "Fokus-Baum",
+        "help.search_properties" => "Eigenschaften durchsuchen",
+        "help.toggle_breakpoint" => "Haltepunkt an/aus",
+        "help.breakpoint_condition" => "Bedingung des Haltepunkts",
+        "help.logpoint" => "Logpoint",
+        "help.resume" => "Fortsetzen",
+        "help.step_over" => "Überspringen",
+        "help.step_into" => "Hineinspringen",
+        "help.step_out" => "Herausspringen",
+        "help.back_to_files" => "Zurück zu den Dateien",
+        "help.open" => "Öffnen",
+        "help.search_files" => "Dateien durchsuchen",
+        "help.next_match" => "Nächster / vorheriger Treffer",
+        "help.remove_breakpoint" => "Haltepunkt entfernen",
+        "help.resize_panel" => "Rechtes Feld kleiner/größer",
+        "help.expand_entry" => "Eintrag aufklappen",
+        "help.log_source" => "Vorherige/nächste Quelle: alle, App, Daemon, Tool",
+        "help.log_level" => "Am wenigsten schwere gezeigte Stufe ausblenden",
+        "help.break_at_error" => "Dort anhalten, wo der Fehler auftrat",
+        "help.sort" => "Allokationen sortieren",
+        "help.collect_garbage" => "Garbage Collection",
+        "help.leak_hunt" => "Leck-Suche an/aus",
+        "help.leak_suspect" => "Zum Leck-Verdacht springen",
+        "help.capture" => "Aufzeichnen",
+        "help.window" => "Kürzeres / längeres Fenster",
+        "help.view" => "Nächste Ansicht",
+        "help.category" => "Nächste Kategorie",
+        "help.filter_events" => "Events filtern",
+        "help.request_details" => "Details der Anfrage",
+        "help.replay" => "GET wiederholen und vergleichen",
+
+        _ => return None,
+    })
+}
@@ -0,0 +1,412 @@
+//! English, the reference catalog: every key the UI looks up is here.
+
+pub fn message(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "common.error" => "Error: {error}",
+        "common.fetching" => "Fetching...",
+        "common.loading" => "Loading...",
+        "common.not_connected" => "Not connected",
+        "common.query_matches" => "{query} ({count} matches)",
+        "common.unknown" => "unknown",
+
+        "app.controls" => "Controls",
+        "app.controls_isolate" => "Controls - Isolate: {isolate}",
+        "app.controls_isolates" => "Controls - Isolate: {isolate} ({previous}/{next}: Switch, {list}: List)",
+        "app.inspector" => "Inspector ({key})",
+        "app.debugger" => "Debugger ({key})",
+        "app.hot_reload" => "Hot Reload ({key})",
+        "app.hot_restart" => "Hot Restart ({key})",
+        "app.auto_on" => "Auto ({key}): ON",
+        "app.auto_off" => "Auto ({key}): OFF",
+        "app.refresh" => "Refresh ({key})",
+        "app.logs_on" => "Logs ({key}): ON",
+        "app.logs_off" => "Logs ({key}): OFF",
+        "app.pause" => "Pause ({key})",
+        "app.toggles" => "Toggles ({key})",
+        "app.resume" => "PAUSED: Resume ({key})",
+        "app.start" => "▶ START APP ({key})",
+        "app.quit" => "Quit ({key})",
+        "app.more" => "More...",
+
+        "tree.title" => "Widget Tree",
+        "tree.title_all" => "Widget Tree (all widgets, A: summary)",
+        "tree.filtered" => "(filtered)",
+        "tree.subtree_focus" => "[subtree focus, z: restore]",
+        "tree.subsystem_failed" => "[{name} failed, i: details]",
+        "tree.app_paused" => "[APP PAUSED]",
+        "tree.tracking_rebuilds" => "[tracking rebuilds, B: stats]",
+        "tree.select_mode" => "[select mode: tap a widget, w: off]",
+        "tree.refreshing" => "{spinner} refreshing...",
+        "tree.as_of" => "- tree as of {time}",
+        "tree.waiting" => "Waiting for data...",
+        "tree.no_matches" => "No nodes match the filter",
+        "tree.loading" => "(loading...)",
+
+        "search.title" => "Search",
+        "search.prompt" => {
+            "Query: {query}\nMatches: {current}/{total}\n\n(Enter: Next, Shift+Enter: Prev, Esc: Cancel)"
+        }
+        "filter.title" => "Filter (F)",
+        "filter.title_editing" => "Filter (Enter: Apply, Esc: Clear)",
+        "logpoint.title" => "Logpoint",
+        "logpoint.prompt" => {
+            "Message: {message}\n\nUse {expr} to interpolate values, e.g. count = {count}\n(Enter: Set, Esc: Cancel)"
+        }
+        "isolate_label.title" => "Isolate Label",
+        "isolate_label.prompt" => {
+            "Label: {label}\n\nShown instead of \"{name}\" for this isolate, in this project\n(Enter: Set, empty removes it, Esc: Cancel)"
+        }
+        "device_input.title" => "Send Input to Device",
+        "device_input.prompt" => {
+            "Input: {input}\n\ntap <x> <y> | back | text <text>  (Android only)\n(Enter: Send, Esc: Cancel)"
+        }
+        "text_search.title" => "Search",
+        "text_search.prompt" => {
+            "Find: {query}\n\nLines containing it, ignoring case, n/N for the next and previous\n(Enter: Find, Esc: Cancel)"
+        }
+        "depth.title" => "Expand Tree to Depth",
+        "depth.prompt" => {
+            "Depth: {depth}\n\nLevels below the root to show, deeper ones are collapsed\n(Enter: Expand, Esc: Cancel)"
+        }
+        "condition.title" => "Breakpoint Condition",
+        "condition.prompt" => {
+            "Condition: {condition}\n\nDart expression, the breakpoint only pauses when it is true\n(Enter: Set, empty clears, Esc: Cancel)"
+        }
+
+        "connection.connecting" => "Connecting...",
+        "connection.reconnecting" => "Reconnecting (attempt {attempt})...",
+        "connection.connected" => "Connected",
+        "connection.disconnected" => "Disconnected, retrying in {seconds}s",
+
+        "status.isolate" => "Isolate: {isolate}",
+        "status.no_isolate" => "No isolate",
+        "status.no_device" => "No device",
+        "status.running" => "Running",
+        "status.paused" => "Paused: {reason}",
+        "status.auto_reload_on" => "Auto reload: ON",
+        "status.auto_reload_off" => "Auto reload: OFF",
+        "status.reloaded" => "Reloaded at {time}",
+        "status.restarted" => "Restarted at {time}",
+        "status.not_reloaded" => "Not reloaded yet",
+        "status.reload_scope" => ", {count} libraries in {packages} packages ({key})",
+        "status.reload_unchanged" => ", nothing changed ({key})",
+        "status.layout_warnings" => "⚠ {count} layout warnings ({key})",
+
+        "isolates.select_keep" => "Select Isolate (Enter: Inspect, n: Label, Esc: Keep current)",
+        "isolates.select_first" => "Select Isolate (Enter: Inspect, n: Label, Esc: Use first)",
+        "isolates.flutter_ui" => "[Flutter UI]",
+        "isolates.paused" => "[Paused: {reason}]",
+        "toggles.title" => "Flutter Toggles (key of a row: Flip it, Enter: Switch, o/Esc: Close)",
+        "toggles.unavailable" => "(unavailable)",
+        "snippets.title" => "Snippets (type to filter, Enter: Run, Esc: Close)",
+        "snippets.none" => {
+            r#"No snippets configured. Add them to .flutter-tui/config.toml or ~/.config/flutter-tui/config.toml:
+
+[[snippets]]
+name = "dump auth state"
+expression = "AuthService.instance.debugDescribe()""#
+        }
+        "eval.title" => "{name} (y: Copy, Esc: Close)",
+        "eval.evaluating" => "Evaluating...",
+        "screenshot.title" => "{widget} {width}x{height} (s: Save PNG, r: Retake, Esc: Close)",
+        "screenshot.title_empty" => "Screenshot (Esc: Close)",
+        "screenshot.taking" => "Taking screenshot...",
+        "replay.title" => "Replay {uri} (- app received, + replayed, Esc: Close)",
+        "replay.replaying" => "Replaying...",
+        "focus_tree.title" => "Focus Tree (r: Refresh, K/Esc: Close)",
+
+        "rebuilds.title" => {
+            "Rebuild Stats by {sort} (s: Sort, c: Reset, Enter: Show widget, B/Esc: Close)"
+        }
+        "rebuilds.none_yet" => "No rebuilds yet, interact with the app",
+        "rebuilds.off" => "Rebuild tracking is off (T: start)",
+        "rebuilds.total" => "Total",
+        "rebuilds.recent" => "Recent",
+        "rebuilds.paints" => "Paints",
+        "rebuilds.widget" => "Widget",
+        "rebuilds.location" => "Location",
+        "reload_scope.title" => "Reload at {time}: {count} libraries changed (U/Esc: Close)",
+        "reload_scope.title_empty" => "Reload Scope (U/Esc: Close)",
+        "reload_scope.none" => "No hot reload since the isolate was inspected.",
+        "reload_scope.unchanged" => {
+            "The reload recompiled no library, so the app runs the same code as before. Check that the file was saved and belongs to the running app."
+        }
+        "layout_warnings.title" => "Layout Warnings: {count} (Enter: Show widget, V/Esc: Close)",
+        "layout_warnings.none" => {
+            "No layout warnings in the details fetched so far. Details are scanned as widgets get selected."
+        }
+        "layout_warnings.no_directionality" => "No Directionality widget above it",
+        "layout_warnings.infinite_size" => "Infinite size {size}",
+        "layout_warnings.unbounded_height" => "Unbounded height constraints, layout failed",
+        "layout_warnings.unbounded_width" => "Unbounded width constraints, layout failed",
+        "sort.total" => "total",
+        "sort.recent" => "recent",
+        "sort.paints" => "paints",
+        "sort.location" => "location",
+        "sort.bytes" => "bytes",
+        "sort.instances" => "instances",
+        "sort.class" => "class",
+
+        "logs.title" => "Logs",
+        "logs.title_focused" => {
+            "Logs (Enter: Expand, b: Break at error, [/]: Source, v: Level, +/-: Resize, 3: Full screen)"
+        }
+        "logs.title_full_screen" => "Logs (Enter: Expand, b: Break at error, [/]: Source, v: Level)",
+        "logs.source_all" => "All",
+        "logs.source_app" => "App",
+        "logs.source_daemon" => "Daemon",
+        "logs.source_tool" => "Tool",
+        "logs.level_all" => "All levels",
+        "logs.level_min" => "{level} and up",
+
+        "info.title" => "App Info (r: Restart failed, i/Esc: Close)",
+        "info.package" => "Package",
+        "info.device" => "Device",
+        "info.mode" => "Mode",
+        "info.flutter" => "Flutter",
+        "info.engine" => "Engine",
+        "info.dart" => "Dart",
+        "info.target" => "Target",
+        "info.vm_service" => "VM Service",
+        "info.display_label" => "Display",
+        "info.display" => "{rate}Hz ({budget}ms frame budget)",
+        "info.subsystems" => "Subsystems",
+
+        "metrics.title" => "Isolates (F5: Refresh, M/Esc: Close)",
+        "metrics.title_as_of" => "Isolates as of {time} (F5: Refresh, M/Esc: Close)",
+        "metrics.isolate" => "Isolate",
+        "metrics.heap" => "Heap",
+        "metrics.capacity" => "capacity",
+        "metrics.external" => "External",
+        "metrics.state" => "State",
+        "metrics.ports" => "Ports",
+        "metrics.legend" => {
+            "Groups share one heap. * inspected isolate. Ports keep an isolate alive while open."
+        }
+
+        "details.title" => "Details",
+        "details.title_raw_json" => "Details (raw JSON, J: close)",
+        "details.search" => "Search properties (/)",
+        "details.search_editing" => "Search properties (Enter: Keep, Esc: Clear)",
+        "details.summary" => {
+            "Type: {widget_type}\nDescription: {description}\nObject ID: {object_id}\nValue ID: {value_id}\n\n{status}"
+        }
+        "details.expired" => "(Object expired — press Ctrl+R to refresh the tree)",
+        "details.fetching" => "(Fetching details...)",
+        "details.no_selection" => "No node selected",
+        "details.no_data" => "No data",
+
+        "debugger.files" => "Files",
+        "debugger.search_files" => "Search Files",
+        "debugger.source" => "Source Code",
+        "debugger.no_file" => "No file open",
+        "debugger.breakpoint_condition" => "(if: {condition})",
+        "debugger.breakpoint_log" => "(log: {template})",
+        "debugger.breakpoint_disabled" => "(disabled)",
+        "debugger.breakpoints" => "Breakpoints",
+        "debugger.breakpoints_focused" => "Breakpoints (Enter: Open, d/Del: Remove)",
+        "debugger.stack" => "Call Stack",
+        "debugger.stack_paused" => "Call Stack of {isolate} (Paused: {reason})",
+        "debugger.stack_others_paused" => " +{count} more paused",
+        "debugger.running" => "Running...",
+        "debugger.paused_at_start" => "Nothing has run yet. Set breakpoints, then start the app with {key}.",
+        "debugger.locals" => "Locals",
+        "debugger.not_paused" => "Not paused",
+
+        "memory.isolates_title" => "Memory (all isolates, sampled every 2s)",
+        "memory.sampling" => "Sampling...",
+        "memory.isolate" => "Isolate",
+        "memory.dart_heap" => "Dart heap",
+        "memory.capacity" => "Capacity",
+        "memory.external" => "External",
+        "memory.total" => "Total",
+        "memory.all_isolates" => "All isolates",
+        "memory.legend" => {
+            "* inspected isolate. External memory is held outside the Dart heap, e.g. decoded images. W: Leak hunt"
+        }
+        "memory.heap_title" => "Heap of the inspected isolate: {heap} (peak {peak})",
+        "memory.heap_title_empty" => "Heap of the inspected isolate",
+        "memory.allocations_title" => "Allocations by {sort} (s: Sort, G: Collect garbage)",
+        "memory.class" => "Class",
+        "memory.instances" => "Instances",
+        "memory.bytes" => "Bytes",
+        "memory.allocated" => "Allocated",
+        "memory.leak_hunt_title" => "Leak Hunt (snapshot every {secs}s, W: Stop)",
+        "memory.leak_hunt_stopped" => "Leak Hunt (stopped, W: Restart)",
+        "memory.first_snapshot" => "Taking the first snapshot...",
+        "memory.snapshots" => "{count} snapshots, last {secs}s ago",
+        "memory.widgets" => "Widgets: {count}",
+        "memory.memory" => "Memory: {bytes}",
+        "memory.probable_leak" => {
+            "Probable leak: the widget count only grew lately. Grew most: {widgets} (j: Show where)"
+        }
+        "memory.no_leak" => "No steady widget growth",
+
+        "profiler.title" => "Profiler: {view} of the last {secs}s (c: Capture, [/]: Window, v: View)",
+        "profiler.fetching" => "Fetching CPU samples...",
+        "profiler.no_capture" => {
+            "No capture yet. Use the app, then press c to profile the last seconds."
+        }
+        "profiler.no_samples" => "No samples in this window, the isolate was idle",
+        "profiler.summary" => {
+            "{count} samples over {extent}s, one every {period}us, captured from the last {secs}s"
+        }
+        "profiler.total" => "Total",
+        "profiler.self" => "Self",
+        "profiler.function" => "Function",
+        "profiler.function_callees" => "Function (callees below)",
+        "profiler.function_callers" => "Function (callers below)",
+        "profiler.location" => "Location",
+        "profiler.top_down" => "Top-down",
+        "profiler.bottom_up" => "Bottom-up",
+        "profiler.functions" => "Functions",
+        "profiler.flame_graph" => "Flame graph",
+
+        "performance.frames_title" => {
+            "Performance: build + raster time per frame, red over the {budget}ms budget"
+        }
+        "performance.waiting" => {
+            "Waiting for frames. Interact with the app; release builds don't report them."
+        }
+        "performance.stats" => {
+            "FPS: {fps}   Janky: {janky} of {count}   Average: build {build}ms, raster {raster}ms\nWorst frame: {worst}"
+        }
+        "performance.worst" => "#{number} {work}ms (build {build}ms, raster {raster}ms)",
+        "performance.filter" => "Filter events (/)",
+        "performance.filter_editing" => "Filter events (Enter: Keep, Esc: Clear)",
+        "performance.timeline_title" => {
+            "Timeline events of {category} (c: Capture the last {secs}s, [/]: Window, f: Category)"
+        }
+        "performance.all_categories" => "all categories",
+        "performance.fetching" => "Fetching the timeline...",
+        "performance.no_capture" => {
+            "No capture yet. Use the app, then press c to capture the last seconds of the timeline."
+        }
+        "performance.no_events" => "No events in this window, is the timeline recording?",
+        "performance.no_matches" => "No events match the filter",
+        "performance.category" => "{name} - {count} events, {total}ms in total",
+        "performance.no_category" => "(no category)",
+        "performance.at_offset" => "at +{secs}s",
+        "performance.captured" => "{count} events captured from the last {secs}s",
+
+        "network.title" => "HTTP Requests: {count} (Enter: Details, d: Replay GET and diff)",
+        "network.none" => "No requests recorded yet. Recording is on from now on.",
+        "network.method" => "Method",
+        "network.code" => "Code",
+        "network.time" => "Time",
+        "network.size" => "Size",
+        "network.url" => "URL",
+        "network.details" => "Details",
+        "network.select" => "Select a request and press Enter",
+        "network.status" => "Status:",
+        "network.in_millis" => "in {millis}ms",
+        "network.request_headers" => "Request headers",
+        "network.request_body" => "Request body",
+        "network.response_headers" => "Response headers",
+        "network.response_body" => "Response body",
+        "network.no_headers" => "(none)",
+        "network.empty_body" => "(empty)",
+        "network.binary_body" => "(binary, {size})",
+        "network.more_lines" => "... {count} more lines",
+
+        "help.title" => "Keyboard Shortcuts (↑/↓: Scroll, ?/Esc: Close)",
+        "help.current" => "(here)",
+        "help.global" => "Everywhere",
+        "help.inspector" => "Widget Tree",
+        "help.details" => "Details",
+        "help.source" => "Source Code",
+        "help.debugger" => "Debugger",
+        "help.logs" => "Logs",
+        "help.memory" => "Memory",
+        "help.profiler" => "Profiler",
+        "help.performance" => "Performance",
+        "help.network" => "Network",
+        "help.inspector_tab" => "Inspector tab",
+        "help.debugger_tab" => "Debugger tab",
+        "help.logs_tab" => "Logs tab",
+        "help.memory_tab" => "Memory tab",
+        "help.profiler_tab" => "Profiler tab",
+        "help.performance_tab" => "Performance tab",
+        "help.network_tab" => "Network tab",
+        "help.cycle_focus" => "Next pane",
+        "help.hot_reload" => "Hot reload",
+        "help.hot_restart" => "Hot restart",
+        "help.auto_reload" => "Hot reload on save on/off",
+        "help.reload_scope" => "Libraries the last reload changed",
+        "help.reassemble" => "Rebuild all widgets",
+        "help.refresh" => "Refresh isolates and tree",
+        "help.pause" => "Pause or resume the app",
+        "help.select_isolate" => "Select isolate",
+        "help.previous_isolate" => "Previous isolate",
+        "help.next_isolate" => "Next isolate",
+        "help.toggle_logs" => "Show or hide the logs",
+        "help.grow_logs" => "Enlarge the logs",
+        "help.shrink_logs" => "Shrink the logs",
+        "help.cycle_log_height" => "Logs at 10/20 rows, half or full screen (or drag their border)",
+        "help.search" => "Search",
+        "help.copy" => "Copy the selection",
+        "help.command_palette" => "Snippet palette",
+        "help.toggles" => "Debug toggles",
+        "help.app_info" => "App info",
+        "help.isolate_metrics" => "Isolate memory",
+        "help.device_input" => "Type text into the app",
+        "help.save_timeline" => "Save the timeline",
+        "help.help" => "This help",
+        "help.quit" => "Quit",
+        "help.suspend" => "Suspend to the shell",
+        "help.resize_pane" => "Narrow/widen the pane (or drag its border)",
+        "help.jump_to_edge" => "Jump to the top/bottom of the pane",
+        "help.search_text" => "Search the lines",
+        "help.vim" => "With vim = true in the config: hjkl, gg/G, Ctrl+D/U",
+        "help.extend_selection" => "Extend the selection (y copies all of it)",
+        "help.extend_breakpoints" => "Select several breakpoints (d removes all of them)",
+        "help.move" => "Move",
+        "help.collapse_expand" => "Collapse / expand",
+        "help.expand_collapse_subtree" => "Expand / collapse subtree",
+        "help.focus_subtree" => "Show only the selected subtree",
+        "help.expand_to_depth" => "Expand to a depth",
+        "help.scroll_to_node" => "Scroll the selection to the top",
+        "help.filter" => "Filter the tree",
+        "help.open_source" => "Open the widget's source",
+        "help.raw_json" => "Details as raw JSON",
+        "help.screenshot" => "Screenshot of the widget",
+        "help.copy_as_dart" => "Copy the widget as Dart",
+        "help.summary_tree" => "Summary or full tree",
+        "help.select_mode" => "Select a widget on the device",
+        "help.rebuild_tracking" => "Track rebuilds",
+        "help.rebuild_stats" => "Rebuild statistics",
+        "help.layout_warnings" => "Layout warnings",
+        "help.focus_tree" => "Focus tree",
+        "help.search_properties" => "Search properties",
+        "help.toggle_breakpoint" => "Toggle breakpoint",
+        "help.breakpoint_condition" => "Breakpoint condition",
+        "help.logpoint" => "Logpoint",
+        "help.resume" => "Resume",
+        "help.step_over" => "Step over",
+        "help.step_into" => "Step into",
+        "help.step_out" => "Step out",
+        "help.back_to_files" => "Back to the files",
+        "help.open" => "Open",
+        "help.search_files" => "Search files",
+        "help.next_match" => "Next / previous match",
+        "help.remove_breakpoint" => "Remove breakpoint",
+        "help.resize_panel" => "Shrink/grow the panel on the right",
+        "help.expand_entry" => "Expand entry",
+        "help.log_source" => "Previous/next source: all, app, daemon, tool",
+        "help.log_level" => "Hide the least severe level shown",
+        "help.break_at_error" => "Break where the error was thrown",
+        "help.sort" => "Sort allocations",
+        "help.collect_garbage" => "Collect garbage",
+        "help.leak_hunt" => "Leak hunt on/off",
+        "help.leak_suspect" => "Jump to the leak suspect",
+        "help.capture" => "Capture",
+        "help.window" => "Shorter / longer window",
+        "help.view" => "Next view",
+        "help.category" => "Next category",
+        "help.filter_events" => "Filter events",
+        "help.request_details" => "Request details",
+        "help.replay" => "Replay GET and diff",
+
+        _ => return None,
+    })
+}
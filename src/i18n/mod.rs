@@ -0,0 +1,102 @@
+//! Message catalogs for the UI's strings, looked up with [`t!`](crate::t).
+//!
+//! Every key lives in the English catalog; other catalogs fall back to it for keys they
+//! don't translate yet. Messages name their placeholders, `"{count} matches"`, so a
+//! translation can reorder them.
+
+mod de;
+mod en;
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Locale {
+    En,
+    De,
+}
+
+impl Locale {
+    /// Reads the conventional environment variables: `LC_ALL`, then `LC_MESSAGES`, then
+    /// `LANG`, e.g. `de_DE.UTF-8`. English when none is set or there is no catalog for it.
+    pub fn detect() -> Self {
+        let value = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        let language = value.split(['_', '.', '@']).next().unwrap_or_default();
+        match language.to_lowercase().as_str() {
+            "de" => Locale::De,
+            _ => Locale::En,
+        }
+    }
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::De,
+        _ => Locale::En,
+    }
+}
+
+/// The message for `key` in the current locale. A key missing from every catalog shows
+/// as itself, so it stands out instead of leaving a blank.
+pub fn text(key: &'static str) -> &'static str {
+    let translated = match locale() {
+        Locale::En => None,
+        Locale::De => de::message(key),
+    };
+    translated.or_else(|| en::message(key)).unwrap_or(key)
+}
+
+/// Replaces the `{name}` placeholders of `message` with the named values. Braces that
+/// don't name a value are kept, as in `{expr}` of the logpoint help.
+pub fn fill(message: &str, values: &[(&str, &dyn Display)]) -> String {
+    let mut filled = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[1..end];
+            values
+                .iter()
+                .find(|(value_name, _)| *value_name == name)
+                .map(|(_, value)| (end, value))
+        });
+        match value {
+            Some((end, value)) => {
+                filled.push_str(&value.to_string());
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// Looks up a UI string in the current locale's catalog: `t!("logs.title")`. Named values
+/// fill the message's placeholders: `t!("common.query_matches", query = query, count = 3)`.
+#[macro_export]
+macro_rules! t {
+    ($key:literal) => {
+        $crate::i18n::text($key)
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::text($key),
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
@@ -0,0 +1,430 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Actions whose key can be changed in the `[keys]` table of the config, e.g.
+/// `hot_reload = "ctrl+h"` or `step_over = "n"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    InspectorTab,
+    DebuggerTab,
+    LogsTab,
+    MemoryTab,
+    ProfilerTab,
+    PerformanceTab,
+    NetworkTab,
+    HotReload,
+    HotRestart,
+    ToggleAutoReload,
+    Refresh,
+    Reassemble,
+    TogglePause,
+    ToggleLogs,
+    GrowLogs,
+    ShrinkLogs,
+    CycleLogHeight,
+    Quit,
+    Search,
+    CycleFocus,
+    CommandPalette,
+    Toggles,
+    AppInfo,
+    IsolateMetrics,
+    SelectIsolate,
+    PreviousIsolate,
+    NextIsolate,
+    ToggleSummaryTree,
+    ToggleRebuildTracking,
+    RebuildStats,
+    LayoutWarnings,
+    ReloadScope,
+    FocusTree,
+    WidgetSelectMode,
+    DeviceInput,
+    Copy,
+    SaveTimeline,
+    ToggleBreakpoint,
+    Resume,
+    StepOver,
+    StepInto,
+    StepOut,
+    Help,
+}
+
+impl Action {
+    pub const ALL: [Action; 43] = [
+        Action::InspectorTab,
+        Action::DebuggerTab,
+        Action::LogsTab,
+        Action::MemoryTab,
+        Action::ProfilerTab,
+        Action::PerformanceTab,
+        Action::NetworkTab,
+        Action::HotReload,
+        Action::HotRestart,
+        Action::ToggleAutoReload,
+        Action::Refresh,
+        Action::Reassemble,
+        Action::TogglePause,
+        Action::ToggleLogs,
+        Action::GrowLogs,
+        Action::ShrinkLogs,
+        Action::CycleLogHeight,
+        Action::Quit,
+        Action::Search,
+        Action::CycleFocus,
+        Action::CommandPalette,
+        Action::Toggles,
+        Action::AppInfo,
+        Action::IsolateMetrics,
+        Action::SelectIsolate,
+        Action::PreviousIsolate,
+        Action::NextIsolate,
+        Action::ToggleSummaryTree,
+        Action::ToggleRebuildTracking,
+        Action::RebuildStats,
+        Action::LayoutWarnings,
+        Action::ReloadScope,
+        Action::FocusTree,
+        Action::WidgetSelectMode,
+        Action::DeviceInput,
+        Action::Copy,
+        Action::SaveTimeline,
+        Action::ToggleBreakpoint,
+        Action::Resume,
+        Action::StepOver,
+        Action::StepInto,
+        Action::StepOut,
+        Action::Help,
+    ];
+
+    /// The name the config refers to the action by.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::InspectorTab => "inspector_tab",
+            Action::DebuggerTab => "debugger_tab",
+            Action::LogsTab => "logs_tab",
+            Action::MemoryTab => "memory_tab",
+            Action::ProfilerTab => "profiler_tab",
+            Action::PerformanceTab => "performance_tab",
+            Action::NetworkTab => "network_tab",
+            Action::HotReload => "hot_reload",
+            Action::HotRestart => "hot_restart",
+            Action::ToggleAutoReload => "toggle_auto_reload",
+            Action::Refresh => "refresh",
+            Action::Reassemble => "reassemble",
+            Action::TogglePause => "toggle_pause",
+            Action::ToggleLogs => "toggle_logs",
+            Action::GrowLogs => "grow_logs",
+            Action::ShrinkLogs => "shrink_logs",
+            Action::CycleLogHeight => "cycle_log_height",
+            Action::Quit => "quit",
+            Action::Search => "search",
+            Action::CycleFocus => "cycle_focus",
+            Action::CommandPalette => "command_palette",
+            Action::Toggles => "toggles",
+            Action::AppInfo => "app_info",
+            Action::IsolateMetrics => "isolate_metrics",
+            Action::SelectIsolate => "select_isolate",
+            Action::PreviousIsolate => "previous_isolate",
+            Action::NextIsolate => "next_isolate",
+            Action::ToggleSummaryTree => "toggle_summary_tree",
+            Action::ToggleRebuildTracking => "toggle_rebuild_tracking",
+            Action::RebuildStats => "rebuild_stats",
+            Action::LayoutWarnings => "layout_warnings",
+            Action::ReloadScope => "reload_scope",
+            Action::FocusTree => "focus_tree",
+            Action::WidgetSelectMode => "widget_select_mode",
+            Action::DeviceInput => "device_input",
+            Action::Copy => "copy",
+            Action::SaveTimeline => "save_timeline",
+            Action::ToggleBreakpoint => "toggle_breakpoint",
+            Action::Resume => "resume",
+            Action::StepOver => "step_over",
+            Action::StepInto => "step_into",
+            Action::StepOut => "step_out",
+            Action::Help => "help",
+        }
+    }
+
+    /// The key the action has unless the config changes it, the one the main loop handles.
+    pub fn default_key(self) -> KeyBinding {
+        let key = |c| KeyBinding::new(KeyCode::Char(c), KeyModifiers::NONE);
+        match self {
+            Action::InspectorTab => key('1'),
+            Action::DebuggerTab => key('2'),
+            Action::LogsTab => key('3'),
+            Action::MemoryTab => key('4'),
+            Action::ProfilerTab => key('5'),
+            Action::PerformanceTab => key('6'),
+            Action::NetworkTab => key('7'),
+            Action::HotReload => key('r'),
+            Action::HotRestart => key('R'),
+            Action::ToggleAutoReload => key('a'),
+            Action::Refresh => KeyBinding::new(KeyCode::F(6), KeyModifiers::NONE),
+            Action::Reassemble => key('u'),
+            Action::TogglePause => key('p'),
+            Action::ToggleLogs => key('l'),
+            Action::GrowLogs => key('+'),
+            Action::ShrinkLogs => key('-'),
+            Action::CycleLogHeight => key('Z'),
+            Action::Quit => key('q'),
+            Action::Search => key('/'),
+            Action::CycleFocus => KeyBinding::new(KeyCode::Tab, KeyModifiers::NONE),
+            Action::CommandPalette => key(':'),
+            Action::Toggles => key('o'),
+            Action::AppInfo => key('i'),
+            Action::IsolateMetrics => key('M'),
+            Action::SelectIsolate => key('S'),
+            Action::PreviousIsolate => key('<'),
+            Action::NextIsolate => key('>'),
+            Action::ToggleSummaryTree => key('A'),
+            Action::ToggleRebuildTracking => key('T'),
+            Action::RebuildStats => key('B'),
+            Action::LayoutWarnings => key('V'),
+            Action::ReloadScope => key('U'),
+            Action::FocusTree => key('K'),
+            Action::WidgetSelectMode => key('w'),
+            Action::DeviceInput => key('I'),
+            Action::Copy => key('y'),
+            Action::SaveTimeline => key('X'),
+            Action::ToggleBreakpoint => key('b'),
+            Action::Resume => KeyBinding::new(KeyCode::F(5), KeyModifiers::NONE),
+            Action::StepOver => KeyBinding::new(KeyCode::F(10), KeyModifiers::NONE),
+            Action::StepInto => KeyBinding::new(KeyCode::F(11), KeyModifiers::NONE),
+            Action::StepOut => KeyBinding::new(KeyCode::F(11), KeyModifiers::SHIFT),
+            Action::Help => key('?'),
+        }
+    }
+
+    /// The key the action moves to with vim navigation on, where that takes its default key.
+    pub fn vim_key(self) -> Option<KeyBinding> {
+        match self {
+            Action::ToggleLogs => Some(KeyBinding::new(KeyCode::Char('l'), KeyModifiers::CONTROL)),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or(())
+    }
+}
+
+/// A key with its modifiers, written like `r`, `R`, `F5`, `ctrl+r` or `alt+enter` in the
+/// config. Letters carry shift in their case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Terminals differ in whether they report shift along with a shifted character
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self {
+            code,
+            modifiers: modifiers
+                & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT),
+        }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        // "+" alone, or as the key after a modifier ("ctrl++"), is the plus key
+        let (prefix, key) = match s.strip_suffix("++") {
+            Some(prefix) => (format!("{}+", prefix), "+"),
+            None if s == "+" => (String::new(), "+"),
+            None => match s.rsplit_once('+') {
+                Some((prefix, key)) => (format!("{}+", prefix), key),
+                None => (String::new(), s),
+            },
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}'", modifier)),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) if (1..=24).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key '{}'", key)),
+                },
+            },
+        };
+        // Ctrl+R arrives as Ctrl+r, Shift+r as R
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            code => code,
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// The keys of the remappable actions. The main loop only handles the default keys;
+/// [`KeyMap::translate`] turns a remapped key into the default key of its action first.
+#[derive(Debug, Default)]
+pub struct KeyMap {
+    remapped: HashMap<Action, KeyBinding>,
+}
+
+impl KeyMap {
+    /// Reads the `[keys]` table of the config, skipping (and logging) what it can't parse.
+    /// A key given to several actions goes to the first of them in [`Action::ALL`]. With
+    /// `vim` on, actions the config leaves alone move off the keys vim navigation takes.
+    pub fn new(keys: &HashMap<String, String>, vim: bool) -> Self {
+        let mut parsed = HashMap::new();
+        for (name, key) in keys {
+            let Ok(action) = name.parse::<Action>() else {
+                log::error!("Ignoring key for unknown action '{}'", name);
+                continue;
+            };
+            match key.parse::<KeyBinding>() {
+                Ok(binding) => {
+                    parsed.insert(action, binding);
+                }
+                Err(e) => log::error!("Ignoring key for {}: {}", name, e),
+            }
+        }
+
+        let mut remapped: HashMap<Action, KeyBinding> = HashMap::new();
+        for action in Action::ALL {
+            let Some(binding) = parsed.remove(&action) else {
+                continue;
+            };
+            if let Some((taken_by, _)) = remapped.iter().find(|(_, b)| **b == binding) {
+                log::error!(
+                    "Ignoring key {} for {}, it is already bound to {}",
+                    binding,
+                    action.name(),
+                    taken_by.name()
+                );
+                continue;
+            }
+            remapped.insert(action, binding);
+        }
+        if vim {
+            for action in Action::ALL {
+                let Some(binding) = action.vim_key() else {
+                    continue;
+                };
+                if !remapped.contains_key(&action) && !remapped.values().any(|b| *b == binding) {
+                    remapped.insert(action, binding);
+                }
+            }
+        }
+        Self { remapped }
+    }
+
+    /// The key an action is triggered with.
+    pub fn key(&self, action: Action) -> KeyBinding {
+        self.remapped
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_key())
+    }
+
+    /// Whether a remapped action took the key, making what it does otherwise unreachable.
+    pub fn is_remapped_key(&self, key: KeyBinding) -> bool {
+        self.remapped.values().any(|binding| *binding == key)
+    }
+
+    /// Turns a key bound to a remapped action into the action's default key. The default
+    /// key of a remapped action becomes a key that does nothing, unless it is bound to
+    /// another action.
+    pub fn translate(&self, key: KeyEvent) -> KeyEvent {
+        if self.remapped.is_empty() {
+            return key;
+        }
+        let pressed = KeyBinding::from_event(&key);
+        if let Some(action) = self
+            .remapped
+            .iter()
+            .find(|(_, binding)| **binding == pressed)
+            .map(|(action, _)| *action)
+        {
+            let default = action.default_key();
+            return KeyEvent::new(default.code, default.modifiers);
+        }
+        if self
+            .remapped
+            .keys()
+            .any(|action| action.default_key() == pressed)
+        {
+            return KeyEvent::new(KeyCode::Null, KeyModifiers::NONE);
+        }
+        key
+    }
+}
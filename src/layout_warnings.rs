@@ -0,0 +1,119 @@
+use crate::vm_service::RemoteDiagnosticsNode;
+
+/// A likely layout problem found in a fetched details subtree, before it turns into an
+/// exception (or while the exception scrolls by in the logs).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutWarning {
+    /// Nearest node with an id at or above where the problem was reported.
+    pub node_id: String,
+    /// Node whose details subtree reported it, selected instead when `node_id` isn't in
+    /// the tree.
+    pub source_id: String,
+    pub widget: String,
+    pub message: String,
+}
+
+/// Warnings of a details subtree fetched for `source_id`, in tree order, one per node and
+/// message.
+pub fn scan(source_id: &str, details: &RemoteDiagnosticsNode) -> Vec<LayoutWarning> {
+    let mut warnings = Vec::new();
+    scan_node(source_id, details, None, &mut warnings);
+    warnings
+}
+
+fn scan_node(
+    source_id: &str,
+    node: &RemoteDiagnosticsNode,
+    owner: Option<(&str, &str)>,
+    warnings: &mut Vec<LayoutWarning>,
+) {
+    let widget = node
+        .widget_runtime_type
+        .as_deref()
+        .or(node.description.as_deref())
+        .unwrap_or_default();
+    let owner = match node.value_id.as_deref().or(node.object_id.as_deref()) {
+        Some(id) => (id, widget),
+        None => owner.unwrap_or((source_id, widget)),
+    };
+    let (node_id, widget) = owner;
+    let mut push = |message: String| {
+        let warning = LayoutWarning {
+            node_id: node_id.to_string(),
+            source_id: source_id.to_string(),
+            widget: widget.to_string(),
+            message,
+        };
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    };
+
+    let properties = node.properties.iter().flatten();
+    let render_properties = node
+        .render_object
+        .iter()
+        .flat_map(|render_object| render_object.properties.iter().flatten());
+    for property in properties.chain(render_properties) {
+        if let Some(message) = property_warning(property) {
+            push(message);
+        }
+    }
+    if let Some(message) = node.render_object.as_deref().and_then(constraint_warning) {
+        push(message);
+    }
+
+    for child in node.children.iter().flatten() {
+        scan_node(source_id, child, Some(owner), warnings);
+    }
+}
+
+/// Properties the framework itself reports at warning level or above, and messages about
+/// a missing Directionality, which come at any level.
+fn property_warning(property: &RemoteDiagnosticsNode) -> Option<String> {
+    let description = property.description.as_deref().unwrap_or_default();
+    let text = match property.name.as_deref() {
+        Some(name) if !name.is_empty() => format!("{}: {}", name, description),
+        _ => description.to_string(),
+    };
+    if matches!(property.level.as_deref(), Some("warning" | "error")) {
+        return Some(text);
+    }
+    if description.to_lowercase().contains("no directionality") {
+        return Some(crate::t!("layout_warnings.no_directionality").to_string());
+    }
+    None
+}
+
+/// A render object that got unbounded constraints and failed to lay out, or sized itself
+/// infinitely: what a Column in a ListView or an Expanded in a scroll view end up as.
+fn constraint_warning(render_object: &RemoteDiagnosticsNode) -> Option<String> {
+    let property = |name| {
+        render_object
+            .properties
+            .iter()
+            .flatten()
+            .find(|p| p.name.as_deref() == Some(name))
+            .and_then(|p| p.description.as_deref())
+    };
+    let constraints = property("constraints")?;
+    let size = property("size").unwrap_or("MISSING");
+    if size.contains("Infinity") {
+        return Some(crate::t!("layout_warnings.infinite_size", size = size));
+    }
+    if size != "MISSING" {
+        return None;
+    }
+    let unbounded = |axis: &str| {
+        constraints.contains("unconstrained")
+            || constraints.contains(&format!("{}<=Infinity", axis))
+            || constraints.contains(&format!("{}=Infinity", axis))
+    };
+    if unbounded("h") {
+        Some(crate::t!("layout_warnings.unbounded_height").to_string())
+    } else if unbounded("w") {
+        Some(crate::t!("layout_warnings.unbounded_width").to_string())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,31 @@
+//! The inspector and debugger behind the `flutter_tui_tools` binary, as a library so the
+//! benchmarks can drive it.
+
+pub mod app_state;
+pub mod breakpoints;
+pub mod clipboard;
+pub mod config;
+pub mod dart_snippet;
+pub mod device_input;
+pub mod editor_sync;
+pub mod flutter_daemon;
+pub mod i18n;
+pub mod keymap;
+pub mod layout_warnings;
+pub mod logger;
+#[cfg(feature = "mock-vm")]
+pub mod mock_vm_service;
+pub mod network;
+pub mod persistence;
+pub mod profiler;
+pub mod rebuilds;
+pub mod reload_scope;
+pub mod screenshot;
+pub mod snapshots;
+pub mod supervisor;
+pub mod synthetic_tree;
+pub mod timeline;
+pub mod tree_filter;
+pub mod ui;
+pub mod vm_logs;
+pub mod vm_service;
@@ -1,12 +1,171 @@
+use anyhow::{Context, Result};
 use log::{Level, Metadata, Record};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use tokio::sync::mpsc;
 
+/// One entry of the Logs pane: a message of the tool itself, or a `dart:developer` log
+/// record of the app with its logger name, error and stack trace.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub level: Level,
+    pub logger: Option<String>,
+    pub message: String,
+    pub error: Option<String>,
+    pub stack_trace: Vec<String>,
+}
+
+/// Where a log entry came from, for the source tabs of the Logs pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogSource {
+    /// Prints and `dart:developer` logs of the app, however they got here.
+    App,
+    /// The `flutter run`/`flutter logs` process.
+    Daemon,
+    /// This tool itself.
+    Tool,
+}
+
+impl LogEntry {
+    pub fn new(level: Level, message: String) -> Self {
+        Self {
+            level,
+            logger: None,
+            message,
+            error: None,
+            stack_trace: Vec::new(),
+        }
+    }
+
+    /// Files the app output of the gesture debug flags under a source of its own, so it
+    /// stands apart from (and can be told from) the rest of the app's prints.
+    pub fn with_debug_source(mut self) -> Self {
+        if self.logger.is_some() {
+            return self;
+        }
+        // e.g. "flutter: Gesture arena 3    ❙ ★ Opening new gesture arena."
+        let tagged = if let Some(start) = self.message.find("Gesture arena ") {
+            Some(("gestures", start))
+        } else if self.message.contains(": HitTestResult(") {
+            // "PointerDownEvent#1c2d3(position: ...): HitTestResult(...)"
+            Some(("hit test", self.message.find("Pointer").unwrap_or(0)))
+        } else {
+            None
+        };
+        if let Some((source, start)) = tagged {
+            self.logger = Some(source.to_string());
+            self.message = self.message[start..].to_string();
+        }
+        self
+    }
+
+    /// Tells the source from the prefixes the VM service and daemon tasks log app and
+    /// flutter tool output with. Everything else the tool logged itself.
+    pub fn source(&self) -> LogSource {
+        if self.logger.is_some() {
+            return LogSource::App;
+        }
+        let message = self.message.as_str();
+        if message.starts_with("App Output: ")
+            || message.starts_with("App Error: ")
+            || message.starts_with("Flutter Output: flutter: ")
+        {
+            LogSource::App
+        } else if message.starts_with("Flutter Output: ")
+            || message.starts_with("Flutter Error: ")
+            || message.starts_with("Sending command to Flutter")
+            || message.starts_with("flutter logs exited")
+        {
+            LogSource::Daemon
+        } else {
+            LogSource::Tool
+        }
+    }
+
+    /// Script URI and line of each stack trace frame, innermost first.
+    pub fn frame_locations(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.stack_trace
+            .iter()
+            .filter_map(|frame| frame_location(frame))
+    }
+
+    /// Whether there is an error or stack trace to show when the entry is expanded.
+    pub fn has_details(&self) -> bool {
+        self.error.is_some() || !self.stack_trace.is_empty()
+    }
+}
+
+/// Where a stack trace frame points, in either the VM format
+/// (`#0      main (package:app/main.dart:12:5)`) or the `package:stack_trace` one
+/// (`package:app/main.dart 12:5  main`).
+fn frame_location(frame: &str) -> Option<(&str, usize)> {
+    let frame = frame.trim();
+    if let (Some(open), Some(close)) = (frame.rfind('('), frame.rfind(')')) {
+        if open < close {
+            // uri:line:column, the column is optional
+            let (rest, last) = frame[open + 1..close].rsplit_once(':')?;
+            let last = last.parse().ok()?;
+            return Some(match rest.rsplit_once(':') {
+                Some((uri, line)) => match line.parse() {
+                    Ok(line) => (uri, line),
+                    Err(_) => (rest, last),
+                },
+                None => (rest, last),
+            });
+        }
+    }
+    let mut parts = frame.split_whitespace();
+    let uri = parts.next()?;
+    let line = parts.next()?.split(':').next()?.parse().ok()?;
+    Some((uri, line))
+}
+
+/// A copy of every log entry on disk, written as it arrives so it has all of a long
+/// session and not just what the Logs pane still holds.
+pub struct LogFile {
+    writer: BufWriter<File>,
+}
+
+impl LogFile {
+    /// Opens `path` for appending, so restarts add to the record instead of replacing it.
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::options()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Appends `entry` with the local time, its level and source, one line for the message
+    /// and an indented one for the error and each stack frame.
+    pub fn write(&mut self, entry: &LogEntry) -> std::io::Result<()> {
+        let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
+        write!(self.writer, "{} {:<5} ", time, entry.level)?;
+        if let Some(logger) = &entry.logger {
+            write!(self.writer, "[{}] ", logger)?;
+        }
+        writeln!(self.writer, "{}", entry.message)?;
+        if let Some(error) = &entry.error {
+            writeln!(self.writer, "    Error: {}", error)?;
+        }
+        for frame in &entry.stack_trace {
+            writeln!(self.writer, "    {}", frame)?;
+        }
+        // Flushed per entry so the file can be followed with `tail -f`
+        self.writer.flush()
+    }
+}
+
 pub struct AppLogger {
-    sender: mpsc::UnboundedSender<String>,
+    sender: mpsc::UnboundedSender<LogEntry>,
 }
 
 impl AppLogger {
-    pub fn new(sender: mpsc::UnboundedSender<String>) -> Self {
+    pub fn new(sender: mpsc::UnboundedSender<LogEntry>) -> Self {
         Self { sender }
     }
 }
@@ -18,7 +177,7 @@ impl log::Log for AppLogger {
 
     fn log(&self, record: &Record) {
         if self.enabled(record.metadata()) {
-            let log_entry = format!("[{}] {}", record.level(), record.args());
+            let log_entry = LogEntry::new(record.level(), record.args().to_string());
             let _ = self.sender.send(log_entry);
         }
     }
@@ -26,7 +185,7 @@ impl log::Log for AppLogger {
     fn flush(&self) {}
 }
 
-pub fn init(sender: mpsc::UnboundedSender<String>) -> Result<(), log::SetLoggerError> {
+pub fn init(sender: mpsc::UnboundedSender<LogEntry>) -> Result<(), log::SetLoggerError> {
     let logger = AppLogger::new(sender);
     log::set_boxed_logger(Box::new(logger))?;
     log::set_max_level(log::LevelFilter::Info);
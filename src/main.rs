@@ -1,8 +1,8 @@
-mod app_state;
-mod flutter_daemon;
-mod logger;
-mod ui;
-mod vm_service;
+use flutter_tui_tools::{
+    app_state, breakpoints, clipboard, config, dart_snippet, device_input, editor_sync,
+    flutter_daemon, i18n, keymap, logger, network, persistence, profiler, screenshot, snapshots,
+    supervisor, synthetic_tree, timeline, ui, vm_logs, vm_service,
+};
 
 use anyhow::Result;
 use app_state::AppState;
@@ -13,17 +13,29 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use flutter_daemon::FlutterDaemon;
+use futures::StreamExt;
 use ignore::gitignore::Gitignore;
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::path::Path;
+use std::sync::Arc;
 use std::{
+    collections::HashSet,
     io,
     time::{Duration, Instant},
 };
 use tokio::sync::mpsc;
 use vm_service::VmServiceClient;
 
+/// How many details requests a prefetch keeps in flight at once.
+const DETAILS_PREFETCH_CONCURRENCY: usize = 4;
+/// How often the Memory tab samples every isolate.
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How often the Network tab fetches the recorded HTTP requests.
+const NETWORK_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How often the UI state and breakpoints are checked for changes to write to disk.
+const JOURNAL_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
@@ -38,42 +50,279 @@ struct Args {
     /// Directory to watch for changes (defaults to app_dir)
     #[arg(short, long)]
     watch_dir: Option<String>,
+
+    /// Connect straight to a VM service URI (e.g. from `flutter run`) instead of running
+    /// `flutter attach`
+    #[arg(long)]
+    vm_uri: Option<String>,
+
+    /// Pause the app's isolates as soon as the VM service is connected
+    #[arg(long)]
+    pause_on_attach: bool,
+
+    /// Name of the isolate to inspect. By default the one running the Flutter UI is picked
+    #[arg(long)]
+    isolate: Option<String>,
+
+    /// Always ask which isolate to inspect when the app has more than one
+    #[arg(long)]
+    choose_isolate: bool,
+
+    /// High-contrast rendering without glyphs or color-only cues, for screen readers and
+    /// limited terminals. Overrides `accessible` in the config
+    #[arg(
+        long,
+        env = "FLUTTER_TUI_ACCESSIBLE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    accessible: Option<bool>,
+
+    /// Language of the UI. By default taken from LC_ALL, LC_MESSAGES or LANG, English when
+    /// there is no catalog for it
+    #[arg(long, env = "FLUTTER_TUI_LOCALE", value_enum)]
+    locale: Option<i18n::Locale>,
+
+    /// Snapshot the widget count and memory every SECS seconds for leak hunting (W on the
+    /// Memory tab toggles it)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    snapshot_interval: Option<u64>,
+
+    /// Seconds of frames and timeline events kept for captures (X saves them to
+    /// .flutter-tui/captures/)
+    #[arg(long, value_name = "SECS", default_value_t = 10)]
+    timeline_window: u64,
+
+    /// Capture the timeline automatically when a frame takes longer than MS milliseconds
+    #[arg(long, value_name = "MS")]
+    capture_jank: Option<u64>,
+
+    /// Capture the timeline automatically on Flutter errors (posted while structured
+    /// errors are on, as under IDEs)
+    #[arg(long)]
+    capture_on_error: bool,
+
+    /// Display refresh rate in Hz that frame times are judged against (e.g. 120 for an
+    /// 8.3ms budget). By default the rate the engine reports for the device is used
+    #[arg(long, value_name = "HZ")]
+    refresh_rate: Option<f64>,
+
+    /// Sync the selection with an editor: the selected widget's creation location is
+    /// written to .flutter-tui/selection and locations written to .flutter-tui/goto are
+    /// selected
+    #[arg(long)]
+    editor_sync: bool,
+
+    /// Set a breakpoint at FILE:LINE (relative to the app directory) before the app
+    /// connects. Can be repeated
+    #[arg(long = "break", value_name = "FILE:LINE", value_parser = breakpoints::parse_location)]
+    breakpoints: Vec<breakpoints::BreakpointEntry>,
+
+    /// Append every log entry, of the tool and the app, to FILE as it arrives
+    #[arg(long, value_name = "FILE")]
+    log_file: Option<std::path::PathBuf>,
+
+    /// Open FILE (optionally FILE:LINE) in the Debugger on startup
+    #[arg(long, value_name = "FILE[:LINE]")]
+    open: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Only stream `flutter logs` from the device, without attaching
+    Logs,
+    /// Print a generated widget tree as inspector JSON, to reproduce a tree shape
+    SyntheticTree {
+        /// Number of nodes
+        #[arg(long, default_value_t = 1000)]
+        nodes: usize,
+        /// Most children of a multi-child widget
+        #[arg(long, default_value_t = 8)]
+        max_children: usize,
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+    },
+}
+
+/// What came of fetching the inspector tree, the refresh spinner runs until one arrives.
+enum TreeFetch {
+    Fetched(Box<vm_service::RemoteDiagnosticsNode>),
+    /// A newer fetch installed its tree first.
+    Superseded,
+    Failed(String),
+}
+
+/// Receivers and state of the VM service task that outlive a restart of it.
+struct VmTaskInputs {
+    rx_uri: mpsc::Receiver<String>,
+    rx_selected_isolate: mpsc::Receiver<String>,
+    rx_details_request: mpsc::Receiver<String>,
+    rx_details_prefetch: mpsc::Receiver<Vec<String>>,
+    rx_properties_request: mpsc::Receiver<(String, String, String)>,
+    rx_children_request: mpsc::Receiver<Vec<String>>,
+    /// Whether to fetch the summary tree (user widgets only) or the full widget tree.
+    rx_summary_tree: tokio::sync::watch::Receiver<bool>,
+    rx_refresh: mpsc::Receiver<()>,
+    last_uri: Option<String>,
+    pause_on_attach: bool,
+}
+
+#[derive(Clone)]
+struct VmTaskOutputs {
+    tx_vm_client: mpsc::Sender<VmServiceClient>,
+    tx_connection_status: mpsc::Sender<app_state::ConnectionStatus>,
+    tx_app_info: mpsc::Sender<app_state::AppInfo>,
+    tx_isolates: mpsc::Sender<(Vec<vm_service::IsolateRef>, Option<String>)>,
+    tx_tree: mpsc::Sender<TreeFetch>,
+    tx_details: mpsc::Sender<(String, vm_service::RemoteDiagnosticsNode, serde_json::Value)>,
+    tx_properties: mpsc::Sender<(String, String, Vec<vm_service::RemoteDiagnosticsNode>)>,
+    tx_children: mpsc::Sender<(String, Vec<vm_service::RemoteDiagnosticsNode>)>,
+    tx_debug_event: mpsc::Sender<app_state::DebugEvent>,
+    tx_services: mpsc::Sender<(String, Option<String>)>,
+    tx_rebuilds: mpsc::Sender<(String, serde_json::Value)>,
+    tx_app_log: mpsc::UnboundedSender<logger::LogEntry>,
+    tx_details_expired: mpsc::Sender<String>,
+    tx_toggles: mpsc::Sender<(String, app_state::ToggleState)>,
+    tx_device_selection: mpsc::Sender<String>,
+    tx_inspector_isolates: mpsc::Sender<HashSet<String>>,
+    tx_refresh_rate: mpsc::Sender<f64>,
+    tx_timeline: mpsc::Sender<timeline::TimelineUpdate>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+    if let Some(Command::SyntheticTree {
+        nodes,
+        max_children,
+        seed,
+    }) = args.command
+    {
+        let shape = synthetic_tree::TreeShape {
+            nodes,
+            max_children,
+            seed,
+        };
+        println!("{}", serde_json::to_string_pretty(&shape.generate())?);
+        return Ok(());
+    }
+    // Opened before the terminal is taken over, so a bad path is reported plainly
+    let mut log_file = args
+        .log_file
+        .as_deref()
+        .map(logger::LogFile::open)
+        .transpose()?;
+    let color_support = ui::palette::ColorSupport::detect();
+    ui::palette::set_color_support(color_support);
+    i18n::set_locale(args.locale.unwrap_or_else(i18n::Locale::detect));
 
     // Setup terminal
-    enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    setup_terminal(&mut stdout)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
+    // A panic would otherwise leave the shell in raw mode on the alternate screen
+    let default_panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal(&mut io::stdout());
+        default_panic_hook(info);
+    }));
 
     // Create app state
     let project_root = std::path::PathBuf::from(&args.app_dir)
         .canonicalize()
         .unwrap_or_else(|_| std::path::PathBuf::from(&args.app_dir));
     let mut app_state = AppState::new(project_root);
-    let (tx_uri, mut rx_uri) = mpsc::channel(1);
+    let (tx_uri, rx_uri) = mpsc::channel(1);
     let (tx_tree, mut rx_tree) = mpsc::channel(1);
     let (tx_log, mut rx_log) = mpsc::unbounded_channel();
-    let (tx_isolates, mut rx_isolates) = mpsc::channel::<Vec<vm_service::IsolateRef>>(1);
-    let (tx_selected_isolate, mut rx_selected_isolate) = mpsc::channel::<String>(1);
-    let (tx_details_request, mut rx_details_request) = mpsc::channel::<String>(1);
-    let (tx_details, mut rx_details) = mpsc::channel::<vm_service::RemoteDiagnosticsNode>(1);
+    let (tx_isolates, mut rx_isolates) =
+        mpsc::channel::<(Vec<vm_service::IsolateRef>, Option<String>)>(1);
+    let (tx_selected_isolate, rx_selected_isolate) = mpsc::channel::<String>(1);
+    let (tx_details_request, rx_details_request) = mpsc::channel::<String>(1);
+    let (tx_details_prefetch, rx_details_prefetch) = mpsc::channel::<Vec<String>>(4);
+    // Node ids the inspector no longer knows, their details can't be fetched anymore
+    let (tx_details_expired, mut rx_details_expired) = mpsc::channel::<String>(4);
+    let (tx_details, mut rx_details) =
+        mpsc::channel::<(String, vm_service::RemoteDiagnosticsNode, serde_json::Value)>(16);
+    // (details node id, property key, property object id)
+    let (tx_properties_request, rx_properties_request) =
+        mpsc::channel::<(String, String, String)>(10);
+    let (tx_properties, mut rx_properties) =
+        mpsc::channel::<(String, String, Vec<vm_service::RemoteDiagnosticsNode>)>(10);
+    // Ids of nodes whose children were cut off the summary tree
+    let (tx_children_request, rx_children_request) = mpsc::channel::<Vec<String>>(4);
+    let (tx_summary_tree, rx_summary_tree) = tokio::sync::watch::channel(true);
+    let (tx_children, mut rx_children) =
+        mpsc::channel::<(String, Vec<vm_service::RemoteDiagnosticsNode>)>(16);
     let (tx_cmd, rx_cmd) = mpsc::channel::<String>(10);
-    let (tx_refresh, mut rx_refresh) = mpsc::channel::<()>(1);
+    let (tx_refresh, rx_refresh) = mpsc::channel::<()>(1);
     let (tx_vm_client, mut rx_vm_client) = mpsc::channel::<vm_service::VmServiceClient>(1);
-    let (tx_debug_event, mut rx_debug_event) =
-        mpsc::channel::<(app_state::DebugState, Option<serde_json::Value>)>(10);
+    let (tx_connection_status, mut rx_connection_status) =
+        mpsc::channel::<app_state::ConnectionStatus>(4);
+    let (tx_debug_event, mut rx_debug_event) = mpsc::channel::<app_state::DebugEvent>(10);
+    let (tx_locals, mut rx_locals) = mpsc::channel::<(String, Vec<app_state::VariableNode>)>(10);
+    let (tx_source_location, mut rx_source_location) = mpsc::channel::<(String, usize)>(1);
+    let (tx_focus_tree, mut rx_focus_tree) = mpsc::channel::<String>(1);
+    let (tx_scripts, mut rx_scripts) = mpsc::channel::<(String, Vec<vm_service::ScriptRef>)>(2);
+    let (tx_screenshot, mut rx_screenshot) = mpsc::channel::<(String, Result<Vec<u8>, String>)>(1);
+    let (tx_http_requests, mut rx_http_requests) =
+        mpsc::channel::<Result<Vec<network::HttpProfileRequest>, String>>(1);
+    let (tx_http_replay, mut rx_http_replay) =
+        mpsc::channel::<Result<Vec<network::DiffLine>, String>>(1);
+    let (tx_isolate_metrics, mut rx_isolate_metrics) =
+        mpsc::channel::<Result<Vec<app_state::IsolateGroupMetrics>, String>>(1);
+    // (request id, the request with its bodies)
+    let (tx_http_details, mut rx_http_details) =
+        mpsc::channel::<(String, Result<network::HttpProfileRequest, String>)>(1);
+    let (tx_eval_result, mut rx_eval_result) = mpsc::channel::<(String, Result<String, String>)>(4);
+    // (service name, registered method alias or None once unregistered)
+    let (tx_services, mut rx_services) = mpsc::channel::<(String, Option<String>)>(10);
+    // (extension kind, data) of rebuild and repaint count events
+    let (tx_rebuilds, mut rx_rebuilds) = mpsc::channel::<(String, serde_json::Value)>(100);
+    let (tx_app_info, mut rx_app_info) = mpsc::channel::<app_state::AppInfo>(10);
+    // (service extension method, enabled)
+    let (tx_toggles, mut rx_toggles) = mpsc::channel::<(String, app_state::ToggleState)>(10);
+    // Node ids of widgets picked on the device in select mode
+    let (tx_device_selection, mut rx_device_selection) = mpsc::channel::<String>(10);
+    // Ids of the isolates that expose the Flutter inspector
+    let (tx_inspector_isolates, mut rx_inspector_isolates) = mpsc::channel::<HashSet<String>>(4);
+    // Refresh rate of the device's display, for the frame budget
+    let (tx_refresh_rate, mut rx_refresh_rate) = mpsc::channel::<f64>(4);
+    let (tx_timeline, mut rx_timeline) = mpsc::channel::<timeline::TimelineUpdate>(256);
+    // Pauses re-sent after a conditional breakpoint's condition held
+    let tx_breakpoint_pause = tx_debug_event.clone();
+    // ("path:line", VM breakpoint id)
+    let (tx_breakpoint_added, mut rx_breakpoint_added) =
+        mpsc::channel::<(String, String, String)>(10);
+    // (isolate id, usage) of every isolate, polled while the Memory tab is open
+    let (tx_memory, mut rx_memory) = mpsc::channel::<Vec<(String, vm_service::MemoryUsage)>>(2);
+    let (tx_allocations, mut rx_allocations) =
+        mpsc::channel::<Result<Vec<vm_service::ClassHeapStats>, String>>(2);
+    let (tx_profile, mut rx_profile) = mpsc::channel::<Result<profiler::CpuProfile, String>>(1);
+    let (tx_timeline_capture, mut rx_timeline_capture) =
+        mpsc::channel::<Result<timeline::TimelineCapture, String>>(1);
+    let (tx_snapshot, mut rx_snapshot) = mpsc::channel::<snapshots::TreeSnapshot>(2);
+    // Termination and suspend requests from outside, e.g. `kill` or a closed terminal
+    let (tx_signal, mut rx_signal) = mpsc::channel::<ProcessSignal>(4);
+    spawn_signal_listener(tx_signal);
 
     app_state.tx_flutter_command = Some(tx_cmd);
 
     // Init logger
+    // Log records of the app bypass the `log` facade to keep their metadata
+    let tx_app_log = tx_log.clone();
     logger::init(tx_log)?;
 
+    // Long-lived subsystems are supervised so a crash is visible and can be recovered from
+    let (tx_task_status, mut rx_task_status) = mpsc::unbounded_channel();
+    let (tx_task_restart, rx_task_restart) = mpsc::channel::<&'static str>(4);
+    let mut supervisor = supervisor::Supervisor::new(tx_task_status);
+
     // Setup File Watcher
     let (tx_watch, mut rx_watch) = mpsc::channel::<()>(1);
     let watch_dir = args.watch_dir.clone().unwrap_or(args.app_dir.clone());
@@ -102,959 +351,4243 @@ async fn main() -> Result<()> {
     let (gitignore, _) = Gitignore::new(path_to_watch.join(".gitignore"));
 
     // Bridge task
-    tokio::spawn(async move {
-        while let Ok(res) = std_rx.recv() {
-            match res {
-                Ok(event) => {
-                    let is_dart_change = event.paths.iter().any(|p| {
-                        // Check gitignore
-                        if gitignore.matched(p, false).is_ignore() {
-                            return false;
-                        }
-                        p.extension().map_or(false, |ext| ext == "dart")
-                    });
+    let std_rx = Arc::new(tokio::sync::Mutex::new(std_rx));
+    supervisor.spawn(
+        "File watcher",
+        supervisor::RestartPolicy::OnFailure { max_restarts: 3 },
+        move || {
+            let std_rx = std_rx.clone();
+            let gitignore = gitignore.clone();
+            let tx_watch = tx_watch.clone();
+            Box::pin(async move {
+                let std_rx = std_rx.lock().await;
+                while let Ok(res) = std_rx.recv() {
+                    match res {
+                        Ok(event) => {
+                            let is_dart_change = event.paths.iter().any(|p| {
+                                // Check gitignore
+                                if gitignore.matched(p, false).is_ignore() {
+                                    return false;
+                                }
+                                p.extension().is_some_and(|ext| ext == "dart")
+                            });
 
-                    if is_dart_change {
-                        log::info!("Dart file changed: {:?}", event.paths);
-                        let _ = tx_watch.send(()).await;
+                            if is_dart_change {
+                                log::info!("Dart file changed: {:?}", event.paths);
+                                let _ = tx_watch.send(()).await;
+                            }
+                        }
+                        Err(e) => log::error!("Watch error: {:?}", e),
                     }
                 }
-                Err(e) => log::error!("Watch error: {:?}", e),
-            }
-        }
-    });
+                Ok(())
+            })
+        },
+    );
+
+    // App metadata that doesn't need the VM
+    app_state.app_info.package_name =
+        app_state::AppInfo::package_name_from_pubspec(&app_state.project_root);
+    app_state.app_info.device = args.device_id.clone();
+    app_state.refresh_rate_override = args.refresh_rate;
+    app_state.snapshot_interval = args.snapshot_interval.map(Duration::from_secs);
+    app_state.timeline =
+        timeline::TimelineRecorder::new(Duration::from_secs(args.timeline_window.max(1)));
+    app_state.timeline.jank_trigger = args.capture_jank.map(Duration::from_millis);
+    app_state.timeline.error_trigger = args.capture_on_error;
+    let mut editor_sync = args
+        .editor_sync
+        .then(|| editor_sync::EditorSync::new(&app_state.project_root));
+    let version_dir = args.app_dir.clone();
+    let tx_flutter_version = tx_app_info.clone();
+    supervisor.spawn(
+        "Flutter version",
+        supervisor::RestartPolicy::Never,
+        move || {
+            let version_dir = version_dir.clone();
+            let tx_flutter_version = tx_flutter_version.clone();
+            Box::pin(async move {
+                let version = FlutterDaemon::flutter_version(&version_dir).await?;
+                let flutter_version = version.framework_version.map(|v| match &version.channel {
+                    Some(channel) => format!("{} ({})", v, channel),
+                    None => v,
+                });
+                let _ = tx_flutter_version
+                    .send(app_state::AppInfo {
+                        flutter_version,
+                        engine_revision: version.engine_revision,
+                        dart_version: version.dart_sdk_version,
+                        ..Default::default()
+                    })
+                    .await;
+                Ok(())
+            })
+        },
+    );
 
     // Start Flutter Daemon
-    let daemon = FlutterDaemon::new(tx_uri);
+    let daemon = FlutterDaemon::new(tx_uri.clone());
     let app_dir = args.app_dir.clone();
     let device_id = args.device_id.clone();
+    let logs_only = matches!(args.command, Some(Command::Logs));
 
-    tokio::spawn(async move {
-        if let Err(e) = daemon.run(&app_dir, device_id.as_deref(), rx_cmd).await {
-            log::error!("Flutter daemon error: {}", e);
-        }
-    });
+    if let Some(uri) = &args.vm_uri {
+        // Nothing to type reload commands into, r/R go through the VM service instead
+        app_state.tx_flutter_command = None;
+        let _ = tx_uri.send(uri.replacen("http://", "ws://", 1)).await;
+    }
+    let run_daemon = args.vm_uri.is_none();
+
+    let rx_cmd = Arc::new(tokio::sync::Mutex::new(rx_cmd));
+    if logs_only || run_daemon {
+        supervisor.spawn(
+            "Flutter daemon",
+            supervisor::RestartPolicy::OnFailure { max_restarts: 3 },
+            move || {
+                let daemon = daemon.clone();
+                let app_dir = app_dir.clone();
+                let device_id = device_id.clone();
+                let rx_cmd = rx_cmd.clone();
+                Box::pin(async move {
+                    if logs_only {
+                        FlutterDaemon::stream_logs(&app_dir, device_id.as_deref()).await
+                    } else {
+                        let mut rx_cmd = rx_cmd.lock().await;
+                        daemon
+                            .run(&app_dir, device_id.as_deref(), &mut rx_cmd)
+                            .await
+                    }
+                })
+            },
+        );
+    }
 
-    // Populate file list and tree
-    app_state.build_file_tree();
+    // Restore UI state from the previous session of this project
+    if let Some(state) = persistence::load(&app_state.project_root) {
+        log::info!("Restoring UI state from previous session");
+        app_state.restore_persisted_state(state);
+    }
+    let saved_breakpoints = breakpoints::load(&app_state.project_root);
+    if !saved_breakpoints.is_empty() {
+        log::info!("Restoring {} breakpoints", saved_breakpoints.len());
+        app_state.restore_breakpoints(saved_breakpoints);
+    }
+    let config = config::load(&app_state.project_root);
+    app_state.snippets = config.snippets;
+    app_state.vim_keys = config.vim.unwrap_or(false);
+    app_state.keymap = keymap::KeyMap::new(&config.keys, app_state.vim_keys);
+    ui::theme::set_theme(config.theme.unwrap_or_default());
+    let accessible = args.accessible.or(config.accessible).unwrap_or(false);
+    // Without colors, the accessible markers carry the selection and focus cues.
+    ui::set_accessible(accessible || color_support == ui::palette::ColorSupport::Monochrome);
+    // Breakpoints and the file asked for on the command line come on top
+    app_state.restore_breakpoints(args.breakpoints.clone());
+    if let Some(open) = &args.open {
+        let (file, line) = open
+            .rsplit_once(':')
+            .and_then(|(file, line)| Some((file, line.parse().ok()?)))
+            .unwrap_or((open.as_str(), 1));
+        app_state.open_source_file(file.trim_start_matches("./"), line);
+    }
+    if logs_only {
+        app_state.logs_only = true;
+        app_state.show_logs = true;
+        app_state.focus = app_state::Focus::Logs;
+    }
 
     // VM Service Task
-    tokio::spawn(async move {
-        if let Some(uri) = rx_uri.recv().await {
-            log::info!("Connected to VM Service at: {}", uri);
-            // Connect and fetch tree
-            if let Ok((client, mut rx_event)) = VmServiceClient::connect(&uri).await {
-                log::info!("VM Service Client connected");
-                let _ = tx_vm_client.send(client.clone()).await;
-
-                // Subscribe to streams
-                if let Err(e) = client.stream_listen("Debug").await {
-                    log::error!("Failed to subscribe to Debug stream: {}", e);
-                } else {
-                    log::info!("Subscribed to Debug stream");
-                }
-                if let Err(e) = client.stream_listen("Isolate").await {
-                    log::error!("Failed to subscribe to Isolate stream: {}", e);
-                } else {
-                    log::info!("Subscribed to Isolate stream");
-                }
-                if let Err(e) = client.stream_listen("Extension").await {
-                    log::error!("Failed to subscribe to Extension stream: {}", e);
-                } else {
-                    log::info!("Subscribed to Extension stream");
-                }
+    // Without the daemon nothing else forwards the app's print() output
+    let forward_stdio = args.vm_uri.is_some();
+    let vm_inputs = Arc::new(tokio::sync::Mutex::new(VmTaskInputs {
+        rx_uri,
+        rx_selected_isolate,
+        rx_details_request,
+        rx_details_prefetch,
+        rx_properties_request,
+        rx_children_request,
+        rx_summary_tree,
+        rx_refresh,
+        last_uri: None,
+        pause_on_attach: args.pause_on_attach,
+    }));
+    let vm_outputs = VmTaskOutputs {
+        tx_vm_client,
+        tx_connection_status,
+        tx_app_info: tx_app_info.clone(),
+        tx_isolates,
+        tx_tree,
+        tx_details,
+        tx_properties,
+        tx_children,
+        tx_debug_event,
+        tx_services,
+        tx_rebuilds,
+        tx_app_log,
+        tx_details_expired,
+        tx_toggles: tx_toggles.clone(),
+        tx_device_selection,
+        tx_inspector_isolates,
+        tx_refresh_rate,
+        tx_timeline,
+    };
+    supervisor.spawn(
+        "VM service",
+        supervisor::RestartPolicy::OnFailure { max_restarts: 5 },
+        move || {
+            let vm_inputs = vm_inputs.clone();
+            let VmTaskOutputs {
+                tx_vm_client,
+                tx_connection_status,
+                tx_app_info,
+                tx_isolates,
+                tx_tree,
+                tx_details,
+                tx_properties,
+                tx_children,
+                tx_debug_event,
+                tx_services,
+                tx_rebuilds,
+                tx_app_log,
+                tx_details_expired,
+                tx_toggles,
+                tx_device_selection,
+                tx_inspector_isolates,
+                tx_refresh_rate,
+                tx_timeline,
+            } = vm_outputs.clone();
+            Box::pin(async move {
+                // Held for the task's lifetime, a restarted task picks up where it left off
+                let mut inputs = vm_inputs.lock().await;
+                let VmTaskInputs {
+                    rx_uri,
+                    rx_selected_isolate,
+                    rx_details_request,
+                    rx_details_prefetch,
+                    rx_properties_request,
+                    rx_children_request,
+                    rx_summary_tree,
+                    rx_refresh,
+                    last_uri,
+                    pause_on_attach,
+                } = &mut *inputs;
+                let mut uri = match last_uri.clone() {
+                    Some(uri) => uri,
+                    None => match rx_uri.recv().await {
+                        Some(uri) => uri,
+                        None => return Ok(()),
+                    },
+                };
+                // Reconnects with backoff whenever the session ends, e.g. because the device
+                // disconnected. A new URI from the daemon (the app was restarted) is used right away.
+                let mut attempt = 0u32;
+                loop {
+                    *last_uri = Some(uri.clone());
+                    log::info!("Connecting to VM Service at: {}", uri);
+                    let status = if attempt == 0 {
+                        app_state::ConnectionStatus::Connecting
+                    } else {
+                        app_state::ConnectionStatus::Reconnecting { attempt }
+                    };
+                    let _ = tx_connection_status.send(status).await;
+                    match VmServiceClient::connect(&uri).await {
+                        Ok((client, mut rx_event)) => {
+                            log::info!("VM Service Client connected");
+                            let _ = tx_vm_client.send(client.clone()).await;
+                            let _ = tx_connection_status
+                                .send(app_state::ConnectionStatus::Connected)
+                                .await;
 
-                if let Ok(vm) = client.get_vm().await {
-                    log::info!("VM fetched: isolates count = {}", vm.isolates.len());
-
-                    // Send isolates to UI
-                    if !vm.isolates.is_empty() {
-                        let _ = tx_isolates.send(vm.isolates.clone()).await;
-
-                        // Wait for selection
-                        let mut current_isolate_id: Option<String> = None;
-                        log::info!("Starting VM Event Loop");
-
-                        loop {
-                            tokio::select! {
-                                Some(event) = rx_event.recv() => {
-                                    // Handle VM Events
-                                    match event.event_kind.as_str() {
-                                        "PauseStart" | "PauseBreakpoint" | "PauseException" | "PauseInterrupted" | "PauseExit" => {
-                                            log::info!("VM Event: {} in {:?}", event.event_kind, event.isolate_id);
-                                            // Fetch stack
-                                            if let Some(isolate_id) = &event.isolate_id {
-                                                if let Ok(stack) = client.get_stack(isolate_id).await {
-                                                    let _ = tx_debug_event.send((app_state::DebugState::Paused {
-                                                        isolate_id: isolate_id.clone(),
-                                                        reason: event.event_kind.clone(),
-                                                    }, Some(stack))).await;
-                                                }
-                                            }
-                                        }
-                                        "Resume" => {
-                                            log::info!("VM Event: Resumed");
-                                            let _ = tx_debug_event.send((app_state::DebugState::Running, None)).await;
+                            // Subscribe to streams
+                            if let Err(e) = client.stream_listen("Debug").await {
+                                log::error!("Failed to subscribe to Debug stream: {}", e);
+                            } else {
+                                log::info!("Subscribed to Debug stream");
+                            }
+                            if let Err(e) = client.stream_listen("Isolate").await {
+                                log::error!("Failed to subscribe to Isolate stream: {}", e);
+                            } else {
+                                log::info!("Subscribed to Isolate stream");
+                            }
+                            if let Err(e) = client.stream_listen("Extension").await {
+                                log::error!("Failed to subscribe to Extension stream: {}", e);
+                            } else {
+                                log::info!("Subscribed to Extension stream");
+                            }
+                            // `flutter run`/`attach` register their reload and restart here
+                            if let Err(e) = client.stream_listen("Service").await {
+                                log::error!("Failed to subscribe to Service stream: {}", e);
+                            }
+                            // dart:developer log() output, which the daemon doesn't forward either
+                            if let Err(e) = client.stream_listen("Logging").await {
+                                log::error!("Failed to subscribe to Logging stream: {}", e);
+                            }
+                            // Recorded continuously for timeline captures
+                            if let Err(e) = client.set_vm_timeline_flags(&timeline::RECORDED_STREAMS).await {
+                                log::error!("Failed to set the recorded timeline streams: {}", e);
+                            }
+                            if let Err(e) = client.stream_listen("Timeline").await {
+                                log::error!("Failed to subscribe to Timeline stream: {}", e);
+                            }
+                            if forward_stdio {
+                                for stream in ["Stdout", "Stderr"] {
+                                    if let Err(e) = client.stream_listen(stream).await {
+                                        log::error!("Failed to subscribe to {} stream: {}", stream, e);
+                                    }
+                                }
+                            }
+
+                            if let Ok(mut vm) = client.get_vm().await {
+                                log::info!("VM fetched: isolates count = {}", vm.isolates.len());
+                                attempt = 0;
+
+                                let vm_target = match (&vm.operating_system, &vm.target_cpu) {
+                                    (Some(os), Some(cpu)) => Some(format!("{} {}", os, cpu)),
+                                    (os, cpu) => os.clone().or(cpu.clone()),
+                                };
+                                let _ = tx_app_info
+                                    .send(app_state::AppInfo {
+                                        // e.g. "3.5.0 (stable) (Tue Jul 30 02:17:59 2024 -0700) on \"android_arm64\""
+                                        dart_version: vm
+                                            .version
+                                            .as_ref()
+                                            .map(|v| v.split(" (").next().unwrap_or(v).to_string()),
+                                        vm_target,
+                                        protocol_version: client.get_version().await.ok(),
+                                        ..Default::default()
+                                    })
+                                    .await;
+
+                                match client.get_display_refresh_rate().await {
+                                    Ok(hz) => {
+                                        log::info!("Display refresh rate: {:.0}Hz", hz);
+                                        let _ = tx_refresh_rate.send(hz).await;
+                                    }
+                                    Err(e) => log::info!("Display refresh rate unknown: {}", e),
+                                }
+
+                                // Pauses from before connecting, like those of an app started with
+                                // `--start-paused`, aren't announced on the Debug stream
+                                let mut already_paused = HashSet::new();
+                                for isolate in &vm.isolates {
+                                    let kind = match client.get_isolate(&isolate.id).await {
+                                        Ok(info) => info.pause_event.map(|event| event.kind),
+                                        Err(_) => None,
+                                    };
+                                    let Some(kind) = kind.filter(|kind| kind.starts_with("Pause")) else {
+                                        continue;
+                                    };
+                                    log::info!("{} is paused on attach ({})", isolate.name, kind);
+                                    let stack = client.get_stack(&isolate.id).await.ok();
+                                    let _ = tx_debug_event.send(app_state::DebugEvent::Paused(app_state::DebugState::Paused {
+                                        isolate_id: isolate.id.clone(),
+                                        reason: kind.clone(),
+                                        breakpoint_id: None,
+                                    }, stack)).await;
+                                    already_paused.insert((isolate.id.clone(), kind));
+                                }
+                                let paused_at_start = already_paused.iter().any(|(_, kind)| kind == "PauseStart");
+
+                                if *pause_on_attach {
+                                    // Stop before the next frame so breakpoints can be set first.
+                                    // Only on the first attach, not after reconnecting.
+                                    *pause_on_attach = false;
+                                    for isolate in &vm.isolates {
+                                        if already_paused.iter().any(|(id, _)| *id == isolate.id) {
+                                            continue;
                                         }
-                                        _ => {
-                                            // log::debug!("VM Event: {}", event.event_kind);
+                                        match client.pause(&isolate.id).await {
+                                            Ok(_) => log::info!("Paused {} on attach", isolate.name),
+                                            Err(e) => {
+                                                log::error!(
+                                                    "Failed to pause {} on attach: {}",
+                                                    isolate.name,
+                                                    e
+                                                )
+                                            }
                                         }
                                     }
                                 }
-                                Some(selected_id) = rx_selected_isolate.recv() => {
-                                    log::info!("VM Task: Received selected isolate ID: {}", selected_id);
-                                    if let Some(isolate_ref) = vm.isolates.iter().find(|i| i.id == selected_id) {
-                                        log::info!("Checking isolate: {}", isolate_ref.name);
-                                        current_isolate_id = Some(isolate_ref.id.clone());
-
-                                        let client = client.clone();
-                                        let isolate_ref = isolate_ref.clone();
-                                        let tx_tree = tx_tree.clone();
-                                        let tx_isolates = tx_isolates.clone();
-                                        let vm_isolates = vm.isolates.clone();
 
-                                        tokio::spawn(async move {
-                                            // Poll for extension
-                                            loop {
-                                                if let Ok(isolate) = client.get_isolate(&isolate_ref.id).await {
-                                                    if let Some(rpcs) = isolate.extension_rpcs {
-                                                        if rpcs.contains(
-                                                            &"ext.flutter.inspector.getRootWidgetSummaryTree"
-                                                                .to_string(),
-                                                        ) {
-                                                            log::info!("Inspector extension found!");
-                                                            break;
+                                // Send isolates to UI
+                                if !vm.isolates.is_empty() {
+                                    let inspector_isolates = client.find_inspector_isolates(&vm.isolates).await;
+                                    let flutter_isolate = vm_service::flutter_isolate(&vm.isolates, &inspector_isolates);
+                                    let _ = tx_isolates.send((vm.isolates.clone(), flutter_isolate)).await;
+                                    // Right after startup the framework may not have registered its
+                                    // extensions yet. An app paused at its start only registers them
+                                    // once started, so the search waits for its first Resume.
+                                    let mut inspector_search: Option<tokio::task::JoinHandle<()>> = None;
+                                    let mut search_on_resume = false;
+                                    if inspector_isolates.is_empty() {
+                                        if paused_at_start {
+                                            search_on_resume = true;
+                                        } else {
+                                            inspector_search = Some(search_inspector_isolates(
+                                                client.clone(),
+                                                vm.isolates.clone(),
+                                                tx_inspector_isolates.clone(),
+                                            ));
+                                        }
+                                    } else {
+                                        let _ = tx_inspector_isolates.send(inspector_isolates).await;
+                                    }
+
+                                    // Wait for selection
+                                    let mut current_isolate_id: Option<String> = None;
+                                    // Waits for the inspector of the selected isolate, then fetches its tree
+                                    let mut inspector_poll: Option<tokio::task::JoinHandle<()>> = None;
+                                    log::info!("Starting VM Event Loop");
+
+                                    loop {
+                                        tokio::select! {
+                                            event = rx_event.recv() => {
+                                                let Some(event) = event else {
+                                                    log::error!("VM Service connection closed");
+                                                    break;
+                                                };
+                                                // Handle VM Events
+                                                match event.event_kind.as_str() {
+                                                    "PauseStart" | "PauseBreakpoint" | "PauseException" | "PauseInterrupted" | "PauseExit" => {
+                                                        log::info!("VM Event: {} in {:?}", event.event_kind, event.isolate_id);
+                                                        // Fetch stack
+                                                        if let Some(isolate_id) = &event.isolate_id {
+                                                            if let Ok(stack) = client.get_stack(isolate_id).await {
+                                                                let breakpoint_id = event.data.get("pauseBreakpoints")
+                                                                    .and_then(|b| b.get(0))
+                                                                    .or_else(|| event.data.get("breakpoint"))
+                                                                    .and_then(|b| b.get("id"))
+                                                                    .and_then(|id| id.as_str())
+                                                                    .map(|id| id.to_string());
+                                                                let _ = tx_debug_event.send(app_state::DebugEvent::Paused(app_state::DebugState::Paused {
+                                                                    isolate_id: isolate_id.clone(),
+                                                                    reason: event.event_kind.clone(),
+                                                                    breakpoint_id,
+                                                                }, Some(stack))).await;
+                                                            }
+                                                        }
+                                                    }
+                                                    "Inspect" => {
+                                                        // Posted when a widget is tapped in select mode
+                                                        if let Some(isolate_id) = event.isolate_id.clone() {
+                                                            let client = client.clone();
+                                                            let tx_device_selection = tx_device_selection.clone();
+                                                            tokio::spawn(async move {
+                                                                match client.get_selected_summary_widget(&isolate_id).await {
+                                                                    Ok(node) => {
+                                                                        if let Some(id) = AppState::get_node_id(&node) {
+                                                                            let _ = tx_device_selection.send(id).await;
+                                                                        }
+                                                                    }
+                                                                    Err(e) => log::error!("Failed to get selected widget: {}", e),
+                                                                }
+                                                            });
+                                                        }
+                                                    }
+                                                    "Resume" => {
+                                                        log::info!("VM Event: Resumed {:?}", event.isolate_id);
+                                                        if std::mem::take(&mut search_on_resume) {
+                                                            inspector_search = Some(search_inspector_isolates(
+                                                                client.clone(),
+                                                                vm.isolates.clone(),
+                                                                tx_inspector_isolates.clone(),
+                                                            ));
+                                                        }
+                                                        if let Some(isolate_id) = event.isolate_id {
+                                                            let _ = tx_debug_event.send(app_state::DebugEvent::Resumed(isolate_id)).await;
                                                         }
                                                     }
+                                                    "IsolateRunnable" => {
+                                                        // e.g. spawned by the app, or the new main isolate of a hot restart
+                                                        let isolate = event.data.get("isolate").cloned()
+                                                            .and_then(|isolate| serde_json::from_value::<vm_service::IsolateRef>(isolate).ok());
+                                                        if let Some(isolate) = isolate {
+                                                            log::info!("VM Event: Isolate {} started", isolate.name);
+                                                            if !vm.isolates.iter().any(|i| i.id == isolate.id) {
+                                                                vm.isolates.push(isolate.clone());
+                                                            }
+                                                            let _ = tx_debug_event.send(app_state::DebugEvent::IsolateStarted(isolate)).await;
+                                                        }
+                                                    }
+                                                    "IsolateExit" => {
+                                                        if let Some(isolate_id) = event.isolate_id {
+                                                            log::info!("VM Event: Isolate {} exited", isolate_id);
+                                                            vm.isolates.retain(|i| i.id != isolate_id);
+                                                            let _ = tx_debug_event.send(app_state::DebugEvent::IsolateExited(isolate_id)).await;
+                                                        }
+                                                    }
+                                                    "ServiceRegistered" | "ServiceUnregistered" => {
+                                                        let service = event.data.get("service").and_then(|s| s.as_str());
+                                                        let method = event.data.get("method").and_then(|m| m.as_str());
+                                                        if let (Some(service), Some(method)) = (service, method) {
+                                                            let registered = (event.event_kind == "ServiceRegistered").then(|| method.to_string());
+                                                            let _ = tx_services.send((service.to_string(), registered)).await;
+                                                        }
+                                                    }
+                                                    "WriteEvent" => {
+                                                        if let Some(text) = vm_logs::decode_write_event(&event.data) {
+                                                            for line in text.lines().filter(|l| !l.trim().is_empty()) {
+                                                                if event.stream_id == "Stderr" {
+                                                                    log::error!("App Error: {}", line);
+                                                                } else {
+                                                                    log::info!("App Output: {}", line);
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    "Logging" => {
+                                                        if let Some(entry) = vm_logs::parse_log_record(&event.data) {
+                                                            let _ = tx_app_log.send(entry);
+                                                        }
+                                                    }
+                                                    "Extension" if matches!(
+                                                        event.data.get("extensionKind").and_then(|k| k.as_str()),
+                                                        Some("Flutter.RebuildDirtyWidgets" | "Flutter.RepaintWidgets")
+                                                    ) => {
+                                                        let kind = event.data.get("extensionKind").and_then(|k| k.as_str()).unwrap_or_default();
+                                                        if let Some(data) = event.data.get("extensionData") {
+                                                            let _ = tx_rebuilds.try_send((kind.to_string(), data.clone()));
+                                                        }
+                                                    }
+                                                    "Extension" => {
+                                                        let update = match event.data.get("extensionKind").and_then(|k| k.as_str()) {
+                                                            Some("Flutter.Frame") => event
+                                                                .data
+                                                                .get("extensionData")
+                                                                .and_then(timeline::FrameTiming::parse)
+                                                                .map(timeline::TimelineUpdate::Frame),
+                                                            Some("Flutter.Error") => Some(timeline::TimelineUpdate::Error),
+                                                            _ => None,
+                                                        };
+                                                        if let Some(update) = update {
+                                                            // Dropped rather than holding up the event loop when the UI lags
+                                                            let _ = tx_timeline.try_send(update);
+                                                        }
+                                                    }
+                                                    "TimelineEvents" => {
+                                                        if let Some(events) = event.data.get("timelineEvents").and_then(|e| e.as_array()) {
+                                                            let _ = tx_timeline.try_send(timeline::TimelineUpdate::Events(events.clone()));
+                                                        }
+                                                    }
+                                                    _ => {
+                                                        // log::debug!("VM Event: {}", event.event_kind);
+                                                    }
                                                 }
-                                                log::info!("Waiting for inspector extension...");
-                                                tokio::time::sleep(Duration::from_secs(1)).await;
                                             }
+                                            Some(selected_id) = rx_selected_isolate.recv() => {
+                                                log::info!("VM Task: Received selected isolate ID: {}", selected_id);
+                                                if let Some(isolate_ref) = vm.isolates.iter().find(|i| i.id == selected_id) {
+                                                    log::info!("Checking isolate: {}", isolate_ref.name);
+                                                    current_isolate_id = Some(isolate_ref.id.clone());
 
-                                            match client
-                                                .get_root_widget_summary_tree("tui_inspector", &isolate_ref.id)
-                                                .await
-                                            {
-                                                Ok(tree) => {
-                                                    log::info!("Root Widget fetched: {:?}", tree.widget_runtime_type);
-                                                    let _ = tx_tree.send(tree).await;
+                                                    let client = client.clone();
+                                                    let isolate_ref = isolate_ref.clone();
+                                                    let tx_tree = tx_tree.clone();
+                                                    let tx_isolates = tx_isolates.clone();
+                                                    let tx_app_info = tx_app_info.clone();
+                                                    let tx_toggles = tx_toggles.clone();
+                                                    let vm_isolates = vm.isolates.clone();
+                                                    let summary_tree = *rx_summary_tree.borrow();
+
+                                                    // Stop waiting on a previously selected isolate
+                                                    if let Some(poll) = inspector_poll.take() {
+                                                        poll.abort();
+                                                    }
+                                                    inspector_poll = Some(tokio::spawn(async move {
+                                                        // Poll for extension
+                                                        loop {
+                                                            if let Ok(isolate) = client.get_isolate(&isolate_ref.id).await {
+                                                                if let Some(rpcs) = isolate.extension_rpcs {
+                                                                    if rpcs.contains(
+                                                                        &"ext.flutter.inspector.getRootWidgetSummaryTree"
+                                                                            .to_string(),
+                                                                    ) {
+                                                                        log::info!("Inspector extension found!");
+                                                                        // Debug-only extensions are compiled out of profile builds
+                                                                        let build_mode = if rpcs.iter().any(|r| r == "ext.flutter.debugPaint") {
+                                                                            "debug"
+                                                                        } else {
+                                                                            "profile"
+                                                                        };
+                                                                        let _ = tx_app_info.send(app_state::AppInfo {
+                                                                            build_mode: Some(build_mode.to_string()),
+                                                                            ..Default::default()
+                                                                        }).await;
+                                                                        for (method, _) in app_state::SERVICE_TOGGLES {
+                                                                            if !rpcs.iter().any(|r| r == method) {
+                                                                                continue;
+                                                                            }
+                                                                            match client.bool_extension(&isolate_ref.id, method, None).await {
+                                                                                Ok(enabled) => {
+                                                                                    let _ = tx_toggles.send((method.to_string(), app_state::ToggleState::Enabled(enabled))).await;
+                                                                                }
+                                                                                Err(e) => log::error!("Failed to read {}: {}", method, e),
+                                                                            }
+                                                                        }
+                                                                        for (method, parameter, _, _) in app_state::CHOICE_TOGGLES {
+                                                                            if !rpcs.iter().any(|r| r == method) {
+                                                                                continue;
+                                                                            }
+                                                                            match client.value_extension(&isolate_ref.id, method, parameter, None).await {
+                                                                                Ok(value) => {
+                                                                                    let _ = tx_toggles.send((method.to_string(), app_state::ToggleState::Value(value))).await;
+                                                                                }
+                                                                                Err(e) => log::error!("Failed to read {}: {}", method, e),
+                                                                            }
+                                                                        }
+                                                                        if build_mode == "debug" {
+                                                                            for (library, name, _) in app_state::DEBUG_PRINT_TOGGLES {
+                                                                                if let Ok(enabled) = client.library_bool(&isolate_ref.id, library, name, None).await {
+                                                                                    let _ = tx_toggles.send((name.to_string(), app_state::ToggleState::Enabled(enabled))).await;
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                        break;
+                                                                    }
+                                                                }
+                                                            }
+                                                            log::info!("Waiting for inspector extension...");
+                                                            tokio::time::sleep(Duration::from_secs(1)).await;
+                                                        }
+
+                                                        let group = client.next_inspector_group();
+                                                        let tree = if summary_tree {
+                                                            client.get_root_widget_summary_tree(&group, &isolate_ref.id).await
+                                                        } else {
+                                                            client.get_root_widget_tree(&group, &isolate_ref.id).await
+                                                        };
+                                                        match tree {
+                                                            Ok(tree) => {
+                                                                log::info!("Root Widget fetched: {:?}", tree.widget_runtime_type);
+                                                                // The replaced tree's ids die with its group
+                                                                let stale_group = match client.install_inspector_group(&group) {
+                                                                    Some(replaced) => {
+                                                                        let _ = tx_tree.send(TreeFetch::Fetched(Box::new(tree))).await;
+                                                                        replaced
+                                                                    }
+                                                                    None => {
+                                                                        let _ = tx_tree.send(TreeFetch::Superseded).await;
+                                                                        group
+                                                                    }
+                                                                };
+                                                                if let Err(e) = client.dispose_group(&isolate_ref.id, &stale_group).await {
+                                                                    log::error!("Failed to dispose object group {}: {}", stale_group, e);
+                                                                }
+                                                            }
+                                                            Err(e) => {
+                                                                let _ = tx_tree.send(TreeFetch::Failed(e.to_string())).await;
+                                                                // Let the user pick another isolate
+                                                                let _ = tx_isolates.send((vm_isolates, None)).await;
+                                                            }
+                                                        }
+                                                    }));
                                                 }
-                                                Err(e) => {
-                                                    log::error!("Failed to fetch tree: {}", e);
-                                                    let _ = tx_isolates.send(vm_isolates).await;
+                                            }
+                                            Some(object_id) = rx_details_request.recv() => {
+                                                if let Some(isolate_id) = &current_isolate_id {
+                                                    log::info!("VM: Fetching details for {} in isolate {}", object_id, isolate_id);
+                                                    match client.get_details_subtree(isolate_id, &object_id, 2).await {
+                                                        Ok((details, raw)) => {
+                                                            log::info!("VM: Details fetched successfully");
+                                                            let _ = tx_details.send((object_id, details, raw)).await;
+                                                        }
+                                                        Err(e) if vm_service::SentinelError::find(&e).is_some() => {
+                                                            log::warn!("VM: Details for {} unavailable: {}", object_id, e);
+                                                            let _ = tx_details_expired.send(object_id).await;
+                                                        }
+                                                        Err(e) => {
+                                                            log::error!("VM: Failed to fetch details: {}", e);
+                                                        }
+                                                    }
+                                                } else {
+                                                    log::warn!("VM: Received details request but current_isolate_id is None");
                                                 }
                                             }
-                                        });
-                                    }
-                                }
-                                Some(object_id) = rx_details_request.recv() => {
-                                    if let Some(isolate_id) = &current_isolate_id {
-                                        log::info!("VM: Fetching details for {} in isolate {}", object_id, isolate_id);
-                                        match client.get_details_subtree(isolate_id, &object_id, 2).await {
-                                            Ok(details) => {
-                                                log::info!("VM: Details fetched successfully");
-                                                let _ = tx_details.send(details).await;
+                                            Some(object_ids) = rx_details_prefetch.recv() => {
+                                                if let Some(isolate_id) = &current_isolate_id {
+                                                    log::info!("VM: Prefetching details for {} nodes", object_ids.len());
+                                                    let results = client.get_details_subtrees(isolate_id, object_ids, 2, DETAILS_PREFETCH_CONCURRENCY);
+                                                    let tx_details = tx_details.clone();
+                                                    tokio::spawn(async move {
+                                                        futures::pin_mut!(results);
+                                                        while let Some((object_id, result)) = results.next().await {
+                                                            match result {
+                                                                Ok((details, raw)) => {
+                                                                    let _ = tx_details.send((object_id, details, raw)).await;
+                                                                }
+                                                                // Expired ids are reported if the node gets selected
+                                                                Err(e) if vm_service::SentinelError::find(&e).is_some() => {}
+                                                                Err(e) => log::error!("VM: Failed to prefetch details for {}: {}", object_id, e),
+                                                            }
+                                                        }
+                                                    });
+                                                }
                                             }
-                                            Err(e) => {
-                                                log::error!("VM: Failed to fetch details: {}", e);
+                                            Some((details_id, key, object_id)) = rx_properties_request.recv() => {
+                                                if let Some(isolate_id) = &current_isolate_id {
+                                                    match client.get_properties(isolate_id, &object_id).await {
+                                                        Ok(properties) => {
+                                                            let _ = tx_properties.send((details_id, key, properties)).await;
+                                                        }
+                                                        Err(e) => {
+                                                            log::error!("VM: Failed to fetch properties: {}", e);
+                                                            let _ = tx_properties.send((details_id, key, Vec::new())).await;
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                            Some(object_ids) = rx_children_request.recv() => {
+                                                if let Some(isolate_id) = current_isolate_id.clone() {
+                                                    let client = client.clone();
+                                                    let tx_children = tx_children.clone();
+                                                    let summary_tree = *rx_summary_tree.borrow();
+                                                    tokio::spawn(async move {
+                                                        for object_id in object_ids {
+                                                            let children = match client.get_children(&isolate_id, &object_id, summary_tree).await {
+                                                                Ok(children) => children,
+                                                                Err(e) => {
+                                                                    log::error!("VM: Failed to fetch children: {}", e);
+                                                                    Vec::new()
+                                                                }
+                                                            };
+                                                            if tx_children.send((object_id, children)).await.is_err() {
+                                                                break;
+                                                            }
+                                                        }
+                                                    });
+                                                }
+                                            }
+                                            Some(_) = rx_refresh.recv() => {
+                                                log::info!("VM: Refreshing isolates and tree...");
+                                                match client.get_vm().await {
+                                                    Ok(refreshed) => {
+                                                        log::info!("VM: Refreshed VM, isolates: {}", refreshed.isolates.len());
+                                                        // Selections look isolates up in here
+                                                        vm.isolates = refreshed.isolates;
+                                                        // Stay on the inspected isolate while it is alive
+                                                        let inspector_isolates = client.find_inspector_isolates(&vm.isolates).await;
+                                                        let preferred = match current_isolate_id.as_ref().filter(|id| vm.isolates.iter().any(|i| &i.id == *id)) {
+                                                            Some(id) => Some(id.clone()),
+                                                            None => vm_service::flutter_isolate(&vm.isolates, &inspector_isolates),
+                                                        };
+                                                        let _ = tx_isolates.send((vm.isolates.clone(), preferred)).await;
+                                                        let _ = tx_inspector_isolates.send(inspector_isolates).await;
+                                                    }
+                                                    Err(e) => {
+                                                        log::error!("Failed to refresh VM: {}", e);
+                                                    }
+                                                }
+                                            }
+                                            else => {
+                                                break;
                                             }
                                         }
-                                    } else {
-                                        log::warn!("VM: Received details request but current_isolate_id is None");
                                     }
-                                }
-                                Some(_) = rx_refresh.recv() => {
-                                    log::info!("VM: Refreshing isolates and tree...");
-                                    match client.get_vm().await {
-                                        Ok(vm) => {
-                                            log::info!("VM: Refreshed VM, isolates: {}", vm.isolates.len());
-                                            let _ = tx_isolates.send(vm.isolates).await;
-                                        }
-                                        Err(e) => {
-                                            log::error!("Failed to refresh VM: {}", e);
-                                        }
+                                    // Their client is gone, a reconnect starts its own
+                                    for task in [inspector_search, inspector_poll].into_iter().flatten() {
+                                        task.abort();
                                     }
                                 }
-                                else => {
-                                    break;
-                                }
                             }
                         }
+                        Err(e) => log::error!("Failed to connect to VM Service: {}", e),
+                    }
+
+                    attempt += 1;
+                    let delay = Duration::from_secs(1 << attempt.min(5));
+                    let _ = tx_connection_status
+                        .send(app_state::ConnectionStatus::Disconnected {
+                            retry_in: delay.as_secs(),
+                        })
+                        .await;
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        Some(new_uri) = rx_uri.recv() => {
+                            log::info!("VM Service URI changed to {}", new_uri);
+                            uri = new_uri;
+                            attempt = 0;
+                        }
                     }
                 }
-            }
-        }
-    });
+            })
+        },
+    );
+
+    tokio::spawn(supervisor.run(rx_task_restart));
+
+    let isolate_name = args.isolate.clone();
+    let choose_isolate = args.choose_isolate;
 
     // Main Loop
     let mut debounce_deadline: Option<Instant> = None;
+    let mut refresh_deadline: Option<Instant> = None;
+    let mut scripts_isolate: Option<String> = None; // Isolate the script baseline was fetched for
+    let mut last_memory_poll: Option<Instant> = None;
+    let mut last_network_poll: Option<Instant> = None;
+    let mut journal = persistence::Journal::default();
+    let mut last_journal = Instant::now();
+    let mut last_snapshot: Option<Instant> = None;
+    let mut last_tab = app_state.current_tab;
+    // The tree's node ids died with the object group when the Inspector tab was left
+    let mut inspector_group_disposed = false;
+    // Selected before the last input was handled, to tell selections the user made
+    let mut selected_before_input: Option<String> = None;
 
     loop {
+        // A widget the user selected wins over the one of before a restart
+        if app_state.pending_selection.is_some()
+            && app_state.selected_node_id() != selected_before_input
+        {
+            app_state.pending_selection = None;
+        }
+
+        match rx_signal.try_recv() {
+            Ok(ProcessSignal::Terminate) => {
+                log::info!("Terminated by a signal, shutting down");
+                quit_flutter(&app_state).await;
+                break;
+            }
+            Ok(ProcessSignal::Suspend) => suspend(&mut terminal)?,
+            Err(_) => {}
+        }
+
         // Update state from channels
-        if let Ok(tree) = rx_tree.try_recv() {
-            app_state.set_root_node(tree);
-            app_state.connection_status = "Connected".to_string();
+        if let Ok(fetch) = rx_tree.try_recv() {
+            app_state.tree_refreshing = false;
+            match fetch {
+                TreeFetch::Fetched(tree) => {
+                    app_state.set_root_node(*tree);
+                    // Expanded nodes deeper than the summary tree goes
+                    request_children(&mut app_state, &tx_children_request);
+                    app_state.connection_status = app_state::ConnectionStatus::Connected;
+                    app_state.tree_updated_at =
+                        Some(chrono::Local::now().format("%H:%M:%S").to_string());
+                }
+                TreeFetch::Superseded => {}
+                TreeFetch::Failed(e) => log::error!("Failed to fetch tree: {}", e),
+            }
         }
 
-        if let Ok(isolates) = rx_isolates.try_recv() {
+        if let Ok(client) = rx_vm_client.try_recv() {
+            log::info!("Main Loop: Received VM Service Client");
+            if app_state.vm_service_client.is_some() {
+                // Reconnected: whatever was paused belongs to the old session
+                app_state.set_debug_state(app_state::DebugState::Running, None);
+                app_state.pause_requested = false;
+                app_state.remember_selection_for_restart();
+            }
+            app_state.vm_service_client = Some(client);
+        }
+
+        while let Ok(status) = rx_connection_status.try_recv() {
+            app_state.connection_status = status;
+        }
+
+        while let Ok((name, status)) = rx_task_status.try_recv() {
+            app_state.subsystems.insert(name, status);
+        }
+
+        if let Ok((isolates, flutter_isolate)) = rx_isolates.try_recv() {
             app_state.available_isolates = isolates;
-            if app_state.available_isolates.len() > 1 {
-                app_state.show_isolate_selection = true;
-                app_state.focus = app_state::Focus::IsolateSelection;
-            } else if let Some(first) = app_state.available_isolates.first() {
-                // Auto-select if only one
-                let _ = tx_selected_isolate.send(first.id.clone()).await;
+            // A new session has new isolate ids
+            if app_state
+                .inspected_isolate
+                .as_ref()
+                .is_some_and(|inspected| {
+                    !app_state
+                        .available_isolates
+                        .iter()
+                        .any(|isolate| isolate.id == inspected.id)
+                })
+            {
+                app_state.inspected_isolate = None;
+            }
+            // The popup is only a fallback when the isolate can't be told automatically
+            let inspected = app_state.inspected_isolate.as_ref().map(|i| i.id.clone());
+            let auto_selected = if app_state.available_isolates.len() == 1 {
+                Some(0)
+            } else if flutter_isolate.is_some() && flutter_isolate == inspected {
+                // Refreshed, still on the same isolate
+                app_state
+                    .available_isolates
+                    .iter()
+                    .position(|isolate| Some(&isolate.id) == inspected.as_ref())
+            } else if choose_isolate {
+                None
+            } else if let Some(name) = &isolate_name {
+                app_state
+                    .available_isolates
+                    .iter()
+                    .position(|isolate| &isolate.name == name)
+            } else {
+                flutter_isolate.and_then(|id| {
+                    app_state
+                        .available_isolates
+                        .iter()
+                        .position(|isolate| isolate.id == id)
+                })
+            };
+            register_breakpoints(&mut app_state, &tx_breakpoint_added);
+            match auto_selected {
+                Some(index) => {
+                    app_state.selected_isolate_index = index;
+                    inspect_selected_isolate(&mut app_state, &tx_selected_isolate);
+                }
+                None => app_state.open_isolate_selection(),
             }
         }
 
-        if let Ok(details) = rx_details.try_recv() {
-            app_state.selected_node_details = Some(details);
+        while let Ok((id, details, raw)) = rx_details.try_recv() {
+            app_state.scan_layout_warnings(&id, &details);
+            // Late and prefetched responses are only cached until their node is selected
+            if app_state
+                .get_selected_node()
+                .and_then(AppState::get_node_id)
+                .as_ref()
+                == Some(&id)
+            {
+                app_state.set_node_details(details, &raw);
+            }
+            app_state.details_cache.insert(id, raw);
         }
 
-        while let Ok(log_entry) = rx_log.try_recv() {
-            // Check for hot reload/restart completion
-            if log_entry.contains("Reloaded") || log_entry.contains("Restarted") {
+        while let Ok((method, state)) = rx_toggles.try_recv() {
+            app_state.toggle_states.insert(method, state);
+        }
+
+        while let Ok(id) = rx_details_expired.try_recv() {
+            app_state.expired_details_id = Some(id);
+        }
+
+        while let Ok((id, children)) = rx_children.try_recv() {
+            app_state.set_node_children(&id, children);
+            // Single-child chains are expanded in one go, keep loading down them
+            request_children(&mut app_state, &tx_children_request);
+        }
+
+        while let Ok((details_id, key, properties)) = rx_properties.try_recv() {
+            let current_id = app_state
+                .selected_node_details
+                .as_ref()
+                .and_then(AppState::get_node_id);
+            if current_id == Some(details_id) {
+                app_state.set_property_children(&key, &properties);
+            }
+        }
+
+        while let Ok(hz) = rx_refresh_rate.try_recv() {
+            app_state.display_refresh_rate = Some(hz);
+        }
+
+        while let Ok(isolates) = rx_inspector_isolates.try_recv() {
+            // Found after the popup opened: pick it as if it had been there from the start
+            let auto_select = app_state.show_isolate_selection
+                && app_state.inspected_isolate.is_none()
+                && !choose_isolate
+                && isolate_name.is_none();
+            app_state.inspector_isolates = isolates;
+            if auto_select {
+                if let Some(index) = app_state
+                    .available_isolates
+                    .iter()
+                    .position(|isolate| app_state.inspector_isolates.contains(&isolate.id))
+                {
+                    app_state.selected_isolate_index = index;
+                    inspect_selected_isolate(&mut app_state, &tx_selected_isolate);
+                }
+            }
+        }
+
+        while let Ok(id) = rx_device_selection.try_recv() {
+            app_state.focus = app_state::Focus::Tree;
+            if !app_state.select_device_widget(id) {
+                // Probably built since the last refresh
                 let _ = tx_refresh.try_send(());
+                app_state.tree_refreshing = true;
+            }
+        }
+
+        if let Some(id) = app_state.take_device_selection_sync() {
+            sync_device_selection(&app_state, id);
+        }
+
+        if let Some(id) = app_state.take_due_details_request() {
+            log::info!("UI: Requesting details for id: {}", id);
+            let _ = tx_details_request.try_send(id);
+        }
+
+        while let Ok(log_entry) = rx_log.try_recv() {
+            // Check for hot reload/restart completion. Flutter can print several of these
+            // lines for one reload, so the refresh is debounced below.
+            if log_entry.message.contains("Reloaded") || log_entry.message.contains("Restarted") {
+                refresh_deadline = Some(Instant::now() + Duration::from_millis(300));
+                app_state.last_reload = Some((
+                    chrono::Local::now().format("%H:%M:%S").to_string(),
+                    log_entry.message.contains("Restarted"),
+                ));
+                if log_entry.message.contains("Restarted") {
+                    // Everything was loaded anew, there's no scope to tell
+                    app_state.reload_scope = None;
+                    app_state.remember_selection_for_restart();
+                }
+            }
+            let log_entry = log_entry.with_debug_source();
+            if let Some(file) = &mut log_file {
+                if let Err(e) = file.write(&log_entry) {
+                    // Given up on rather than failing (and reporting) every entry after
+                    log_file = None;
+                    log::error!("Failed to write the log file: {}", e);
+                }
             }
             app_state.add_log(log_entry);
         }
 
-        if let Ok(client) = rx_vm_client.try_recv() {
-            log::info!("Main Loop: Received VM Service Client");
-            app_state.vm_service_client = Some(client);
+        while let Ok((service, method)) = rx_services.try_recv() {
+            match method {
+                Some(method) => {
+                    app_state.registered_services.insert(service, method);
+                }
+                None => {
+                    app_state.registered_services.remove(&service);
+                }
+            }
         }
 
-        if let Ok((state, stack)) = rx_debug_event.try_recv() {
-            log::info!("Main Loop: Received Debug Event: {:?}", state);
-            app_state.debug_state = state;
-            if let Some(stack) = stack {
-                app_state.stack_trace = Some(stack);
+        while let Ok((kind, data)) = rx_rebuilds.try_recv() {
+            if !app_state.rebuild_tracking {
+                continue;
+            }
+            if kind == "Flutter.RepaintWidgets" {
+                app_state.repaints.record(&data);
+            } else {
+                app_state.rebuilds.record(&data);
             }
         }
 
-        // Handle File Watcher Events
-        if let Ok(_) = rx_watch.try_recv() {
-            // Reset debounce timer
-            debounce_deadline = Some(Instant::now() + Duration::from_millis(500));
+        while let Ok(info) = rx_app_info.try_recv() {
+            app_state.app_info.merge(info);
         }
 
-        // Check Debounce Timer
-        if let Some(deadline) = debounce_deadline {
-            if Instant::now() >= deadline {
-                debounce_deadline = None;
-                if app_state.auto_reload {
-                    if let Some(tx) = &app_state.tx_flutter_command {
-                        let _ = tx.send("r".to_string()).await;
+        while let Ok((key, isolate_id, vm_id)) = rx_breakpoint_added.try_recv() {
+            if app_state.breakpoints.contains(&key) {
+                app_state
+                    .breakpoint_vm_ids
+                    .entry(key)
+                    .or_default()
+                    .insert(isolate_id, vm_id);
+            } else {
+                // Removed while the VM was still adding it, which left it set there
+                unregister_breakpoint(
+                    &app_state,
+                    key,
+                    std::collections::HashMap::from([(isolate_id, vm_id)]),
+                );
+            }
+        }
+
+        while let Ok(event) = rx_debug_event.try_recv() {
+            log::info!("Main Loop: Received Debug Event: {:?}", event);
+            let (state, stack) = match event {
+                app_state::DebugEvent::Paused(state, stack) => (state, stack),
+                app_state::DebugEvent::Resumed(isolate_id) => {
+                    app_state.isolate_resumed(&isolate_id);
+                    continue;
+                }
+                app_state::DebugEvent::IsolateStarted(isolate) => {
+                    for (bp_id, line) in app_state.enabled_breakpoints() {
+                        add_isolate_breakpoint(
+                            &app_state,
+                            &isolate.id,
+                            bp_id,
+                            line,
+                            &tx_breakpoint_added,
+                        );
                     }
+                    app_state.isolate_started(isolate);
+                    continue;
+                }
+                app_state::DebugEvent::IsolateExited(isolate_id) => {
+                    app_state.isolate_exited(&isolate_id);
+                    continue;
+                }
+            };
+            let special_breakpoint = match &state {
+                app_state::DebugState::Paused {
+                    breakpoint_id: Some(breakpoint_id),
+                    ..
+                } => app_state
+                    .breakpoint_key_for_vm_id(breakpoint_id)
+                    .filter(|key| {
+                        app_state.logpoints.contains_key(*key)
+                            || app_state.breakpoint_conditions.contains_key(*key)
+                    })
+                    .map(|key| key.to_string()),
+                _ => None,
+            };
+
+            if let Some(key) = special_breakpoint {
+                // Logpoints and conditional breakpoints are decided in the background; only a
+                // real pause is sent back to this loop
+                run_breakpoint_actions(&app_state, key, state, stack, tx_breakpoint_pause.clone());
+            } else {
+                // A pause from the "Pause app" control is for freezing the UI, stay put
+                let jump_to_source = matches!(
+                    &state,
+                    app_state::DebugState::Paused { reason, .. }
+                        if reason == "PauseBreakpoint"
+                            || (reason == "PauseInterrupted" && !app_state.pause_requested)
+                            || reason == "PauseException"
+                );
+                let started_paused = matches!(
+                    &state,
+                    app_state::DebugState::Paused { reason, .. } if reason == "PauseStart"
+                );
+                app_state.set_debug_state(state, stack);
+
+                // Bring the paused line into view instead of making the user hunt for it
+                if jump_to_source {
+                    app_state.current_tab = app_state::Tab::Debugger;
+                    app_state.focus = app_state::Focus::DebuggerSource;
+                    navigate_to_frame(&app_state, 0, tx_source_location.clone());
+                }
+                // Started paused: nothing ran yet, so breakpoints are all there is to do
+                if started_paused {
+                    app_state.current_tab = app_state::Tab::Debugger;
                 }
             }
         }
 
-        terminal.draw(|f| ui::draw(f, &app_state))?;
+        while let Ok((key, children)) = rx_locals.try_recv() {
+            app_state.set_local_children(&key, children);
+        }
 
-        if crossterm::event::poll(Duration::from_millis(100))? {
-            match event::read()? {
-                Event::Key(key) => {
-                    if app_state.show_isolate_selection {
-                        match key.code {
-                            KeyCode::Char('q') => break,
-                            KeyCode::Up => app_state.move_isolate_selection(-1),
-                            KeyCode::Down => app_state.move_isolate_selection(1),
-                            KeyCode::Enter => {
-                                if let Some(isolate) = app_state
-                                    .available_isolates
-                                    .get(app_state.selected_isolate_index)
-                                {
-                                    let _ = tx_selected_isolate.send(isolate.id.clone()).await;
-                                    app_state.show_isolate_selection = false;
-                                    app_state.focus = app_state::Focus::Tree;
-                                }
-                            }
-                            _ => {}
+        if let Ok((widget, png)) = rx_screenshot.try_recv() {
+            app_state.screenshot = Some(png.and_then(|png| {
+                screenshot::Screenshot::decode(widget, png).map_err(|e| e.to_string())
+            }));
+        }
+
+        if let Ok(requests) = rx_http_requests.try_recv() {
+            match requests {
+                Ok(requests) => {
+                    if let Some(network) = &mut app_state.network {
+                        network.set_requests(requests);
+                    }
+                }
+                Err(e) => log::error!("Failed to get the HTTP profile: {}", e),
+            }
+        }
+        if let Ok(metrics) = rx_isolate_metrics.try_recv() {
+            app_state.isolate_metrics = Some(metrics);
+            app_state.isolate_metrics_at =
+                Some(chrono::Local::now().format("%H:%M:%S").to_string());
+        }
+        if let Ok((id, request)) = rx_http_details.try_recv() {
+            if let Some(details) = app_state
+                .network
+                .as_mut()
+                .and_then(|network| network.details.as_mut())
+                .filter(|details| details.id == id)
+            {
+                details.request = Some(request);
+            }
+        }
+
+        if let Ok(report) = rx_http_replay.try_recv() {
+            if let Some(replay) = &mut app_state.http_replay {
+                replay.report = Some(report);
+            }
+        }
+
+        while let Ok((name, output)) = rx_eval_result.try_recv() {
+            if let Some(result) = app_state
+                .eval_result
+                .as_mut()
+                .filter(|result| result.name == name)
+            {
+                result.output = Some(output);
+            }
+        }
+
+        let inspected = app_state.inspected_isolate.as_ref().map(|i| i.id.clone());
+        if inspected.is_some() && inspected != scripts_isolate {
+            // The baseline the next hot reload is compared against
+            scripts_isolate = inspected;
+            fetch_scripts(&app_state, &tx_scripts);
+        }
+        while let Ok((isolate_id, scripts)) = rx_scripts.try_recv() {
+            app_state.set_scripts(isolate_id, scripts);
+        }
+
+        if let Ok(dump) = rx_focus_tree.try_recv() {
+            // Start with the focused node in view
+            app_state.focus_tree_scroll = dump
+                .lines()
+                .position(|line| line.contains("PRIMARY FOCUS"))
+                .map_or(0, |line| line.saturating_sub(5));
+            app_state.focus_tree = Some(dump);
+        }
+
+        if let Some(sync) = &mut editor_sync {
+            if let Some((file, line)) = sync.take_goto() {
+                if app_state.select_widget_at(&file, line) {
+                    app_state.current_tab = app_state::Tab::Inspector;
+                    app_state.focus = app_state::Focus::Tree;
+                } else {
+                    log::info!("No widget created at {}:{}", file.display(), line);
+                }
+            }
+            sync.write_selection(
+                app_state
+                    .get_selected_node()
+                    .and_then(|node| node.creation_location.as_ref()),
+            );
+        }
+
+        if let Ok((file_uri, line)) = rx_source_location.try_recv() {
+            app_state.show_source_location(&file_uri, line);
+        }
+
+        // Handle File Watcher Events
+        if rx_watch.try_recv().is_ok() {
+            // Reset debounce timer
+            debounce_deadline = Some(Instant::now() + Duration::from_millis(500));
+        }
+
+        // Check Debounce Timer
+        if let Some(deadline) = debounce_deadline {
+            if Instant::now() >= deadline {
+                debounce_deadline = None;
+                if app_state.auto_reload {
+                    hot_reload(&app_state, false).await;
+                }
+            }
+        }
+
+        if let Some(deadline) = refresh_deadline {
+            if Instant::now() >= deadline {
+                refresh_deadline = None;
+                let _ = tx_refresh.try_send(());
+                app_state.tree_refreshing = true;
+                if app_state
+                    .last_reload
+                    .as_ref()
+                    .is_some_and(|(_, restart)| !restart)
+                {
+                    fetch_scripts(&app_state, &tx_scripts);
+                }
+            }
+        }
+
+        if app_state.init_current_tab() && app_state.current_tab == app_state::Tab::Profiler {
+            // Samples are only taken from here on
+            enable_profiler(&app_state);
+        }
+        if app_state.current_tab != last_tab {
+            if last_tab == app_state::Tab::Inspector {
+                inspector_group_disposed = dispose_inspector_group(&app_state).is_some();
+            } else if app_state.current_tab == app_state::Tab::Inspector && inspector_group_disposed
+            {
+                inspector_group_disposed = false;
+                let _ = tx_refresh.try_send(());
+                app_state.tree_refreshing = true;
+            }
+            last_tab = app_state.current_tab;
+        }
+
+        if last_journal.elapsed() >= JOURNAL_INTERVAL {
+            last_journal = Instant::now();
+            journal_state(&app_state, &mut journal);
+        }
+
+        if app_state.current_tab == app_state::Tab::Network
+            && last_network_poll.is_none_or(|at| at.elapsed() >= NETWORK_POLL_INTERVAL)
+        {
+            last_network_poll = Some(Instant::now());
+            fetch_http_profile(&app_state, &tx_http_requests);
+        }
+
+        if app_state.current_tab == app_state::Tab::Memory
+            && last_memory_poll.is_none_or(|at| at.elapsed() >= MEMORY_POLL_INTERVAL)
+        {
+            last_memory_poll = Some(Instant::now());
+            poll_isolate_memory(&app_state, &tx_memory);
+            fetch_allocation_profile(&app_state, false, &tx_allocations);
+        }
+        while let Ok(samples) = rx_memory.try_recv() {
+            app_state.set_isolate_memory(samples);
+        }
+        while let Ok(allocations) = rx_allocations.try_recv() {
+            if let Some(memory) = &mut app_state.memory {
+                memory.allocations = Some(allocations);
+            }
+        }
+        while let Ok(capture) = rx_timeline_capture.try_recv() {
+            if let Some(performance) = &mut app_state.performance {
+                performance.set_timeline(capture);
+            }
+        }
+        while let Ok(profile) = rx_profile.try_recv() {
+            if let Some(profiler) = &mut app_state.profiler {
+                profiler.set_profile(profile);
+            }
+        }
+        while let Ok(update) = rx_timeline.try_recv() {
+            if let (timeline::TimelineUpdate::Frame(frame), Some(performance)) =
+                (&update, &mut app_state.performance)
+            {
+                performance.push_frame(*frame);
+            }
+            if let Some(reason) = app_state.timeline.record(update) {
+                save_timeline_capture(&app_state, &reason);
+            }
+        }
+
+        if let Some(interval) = app_state.snapshot_interval {
+            if last_snapshot.is_none_or(|at| at.elapsed() >= interval) {
+                last_snapshot = Some(Instant::now());
+                take_snapshot(&app_state, &tx_snapshot);
+            }
+        } else {
+            last_snapshot = None;
+        }
+        while let Ok(snapshot) = rx_snapshot.try_recv() {
+            app_state.snapshots.push(snapshot);
+        }
+
+        app_state.spinner_tick = app_state.spinner_tick.wrapping_add(1);
+        terminal.draw(|f| ui::draw(f, &app_state))?;
+        selected_before_input = app_state.selected_node_id();
+
+        if crossterm::event::poll(Duration::from_millis(100))? {
+            match event::read()? {
+                Event::Key(key) => {
+                    // Raw mode turns Ctrl+C and Ctrl+Z into keys instead of signals, they
+                    // work the same from any popup or input
+                    if key.modifiers.contains(event::KeyModifiers::CONTROL) {
+                        match key.code {
+                            KeyCode::Char('c') => {
+                                quit_flutter(&app_state).await;
+                                break;
+                            }
+                            KeyCode::Char('z') => {
+                                suspend(&mut terminal)?;
+                                continue;
+                            }
+                            // Ctrl+arrows resize the pane in focus
+                            KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                                let delta = match key.code {
+                                    KeyCode::Right | KeyCode::Down => 1,
+                                    _ => -1,
+                                };
+                                let vertical = matches!(key.code, KeyCode::Up | KeyCode::Down);
+                                if app_state.resize_focused_pane(delta, vertical) {
+                                    continue;
+                                }
+                            }
+                            _ => {}
                         }
-                    } else if app_state.focus == app_state::Focus::Search {
+                    }
+                    // The isolate popup only takes the keys it needs, so reload, quit etc.
+                    // keep working while it is open
+                    if app_state.show_isolate_selection
+                        && app_state.focus != app_state::Focus::IsolateLabelInput
+                        && matches!(
+                            key.code,
+                            KeyCode::Up
+                                | KeyCode::Down
+                                | KeyCode::Enter
+                                | KeyCode::Esc
+                                | KeyCode::Char('n')
+                        )
+                    {
                         match key.code {
                             KeyCode::Esc => {
-                                app_state.focus = app_state::Focus::Tree;
-                            }
-                            KeyCode::Enter => {
-                                if key.modifiers.contains(event::KeyModifiers::SHIFT) {
-                                    app_state.prev_match();
+                                if app_state.inspected_isolate.is_some() {
+                                    app_state.show_isolate_selection = false;
+                                    app_state.focus = app_state::Focus::Tree;
                                 } else {
-                                    app_state.next_match();
+                                    app_state.selected_isolate_index = 0;
+                                    inspect_selected_isolate(&mut app_state, &tx_selected_isolate);
                                 }
                             }
-                            KeyCode::Char(c) => {
-                                app_state.search_query.push(c);
-                                app_state.perform_search();
+                            KeyCode::Up => app_state.move_isolate_selection(-1),
+                            KeyCode::Down => app_state.move_isolate_selection(1),
+                            KeyCode::Enter => {
+                                inspect_selected_isolate(&mut app_state, &tx_selected_isolate);
+                            }
+                            KeyCode::Char('n') => app_state.edit_isolate_label(),
+                            _ => {}
+                        }
+                    } else if app_state.focus == app_state::Focus::IsolateLabelInput {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app_state.isolate_label_input.clear();
+                                app_state.focus = app_state::Focus::IsolateSelection;
                             }
+                            KeyCode::Enter => app_state.apply_isolate_label(),
+                            KeyCode::Char(c) => app_state.isolate_label_input.push(c),
                             KeyCode::Backspace => {
-                                app_state.search_query.pop();
-                                app_state.perform_search();
+                                app_state.isolate_label_input.pop();
                             }
                             _ => {}
                         }
-                    } else if app_state.focus == app_state::Focus::DebuggerSource {
+                    } else if app_state.http_replay.is_some() {
                         match key.code {
-                            KeyCode::Esc => {
-                                app_state.focus = app_state::Focus::DebuggerFiles;
+                            KeyCode::Esc | KeyCode::Enter => app_state.http_replay = None,
+                            KeyCode::Up => {
+                                app_state.http_replay_scroll =
+                                    app_state.http_replay_scroll.saturating_sub(1);
                             }
-                            KeyCode::Char('b') => {
-                                if let Some(line_idx) = app_state.source_selected_line {
-                                    if let Some(path) = &app_state.open_file_path {
-                                        let line = line_idx + 1;
-                                        let bp_id = format!("{}:{}", path, line);
-
-                                        let is_existing = app_state.breakpoints.contains(&bp_id);
-                                        if is_existing {
-                                            app_state.breakpoints.remove(&bp_id);
-                                            // TODO: Send removeBreakpoint to VM
-                                        } else {
-                                            app_state.breakpoints.insert(bp_id.clone());
-                                            // Send addBreakpoint to VM
-                                            if let Some(client) = &app_state.vm_service_client {
-                                                let client = client.clone();
-                                                if let Some(isolate) = app_state
-                                                    .available_isolates
-                                                    .get(app_state.selected_isolate_index)
-                                                {
-                                                    let isolate_id = isolate.id.clone();
-                                                    let full_path =
-                                                        app_state.project_root.join(path);
-                                                    let script_uri = format!(
-                                                        "file://{}",
-                                                        full_path.to_string_lossy()
-                                                    );
-
-                                                    log::info!("Attempting to set breakpoint at {} line {}", script_uri, line);
-
-                                                    tokio::spawn(async move {
-                                                        match client
-                                                            .add_breakpoint_with_script_uri(
-                                                                &isolate_id,
-                                                                &script_uri,
-                                                                line,
-                                                            )
-                                                            .await
-                                                        {
-                                                            Ok(response) => {
-                                                                log::info!(
-                                                                    "Added breakpoint at {}:{}",
-                                                                    script_uri,
-                                                                    line
-                                                                );
-                                                                log::info!(
-                                                                    "VM Response: {:?}",
-                                                                    response
-                                                                );
-                                                            }
-                                                            Err(e) => log::error!(
-                                                                "Failed to add breakpoint: {}",
-                                                                e
-                                                            ),
-                                                        }
-                                                    });
-                                                }
-                                            }
+                            KeyCode::Down => app_state.http_replay_scroll += 1,
+                            KeyCode::PageUp => {
+                                app_state.http_replay_scroll =
+                                    app_state.http_replay_scroll.saturating_sub(10);
+                            }
+                            KeyCode::PageDown => app_state.http_replay_scroll += 10,
+                            _ => {}
+                        }
+                    } else if app_state.show_screenshot {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('P') => app_state.show_screenshot = false,
+                            KeyCode::Char('s') => {
+                                if let Some(Ok(screenshot)) = &app_state.screenshot {
+                                    match screenshot.save(&app_state.project_root) {
+                                        Ok(path) => {
+                                            log::info!("Saved screenshot to {}", path.display())
                                         }
+                                        Err(e) => log::error!("Failed to save screenshot: {}", e),
                                     }
-                                } else {
-                                    log::warn!("Cannot toggle breakpoint: No line selected. Please open a file and select a line.");
                                 }
                             }
-                            KeyCode::F(5) => {
-                                // Resume
-                                if let Some(client) = &app_state.vm_service_client {
-                                    let client = client.clone();
-                                    if let Some(isolate) = app_state
-                                        .available_isolates
-                                        .get(app_state.selected_isolate_index)
-                                    {
-                                        let isolate_id = isolate.id.clone();
-                                        tokio::spawn(async move {
-                                            let _ = client.resume(&isolate_id, None).await;
-                                        });
+                            KeyCode::Char('r') => take_screenshot(&mut app_state, &tx_screenshot),
+                            _ => {}
+                        }
+                    } else if app_state.eval_result.is_some() {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Enter => app_state.eval_result = None,
+                            KeyCode::Up => {
+                                app_state.eval_result_scroll =
+                                    app_state.eval_result_scroll.saturating_sub(1);
+                            }
+                            KeyCode::Down => app_state.eval_result_scroll += 1,
+                            KeyCode::PageUp => {
+                                app_state.eval_result_scroll =
+                                    app_state.eval_result_scroll.saturating_sub(10);
+                            }
+                            KeyCode::PageDown => app_state.eval_result_scroll += 10,
+                            KeyCode::Char('y') => {
+                                if let Some(Some(Ok(output))) =
+                                    app_state.eval_result.as_ref().map(|r| &r.output)
+                                {
+                                    if let Err(e) = clipboard::copy(output) {
+                                        log::error!("Failed to copy to the clipboard: {}", e);
                                     }
                                 }
                             }
-                            KeyCode::F(10) => {
-                                // Step Over
-                                if let Some(client) = &app_state.vm_service_client {
-                                    let client = client.clone();
-                                    if let Some(isolate) = app_state
-                                        .available_isolates
-                                        .get(app_state.selected_isolate_index)
-                                    {
-                                        let isolate_id = isolate.id.clone();
-                                        tokio::spawn(async move {
-                                            let _ = client.resume(&isolate_id, Some("Over")).await;
-                                        });
-                                    }
+                            _ => {}
+                        }
+                    } else if app_state.show_command_palette {
+                        match key.code {
+                            KeyCode::Esc => app_state.show_command_palette = false,
+                            KeyCode::Up => {
+                                app_state.palette_selected_index =
+                                    app_state.palette_selected_index.saturating_sub(1);
+                            }
+                            KeyCode::Down => {
+                                app_state.palette_selected_index =
+                                    (app_state.palette_selected_index + 1)
+                                        .min(app_state.palette_snippets().len().saturating_sub(1));
+                            }
+                            KeyCode::Enter => {
+                                let snippet = app_state
+                                    .palette_snippets()
+                                    .get(app_state.palette_selected_index)
+                                    .map(|snippet| (*snippet).clone());
+                                if let Some(snippet) = snippet {
+                                    app_state.show_command_palette = false;
+                                    run_snippet(&mut app_state, snippet, &tx_eval_result);
                                 }
                             }
-                            KeyCode::F(11) => {
-                                // Step Into
-                                if let Some(client) = &app_state.vm_service_client {
-                                    let client = client.clone();
-                                    if let Some(isolate) = app_state
-                                        .available_isolates
-                                        .get(app_state.selected_isolate_index)
+                            KeyCode::Char(c) => {
+                                app_state.palette_query.push(c);
+                                app_state.palette_selected_index = 0;
+                            }
+                            KeyCode::Backspace => {
+                                app_state.palette_query.pop();
+                                app_state.palette_selected_index = 0;
+                            }
+                            _ => {}
+                        }
+                    } else if app_state.show_app_bar_menu {
+                        match key.code {
+                            KeyCode::Esc => app_state.show_app_bar_menu = false,
+                            KeyCode::Up => app_state.move_app_bar_menu_selection(-1),
+                            KeyCode::Down => app_state.move_app_bar_menu_selection(1),
+                            KeyCode::Enter => {
+                                let button = app_state
+                                    .app_bar_overflow
+                                    .borrow()
+                                    .get(app_state.app_bar_menu_selected_index)
+                                    .copied();
+                                if let Some(button) = button {
+                                    if press_app_bar_button(&mut app_state, button, &tx_refresh)
+                                        .await
                                     {
-                                        let isolate_id = isolate.id.clone();
-                                        tokio::spawn(async move {
-                                            let _ = client.resume(&isolate_id, Some("Into")).await;
-                                        });
+                                        quit_flutter(&app_state).await;
+                                        break;
                                     }
                                 }
                             }
                             _ => {}
                         }
-                    } else {
+                    } else if app_state.show_toggles {
                         match key.code {
-                            KeyCode::Char('1') => {
-                                app_state.current_tab = app_state::Tab::Inspector;
+                            KeyCode::Esc | KeyCode::Char('o') => {
+                                app_state.show_toggles = false;
                             }
-                            KeyCode::Char('2') => {
-                                app_state.current_tab = app_state::Tab::Debugger;
+                            KeyCode::Up => {
+                                app_state.toggles_selected_index =
+                                    app_state.toggles_selected_index.saturating_sub(1);
                             }
-                            KeyCode::Char('l') => {
-                                app_state.show_logs = !app_state.show_logs;
+                            KeyCode::Down => {
+                                app_state.toggles_selected_index =
+                                    (app_state.toggles_selected_index + 1)
+                                        .min(app_state::TOGGLE_COUNT - 1);
                             }
-                            KeyCode::Char('q') => {
-                                if let Some(tx) = &app_state.tx_flutter_command {
-                                    let _ = tx.send("q".to_string()).await;
-                                }
-                                break;
+                            KeyCode::Enter | KeyCode::Char(' ') => {
+                                toggle_service_extension(&app_state, &tx_toggles);
                             }
-                            KeyCode::Char('r') => {
-                                if let Some(tx) = &app_state.tx_flutter_command {
-                                    let _ = tx.send("r".to_string()).await;
+                            KeyCode::Char('q') => break,
+                            KeyCode::Char(c) => {
+                                // Each row's own key flips it without moving there first
+                                if let Some(index) = app_state::TOGGLE_KEYS
+                                    [..app_state::TOGGLE_COUNT]
+                                    .iter()
+                                    .position(|key| *key == c)
+                                {
+                                    app_state.toggles_selected_index = index;
+                                    toggle_service_extension(&app_state, &tx_toggles);
                                 }
                             }
-                            KeyCode::Char('R') => {
-                                if let Some(tx) = &app_state.tx_flutter_command {
-                                    let _ = tx.send("R".to_string()).await;
-                                }
+                            _ => {}
+                        }
+                    } else if app_state.show_rebuild_stats {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('B') => {
+                                app_state.show_rebuild_stats = false;
                             }
-                            KeyCode::Char('a') => {
-                                app_state.auto_reload = !app_state.auto_reload;
+                            KeyCode::Up => {
+                                app_state.rebuild_stats_selected_index =
+                                    app_state.rebuild_stats_selected_index.saturating_sub(1);
                             }
-                            KeyCode::Char('f') => {
-                                if app_state.focus == app_state::Focus::Tree {
-                                    app_state.focus_selected_node();
-                                }
+                            KeyCode::Down => {
+                                app_state.rebuild_stats_selected_index =
+                                    (app_state.rebuild_stats_selected_index + 1)
+                                        .min(app_state.rebuild_stats().len().saturating_sub(1));
                             }
-                            KeyCode::Char('/') => {
-                                if app_state.focus == app_state::Focus::DebuggerFiles {
-                                    app_state.focus = app_state::Focus::DebuggerSearch;
-                                    app_state.debugger_search_query.clear();
-                                } else {
-                                    app_state.focus = app_state::Focus::Search;
-                                    app_state.search_query.clear();
-                                }
+                            KeyCode::Char('s') => {
+                                app_state.rebuild_stats_sort = app_state.rebuild_stats_sort.next();
+                                app_state.rebuild_stats_selected_index = 0;
                             }
-                            KeyCode::Tab => app_state.cycle_focus(),
-                            KeyCode::Esc => {
-                                if app_state.focus == app_state::Focus::DebuggerSearch {
-                                    app_state.focus = app_state::Focus::DebuggerFiles;
-                                } else if app_state.focus == app_state::Focus::Search {
-                                    app_state.focus = app_state::Focus::Tree;
-                                } else if app_state.focus == app_state::Focus::DebuggerSource {
-                                    app_state.focus = app_state::Focus::DebuggerFiles;
-                                }
+                            KeyCode::Enter => app_state.jump_to_rebuild_stat(),
+                            KeyCode::Char('c') => {
+                                // Measure from scratch, e.g. after a hot reload
+                                app_state.rebuilds.reset_counts();
+                                app_state.repaints.reset_counts();
+                                app_state.rebuild_stats_selected_index = 0;
                             }
-                            KeyCode::Char(c)
-                                if app_state.focus == app_state::Focus::DebuggerSearch =>
-                            {
-                                app_state.debugger_search_query.push(c);
-                                app_state.perform_debugger_search();
+                            KeyCode::Char('T') => toggle_rebuild_tracking(&mut app_state),
+                            KeyCode::Char('q') => break,
+                            _ => {}
+                        }
+                    } else if app_state.show_layout_warnings {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('V') => {
+                                app_state.show_layout_warnings = false;
                             }
-                            KeyCode::Backspace
-                                if app_state.focus == app_state::Focus::DebuggerSearch =>
-                            {
-                                app_state.debugger_search_query.pop();
-                                app_state.perform_debugger_search();
+                            KeyCode::Up => {
+                                app_state.layout_warnings_selected_index =
+                                    app_state.layout_warnings_selected_index.saturating_sub(1);
                             }
-                            KeyCode::Enter
-                                if app_state.focus == app_state::Focus::DebuggerSearch =>
-                            {
-                                app_state.next_debugger_match();
+                            KeyCode::Down => {
+                                app_state.layout_warnings_selected_index =
+                                    (app_state.layout_warnings_selected_index + 1)
+                                        .min(app_state.layout_warnings.len().saturating_sub(1));
                             }
-                            KeyCode::Char('n')
-                                if app_state.focus == app_state::Focus::DebuggerFiles =>
-                            {
-                                app_state.next_debugger_match();
+                            KeyCode::Enter => app_state.jump_to_layout_warning(),
+                            KeyCode::Char('q') => break,
+                            _ => {}
+                        }
+                    } else if app_state.show_reload_scope {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('U') => {
+                                app_state.show_reload_scope = false;
                             }
-                            KeyCode::Char('N')
-                                if app_state.focus == app_state::Focus::DebuggerFiles =>
-                            {
-                                app_state.previous_debugger_match();
+                            KeyCode::Up => {
+                                app_state.reload_scope_scroll =
+                                    app_state.reload_scope_scroll.saturating_sub(1);
                             }
-                            KeyCode::Up => match app_state.focus {
-                                app_state::Focus::Tree => {
-                                    if app_state.current_tab == app_state::Tab::Inspector {
-                                        app_state.move_selection(-1);
-                                        let (cols, rows) = terminal
-                                            .size()
-                                            .map(|r| (r.width, r.height))
-                                            .unwrap_or((0, 0));
-                                        let tree_height = (rows.saturating_sub(3 + 10)) as usize; // Approx tree height (minus app bar and logs)
-                                        let tree_width = (cols as f32 * 0.75) as usize;
-                                        app_state.update_tree_scroll(tree_height.saturating_sub(2));
-                                        app_state.ensure_horizontal_visibility(
-                                            tree_width.saturating_sub(2),
-                                        );
-
-                                        // Request details
-                                        if let Some(node) = app_state.get_selected_node() {
-                                            if let Some(id) = AppState::get_node_id(node) {
-                                                log::info!("UI: Requesting details for id: {}", id);
-                                                let _ = tx_details_request.try_send(id);
-                                            } else {
-                                                log::warn!(
-                                                    "UI: Selected node has no object_id or value_id"
-                                                );
-                                            }
-                                        } else {
-                                            log::warn!("UI: No node selected");
-                                        }
-                                    }
-                                }
-                                app_state::Focus::Logs => app_state.scroll_logs(-1),
-                                app_state::Focus::DebuggerFiles => {
-                                    app_state.move_debugger_selection(-1);
-                                    let (cols, rows) = terminal
-                                        .size()
-                                        .map(|r| (r.width, r.height))
-                                        .unwrap_or((0, 0));
-                                    // Calculate debugger tree height.
-                                    // Layout is: 20% width. Height is full area minus borders?
-                                    // In debugger.rs: chunks[0] is File Explorer.
-                                    // We stored height in app_state.debugger_tree_height
-                                    let tree_height = *app_state.debugger_tree_height.borrow();
-                                    app_state
-                                        .update_debugger_tree_scroll(tree_height.saturating_sub(2));
-                                }
-                                app_state::Focus::DebuggerSource => {
-                                    if let Some(current) = app_state.source_selected_line {
-                                        if current > 0 {
-                                            app_state.source_selected_line = Some(current - 1);
-                                            if current - 1 < app_state.source_scroll_offset {
-                                                app_state.source_scroll_offset = current - 1;
-                                            }
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            },
-                            KeyCode::Down => match app_state.focus {
-                                app_state::Focus::Tree => {
-                                    if app_state.current_tab == app_state::Tab::Inspector {
-                                        app_state.move_selection(1);
-                                        let (cols, rows) = terminal
-                                            .size()
-                                            .map(|r| (r.width, r.height))
-                                            .unwrap_or((0, 0));
-                                        let tree_height = (rows.saturating_sub(3 + 10)) as usize; // Approx tree height
-                                        let tree_width = (cols as f32 * 0.75) as usize;
-                                        app_state.update_tree_scroll(tree_height.saturating_sub(2));
-                                        app_state.ensure_horizontal_visibility(
-                                            tree_width.saturating_sub(2),
-                                        );
-
-                                        // Request details
-                                        if let Some(node) = app_state.get_selected_node() {
-                                            if let Some(id) = AppState::get_node_id(node) {
-                                                log::info!("UI: Requesting details for id: {}", id);
-                                                let _ = tx_details_request.try_send(id);
-                                            } else {
-                                                log::warn!(
-                                                    "UI: Selected node has no object_id or value_id"
-                                                );
-                                            }
-                                        } else {
-                                            log::warn!("UI: No node selected");
-                                        }
-                                    }
+                            KeyCode::Down => app_state.reload_scope_scroll += 1,
+                            KeyCode::PageUp => {
+                                app_state.reload_scope_scroll =
+                                    app_state.reload_scope_scroll.saturating_sub(10);
+                            }
+                            KeyCode::PageDown => app_state.reload_scope_scroll += 10,
+                            KeyCode::Char('q') => break,
+                            _ => {}
+                        }
+                    } else if app_state.show_focus_tree {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('K') => {
+                                app_state.show_focus_tree = false;
+                            }
+                            KeyCode::Up => {
+                                app_state.focus_tree_scroll =
+                                    app_state.focus_tree_scroll.saturating_sub(1);
+                            }
+                            KeyCode::Down => app_state.focus_tree_scroll += 1,
+                            KeyCode::PageUp => {
+                                app_state.focus_tree_scroll =
+                                    app_state.focus_tree_scroll.saturating_sub(10);
+                            }
+                            KeyCode::PageDown => app_state.focus_tree_scroll += 10,
+                            KeyCode::Char('r') => fetch_focus_tree(&app_state, &tx_focus_tree),
+                            KeyCode::Char('q') => break,
+                            _ => {}
+                        }
+                    } else if app_state.show_isolate_metrics {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('M') => {
+                                app_state.show_isolate_metrics = false;
+                            }
+                            KeyCode::F(5) => {
+                                fetch_isolate_metrics(&mut app_state, &tx_isolate_metrics)
+                            }
+                            KeyCode::Char('q') => break,
+                            _ => {}
+                        }
+                    } else if app_state.show_help {
+                        match app_state.keymap.translate(key).code {
+                            KeyCode::Esc | KeyCode::Char('?') | KeyCode::Enter => {
+                                app_state.show_help = false;
+                            }
+                            KeyCode::Up => {
+                                app_state.help_scroll = app_state.help_scroll.saturating_sub(1);
+                            }
+                            KeyCode::Down => app_state.move_help_scroll(1),
+                            KeyCode::PageUp => {
+                                app_state.help_scroll = app_state.help_scroll.saturating_sub(10);
+                            }
+                            KeyCode::PageDown => app_state.move_help_scroll(10),
+                            KeyCode::Char('q') => break,
+                            _ => {}
+                        }
+                    } else if app_state.show_app_info {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('i') | KeyCode::Enter => {
+                                app_state.show_app_info = false;
+                            }
+                            KeyCode::Char('r') => {
+                                for name in app_state.failed_subsystems() {
+                                    let _ = tx_task_restart.try_send(name);
                                 }
-                                app_state::Focus::Logs => app_state.scroll_logs(1),
-                                app_state::Focus::DebuggerFiles => {
-                                    app_state.move_debugger_selection(1);
-                                    let tree_height = *app_state.debugger_tree_height.borrow();
-                                    app_state
-                                        .update_debugger_tree_scroll(tree_height.saturating_sub(2));
+                            }
+                            KeyCode::Char('q') => break,
+                            _ => {}
+                        }
+                    } else if app_state.focus == app_state::Focus::Search {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app_state.focus = app_state::Focus::Tree;
+                            }
+                            KeyCode::Enter => {
+                                if key.modifiers.contains(event::KeyModifiers::SHIFT) {
+                                    app_state.prev_match();
+                                } else {
+                                    app_state.next_match();
                                 }
-                                app_state::Focus::DebuggerSource => {
-                                    if let Some(current) = app_state.source_selected_line {
-                                        if let Some(content) = &app_state.open_file_content {
-                                            if current < content.len().saturating_sub(1) {
-                                                app_state.source_selected_line = Some(current + 1);
-                                                let inner_height = app_state
-                                                    .debugger_source_area
-                                                    .borrow()
-                                                    .height
-                                                    .saturating_sub(2)
-                                                    as usize;
-                                                if current + 1
-                                                    >= app_state.source_scroll_offset + inner_height
-                                                {
-                                                    app_state.source_scroll_offset =
-                                                        current + 1 - inner_height + 1;
-                                                }
-                                            }
-                                        }
-                                    }
+                            }
+                            KeyCode::Char(c) => {
+                                app_state.search_query.push(c);
+                                app_state.perform_search();
+                            }
+                            KeyCode::Backspace => {
+                                app_state.search_query.pop();
+                                app_state.perform_search();
+                            }
+                            _ => {}
+                        }
+                    } else if app_state.focus == app_state::Focus::DetailsSearch {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app_state.set_details_search(String::new());
+                                app_state.focus = app_state::Focus::Details;
+                            }
+                            KeyCode::Enter => app_state.focus = app_state::Focus::Details,
+                            KeyCode::Char(c) => {
+                                let mut query = app_state.details_search_query.clone();
+                                query.push(c);
+                                app_state.set_details_search(query);
+                            }
+                            KeyCode::Backspace => {
+                                let mut query = app_state.details_search_query.clone();
+                                query.pop();
+                                app_state.set_details_search(query);
+                            }
+                            _ => {}
+                        }
+                    } else if app_state.focus == app_state::Focus::TimelineFilter {
+                        if let Some(performance) = &mut app_state.performance {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    performance.set_filter(String::new());
+                                    app_state.focus = app_state::Focus::Timeline;
+                                }
+                                KeyCode::Enter => app_state.focus = app_state::Focus::Timeline,
+                                KeyCode::Char(c) => {
+                                    let mut filter = performance.filter.clone();
+                                    filter.push(c);
+                                    performance.set_filter(filter);
+                                }
+                                KeyCode::Backspace => {
+                                    let mut filter = performance.filter.clone();
+                                    filter.pop();
+                                    performance.set_filter(filter);
                                 }
                                 _ => {}
-                            },
-                            KeyCode::Left => {
-                                if app_state.focus == app_state::Focus::Tree
-                                    && app_state.current_tab == app_state::Tab::Inspector
-                                {
-                                    if key.modifiers.contains(event::KeyModifiers::SHIFT) {
-                                        app_state.scroll_tree_horizontal(-1);
-                                    } else if !app_state.collapse_selected() {
-                                        app_state.select_parent();
-                                        let (cols, rows) = terminal
-                                            .size()
-                                            .map(|r| (r.width, r.height))
-                                            .unwrap_or((0, 0));
-                                        let tree_height = (rows.saturating_sub(3 + 10)) as usize;
-                                        let tree_width = (cols as f32 * 0.75) as usize;
-                                        app_state.update_tree_scroll(tree_height.saturating_sub(2));
-                                        app_state.ensure_horizontal_visibility(
-                                            tree_width.saturating_sub(2),
-                                        );
-
-                                        // Request details
-                                        if let Some(node) = app_state.get_selected_node() {
-                                            if let Some(id) = AppState::get_node_id(node) {
-                                                log::info!("UI: Requesting details for id: {}", id);
-                                                let _ = tx_details_request.try_send(id);
-                                            }
+                            }
+                        }
+                    } else if app_state.focus == app_state::Focus::Filter {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app_state.clear_filter();
+                                app_state.focus = app_state::Focus::Tree;
+                            }
+                            KeyCode::Enter => {
+                                app_state.focus = app_state::Focus::Tree;
+                                app_state.request_details();
+                            }
+                            KeyCode::Char(c) => {
+                                app_state.filter_query.push(c);
+                                app_state.apply_filter();
+                            }
+                            KeyCode::Backspace => {
+                                app_state.filter_query.pop();
+                                app_state.apply_filter();
+                            }
+                            _ => {}
+                        }
+                    } else if app_state.focus == app_state::Focus::LogpointInput {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app_state.focus = app_state::Focus::DebuggerSource;
+                            }
+                            KeyCode::Enter => {
+                                app_state.focus = app_state::Focus::DebuggerSource;
+                                let template = std::mem::take(&mut app_state.logpoint_input);
+                                if let Some((bp_id, line)) = app_state.selected_breakpoint_key() {
+                                    if !template.is_empty() {
+                                        app_state.logpoints.insert(bp_id.clone(), template);
+                                        if app_state.breakpoints.insert(bp_id.clone()) {
+                                            add_vm_breakpoint(
+                                                &app_state,
+                                                bp_id,
+                                                line,
+                                                &tx_breakpoint_added,
+                                            );
                                         }
                                     }
-                                } else if app_state.focus == app_state::Focus::DebuggerFiles {
-                                    app_state.toggle_debugger_expand();
                                 }
                             }
-                            KeyCode::Right => {
-                                if app_state.focus == app_state::Focus::Tree
-                                    && app_state.current_tab == app_state::Tab::Inspector
-                                {
-                                    if key.modifiers.contains(event::KeyModifiers::SHIFT) {
-                                        app_state.scroll_tree_horizontal(1);
-                                    } else if !app_state.expand_selected() {
-                                        app_state.select_first_child();
-                                        let (cols, rows) = terminal
-                                            .size()
-                                            .map(|r| (r.width, r.height))
-                                            .unwrap_or((0, 0));
-                                        let tree_height = (rows.saturating_sub(3 + 10)) as usize;
-                                        let tree_width = (cols as f32 * 0.75) as usize;
-                                        app_state.update_tree_scroll(tree_height.saturating_sub(2));
-                                        app_state.ensure_horizontal_visibility(
-                                            tree_width.saturating_sub(2),
-                                        );
-
-                                        // Request details
-                                        if let Some(node) = app_state.get_selected_node() {
-                                            if let Some(id) = AppState::get_node_id(node) {
-                                                log::info!("UI: Requesting details for id: {}", id);
-                                                let _ = tx_details_request.try_send(id);
+                            KeyCode::Char(c) => app_state.logpoint_input.push(c),
+                            KeyCode::Backspace => {
+                                app_state.logpoint_input.pop();
+                            }
+                            _ => {}
+                        }
+                    } else if app_state.focus == app_state::Focus::DeviceInput {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app_state.focus = app_state.device_input_return_focus;
+                            }
+                            KeyCode::Enter => {
+                                app_state.focus = app_state.device_input_return_focus;
+                                match device_input::InputAction::parse(&app_state.device_input) {
+                                    Ok(action) => {
+                                        let device_id = args.device_id.clone();
+                                        let target_os = app_state
+                                            .app_info
+                                            .vm_target
+                                            .as_deref()
+                                            .and_then(|t| t.split_whitespace().next())
+                                            .map(|os| os.to_string());
+                                        tokio::spawn(async move {
+                                            match device_input::send(
+                                                &action,
+                                                device_id.as_deref(),
+                                                target_os.as_deref(),
+                                            )
+                                            .await
+                                            {
+                                                Ok(()) => log::info!("Sent input: {:?}", action),
+                                                Err(e) => {
+                                                    log::error!("Failed to send input: {}", e)
+                                                }
                                             }
-                                        }
+                                        });
                                     }
-                                } else if app_state.focus == app_state::Focus::DebuggerFiles {
-                                    app_state.toggle_debugger_expand();
+                                    Err(e) => log::error!("{}", e),
                                 }
                             }
-                            KeyCode::Enter | KeyCode::Char(' ') => match app_state.focus {
-                                app_state::Focus::IsolateSelection => {
-                                    if let Some(isolate) = app_state
-                                        .available_isolates
-                                        .get(app_state.selected_isolate_index)
-                                    {
-                                        let id = &isolate.id;
-                                        log::info!("Selecting isolate: {}", id);
-                                        let _ = tx_selected_isolate.try_send(id.clone());
-                                        app_state.show_isolate_selection = false;
-                                        app_state.focus = app_state::Focus::Tree;
+                            KeyCode::Char(c) => app_state.device_input.push(c),
+                            KeyCode::Backspace => {
+                                app_state.device_input.pop();
+                            }
+                            _ => {}
+                        }
+                    } else if app_state.focus == app_state::Focus::DepthInput {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app_state.focus = app_state::Focus::Tree;
+                            }
+                            KeyCode::Enter => {
+                                app_state.focus = app_state::Focus::Tree;
+                                let input = std::mem::take(&mut app_state.depth_input);
+                                match input.trim().parse::<usize>() {
+                                    Ok(depth) => {
+                                        app_state.expand_to_depth(depth);
+                                        request_children(&mut app_state, &tx_children_request);
                                     }
-                                }
-                                app_state::Focus::DebuggerFiles => {
-                                    app_state.activate_selected_debugger_node();
-                                }
-                                _ => {}
-                            },
-                            KeyCode::Char('b') => {
-                                if app_state.focus == app_state::Focus::DebuggerSource {
-                                    app_state.toggle_breakpoint();
+                                    Err(_) => log::error!("Not a depth: {:?}", input),
                                 }
                             }
-                            KeyCode::PageUp => {
-                                if app_state.focus == app_state::Focus::Logs {
-                                    app_state.scroll_logs(-10);
-                                } else if app_state.focus == app_state::Focus::DebuggerSource {
-                                    if app_state.source_scroll_offset > 10 {
-                                        app_state.source_scroll_offset -= 10;
+                            KeyCode::Char(c) if c.is_ascii_digit() => app_state.depth_input.push(c),
+                            KeyCode::Backspace => {
+                                app_state.depth_input.pop();
+                            }
+                            _ => {}
+                        }
+                    } else if app_state.focus == app_state::Focus::ConditionInput {
+                        match key.code {
+                            KeyCode::Esc => {
+                                app_state.focus = app_state::Focus::DebuggerSource;
+                            }
+                            KeyCode::Enter => {
+                                app_state.focus = app_state::Focus::DebuggerSource;
+                                let condition = std::mem::take(&mut app_state.condition_input);
+                                if let Some((bp_id, line)) = app_state.selected_breakpoint_key() {
+                                    if condition.trim().is_empty() {
+                                        app_state.breakpoint_conditions.remove(&bp_id);
                                     } else {
-                                        app_state.source_scroll_offset = 0;
+                                        app_state
+                                            .breakpoint_conditions
+                                            .insert(bp_id.clone(), condition);
+                                        if app_state.breakpoints.insert(bp_id.clone()) {
+                                            add_vm_breakpoint(
+                                                &app_state,
+                                                bp_id,
+                                                line,
+                                                &tx_breakpoint_added,
+                                            );
+                                        }
                                     }
                                 }
                             }
-                            KeyCode::PageDown => {
-                                if app_state.focus == app_state::Focus::Logs {
-                                    app_state.scroll_logs(10);
-                                } else if app_state.focus == app_state::Focus::DebuggerSource {
-                                    app_state.source_scroll_offset += 10;
-                                }
+                            KeyCode::Char(c) => app_state.condition_input.push(c),
+                            KeyCode::Backspace => {
+                                app_state.condition_input.pop();
                             }
-                            KeyCode::F(5) => {
-                                let _ = tx_refresh.try_send(());
+                            _ => {}
+                        }
+                    } else if app_state.focus == app_state::Focus::TextSearch {
+                        match key.code {
+                            KeyCode::Esc => app_state.focus = app_state.text_search_return_focus,
+                            KeyCode::Enter => app_state.apply_text_search(),
+                            KeyCode::Char(c) => app_state.text_search_input.push(c),
+                            KeyCode::Backspace => {
+                                app_state.text_search_input.pop();
                             }
                             _ => {}
                         }
-                    }
-                }
-                Event::Mouse(mouse) => {
-                    if !app_state.show_isolate_selection {
-                        match mouse.kind {
-                            event::MouseEventKind::Down(event::MouseButton::Left) => {
-                                // App Bar Click Handling
-                                if mouse.row < 3 {
-                                    // Button width is 20
-                                    let button_index = (mouse.column as usize) / 20;
-                                    match button_index {
-                                        0 => app_state.current_tab = app_state::Tab::Inspector,
-                                        1 => app_state.current_tab = app_state::Tab::Debugger,
-                                        2 => {
-                                            // Hot Reload
-                                            if let Some(tx) = &app_state.tx_flutter_command {
-                                                let _ = tx.send("r".to_string()).await;
-                                            }
-                                        }
-                                        3 => {
-                                            // Hot Restart
-                                            if let Some(tx) = &app_state.tx_flutter_command {
-                                                let _ = tx.send("R".to_string()).await;
-                                            }
-                                        }
-                                        4 => {
-                                            // Auto Hot Reload Toggle
-                                            app_state.auto_reload = !app_state.auto_reload;
-                                            log::info!(
-                                                "Auto Hot Reload: {}",
-                                                if app_state.auto_reload { "ON" } else { "OFF" }
-                                            );
-                                        }
-                                        5 => {
-                                            // Refresh Isolates
-                                            let _ = tx_refresh.try_send(());
-                                        }
-                                        6 => {
-                                            // Logs Toggle
-                                            app_state.show_logs = !app_state.show_logs;
-                                        }
-                                        7 => {
-                                            // Quit
-                                            if let Some(tx) = &app_state.tx_flutter_command {
-                                                let _ = tx.send("q".to_string()).await;
-                                            }
-                                            break;
-                                        }
-                                        _ => {}
+                    } else if app_state.focus == app_state::Focus::DebuggerSource {
+                        let size = terminal
+                            .size()
+                            .map(|r| (r.width, r.height))
+                            .unwrap_or((0, 0));
+                        let Some(key) = vim_key(&mut app_state, key, size) else {
+                            continue;
+                        };
+                        let key = app_state.keymap.translate(key);
+                        match key.code {
+                            KeyCode::Esc => {
+                                app_state.focus = app_state::Focus::DebuggerFiles;
+                            }
+                            KeyCode::Char('/') => app_state.open_text_search(),
+                            KeyCode::Char('n') => {
+                                app_state.next_text_match(true);
+                            }
+                            KeyCode::Char('N') => {
+                                app_state.next_text_match(false);
+                            }
+                            KeyCode::Up | KeyCode::Down | KeyCode::Home | KeyCode::End => {
+                                let size = terminal
+                                    .size()
+                                    .map(|r| (r.width, r.height))
+                                    .unwrap_or((0, 0));
+                                move_in_focused_pane(&mut app_state, key, size);
+                            }
+                            KeyCode::Char('b') => {
+                                if let Some((bp_id, line)) = app_state.selected_breakpoint_key() {
+                                    let is_existing = app_state.breakpoints.contains(&bp_id);
+                                    if is_existing {
+                                        remove_vm_breakpoint(&mut app_state, &bp_id);
+                                    } else {
+                                        app_state.breakpoints.insert(bp_id.clone());
+                                        add_vm_breakpoint(
+                                            &app_state,
+                                            bp_id,
+                                            line,
+                                            &tx_breakpoint_added,
+                                        );
                                     }
                                 } else {
-                                    // Tree Interaction
-                                    let x = mouse.column;
-                                    let y = mouse.row;
-
-                                    // Inspector Tree
-                                    if app_state.current_tab == app_state::Tab::Inspector {
-                                        let inspector_area =
-                                            *app_state.inspector_tree_area.borrow();
-                                        if x >= inspector_area.x
-                                            && x < inspector_area.x + inspector_area.width
-                                            && y >= inspector_area.y
-                                            && y < inspector_area.y + inspector_area.height
-                                        {
-                                            app_state.focus = app_state::Focus::Tree;
-                                            let relative_y = (y - inspector_area.y) as usize;
-                                            let index = relative_y + app_state.tree_scroll_offset;
-
-                                            let count = *app_state.inspector_visible_count.borrow();
-                                            if index < count {
-                                                if index == app_state.selected_index {
-                                                    app_state.toggle_expand();
-                                                } else {
-                                                    app_state.selected_index = index;
-                                                    // Request details
-                                                    if let Some(node) =
-                                                        app_state.get_selected_node()
-                                                    {
-                                                        if let Some(id) =
-                                                            AppState::get_node_id(node)
-                                                        {
-                                                            log::info!(
-                                                                "UI: Requesting details for id: {}",
-                                                                id
-                                                            );
-                                                            let _ = tx_details_request.try_send(id);
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-
-                                    // Debugger Tree
-                                    if app_state.current_tab == app_state::Tab::Debugger {
-                                        let debugger_area = *app_state.debugger_tree_area.borrow();
-                                        if x >= debugger_area.x
-                                            && x < debugger_area.x + debugger_area.width
-                                            && y >= debugger_area.y
-                                            && y < debugger_area.y + debugger_area.height
-                                        {
-                                            app_state.focus = app_state::Focus::DebuggerFiles;
-                                            let relative_y = (y - debugger_area.y) as usize;
-                                            let index =
-                                                relative_y + app_state.debugger_tree_scroll_offset;
-
-                                            let count = *app_state.debugger_visible_count.borrow();
-                                            if index < count {
-                                                if index == app_state.debugger_selected_index {
-                                                    app_state.activate_selected_debugger_node();
-                                                } else {
-                                                    app_state.debugger_selected_index = index;
-                                                }
-                                            }
-                                        }
-                                    }
-
-                                    if app_state.current_tab == app_state::Tab::Debugger {
-                                        let source_area = *app_state.debugger_source_area.borrow();
-                                        if x >= source_area.x
-                                            && x < source_area.x + source_area.width
-                                            && y >= source_area.y
-                                            && y < source_area.y + source_area.height
-                                        {
-                                            app_state.focus = app_state::Focus::DebuggerSource;
-                                            // Calculate clicked line
-                                            let relative_y =
-                                                y.saturating_sub(source_area.y) as usize;
-                                            let line_index =
-                                                app_state.source_scroll_offset + relative_y;
-                                            app_state.source_selected_line = Some(line_index);
-                                        }
-                                    }
+                                    log::warn!("Cannot toggle breakpoint: No line selected. Please open a file and select a line.");
                                 }
                             }
-                            event::MouseEventKind::ScrollDown => {
-                                let x = mouse.column;
-                                let y = mouse.row;
-
-                                // Inspector
-                                let inspector_area = *app_state.inspector_tree_area.borrow();
-                                if x >= inspector_area.x
-                                    && x < inspector_area.x + inspector_area.width
-                                    && y >= inspector_area.y
-                                    && y < inspector_area.y + inspector_area.height
-                                {
-                                    app_state.scroll_tree(1);
+                            KeyCode::Char('L') => {
+                                if let Some((bp_id, _)) = app_state.selected_breakpoint_key() {
+                                    app_state.logpoint_input = app_state
+                                        .logpoints
+                                        .get(&bp_id)
+                                        .cloned()
+                                        .unwrap_or_default();
+                                    app_state.focus = app_state::Focus::LogpointInput;
                                 }
-
-                                // Debugger
-                                let debugger_area = *app_state.debugger_tree_area.borrow();
-                                if x >= debugger_area.x
-                                    && x < debugger_area.x + debugger_area.width
-                                    && y >= debugger_area.y
-                                    && y < debugger_area.y + debugger_area.height
-                                {
-                                    app_state.move_debugger_selection(1);
+                            }
+                            KeyCode::Char('C') => {
+                                if let Some((bp_id, _)) = app_state.selected_breakpoint_key() {
+                                    app_state.condition_input = app_state
+                                        .breakpoint_conditions
+                                        .get(&bp_id)
+                                        .cloned()
+                                        .unwrap_or_default();
+                                    app_state.focus = app_state::Focus::ConditionInput;
                                 }
+                            }
+                            KeyCode::F(5) => resume_isolate(&app_state, None),
+                            KeyCode::F(10) => resume_isolate(&app_state, Some("Over")),
+                            KeyCode::F(11)
+                                if key.modifiers.contains(event::KeyModifiers::SHIFT) =>
+                            {
+                                resume_isolate(&app_state, Some("Out"))
+                            }
+                            KeyCode::F(11) => resume_isolate(&app_state, Some("Into")),
+                            KeyCode::Char('?') => app_state.open_help(),
+                            _ => {}
+                        }
+                    } else {
+                        // Typed into the search box as they are
+                        let key = if app_state.focus == app_state::Focus::DebuggerSearch {
+                            key
+                        } else {
+                            let size = terminal
+                                .size()
+                                .map(|r| (r.width, r.height))
+                                .unwrap_or((0, 0));
+                            let Some(key) = vim_key(&mut app_state, key, size) else {
+                                continue;
+                            };
+                            app_state.keymap.translate(key)
+                        };
+                        match key.code {
+                            KeyCode::Char('1') => {
+                                if matches!(
+                                    app_state.current_tab,
+                                    app_state::Tab::Logs
+                                        | app_state::Tab::Memory
+                                        | app_state::Tab::Profiler
+                                        | app_state::Tab::Performance
+                                        | app_state::Tab::Network
+                                ) {
+                                    app_state.focus = app_state::Focus::Tree;
+                                }
+                                app_state.current_tab = app_state::Tab::Inspector;
+                            }
+                            KeyCode::Char('2') => {
+                                if matches!(
+                                    app_state.current_tab,
+                                    app_state::Tab::Logs
+                                        | app_state::Tab::Memory
+                                        | app_state::Tab::Profiler
+                                        | app_state::Tab::Performance
+                                        | app_state::Tab::Network
+                                ) {
+                                    app_state.focus = app_state::Focus::DebuggerFiles;
+                                }
+                                app_state.current_tab = app_state::Tab::Debugger;
+                            }
+                            KeyCode::Char('3') => {
+                                app_state.current_tab = app_state::Tab::Logs;
+                                app_state.focus = app_state::Focus::Logs;
+                            }
+                            KeyCode::Char('4') => {
+                                app_state.current_tab = app_state::Tab::Memory;
+                                app_state.focus = app_state::Focus::Memory;
+                            }
+                            KeyCode::Char('s')
+                                if app_state.current_tab == app_state::Tab::Memory =>
+                            {
+                                if let Some(memory) = &mut app_state.memory {
+                                    memory.allocation_sort = memory.allocation_sort.next();
+                                    memory.allocation_selected_index = 0;
+                                }
+                            }
+                            KeyCode::Char('G')
+                                if app_state.current_tab == app_state::Tab::Memory =>
+                            {
+                                fetch_allocation_profile(&app_state, true, &tx_allocations);
+                            }
+                            KeyCode::Char('W')
+                                if app_state.current_tab == app_state::Tab::Memory =>
+                            {
+                                app_state.toggle_snapshots(Duration::from_secs(
+                                    args.snapshot_interval.unwrap_or(10),
+                                ));
+                            }
+                            KeyCode::Char('j')
+                                if app_state.current_tab == app_state::Tab::Memory =>
+                            {
+                                app_state.jump_to_leak_suspect();
+                            }
+                            KeyCode::Char('X') => save_timeline_capture(&app_state, "manual"),
+                            KeyCode::Char('5') => {
+                                app_state.current_tab = app_state::Tab::Profiler;
+                                app_state.focus = app_state::Focus::Profiler;
+                            }
+                            KeyCode::Char('6') => {
+                                app_state.current_tab = app_state::Tab::Performance;
+                                app_state.focus = app_state::Focus::Timeline;
+                            }
+                            KeyCode::Char('c')
+                                if app_state.current_tab == app_state::Tab::Performance =>
+                            {
+                                capture_timeline(&mut app_state, &tx_timeline_capture);
+                            }
+                            KeyCode::Char('[') | KeyCode::Char(']')
+                                if app_state.focus == app_state::Focus::Logs =>
+                            {
+                                app_state.cycle_log_source(if key.code == KeyCode::Char(']') {
+                                    1
+                                } else {
+                                    -1
+                                });
+                            }
+                            KeyCode::Char('v') if app_state.focus == app_state::Focus::Logs => {
+                                app_state.cycle_log_level();
+                            }
+                            KeyCode::Char('[') | KeyCode::Char(']')
+                                if app_state.current_tab == app_state::Tab::Performance =>
+                            {
+                                if let Some(performance) = &mut app_state.performance {
+                                    performance.change_window(
+                                        if key.code == KeyCode::Char(']') { 1 } else { -1 },
+                                    );
+                                }
+                            }
+                            KeyCode::Char('f')
+                                if app_state.current_tab == app_state::Tab::Performance =>
+                            {
+                                if let Some(performance) = &mut app_state.performance {
+                                    performance.cycle_category();
+                                }
+                            }
+                            KeyCode::Char('c')
+                                if app_state.current_tab == app_state::Tab::Profiler =>
+                            {
+                                capture_profile(&mut app_state, &tx_profile);
+                            }
+                            KeyCode::Char('[') | KeyCode::Char(']')
+                                if app_state.current_tab == app_state::Tab::Profiler =>
+                            {
+                                if let Some(profiler) = &mut app_state.profiler {
+                                    profiler.change_window(if key.code == KeyCode::Char(']') {
+                                        1
+                                    } else {
+                                        -1
+                                    });
+                                }
+                            }
+                            KeyCode::Char('v')
+                                if app_state.current_tab == app_state::Tab::Profiler =>
+                            {
+                                if let Some(profiler) = &mut app_state.profiler {
+                                    profiler.cycle_view();
+                                }
+                            }
+                            KeyCode::Char('+') | KeyCode::Char('=') => {
+                                app_state.resize_log_panel(1)
+                            }
+                            KeyCode::Char('-') => app_state.resize_log_panel(-1),
+                            KeyCode::Char('Z') => app_state.cycle_log_panel_height(),
+                            KeyCode::Char('l') => {
+                                app_state.show_logs = !app_state.show_logs;
+                            }
+                            KeyCode::Char('q') => {
+                                quit_flutter(&app_state).await;
+                                break;
+                            }
+                            KeyCode::Char('r')
+                                if key.modifiers.contains(event::KeyModifiers::CONTROL) =>
+                            {
+                                let _ = tx_refresh.try_send(());
+                                app_state.tree_refreshing = true;
+                            }
+                            KeyCode::Char('r') => hot_reload(&app_state, false).await,
+                            KeyCode::Char('R') => hot_reload(&app_state, true).await,
+                            KeyCode::Char('a') => {
+                                app_state.auto_reload = !app_state.auto_reload;
+                            }
+                            KeyCode::Char('p') => toggle_app_pause(&mut app_state),
+                            KeyCode::Char('i') => app_state.show_app_info = true,
+                            KeyCode::Char('?') => app_state.open_help(),
+                            KeyCode::Char('M') => {
+                                app_state.show_isolate_metrics = true;
+                                fetch_isolate_metrics(&mut app_state, &tx_isolate_metrics);
+                            }
+                            KeyCode::Char('o') => app_state.show_toggles = true,
+                            KeyCode::Char(':') => app_state.open_command_palette(),
+                            KeyCode::Char('7') => {
+                                app_state.current_tab = app_state::Tab::Network;
+                                app_state.focus = app_state::Focus::Network;
+                            }
+                            KeyCode::Char('d')
+                                if app_state.current_tab == app_state::Tab::Network =>
+                            {
+                                replay_http_request(&mut app_state, &tx_http_replay);
+                            }
+                            KeyCode::Char('S') if !app_state.available_isolates.is_empty() => {
+                                app_state.open_isolate_selection();
+                            }
+                            KeyCode::Char('<') | KeyCode::Char('>')
+                                if app_state.available_isolates.len() > 1 =>
+                            {
+                                // Switch isolates in place, without the selection popup
+                                let delta = if key.code == KeyCode::Char('>') {
+                                    1
+                                } else {
+                                    -1
+                                };
+                                app_state.move_isolate_selection(delta);
+                                if inspect_selected_isolate(
+                                    &mut app_state,
+                                    &tx_selected_isolate,
+                                ) {
+                                    app_state.tree_refreshing = true;
+                                }
+                            }
+                            KeyCode::Char('T') => toggle_rebuild_tracking(&mut app_state),
+                            KeyCode::Char('K') => {
+                                app_state.show_focus_tree = true;
+                                app_state.focus_tree = None;
+                                fetch_focus_tree(&app_state, &tx_focus_tree);
+                            }
+                            KeyCode::Char('B') => {
+                                app_state.show_rebuild_stats = true;
+                                app_state.rebuild_stats_selected_index = 0;
+                            }
+                            KeyCode::Char('V') => {
+                                app_state.show_layout_warnings = true;
+                                app_state.layout_warnings_selected_index = 0;
+                            }
+                            KeyCode::Char('U') => {
+                                app_state.show_reload_scope = true;
+                                app_state.reload_scope_scroll = 0;
+                            }
+                            KeyCode::Char('w') => toggle_widget_select_mode(&mut app_state),
+                            KeyCode::Char('A') => {
+                                app_state.summary_tree = !app_state.summary_tree;
+                                let _ = tx_summary_tree.send(app_state.summary_tree);
+                                let _ = tx_refresh.try_send(());
+                                app_state.tree_refreshing = true;
+                            }
+                            KeyCode::Char('g')
+                                if app_state.current_tab == app_state::Tab::Inspector =>
+                            {
+                                app_state.open_widget_source();
+                            }
+                            KeyCode::Char('J')
+                                if app_state.current_tab == app_state::Tab::Inspector =>
+                            {
+                                app_state.toggle_raw_json();
+                            }
+                            KeyCode::Char('I') => {
+                                app_state.device_input_return_focus = app_state.focus;
+                                app_state.focus = app_state::Focus::DeviceInput;
+                            }
+                            KeyCode::Char('u') => reassemble_app(&mut app_state, &tx_refresh),
+                            KeyCode::Char('f') if app_state.focus == app_state::Focus::Tree => {
+                                app_state.focus_selected_node();
+                            }
+                            KeyCode::Char('e') if app_state.focus == app_state::Focus::Tree => {
+                                app_state.expand_subtree();
+                                request_children(&mut app_state, &tx_children_request);
+                            }
+                            KeyCode::Char('E') if app_state.focus == app_state::Focus::Tree => {
+                                app_state.collapse_subtree();
+                            }
+                            KeyCode::Char('y') => {
+                                if let Some(text) = app_state.selected_text() {
+                                    match clipboard::copy(&text) {
+                                        Ok(()) => log::info!("Copied: {}", text.lines().next().unwrap_or_default()),
+                                        Err(e) => log::error!("Failed to copy to the clipboard: {}", e),
+                                    }
+                                }
+                            }
+                            KeyCode::Char('P') if app_state.focus == app_state::Focus::Tree => {
+                                take_screenshot(&mut app_state, &tx_screenshot);
+                            }
+                            KeyCode::Char('Y') if app_state.focus == app_state::Focus::Tree => {
+                                copy_widget_as_dart(&app_state);
+                            }
+                            KeyCode::Char('z') if app_state.focus == app_state::Focus::Tree => {
+                                app_state.toggle_subtree_focus();
+                            }
+                            KeyCode::Char('D') if app_state.focus == app_state::Focus::Tree => {
+                                app_state.depth_input.clear();
+                                app_state.focus = app_state::Focus::DepthInput;
+                            }
+                            KeyCode::Char('F')
+                                if app_state.current_tab == app_state::Tab::Inspector =>
+                            {
+                                app_state.focus = app_state::Focus::Filter;
+                            }
+                            KeyCode::Char('/') => {
+                                if app_state.focus == app_state::Focus::Logs {
+                                    app_state.open_text_search();
+                                } else if app_state.focus == app_state::Focus::DebuggerFiles {
+                                    app_state.focus = app_state::Focus::DebuggerSearch;
+                                    app_state.debugger_search_query.clear();
+                                } else if app_state.focus == app_state::Focus::Details {
+                                    app_state.focus = app_state::Focus::DetailsSearch;
+                                } else if app_state.focus == app_state::Focus::Timeline {
+                                    app_state.focus = app_state::Focus::TimelineFilter;
+                                } else {
+                                    app_state.focus = app_state::Focus::Search;
+                                    app_state.search_query.clear();
+                                }
+                            }
+                            KeyCode::Tab => app_state.cycle_focus(),
+                            KeyCode::Esc => {
+                                if app_state.focus == app_state::Focus::DebuggerSearch {
+                                    app_state.focus = app_state::Focus::DebuggerFiles;
+                                } else if app_state.focus == app_state::Focus::Search {
+                                    app_state.focus = app_state::Focus::Tree;
+                                } else if app_state.focus == app_state::Focus::DebuggerSource {
+                                    app_state.focus = app_state::Focus::DebuggerFiles;
+                                }
+                            }
+                            KeyCode::Char(c)
+                                if app_state.focus == app_state::Focus::DebuggerSearch =>
+                            {
+                                app_state.debugger_search_query.push(c);
+                                app_state.perform_debugger_search();
+                            }
+                            KeyCode::Backspace
+                                if app_state.focus == app_state::Focus::DebuggerSearch =>
+                            {
+                                app_state.debugger_search_query.pop();
+                                app_state.perform_debugger_search();
+                            }
+                            KeyCode::Enter
+                                if app_state.focus == app_state::Focus::DebuggerSearch =>
+                            {
+                                app_state.next_debugger_match();
+                            }
+                            KeyCode::Char('n')
+                                if app_state.focus == app_state::Focus::DebuggerFiles =>
+                            {
+                                app_state.next_debugger_match();
+                            }
+                            KeyCode::Char('N')
+                                if app_state.focus == app_state::Focus::DebuggerFiles =>
+                            {
+                                app_state.previous_debugger_match();
+                            }
+                            KeyCode::Char('n') if app_state.focus == app_state::Focus::Tree => {
+                                app_state.next_match();
+                                app_state.request_details();
+                            }
+                            KeyCode::Char('N') if app_state.focus == app_state::Focus::Tree => {
+                                app_state.prev_match();
+                                app_state.request_details();
+                            }
+                            KeyCode::Char('n') if app_state.focus == app_state::Focus::Logs => {
+                                app_state.next_text_match(true);
+                            }
+                            KeyCode::Char('N') if app_state.focus == app_state::Focus::Logs => {
+                                app_state.next_text_match(false);
+                            }
+                            KeyCode::Up | KeyCode::Down | KeyCode::Home | KeyCode::End => {
+                                let size = terminal
+                                    .size()
+                                    .map(|r| (r.width, r.height))
+                                    .unwrap_or((0, 0));
+                                move_in_focused_pane(&mut app_state, key, size);
+                            }
+                            KeyCode::Left => {
+                                if app_state.focus == app_state::Focus::Tree
+                                    && app_state.current_tab == app_state::Tab::Inspector
+                                {
+                                    if key.modifiers.contains(event::KeyModifiers::SHIFT) {
+                                        app_state.scroll_tree_horizontal(-1);
+                                    } else if !app_state.collapse_selected() {
+                                        app_state.select_parent();
+                                        let (cols, rows) = terminal
+                                            .size()
+                                            .map(|r| (r.width, r.height))
+                                            .unwrap_or((0, 0));
+                                        let tree_height = (rows
+                                            .saturating_sub(3 + ui::STATUS_BAR_HEIGHT + app_state.log_panel_rows()))
+                                            as usize;
+                                        let tree_width = cols as usize * app_state.inspector_split[0] as usize / 100;
+                                        app_state.update_tree_scroll(tree_height.saturating_sub(2));
+                                        app_state.ensure_horizontal_visibility(
+                                            tree_width.saturating_sub(2),
+                                        );
+
+                                        app_state.request_details();
+                                    }
+                                } else if app_state.focus == app_state::Focus::DebuggerFiles {
+                                    app_state.toggle_debugger_expand();
+                                } else if app_state.focus == app_state::Focus::DebuggerLocals {
+                                    app_state.collapse_selected_local();
+                                } else if app_state.focus == app_state::Focus::Details {
+                                    app_state.collapse_selected_property();
+                                } else if app_state.focus == app_state::Focus::Profiler {
+                                    if let Some(profiler) = &mut app_state.profiler {
+                                        profiler.set_row_expanded(false);
+                                    }
+                                }
+                            }
+                            KeyCode::Right => {
+                                if app_state.focus == app_state::Focus::Tree
+                                    && app_state.current_tab == app_state::Tab::Inspector
+                                {
+                                    if key.modifiers.contains(event::KeyModifiers::SHIFT) {
+                                        app_state.scroll_tree_horizontal(1);
+                                    } else if app_state.expand_selected() {
+                                        prefetch_details(&app_state, &tx_details_prefetch);
+                                        request_children(&mut app_state, &tx_children_request);
+                                    } else {
+                                        app_state.select_first_child();
+                                        let (cols, rows) = terminal
+                                            .size()
+                                            .map(|r| (r.width, r.height))
+                                            .unwrap_or((0, 0));
+                                        let tree_height = (rows
+                                            .saturating_sub(3 + ui::STATUS_BAR_HEIGHT + app_state.log_panel_rows()))
+                                            as usize;
+                                        let tree_width = cols as usize * app_state.inspector_split[0] as usize / 100;
+                                        app_state.update_tree_scroll(tree_height.saturating_sub(2));
+                                        app_state.ensure_horizontal_visibility(
+                                            tree_width.saturating_sub(2),
+                                        );
+
+                                        app_state.request_details();
+                                    }
+                                } else if app_state.focus == app_state::Focus::DebuggerFiles {
+                                    app_state.toggle_debugger_expand();
+                                } else if app_state.focus == app_state::Focus::DebuggerLocals {
+                                    if let Some((key, object_id)) =
+                                        app_state.expand_selected_local()
+                                    {
+                                        fetch_local_children(
+                                            &app_state,
+                                            key,
+                                            object_id,
+                                            tx_locals.clone(),
+                                        );
+                                    }
+                                } else if app_state.focus == app_state::Focus::Details {
+                                    if let Some((key, object_id)) =
+                                        app_state.expand_selected_property()
+                                    {
+                                        request_property_children(
+                                            &app_state,
+                                            key,
+                                            object_id,
+                                            &tx_properties_request,
+                                        );
+                                    }
+                                } else if app_state.focus == app_state::Focus::Profiler {
+                                    if let Some(profiler) = &mut app_state.profiler {
+                                        profiler.set_row_expanded(true);
+                                    }
+                                }
+                            }
+                            KeyCode::Enter | KeyCode::Char(' ') => match app_state.focus {
+                                app_state::Focus::Network => {
+                                    fetch_http_details(&mut app_state, &tx_http_details);
+                                }
+                                app_state::Focus::IsolateSelection => {
+                                    inspect_selected_isolate(
+                                        &mut app_state,
+                                        &tx_selected_isolate,
+                                    );
+                                }
+                                app_state::Focus::DebuggerFiles => {
+                                    app_state.activate_selected_debugger_node();
+                                }
+                                app_state::Focus::Details => {
+                                    if let Some((key, object_id)) =
+                                        app_state.toggle_selected_property()
+                                    {
+                                        request_property_children(
+                                            &app_state,
+                                            key,
+                                            object_id,
+                                            &tx_properties_request,
+                                        );
+                                    }
+                                }
+                                app_state::Focus::DebuggerBreakpoints => {
+                                    app_state.open_selected_breakpoint();
+                                }
+                                app_state::Focus::Logs => app_state.toggle_selected_log(),
+                                app_state::Focus::Profiler => {
+                                    if let Some(profiler) = &mut app_state.profiler {
+                                        profiler.toggle_row();
+                                    }
+                                }
+                                app_state::Focus::DebuggerStack => {
+                                    app_state.select_frame(app_state.stack_selected_index);
+                                    navigate_to_frame(
+                                        &app_state,
+                                        app_state.stack_selected_index,
+                                        tx_source_location.clone(),
+                                    );
+                                }
+                                app_state::Focus::DebuggerLocals => {
+                                    if let Some((key, object_id)) =
+                                        app_state.toggle_selected_local()
+                                    {
+                                        fetch_local_children(
+                                            &app_state,
+                                            key,
+                                            object_id,
+                                            tx_locals.clone(),
+                                        );
+                                    }
+                                }
+                                _ => {}
+                            },
+                            KeyCode::Delete | KeyCode::Char('d')
+                                if app_state.focus == app_state::Focus::DebuggerBreakpoints =>
+                            {
+                                for bp_id in app_state.marked_or_selected_breakpoints() {
+                                    remove_vm_breakpoint(&mut app_state, &bp_id);
+                                }
+                                app_state.breakpoint_mark_anchor = None;
+                            }
+                            KeyCode::Char('b') if app_state.focus == app_state::Focus::Logs => {
+                                match app_state.selected_log_breakpoint_key() {
+                                    Some((bp_id, _)) if app_state.breakpoints.contains(&bp_id) => {
+                                        log::info!("Breakpoint at {} already set", bp_id);
+                                    }
+                                    Some((bp_id, line)) => {
+                                        app_state.breakpoints.insert(bp_id.clone());
+                                        add_vm_breakpoint(
+                                            &app_state,
+                                            bp_id,
+                                            line,
+                                            &tx_breakpoint_added,
+                                        );
+                                    }
+                                    None => log::warn!(
+                                        "No frame of this project in the selected entry's stack trace"
+                                    ),
+                                }
+                            }
+                            KeyCode::PageUp => {
+                                if app_state.focus == app_state::Focus::Logs {
+                                    app_state.scroll_logs(-10);
+                                } else if app_state.focus == app_state::Focus::Details {
+                                    app_state.move_details_selection(-10);
+                                } else if app_state.focus == app_state::Focus::DebuggerSource {
+                                    if app_state.source_scroll_offset > 10 {
+                                        app_state.source_scroll_offset -= 10;
+                                    } else {
+                                        app_state.source_scroll_offset = 0;
+                                    }
+                                }
+                            }
+                            KeyCode::PageDown => {
+                                if app_state.focus == app_state::Focus::Logs {
+                                    app_state.scroll_logs(10);
+                                } else if app_state.focus == app_state::Focus::Details {
+                                    app_state.move_details_selection(10);
+                                } else if app_state.focus == app_state::Focus::DebuggerSource {
+                                    app_state.source_scroll_offset += 10;
+                                }
+                            }
+                            KeyCode::F(5) if app_state.current_tab == app_state::Tab::Debugger => {
+                                resume_isolate(&app_state, None);
+                            }
+                            KeyCode::F(6) => {
+                                let _ = tx_refresh.try_send(());
+                                app_state.tree_refreshing = true;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Event::Mouse(mouse) if !app_state.show_isolate_selection => match mouse.kind {
+                    event::MouseEventKind::Down(event::MouseButton::Left) => {
+                        // App Bar Click Handling, against where the buttons were drawn
+                        if let Some(button) = app_state.app_bar_button_at(mouse.column, mouse.row) {
+                            if press_app_bar_button(&mut app_state, button, &tx_refresh).await {
+                                quit_flutter(&app_state).await;
+                                break;
+                            }
+                        } else if app_state.show_app_bar_menu {
+                            // Clicking elsewhere dismisses the menu
+                            app_state.show_app_bar_menu = false;
+                        } else if app_state.is_log_border(mouse.row) {
+                            app_state.dragging_log_border = true;
+                        } else if let Some(border) =
+                            app_state.split_border_at(mouse.column, mouse.row)
+                        {
+                            app_state.dragging_split_border = Some(border);
+                        } else if mouse.row >= 3 {
+                            // Tree Interaction
+                            let x = mouse.column;
+                            let y = mouse.row;
+
+                            // Breadcrumb: jump to the clicked ancestor
+                            if app_state.current_tab == app_state::Tab::Inspector {
+                                if let Some(id) = app_state.breadcrumb_at(x, y) {
+                                    app_state.focus = app_state::Focus::Tree;
+                                    if app_state.select_node_by_id(&id) {
+                                        app_state.request_details();
+                                    }
+                                }
+                            }
+
+                            // Inspector Tree
+                            if app_state.current_tab == app_state::Tab::Inspector {
+                                let inspector_area = *app_state.inspector_tree_area.borrow();
+                                if x >= inspector_area.x
+                                    && x < inspector_area.x + inspector_area.width
+                                    && y >= inspector_area.y
+                                    && y < inspector_area.y + inspector_area.height
+                                {
+                                    app_state.focus = app_state::Focus::Tree;
+                                    let relative_y = (y - inspector_area.y) as usize;
+                                    let index = relative_y + app_state.tree_scroll_offset;
+
+                                    let count = *app_state.inspector_visible_count.borrow();
+                                    if index < count {
+                                        if index == app_state.selected_index {
+                                            app_state.toggle_expand();
+                                            prefetch_details(&app_state, &tx_details_prefetch);
+                                            request_children(&mut app_state, &tx_children_request);
+                                        } else {
+                                            app_state.selected_index = index;
+                                            app_state.request_details();
+                                        }
+                                    }
+                                }
+                            }
+
+                            // Details
+                            if app_state.current_tab == app_state::Tab::Inspector {
+                                let details_area = *app_state.details_tree_area.borrow();
+                                if x >= details_area.x
+                                    && x < details_area.x + details_area.width
+                                    && y >= details_area.y
+                                    && y < details_area.y + details_area.height
+                                {
+                                    app_state.focus = app_state::Focus::Details;
+                                    let row = (y - details_area.y) as usize;
+                                    if let Some((key, object_id)) = app_state.click_details_row(row)
+                                    {
+                                        request_property_children(
+                                            &app_state,
+                                            key,
+                                            object_id,
+                                            &tx_properties_request,
+                                        );
+                                    }
+                                }
+                            }
+
+                            // Debugger Tree
+                            if app_state.current_tab == app_state::Tab::Debugger {
+                                let debugger_area = *app_state.debugger_tree_area.borrow();
+                                if x >= debugger_area.x
+                                    && x < debugger_area.x + debugger_area.width
+                                    && y >= debugger_area.y
+                                    && y < debugger_area.y + debugger_area.height
+                                {
+                                    app_state.focus = app_state::Focus::DebuggerFiles;
+                                    let relative_y = (y - debugger_area.y) as usize;
+                                    let index = relative_y + app_state.debugger_tree_scroll_offset;
+
+                                    let count = *app_state.debugger_visible_count.borrow();
+                                    if index < count {
+                                        if index == app_state.debugger_selected_index {
+                                            app_state.activate_selected_debugger_node();
+                                        } else {
+                                            app_state.debugger_selected_index = index;
+                                        }
+                                    }
+                                }
+                            }
+
+                            if app_state.current_tab == app_state::Tab::Debugger {
+                                let source_area = *app_state.debugger_source_area.borrow();
+                                if x >= source_area.x
+                                    && x < source_area.x + source_area.width
+                                    && y >= source_area.y
+                                    && y < source_area.y + source_area.height
+                                {
+                                    app_state.focus = app_state::Focus::DebuggerSource;
+                                    // Calculate clicked line
+                                    let relative_y = y.saturating_sub(source_area.y) as usize;
+                                    let line_index = app_state.source_scroll_offset + relative_y;
+                                    app_state.source_selected_line = Some(line_index);
+                                }
+                            }
+                        }
+                    }
+                    event::MouseEventKind::ScrollDown => {
+                        let x = mouse.column;
+                        let y = mouse.row;
+
+                        // Inspector
+                        let inspector_area = *app_state.inspector_tree_area.borrow();
+                        if x >= inspector_area.x
+                            && x < inspector_area.x + inspector_area.width
+                            && y >= inspector_area.y
+                            && y < inspector_area.y + inspector_area.height
+                        {
+                            app_state.scroll_tree(1);
+                        }
+
+                        // Details
+                        let details_area = *app_state.details_tree_area.borrow();
+                        if app_state.current_tab == app_state::Tab::Inspector
+                            && x >= details_area.x
+                            && x < details_area.x + details_area.width
+                            && y >= details_area.y
+                            && y < details_area.y + details_area.height
+                        {
+                            app_state.move_details_selection(1);
+                        }
+
+                        // Debugger
+                        let debugger_area = *app_state.debugger_tree_area.borrow();
+                        if x >= debugger_area.x
+                            && x < debugger_area.x + debugger_area.width
+                            && y >= debugger_area.y
+                            && y < debugger_area.y + debugger_area.height
+                        {
+                            app_state.move_debugger_selection(1);
+                        }
+
+                        // Logs
+                        let logs_area = *app_state.logs_area.borrow();
+                        if logs_area.contains(ratatui::layout::Position::new(x, y)) {
+                            app_state.scroll_logs(1);
+                        }
+
+                        // Debugger Source
+                        let source_area = *app_state.debugger_source_area.borrow();
+                        if x >= source_area.x
+                            && x < source_area.x + source_area.width
+                            && y >= source_area.y
+                            && y < source_area.y + source_area.height
+                        {
+                            app_state.source_scroll_offset += 1;
+                        }
+                    }
+                    event::MouseEventKind::ScrollUp => {
+                        let x = mouse.column;
+                        let y = mouse.row;
+
+                        // Inspector
+                        let inspector_area = *app_state.inspector_tree_area.borrow();
+                        if x >= inspector_area.x
+                            && x < inspector_area.x + inspector_area.width
+                            && y >= inspector_area.y
+                            && y < inspector_area.y + inspector_area.height
+                        {
+                            app_state.scroll_tree(-1);
+                        }
+
+                        // Details
+                        let details_area = *app_state.details_tree_area.borrow();
+                        if app_state.current_tab == app_state::Tab::Inspector
+                            && x >= details_area.x
+                            && x < details_area.x + details_area.width
+                            && y >= details_area.y
+                            && y < details_area.y + details_area.height
+                        {
+                            app_state.move_details_selection(-1);
+                        }
+
+                        // Debugger
+                        let debugger_area = *app_state.debugger_tree_area.borrow();
+                        if x >= debugger_area.x
+                            && x < debugger_area.x + debugger_area.width
+                            && y >= debugger_area.y
+                            && y < debugger_area.y + debugger_area.height
+                        {
+                            app_state.move_debugger_selection(-1);
+                        }
+
+                        // Logs
+                        let logs_area = *app_state.logs_area.borrow();
+                        if logs_area.contains(ratatui::layout::Position::new(x, y)) {
+                            app_state.scroll_logs(-1);
+                        }
+
+                        // Debugger Source
+                        let source_area = *app_state.debugger_source_area.borrow();
+                        if x >= source_area.x
+                            && x < source_area.x + source_area.width
+                            && y >= source_area.y
+                            && y < source_area.y + source_area.height
+                            && app_state.source_scroll_offset > 0
+                        {
+                            app_state.source_scroll_offset -= 1;
+                        }
+                    }
+                    event::MouseEventKind::Drag(event::MouseButton::Left) => {
+                        if app_state.dragging_log_border {
+                            app_state.drag_log_border(mouse.row);
+                        } else if let Some(border) = app_state.dragging_split_border {
+                            app_state.drag_split_border(border, mouse.column);
+                        }
+                    }
+                    event::MouseEventKind::Up(event::MouseButton::Left) => {
+                        app_state.dragging_split_border = None;
+                        app_state.dragging_log_border = false;
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    // Don't leave the app holding on to everything the inspector looked at
+    if let Some(handle) = dispose_inspector_group(&app_state) {
+        let _ = tokio::time::timeout(Duration::from_secs(1), handle).await;
+    }
+
+    // Restore terminal. After a hangup there is none left, the state is saved regardless
+    if let Err(e) = restore_terminal(terminal.backend_mut()) {
+        log::error!("Failed to restore the terminal: {}", e);
+    }
+
+    if let Err(e) = persistence::save(&app_state.project_root, &app_state.persisted_state()) {
+        eprintln!("Failed to save UI state: {}", e);
+    }
+    if let Err(e) = breakpoints::save(&app_state.project_root, &app_state.breakpoint_entries()) {
+        eprintln!("Failed to save breakpoints: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Signals that the main loop acts on.
+enum ProcessSignal {
+    /// SIGINT, SIGTERM or SIGHUP: shut down as `q` does.
+    Terminate,
+    /// SIGTSTP, e.g. from `kill -TSTP`: hand the terminal back and stop.
+    Suspend,
+}
+
+#[cfg(unix)]
+fn spawn_signal_listener(tx_signal: mpsc::Sender<ProcessSignal>) {
+    use tokio::signal::unix::{signal, SignalKind};
+    tokio::spawn(async move {
+        let (Ok(mut interrupt), Ok(mut terminate), Ok(mut hangup), Ok(mut stop)) = (
+            signal(SignalKind::interrupt()),
+            signal(SignalKind::terminate()),
+            signal(SignalKind::hangup()),
+            signal(SignalKind::from_raw(libc::SIGTSTP)),
+        ) else {
+            log::error!("Failed to install signal handlers");
+            return;
+        };
+        loop {
+            let received = tokio::select! {
+                _ = interrupt.recv() => ProcessSignal::Terminate,
+                _ = terminate.recv() => ProcessSignal::Terminate,
+                _ = hangup.recv() => ProcessSignal::Terminate,
+                _ = stop.recv() => ProcessSignal::Suspend,
+            };
+            if tx_signal.send(received).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_signal_listener(tx_signal: mpsc::Sender<ProcessSignal>) {
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if tx_signal.send(ProcessSignal::Terminate).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn setup_terminal(out: &mut impl io::Write) -> Result<()> {
+    enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, EnableMouseCapture)?;
+    Ok(())
+}
+
+fn restore_terminal(out: &mut impl io::Write) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        out,
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        crossterm::cursor::Show
+    )?;
+    Ok(())
+}
+
+/// Hands the terminal back to the shell and stops, like Ctrl+Z outside raw mode does.
+/// Picks up where it left off once the shell continues it with `fg`.
+#[cfg(unix)]
+fn suspend(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    restore_terminal(terminal.backend_mut())?;
+    // SIGSTOP rather than SIGTSTP, which is caught to get here
+    // SAFETY: raise has no preconditions
+    unsafe {
+        libc::raise(libc::SIGSTOP);
+    }
+    setup_terminal(terminal.backend_mut())?;
+    // The shell drew over the screen, and the window may have been resized meanwhile
+    terminal.clear()?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn suspend(_terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    Ok(())
+}
+
+/// Ends `flutter attach` before the TUI exits.
+async fn quit_flutter(app_state: &AppState) {
+    if let Some(tx) = &app_state.tx_flutter_command {
+        let _ = tx.send("q".to_string()).await;
+    }
+}
+
+/// Dumps the app's focus tree for the Focus Tree popup.
+fn fetch_focus_tree(app_state: &AppState, tx_focus_tree: &mpsc::Sender<String>) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = &app_state.inspected_isolate else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    let tx_focus_tree = tx_focus_tree.clone();
+    tokio::spawn(async move {
+        match client.dump_focus_tree(&isolate_id).await {
+            Ok(dump) => {
+                let _ = tx_focus_tree.send(dump).await;
+            }
+            Err(e) => {
+                log::error!("Failed to dump the focus tree: {}", e);
+                let _ = tx_focus_tree
+                    .send(format!("Failed to dump the focus tree: {}", e))
+                    .await;
+            }
+        }
+    });
+}
+
+/// Fetches the scripts of the inspected isolate, for telling what a hot reload changed.
+fn fetch_scripts(
+    app_state: &AppState,
+    tx_scripts: &mpsc::Sender<(String, Vec<vm_service::ScriptRef>)>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = &app_state.inspected_isolate else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    let tx_scripts = tx_scripts.clone();
+    tokio::spawn(async move {
+        match client.get_scripts(&isolate_id).await {
+            Ok(scripts) => {
+                let _ = tx_scripts.send((isolate_id, scripts)).await;
+            }
+            Err(e) => log::error!("Failed to fetch the scripts: {}", e),
+        }
+    });
+}
+
+/// Disposes the current inspector object group in the inspected isolate, so the app no
+/// longer pins the elements and render objects of the fetched tree and details.
+fn dispose_inspector_group(app_state: &AppState) -> Option<tokio::task::JoinHandle<()>> {
+    let client = app_state.vm_service_client.clone()?;
+    let isolate_id = app_state.inspected_isolate.as_ref()?.id.clone();
+    Some(tokio::spawn(async move {
+        let group = client.inspector_group();
+        match client.dispose_group(&isolate_id, &group).await {
+            Ok(_) => log::info!("Disposed inspector object group"),
+            Err(e) => log::error!("Failed to dispose inspector object group: {}", e),
+        }
+    }))
+}
+
+/// Fetches the children of expanded nodes that the summary tree cut off.
+fn request_children(app_state: &mut AppState, tx_children_request: &mpsc::Sender<Vec<String>>) {
+    let ids = app_state.take_children_requests();
+    if ids.is_empty() {
+        return;
+    }
+    if let Err(e) = tx_children_request.try_send(ids.clone()) {
+        log::error!("Failed to request children: {}", e);
+        for id in ids {
+            app_state.children_loading.remove(&id);
+        }
+    }
+}
+
+/// Asks the VM task to fetch details for the children of a just-expanded node, so
+/// moving down into them doesn't wait on a round-trip per keypress.
+fn prefetch_details(app_state: &AppState, tx_details_prefetch: &mpsc::Sender<Vec<String>>) {
+    let ids = app_state.details_prefetch_ids();
+    if !ids.is_empty() {
+        let _ = tx_details_prefetch.try_send(ids);
+    }
+}
+
+/// Loads the fields of a Locals entry with `getObject` in the background and hands the
+/// resulting child nodes back to the main loop.
+fn fetch_local_children(
+    app_state: &AppState,
+    key: String,
+    object_id: String,
+    tx_locals: mpsc::Sender<(String, Vec<app_state::VariableNode>)>,
+) {
+    let (Some(client), app_state::DebugState::Paused { isolate_id, .. }) =
+        (&app_state.vm_service_client, &app_state.debug_state)
+    else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_id = isolate_id.clone();
+
+    tokio::spawn(async move {
+        let children = match client.get_object(&isolate_id, &object_id).await {
+            Ok(object) => app_state::VariableNode::children_from_object(&key, &object),
+            Err(e) => {
+                if let Some(sentinel) = vm_service::SentinelError::find(&e) {
+                    log::warn!("Object {} unavailable: {}", object_id, sentinel);
+                    let value = format!("{} — reselect the frame to refresh", sentinel);
+                    let children = vec![app_state::VariableNode::leaf(
+                        &key,
+                        "<expired>",
+                        &sentinel.kind,
+                        &value,
+                    )];
+                    let _ = tx_locals.send((key, children)).await;
+                    return;
+                }
+                log::error!("Failed to fetch object {}: {}", object_id, e);
+                vec![app_state::VariableNode::leaf(
+                    &key,
+                    "<error>",
+                    "Error",
+                    &e.to_string(),
+                )]
+            }
+        };
+        let _ = tx_locals.send((key, children)).await;
+    });
+}
+
+/// Resolves the source location of a stack frame in the background and sends the file
+/// URI and line back to the main loop so the source pane can jump there.
+fn navigate_to_frame(
+    app_state: &AppState,
+    frame_index: usize,
+    tx_source_location: mpsc::Sender<(String, usize)>,
+) {
+    let (Some(client), app_state::DebugState::Paused { isolate_id, .. }) =
+        (&app_state.vm_service_client, &app_state.debug_state)
+    else {
+        return;
+    };
+    let Some(frame) = app_state.stack_frames().get(frame_index).cloned() else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_id = isolate_id.clone();
+
+    tokio::spawn(async move {
+        match client.resolve_frame_location(&isolate_id, &frame).await {
+            Ok(location) => {
+                let _ = tx_source_location.send(location).await;
+            }
+            Err(e) => log::error!("Failed to resolve frame location: {}", e),
+        }
+    });
+}
 
-                                // Logs
-                                let (_, rows) = terminal
-                                    .size()
-                                    .map(|r| (r.width, r.height))
-                                    .unwrap_or((0, 0));
-                                if app_state.show_logs && y >= rows.saturating_sub(10) {
-                                    app_state.scroll_logs(1);
-                                }
+/// Does what clicking an app bar button (or picking it from the More menu) does. Returns
+/// true for Quit, which the caller handles.
+async fn press_app_bar_button(
+    app_state: &mut AppState,
+    button: app_state::AppBarButton,
+    tx_refresh: &mpsc::Sender<()>,
+) -> bool {
+    use app_state::AppBarButton;
 
-                                // Debugger Source
-                                let source_area = *app_state.debugger_source_area.borrow();
-                                if x >= source_area.x
-                                    && x < source_area.x + source_area.width
-                                    && y >= source_area.y
-                                    && y < source_area.y + source_area.height
-                                {
-                                    app_state.source_scroll_offset += 1;
-                                }
-                            }
-                            event::MouseEventKind::ScrollUp => {
-                                let x = mouse.column;
-                                let y = mouse.row;
+    if button == AppBarButton::More {
+        app_state.show_app_bar_menu = !app_state.show_app_bar_menu;
+        app_state.app_bar_menu_selected_index = 0;
+        return false;
+    }
+    app_state.show_app_bar_menu = false;
+    match button {
+        AppBarButton::Inspector => app_state.current_tab = app_state::Tab::Inspector,
+        AppBarButton::Debugger => app_state.current_tab = app_state::Tab::Debugger,
+        AppBarButton::HotReload => hot_reload(app_state, false).await,
+        AppBarButton::HotRestart => hot_reload(app_state, true).await,
+        AppBarButton::AutoReload => {
+            app_state.auto_reload = !app_state.auto_reload;
+            log::info!(
+                "Auto Hot Reload: {}",
+                if app_state.auto_reload { "ON" } else { "OFF" }
+            );
+        }
+        AppBarButton::Refresh => {
+            // Refresh Isolates
+            let _ = tx_refresh.try_send(());
+            app_state.tree_refreshing = true;
+        }
+        AppBarButton::Logs => app_state.show_logs = !app_state.show_logs,
+        AppBarButton::Pause => toggle_app_pause(app_state),
+        AppBarButton::Toggles => app_state.show_toggles = !app_state.show_toggles,
+        AppBarButton::Quit => return true,
+        AppBarButton::More => {}
+    }
+    false
+}
 
-                                // Inspector
-                                let inspector_area = *app_state.inspector_tree_area.borrow();
-                                if x >= inspector_area.x
-                                    && x < inspector_area.x + inspector_area.width
-                                    && y >= inspector_area.y
-                                    && y < inspector_area.y + inspector_area.height
-                                {
-                                    app_state.scroll_tree(-1);
-                                }
+/// Resumes the isolate the Debugger shows paused (or else the selected one), optionally
+/// stepping ("Over", "Into", "Out").
+fn resume_isolate(app_state: &AppState, step: Option<&'static str>) {
+    if let Some(client) = &app_state.vm_service_client {
+        let client = client.clone();
+        let isolate_id = app_state
+            .debugged_isolate_id()
+            .map(str::to_string)
+            .or_else(|| {
+                app_state
+                    .available_isolates
+                    .get(app_state.selected_isolate_index)
+                    .map(|isolate| isolate.id.clone())
+            });
+        if let Some(isolate_id) = isolate_id {
+            tokio::spawn(async move {
+                let _ = client.resume(&isolate_id, step).await;
+            });
+        }
+    }
+}
 
-                                // Debugger
-                                let debugger_area = *app_state.debugger_tree_area.borrow();
-                                if x >= debugger_area.x
-                                    && x < debugger_area.x + debugger_area.width
-                                    && y >= debugger_area.y
-                                    && y < debugger_area.y + debugger_area.height
-                                {
-                                    app_state.move_debugger_selection(-1);
-                                }
+/// Hot reloads (or restarts) through the `flutter` process when there is one. Without it
+/// (`--vm-uri`, or the process exited) the VM service is used: the reload/restart that a
+/// running `flutter` tool registered with it if available, otherwise `reloadSources`
+/// followed by a reassemble.
+async fn hot_reload(app_state: &AppState, restart: bool) {
+    if let Some(tx) = &app_state.tx_flutter_command {
+        if !tx.is_closed() {
+            let _ = tx.send(if restart { "R" } else { "r" }.to_string()).await;
+            return;
+        }
+    }
 
-                                // Logs
-                                let (_, rows) = terminal
-                                    .size()
-                                    .map(|r| (r.width, r.height))
-                                    .unwrap_or((0, 0));
-                                if app_state.show_logs && y >= rows.saturating_sub(10) {
-                                    app_state.scroll_logs(-1);
-                                }
+    let Some(client) = &app_state.vm_service_client else {
+        log::error!("Cannot reload: not connected");
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
 
-                                // Debugger Source
-                                let source_area = *app_state.debugger_source_area.borrow();
-                                if x >= source_area.x
-                                    && x < source_area.x + source_area.width
-                                    && y >= source_area.y
-                                    && y < source_area.y + source_area.height
-                                {
-                                    if app_state.source_scroll_offset > 0 {
-                                        app_state.source_scroll_offset -= 1;
-                                    }
-                                }
-                            }
-                            _ => {}
-                        }
+    let service = if restart {
+        "hotRestart"
+    } else {
+        "reloadSources"
+    };
+    let registered = app_state.registered_services.get(service).cloned();
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    tokio::spawn(async move {
+        let result = match registered {
+            Some(method) => client.call_registered_service(&method, &isolate_id).await,
+            None if restart => Err(anyhow::anyhow!(
+                "hot restart needs a running flutter tool (flutter run or attach)"
+            )),
+            None => match client.reload_sources(&isolate_id).await {
+                Ok(_) => client.reassemble(&isolate_id).await,
+                Err(e) => Err(e),
+            },
+        };
+        // The "Reloaded"/"Restarted" wording also triggers the tree refresh
+        match result {
+            Ok(_) if restart => log::info!("Restarted application via VM service"),
+            Ok(_) => log::info!("Reloaded application via VM service"),
+            Err(e) => log::error!("VM service {} failed: {}", service, e),
+        }
+    });
+}
+
+/// Flips the service extension selected in the Toggles popup, or moves a choice on to its
+/// next value. The popup shows the state the app reports back.
+fn toggle_service_extension(
+    app_state: &AppState,
+    tx_toggles: &mpsc::Sender<(String, app_state::ToggleState)>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+    let Some(row) = app_state::toggle_rows().nth(app_state.toggles_selected_index) else {
+        return;
+    };
+    let method = row.method;
+    let state = app_state.toggle_states.get(method);
+    let enabled = state != Some(&app_state::ToggleState::Enabled(true));
+    // The value after the current one, the first when the app reported something else
+    let next_value = match (row.kind, state) {
+        (
+            app_state::ToggleKind::Choice { values, .. },
+            Some(app_state::ToggleState::Value(value)),
+        ) => values
+            .iter()
+            .position(|v| v == value)
+            .map_or(values[0], |i| values[(i + 1) % values.len()]),
+        (app_state::ToggleKind::Choice { values, .. }, _) => values[0],
+        _ => "",
+    };
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    let tx_toggles = tx_toggles.clone();
+
+    tokio::spawn(async move {
+        let result = match row.kind {
+            app_state::ToggleKind::Extension => client
+                .bool_extension(&isolate_id, method, Some(enabled))
+                .await
+                .map(app_state::ToggleState::Enabled),
+            app_state::ToggleKind::DebugFlag { library } => client
+                .library_bool(&isolate_id, library, method, Some(enabled))
+                .await
+                .map(app_state::ToggleState::Enabled),
+            app_state::ToggleKind::Choice { parameter, .. } => client
+                .value_extension(&isolate_id, method, parameter, Some(next_value))
+                .await
+                .map(app_state::ToggleState::Value),
+        };
+        match result {
+            Ok(state) => {
+                match &state {
+                    app_state::ToggleState::Enabled(enabled) => log::info!(
+                        "{} {}",
+                        method,
+                        if *enabled { "enabled" } else { "disabled" }
+                    ),
+                    app_state::ToggleState::Value(value) => {
+                        log::info!("{} set to {}", method, value)
                     }
                 }
-                _ => {}
+                let _ = tx_toggles.send((method.to_string(), state)).await;
+            }
+            Err(e) => log::error!("Failed to toggle {}: {}", method, e),
+        }
+    });
+}
+
+/// Forces the framework to rebuild ("hot reload UI only") and refreshes the tree once done.
+fn reassemble_app(app_state: &mut AppState, tx_refresh: &mpsc::Sender<()>) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+
+    app_state.tree_refreshing = true;
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    let tx_refresh = tx_refresh.clone();
+    tokio::spawn(async move {
+        match client.reassemble(&isolate_id).await {
+            Ok(_) => log::info!("Reassembled application"),
+            Err(e) => log::error!("Failed to reassemble: {}", e),
+        }
+        let _ = tx_refresh.send(()).await;
+    });
+}
+
+/// Counts the widgets of the full tree and samples the inspected isolate's memory. The
+/// tree is fetched into an object group of its own that is disposed right after.
+fn take_snapshot(app_state: &AppState, tx_snapshot: &mpsc::Sender<snapshots::TreeSnapshot>) {
+    const SNAPSHOT_GROUP: &str = "tui_snapshot";
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = &app_state.inspected_isolate else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    let tx_snapshot = tx_snapshot.clone();
+    tokio::spawn(async move {
+        let tree = client
+            .get_root_widget_tree(SNAPSHOT_GROUP, &isolate_id)
+            .await;
+        let _ = client.dispose_group(&isolate_id, SNAPSHOT_GROUP).await;
+        let tree = match tree {
+            Ok(tree) => tree,
+            Err(e) => {
+                log::error!("Failed to snapshot the widget tree: {}", e);
+                return;
             }
+        };
+        let memory = client
+            .get_memory_usage(&isolate_id)
+            .await
+            .map(|usage| usage.heap_usage + usage.external_usage)
+            .unwrap_or(0);
+        let _ = tx_snapshot
+            .send(snapshots::TreeSnapshot::new(&tree, memory))
+            .await;
+    });
+}
+
+/// Saves the recorded timeline window, for a trigger or by hand.
+fn save_timeline_capture(app_state: &AppState, reason: &str) {
+    match app_state.timeline.save(&app_state.project_root, reason) {
+        Ok(path) => log::info!(
+            "Timeline capture ({}, {} frames) saved to {}",
+            reason,
+            app_state.timeline.frame_count(),
+            path.display()
+        ),
+        Err(e) => log::error!("Failed to save timeline capture: {}", e),
+    }
+}
+
+/// Turns on the VM's CPU profiler, which release-like setups may start with off.
+fn enable_profiler(app_state: &AppState) {
+    let Some(client) = app_state.vm_service_client.clone() else {
+        return;
+    };
+    tokio::spawn(async move {
+        if let Err(e) = client.set_vm_flag("profiler", "true").await {
+            log::error!("Failed to enable the CPU profiler: {}", e);
+        }
+    });
+}
+
+/// Fetches the inspected isolate's CPU samples of the selected window back from now.
+fn capture_profile(
+    app_state: &mut AppState,
+    tx_profile: &mpsc::Sender<Result<profiler::CpuProfile, String>>,
+) {
+    let Some(client) = app_state.vm_service_client.clone() else {
+        return;
+    };
+    let Some(isolate) = &app_state.inspected_isolate else {
+        return;
+    };
+    let isolate_id = isolate.id.clone();
+    let Some(profiler) = &mut app_state.profiler else {
+        return;
+    };
+    let window_secs = profiler.window_secs();
+    let tx_profile = tx_profile.clone();
+    profiler.capturing = true;
+    tokio::spawn(async move {
+        let result = async {
+            client.set_vm_flag("profiler", "true").await?;
+            let extent = window_secs as i64 * 1_000_000;
+            let now = client.get_vm_timeline_micros().await?;
+            client
+                .get_cpu_samples(&isolate_id, now - extent, extent)
+                .await
+        }
+        .await;
+        let profile = result
+            .map(|samples| profiler::CpuProfile::new(window_secs, samples))
+            .map_err(|e| format!("Failed to get CPU samples: {}", e));
+        let _ = tx_profile.send(profile).await;
+    });
+}
+
+/// Writes the UI state and the breakpoints to disk when they changed since the last time.
+fn journal_state(app_state: &AppState, journal: &mut persistence::Journal) {
+    let root = &app_state.project_root;
+    let files = [
+        (
+            persistence::state_path(root),
+            serde_json::to_vec_pretty(&app_state.persisted_state()),
+        ),
+        (
+            breakpoints::breakpoints_path(root),
+            serde_json::to_vec_pretty(&app_state.breakpoint_entries()),
+        ),
+    ];
+    for (path, content) in files {
+        let result = content
+            .map_err(anyhow::Error::from)
+            .and_then(|content| journal.write(path, content));
+        if let Err(e) = result {
+            log::error!("{:#}", e);
         }
     }
+}
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+/// Vim keys in the panes they navigate: hjkl for the arrows, gg/G for Ctrl+Home/End,
+/// Ctrl+D/U for half a page and gd for `g`, opening a widget's source. Keys the config
+/// binds to an action are left to it. Returns the key to handle in place of `key`, None
+/// once it has been handled here.
+fn vim_key(
+    app_state: &mut AppState,
+    key: event::KeyEvent,
+    size: (u16, u16),
+) -> Option<event::KeyEvent> {
+    use app_state::Focus;
+    if !app_state.vim_keys
+        || !matches!(
+            app_state.focus,
+            Focus::Tree | Focus::DebuggerFiles | Focus::DebuggerSource | Focus::Logs
+        )
+    {
+        return Some(key);
+    }
+    let pending_g = std::mem::take(&mut app_state.vim_pending_g);
+    if app_state
+        .keymap
+        .is_remapped_key(keymap::KeyBinding::from_event(&key))
+    {
+        return Some(key);
+    }
+    let to = |code, modifiers| Some(event::KeyEvent::new(code, modifiers));
+    let none = event::KeyModifiers::NONE;
+    if key.modifiers.contains(event::KeyModifiers::CONTROL) {
+        let direction = match key.code {
+            KeyCode::Char('d') => 1,
+            KeyCode::Char('u') => -1,
+            _ => return Some(key),
+        };
+        let half_page = (app_state.focused_pane_height() / 2).max(1) as isize;
+        app_state.anchor_marks(false);
+        move_focused_selection(app_state, direction * half_page, size);
+        return None;
+    }
+    match key.code {
+        KeyCode::Char('h') => to(KeyCode::Left, none),
+        KeyCode::Char('j') => to(KeyCode::Down, none),
+        KeyCode::Char('k') => to(KeyCode::Up, none),
+        KeyCode::Char('l') => to(KeyCode::Right, none),
+        KeyCode::Char('G') => to(KeyCode::End, event::KeyModifiers::CONTROL),
+        KeyCode::Char('g') if pending_g => to(KeyCode::Home, event::KeyModifiers::CONTROL),
+        KeyCode::Char('d') if pending_g => to(KeyCode::Char('g'), none),
+        KeyCode::Char('g') => {
+            app_state.vim_pending_g = true;
+            None
+        }
+        _ => Some(key),
+    }
+}
 
-    Ok(())
+/// A move far enough to reach either end of any pane, that still adds to an index
+/// without overflowing.
+const TO_EDGE: isize = isize::MAX / 2;
+
+/// Up/Down in the focused pane, with Shift extending the selection of the lists that have
+/// one and Ctrl+Home/End jumping to either end. `size` is the terminal's.
+fn move_in_focused_pane(app_state: &mut AppState, key: event::KeyEvent, size: (u16, u16)) {
+    let delta = match key.code {
+        KeyCode::Up => -1,
+        KeyCode::Down => 1,
+        KeyCode::Home if key.modifiers.contains(event::KeyModifiers::CONTROL) => -TO_EDGE,
+        KeyCode::End if key.modifiers.contains(event::KeyModifiers::CONTROL) => TO_EDGE,
+        _ => return,
+    };
+    app_state.anchor_marks(key.modifiers.contains(event::KeyModifiers::SHIFT));
+    move_focused_selection(app_state, delta, size);
+}
+
+/// Moves the cursor of the focused pane by `delta` rows, scrolling to keep it shown.
+fn move_focused_selection(app_state: &mut AppState, delta: isize, size: (u16, u16)) {
+    match app_state.focus {
+        app_state::Focus::Tree if app_state.current_tab == app_state::Tab::Inspector => {
+            app_state.move_selection(delta);
+            let (cols, rows) = size;
+            let tree_height = (rows
+                .saturating_sub(3 + ui::STATUS_BAR_HEIGHT + app_state.log_panel_rows()))
+                as usize; // Approx tree height (minus app bar and logs)
+            let tree_width = cols as usize * app_state.inspector_split[0] as usize / 100;
+            app_state.update_tree_scroll(tree_height.saturating_sub(2));
+            app_state.ensure_horizontal_visibility(tree_width.saturating_sub(2));
+
+            app_state.request_details();
+        }
+        app_state::Focus::Logs => app_state.move_log_selection(delta),
+        app_state::Focus::Profiler => {
+            if let Some(profiler) = &mut app_state.profiler {
+                profiler.move_selection(delta);
+            }
+        }
+        app_state::Focus::Memory => {
+            if let Some(memory) = &mut app_state.memory {
+                memory.move_allocation_selection(delta);
+            }
+        }
+        app_state::Focus::Timeline => {
+            if let Some(performance) = &mut app_state.performance {
+                performance.move_selection(delta);
+            }
+        }
+        app_state::Focus::Network => {
+            if let Some(network) = &mut app_state.network {
+                network.move_selection(delta);
+            }
+        }
+        app_state::Focus::NetworkDetails => {
+            if let Some(network) = &mut app_state.network {
+                network.details_scroll = network
+                    .details_scroll
+                    .saturating_add_signed(delta)
+                    .min(*network.details_max_scroll.borrow());
+            }
+        }
+        app_state::Focus::DebuggerLocals => app_state.move_locals_selection(delta),
+        app_state::Focus::Details => app_state.move_details_selection(delta),
+        app_state::Focus::DebuggerStack => app_state.move_stack_selection(delta),
+        app_state::Focus::DebuggerBreakpoints => app_state.move_breakpoint_selection(delta),
+        app_state::Focus::DebuggerFiles => {
+            app_state.move_debugger_selection(delta);
+            let tree_height = *app_state.debugger_tree_height.borrow();
+            app_state.update_debugger_tree_scroll(tree_height.saturating_sub(2));
+        }
+        app_state::Focus::DebuggerSource => app_state.move_source_selection(delta),
+        _ => {}
+    }
+}
+
+/// Fetches the VM's timeline events of the selected window back from now.
+fn capture_timeline(
+    app_state: &mut AppState,
+    tx_timeline_capture: &mpsc::Sender<Result<timeline::TimelineCapture, String>>,
+) {
+    let Some(client) = app_state.vm_service_client.clone() else {
+        return;
+    };
+    let Some(performance) = &mut app_state.performance else {
+        return;
+    };
+    let window_secs = performance.window_secs();
+    let tx_timeline_capture = tx_timeline_capture.clone();
+    performance.capturing = true;
+    tokio::spawn(async move {
+        let result = async {
+            // Recording may have been turned off by another tool since connecting
+            client
+                .set_vm_timeline_flags(&timeline::RECORDED_STREAMS)
+                .await?;
+            let extent = window_secs as i64 * 1_000_000;
+            let now = client.get_vm_timeline_micros().await?;
+            let events = client.get_vm_timeline(now - extent, extent).await?;
+            Ok::<_, anyhow::Error>(timeline::TimelineCapture::new(
+                window_secs,
+                now - extent,
+                &events,
+            ))
+        }
+        .await;
+        let capture = result.map_err(|e| format!("Failed to get the timeline: {}", e));
+        let _ = tx_timeline_capture.send(capture).await;
+    });
+}
+
+/// Fetches the inspected isolate's heap usage per class, optionally collecting garbage
+/// first.
+fn fetch_allocation_profile(
+    app_state: &AppState,
+    gc: bool,
+    tx_allocations: &mpsc::Sender<Result<Vec<vm_service::ClassHeapStats>, String>>,
+) {
+    let Some(client) = app_state.vm_service_client.clone() else {
+        return;
+    };
+    let Some(isolate) = &app_state.inspected_isolate else {
+        return;
+    };
+    let isolate_id = isolate.id.clone();
+    let tx_allocations = tx_allocations.clone();
+    tokio::spawn(async move {
+        let result = client
+            .get_allocation_profile(&isolate_id, gc)
+            .await
+            .map(|profile| profile.members)
+            .map_err(|e| format!("Failed to get the allocation profile: {}", e));
+        if gc && result.is_ok() {
+            log::info!("Collected garbage in {}", isolate_id);
+        }
+        let _ = tx_allocations.send(result).await;
+    });
+}
+
+/// Samples the memory of every isolate, not just the inspected one.
+fn poll_isolate_memory(
+    app_state: &AppState,
+    tx_memory: &mpsc::Sender<Vec<(String, vm_service::MemoryUsage)>>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_ids: Vec<String> = app_state
+        .available_isolates
+        .iter()
+        .map(|isolate| isolate.id.clone())
+        .collect();
+    let tx_memory = tx_memory.clone();
+    tokio::spawn(async move {
+        let results = futures::future::join_all(
+            isolate_ids
+                .iter()
+                .map(|isolate_id| client.get_memory_usage(isolate_id)),
+        )
+        .await;
+        let mut samples = Vec::new();
+        for (isolate_id, result) in isolate_ids.into_iter().zip(results) {
+            match result {
+                Ok(usage) => samples.push((isolate_id, usage)),
+                Err(e) => log::error!("Failed to get memory usage of {}: {}", isolate_id, e),
+            }
+        }
+        let _ = tx_memory.send(samples).await;
+    });
+}
+
+/// Fetches memory, pause state and live ports of every isolate, grouped by isolate group.
+fn fetch_isolate_metrics(
+    app_state: &mut AppState,
+    tx_isolate_metrics: &mpsc::Sender<Result<Vec<app_state::IsolateGroupMetrics>, String>>,
+) {
+    let Some(client) = app_state.vm_service_client.clone() else {
+        return;
+    };
+    let tx_isolate_metrics = tx_isolate_metrics.clone();
+    app_state.isolate_metrics = None;
+    tokio::spawn(async move {
+        let client = &client;
+        let isolate_metrics = |isolate: vm_service::IsolateRef| async move {
+            let (info, usage) = futures::future::join(
+                client.get_isolate(&isolate.id),
+                client.get_memory_usage(&isolate.id),
+            )
+            .await;
+            let info = info.ok();
+            app_state::IsolateMetrics {
+                id: isolate.id,
+                name: isolate.name,
+                usage: usage.ok(),
+                pause_event: info
+                    .as_ref()
+                    .and_then(|info| info.pause_event.as_ref())
+                    .map(|event| event.kind.clone()),
+                live_ports: info.and_then(|info| info.live_ports),
+            }
+        };
+        let group_metrics = |group: vm_service::IsolateGroupRef| async move {
+            let (isolates, usage) = futures::future::join(
+                client.get_isolate_group(&group.id),
+                client.get_isolate_group_memory_usage(&group.id),
+            )
+            .await;
+            let isolates = isolates.map(|g| g.isolates).unwrap_or_default();
+            app_state::IsolateGroupMetrics {
+                name: group.name,
+                usage: usage.ok(),
+                isolates: futures::future::join_all(isolates.into_iter().map(isolate_metrics))
+                    .await,
+            }
+        };
+
+        let result = match client.get_vm().await {
+            // Before isolate groups, every isolate had a heap of its own
+            Ok(vm) if vm.isolate_groups.is_empty() => Ok(vec![app_state::IsolateGroupMetrics {
+                name: "VM".to_string(),
+                usage: None,
+                isolates: futures::future::join_all(vm.isolates.into_iter().map(isolate_metrics))
+                    .await,
+            }]),
+            Ok(vm) => Ok(futures::future::join_all(
+                vm.isolate_groups.into_iter().map(group_metrics),
+            )
+            .await),
+            Err(e) => Err(format!("Failed to get the VM: {}", e)),
+        };
+        let _ = tx_isolate_metrics.send(result).await;
+    });
+}
+
+/// Turns the framework's per-frame rebuild and repaint counts (and the tree spark-lines)
+/// on or off.
+fn toggle_rebuild_tracking(app_state: &mut AppState) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+
+    let enabled = !app_state.rebuild_tracking;
+    app_state.rebuild_tracking = enabled;
+    app_state.rebuilds.clear();
+    app_state.repaints.clear();
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    tokio::spawn(async move {
+        match client
+            .track_rebuild_dirty_widgets(&isolate_id, enabled)
+            .await
+        {
+            Ok(_) => log::info!(
+                "Rebuild tracking {}",
+                if enabled { "enabled" } else { "disabled" }
+            ),
+            Err(e) => log::error!("Failed to toggle rebuild tracking: {}", e),
+        }
+        if let Err(e) = client.track_repaint_widgets(&isolate_id, enabled).await {
+            log::error!("Failed to toggle repaint tracking: {}", e);
+        }
+    });
+}
+
+/// Turns on-device widget selection on or off. While it is on, widgets tapped on the
+/// device are selected in the tree and the tree selection is highlighted on the device.
+fn toggle_widget_select_mode(app_state: &mut AppState) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+
+    let enabled = !app_state.widget_select_mode;
+    app_state.widget_select_mode = enabled;
+    // Highlight the current selection on the device straight away
+    app_state.device_selection = None;
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    tokio::spawn(async move {
+        match client
+            .bool_extension(&isolate_id, "ext.flutter.inspector.show", Some(enabled))
+            .await
+        {
+            Ok(_) => log::info!(
+                "Widget select mode {}",
+                if enabled { "enabled" } else { "disabled" }
+            ),
+            Err(e) => log::error!("Failed to toggle widget select mode: {}", e),
+        }
+    });
+}
+
+fn sync_device_selection(app_state: &AppState, id: String) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    tokio::spawn(async move {
+        if let Err(e) = client.set_selection_by_id(&isolate_id, &id).await {
+            log::error!("Failed to select widget on the device: {}", e);
+        }
+    });
+}
+
+/// Pauses the selected (UI) isolate so the current frame stays on screen, or resumes it if
+/// it is already paused.
+fn toggle_app_pause(app_state: &mut AppState) {
+    match app_state.pause_control() {
+        app_state::PauseControl::Start => return start_app(app_state),
+        app_state::PauseControl::Resume => return resume_isolate(app_state, None),
+        app_state::PauseControl::Pause => {}
+    }
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+
+    app_state.pause_requested = true;
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    tokio::spawn(async move {
+        if let Err(e) = client.pause(&isolate_id).await {
+            log::error!("Failed to pause isolate: {}", e);
+        }
+    });
+}
+
+/// Looks for the isolates with the inspector extensions once a second, for up to 30 seconds.
+fn search_inspector_isolates(
+    client: VmServiceClient,
+    isolates: Vec<vm_service::IsolateRef>,
+    tx_inspector_isolates: mpsc::Sender<HashSet<String>>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        for _ in 0..30 {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let found = client.find_inspector_isolates(&isolates).await;
+            if !found.is_empty() {
+                let _ = tx_inspector_isolates.send(found).await;
+                break;
+            }
+        }
+    })
+}
+
+/// Resumes the isolates of an app started with `--start-paused`, once the breakpoints that
+/// should catch its startup are set.
+fn start_app(app_state: &AppState) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    for isolate_id in app_state.isolates_paused_at_start() {
+        log::info!("Starting {}", app_state.isolate_name(&isolate_id));
+        let client = client.clone();
+        tokio::spawn(async move {
+            if let Err(e) = client.resume(&isolate_id, None).await {
+                log::error!("Failed to start isolate {}: {}", isolate_id, e);
+            }
+        });
+    }
+}
+
+/// Asks the VM task for the nested properties of a Details entry. The request is tagged
+/// with the inspected node so late results for a previous selection are dropped.
+fn request_property_children(
+    app_state: &AppState,
+    key: String,
+    object_id: String,
+    tx_properties_request: &mpsc::Sender<(String, String, String)>,
+) {
+    if let Some(details_id) = app_state
+        .selected_node_details
+        .as_ref()
+        .and_then(AppState::get_node_id)
+    {
+        let _ = tx_properties_request.try_send((details_id, key, object_id));
+    }
+}
+
+/// Registers a breakpoint with every isolate of the app, so it is hit whichever isolate
+/// runs the line.
+fn add_vm_breakpoint(
+    app_state: &AppState,
+    bp_id: String,
+    line: usize,
+    tx_breakpoint_added: &mpsc::Sender<(String, String, String)>,
+) {
+    for isolate in &app_state.available_isolates {
+        add_isolate_breakpoint(
+            app_state,
+            &isolate.id,
+            bp_id.clone(),
+            line,
+            tx_breakpoint_added,
+        );
+    }
+}
+
+/// Registers a breakpoint with one isolate and reports the VM's breakpoint id back to the
+/// main loop, keyed by "path:line" and the isolate.
+fn add_isolate_breakpoint(
+    app_state: &AppState,
+    isolate_id: &str,
+    bp_id: String,
+    line: usize,
+    tx_breakpoint_added: &mpsc::Sender<(String, String, String)>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some((path, _)) = bp_id.rsplit_once(':') else {
+        return;
+    };
+
+    let client = client.clone();
+    let isolate_id = isolate_id.to_string();
+    let full_path = app_state.project_root.join(path);
+    let script_uri = format!("file://{}", full_path.to_string_lossy());
+    let tx_breakpoint_added = tx_breakpoint_added.clone();
+
+    log::info!(
+        "Attempting to set breakpoint at {} line {}",
+        script_uri,
+        line
+    );
+
+    tokio::spawn(async move {
+        match client
+            .add_breakpoint_with_script_uri(&isolate_id, &script_uri, line)
+            .await
+        {
+            Ok(breakpoint) => {
+                log::info!(
+                    "Added breakpoint {} at {}:{} in {} (resolved: {})",
+                    breakpoint.id,
+                    script_uri,
+                    line,
+                    isolate_id,
+                    breakpoint.resolved
+                );
+                let _ = tx_breakpoint_added
+                    .send((bp_id, isolate_id, breakpoint.id))
+                    .await;
+            }
+            Err(e) => log::error!("Failed to add breakpoint: {}", e),
+        }
+    });
+}
+
+/// Drops a breakpoint locally and removes it from every isolate it was registered with.
+fn remove_vm_breakpoint(app_state: &mut AppState, bp_id: &str) {
+    let vm_ids = app_state.remove_breakpoint(bp_id);
+    unregister_breakpoint(app_state, bp_id.to_string(), vm_ids);
+}
+
+/// Removes breakpoint `bp_id` from the VM, given its VM id in each isolate it was set in.
+fn unregister_breakpoint(
+    app_state: &AppState,
+    bp_id: String,
+    vm_ids: std::collections::HashMap<String, String>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+
+    let client = client.clone();
+    tokio::spawn(async move {
+        for (isolate_id, vm_id) in vm_ids {
+            match client.remove_breakpoint(&isolate_id, &vm_id).await {
+                Ok(_) => log::info!("Removed breakpoint at {} from {}", bp_id, isolate_id),
+                Err(e) => log::error!("Failed to remove breakpoint at {}: {}", bp_id, e),
+            }
+        }
+    });
+}
+
+/// Makes the isolate at `selected_isolate_index` the inspected one: the VM task fetches its
+/// widget tree, and the Debugger shows its pause if it is paused. Closes the selection popup
+/// if open. Returns false if there is nothing to select.
+fn inspect_selected_isolate(
+    app_state: &mut AppState,
+    tx_selected_isolate: &mpsc::Sender<String>,
+) -> bool {
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return false;
+    };
+    log::info!("Selecting isolate: {}", isolate.id);
+    if let Err(e) = tx_selected_isolate.try_send(isolate.id.clone()) {
+        log::error!("Failed to select isolate: {}", e);
+        return false;
+    }
+    let isolate = isolate.clone();
+    app_state.show_paused_isolate(&isolate.id);
+    app_state.inspected_isolate = Some(isolate);
+    if app_state.show_isolate_selection {
+        app_state.show_isolate_selection = false;
+        app_state.focus = app_state::Focus::Tree;
+    }
+    true
+}
+
+/// Registers every enabled breakpoint with each isolate that doesn't have it yet.
+///
+/// The VM forgets breakpoints when an isolate goes away (e.g. on hot restart), so this runs
+/// whenever a new isolate list arrives.
+fn register_breakpoints(
+    app_state: &mut AppState,
+    tx_breakpoint_added: &mpsc::Sender<(String, String, String)>,
+) {
+    let live: HashSet<String> = app_state
+        .available_isolates
+        .iter()
+        .map(|isolate| isolate.id.clone())
+        .collect();
+    for vm_ids in app_state.breakpoint_vm_ids.values_mut() {
+        vm_ids.retain(|isolate_id, _| live.contains(isolate_id));
+    }
+    for (bp_id, line) in app_state.enabled_breakpoints() {
+        for isolate_id in &live {
+            let registered = app_state
+                .breakpoint_vm_ids
+                .get(&bp_id)
+                .is_some_and(|vm_ids| vm_ids.contains_key(isolate_id));
+            if !registered {
+                add_isolate_breakpoint(
+                    app_state,
+                    isolate_id,
+                    bp_id.clone(),
+                    line,
+                    tx_breakpoint_added,
+                );
+            }
+        }
+    }
+}
+
+/// Handles a hit on a conditional breakpoint or logpoint in the background.
+///
+/// The condition is evaluated in the paused frame and the isolate resumed if it doesn't hold.
+/// Logpoints then log their message and resume; plain breakpoints re-send the pause without
+/// its breakpoint id so the main loop treats it like any other.
+fn run_breakpoint_actions(
+    app_state: &AppState,
+    key: String,
+    state: app_state::DebugState,
+    stack: Option<vm_service::Stack>,
+    tx_debug_event: mpsc::Sender<app_state::DebugEvent>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let app_state::DebugState::Paused {
+        isolate_id, reason, ..
+    } = state
+    else {
+        return;
+    };
+    let client = client.clone();
+    let condition = app_state.breakpoint_conditions.get(&key).cloned();
+    let expression = app_state
+        .logpoints
+        .get(&key)
+        .map(|template| breakpoints::logpoint_expression(template));
+
+    tokio::spawn(async move {
+        if let Some(condition) = condition {
+            let holds = match client.evaluate_in_frame(&isolate_id, 0, &condition).await {
+                Ok(result) if result.get("type").and_then(|t| t.as_str()) == Some("@Error") => {
+                    // Stop so a broken condition doesn't go unnoticed
+                    log::error!(
+                        "Breakpoint condition at {} failed: {}",
+                        key,
+                        result
+                            .get("message")
+                            .and_then(|m| m.as_str())
+                            .unwrap_or("unknown error")
+                    );
+                    true
+                }
+                Ok(result) => result.get("valueAsString").and_then(|v| v.as_str()) == Some("true"),
+                Err(e) => {
+                    log::error!("Breakpoint condition at {} failed: {}", key, e);
+                    true
+                }
+            };
+            if !holds {
+                if let Err(e) = client.resume(&isolate_id, None).await {
+                    log::error!("Failed to resume after breakpoint condition: {}", e);
+                }
+                return;
+            }
+        }
+
+        let Some(expression) = expression else {
+            let state = app_state::DebugState::Paused {
+                isolate_id,
+                reason,
+                breakpoint_id: None,
+            };
+            let _ = tx_debug_event
+                .send(app_state::DebugEvent::Paused(state, stack))
+                .await;
+            return;
+        };
+
+        match client.evaluate_in_frame(&isolate_id, 0, &expression).await {
+            Ok(result) => {
+                let message = result
+                    .get("valueAsString")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("<no value>");
+                log::info!("[Logpoint] {}: {}", key, message);
+            }
+            Err(e) => log::error!("[Logpoint] {}: failed to evaluate: {}", key, e),
+        }
+        if let Err(e) = client.resume(&isolate_id, None).await {
+            log::error!("Failed to resume after logpoint: {}", e);
+        }
+    });
+}
+
+/// Copies an approximate Dart constructor call for the selected widget, built from its
+/// details when they are loaded so the properties are included.
+fn copy_widget_as_dart(app_state: &AppState) {
+    let Some(node) = app_state
+        .selected_node_details
+        .as_ref()
+        .or(app_state.get_selected_node())
+    else {
+        return;
+    };
+    let snippet = dart_snippet::widget_snippet(node);
+    match clipboard::copy(&snippet) {
+        Ok(()) => log::info!(
+            "Copied {} as Dart ({} lines)",
+            node.widget_runtime_type.as_deref().unwrap_or("widget"),
+            snippet.lines().count()
+        ),
+        Err(e) => log::error!("Failed to copy to the clipboard: {}", e),
+    }
+}
+
+/// Evaluates a config snippet in the inspected isolate and shows the result in a popup.
+fn run_snippet(
+    app_state: &mut AppState,
+    snippet: config::Snippet,
+    tx_eval_result: &mpsc::Sender<(String, Result<String, String>)>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        log::warn!("Cannot run {}: not connected", snippet.name);
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    let tx_eval_result = tx_eval_result.clone();
+    app_state.eval_result = Some(app_state::EvalResult {
+        name: snippet.name.clone(),
+        expression: snippet.expression.clone(),
+        output: None,
+    });
+    app_state.eval_result_scroll = 0;
+
+    tokio::spawn(async move {
+        let result = client
+            .evaluate_in_library(&isolate_id, snippet.library.as_deref(), &snippet.expression)
+            .await
+            .map_err(|e| e.to_string());
+        let _ = tx_eval_result.send((snippet.name, result)).await;
+    });
+}
+
+/// Screenshots the selected widget into the screenshot popup.
+fn take_screenshot(
+    app_state: &mut AppState,
+    tx_screenshot: &mpsc::Sender<(String, Result<Vec<u8>, String>)>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = &app_state.inspected_isolate else {
+        return;
+    };
+    let Some(node) = app_state.get_selected_node() else {
+        return;
+    };
+    let Some(object_id) = AppState::get_node_id(node) else {
+        return;
+    };
+    let widget = node
+        .widget_runtime_type
+        .clone()
+        .or(node.description.clone())
+        .unwrap_or_else(|| "widget".to_string());
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    let tx_screenshot = tx_screenshot.clone();
+    app_state.show_screenshot = true;
+    app_state.screenshot = None;
+
+    tokio::spawn(async move {
+        // Plenty for the terminal, and a reasonable file to save
+        let result = client
+            .screenshot(&isolate_id, &object_id, 800, 800)
+            .await
+            .map_err(|e| e.to_string());
+        let _ = tx_screenshot.send((widget, result)).await;
+    });
+}
+
+/// Makes sure `dart:io` records HTTP requests, then fetches what it recorded so far.
+fn fetch_http_profile(
+    app_state: &AppState,
+    tx_http_requests: &mpsc::Sender<Result<Vec<network::HttpProfileRequest>, String>>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    let tx_http_requests = tx_http_requests.clone();
+
+    tokio::spawn(async move {
+        if let Err(e) = client
+            .bool_extension(
+                &isolate_id,
+                "ext.dart.io.httpEnableTimelineLogging",
+                Some(true),
+            )
+            .await
+        {
+            log::error!("Failed to enable HTTP logging: {}", e);
+        }
+        let result = client
+            .get_http_profile(&isolate_id)
+            .await
+            .map_err(|e| e.to_string());
+        let _ = tx_http_requests.send(result).await;
+    });
+}
+
+/// Fetches the selected request with its headers and bodies for the details pane.
+fn fetch_http_details(
+    app_state: &mut AppState,
+    tx_http_details: &mpsc::Sender<(String, Result<network::HttpProfileRequest, String>)>,
+) {
+    let Some(client) = app_state.vm_service_client.clone() else {
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+    let isolate_id = isolate.id.clone();
+    let Some(network) = &mut app_state.network else {
+        return;
+    };
+    let Some(request) = network.selected_request() else {
+        return;
+    };
+    let id = request.id.clone();
+    network.details = Some(app_state::HttpDetails {
+        id: id.clone(),
+        request: None,
+    });
+    network.details_scroll = 0;
+    let tx_http_details = tx_http_details.clone();
+
+    tokio::spawn(async move {
+        let result = client
+            .get_http_profile_request(&isolate_id, &id)
+            .await
+            .map_err(|e| e.to_string());
+        let _ = tx_http_details.send((id, result)).await;
+    });
+}
+
+/// Fetches the selected request with its bodies and replays it from the host.
+fn replay_http_request(
+    app_state: &mut AppState,
+    tx_http_replay: &mpsc::Sender<Result<Vec<network::DiffLine>, String>>,
+) {
+    let Some(client) = &app_state.vm_service_client else {
+        return;
+    };
+    let Some(isolate) = app_state
+        .available_isolates
+        .get(app_state.selected_isolate_index)
+    else {
+        return;
+    };
+    let Some(request) = app_state
+        .network
+        .as_ref()
+        .and_then(|network| network.selected_request())
+    else {
+        return;
+    };
+    let client = client.clone();
+    let isolate_id = isolate.id.clone();
+    let id = request.id.clone();
+    let tx_http_replay = tx_http_replay.clone();
+    app_state.http_replay = Some(app_state::HttpReplay {
+        uri: format!("{} {}", request.method, request.uri),
+        report: None,
+    });
+    app_state.http_replay_scroll = 0;
+
+    tokio::spawn(async move {
+        let result = match client.get_http_profile_request(&isolate_id, &id).await {
+            Ok(request) => network::replay(&request).await,
+            Err(e) => Err(e),
+        };
+        let _ = tx_http_replay.send(result.map_err(|e| e.to_string())).await;
+    });
 }
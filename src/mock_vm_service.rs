@@ -0,0 +1,511 @@
+//! A VM service to test against without a device: a WebSocket server answering the part of
+//! the protocol the inspector and debugger use (`getVM`, `getIsolate`, streams, breakpoints
+//! and the inspector extensions) from canned isolates and widget trees.
+//!
+//! Only built with the `mock-vm` feature.
+
+use crate::synthetic_tree::TreeShape;
+use anyhow::{Context, Result};
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+/// JSON-RPC error codes of the VM service protocol.
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const STREAM_ALREADY_SUBSCRIBED: i64 = 103;
+const STREAM_NOT_SUBSCRIBED: i64 = 104;
+
+const INSPECTOR_EXTENSIONS: [&str; 12] = [
+    "ext.flutter.inspector.getRootWidgetSummaryTree",
+    "ext.flutter.inspector.getRootWidgetTree",
+    "ext.flutter.inspector.getRootWidget",
+    "ext.flutter.inspector.getChildrenSummaryTree",
+    "ext.flutter.inspector.getChildrenDetailsSubtree",
+    "ext.flutter.inspector.getDetailsSubtree",
+    "ext.flutter.inspector.getProperties",
+    "ext.flutter.inspector.getSelectedSummaryWidget",
+    "ext.flutter.inspector.setSelectionById",
+    "ext.flutter.inspector.disposeGroup",
+    "ext.flutter.inspector.trackRebuildDirtyWidgets",
+    "ext.flutter.inspector.trackRepaintWidgets",
+];
+
+/// An isolate of the mock VM. Isolates with a widget tree expose the inspector extensions.
+#[derive(Debug, Clone)]
+pub struct MockIsolate {
+    pub id: String,
+    pub name: String,
+    /// The inspector's tree, as the framework serializes it.
+    pub tree: Option<Value>,
+    /// The kind of the event the isolate is paused on, `None` while running.
+    pub pause_kind: Option<String>,
+}
+
+impl MockIsolate {
+    /// The `main` isolate of a Flutter app showing a synthetic tree of `shape`.
+    pub fn flutter(id: &str, shape: TreeShape) -> Self {
+        Self {
+            id: id.to_string(),
+            name: "main".to_string(),
+            tree: Some(serde_json::to_value(shape.generate()).expect("trees serialize")),
+            pause_kind: None,
+        }
+    }
+
+    /// An isolate without the inspector, like a background worker.
+    pub fn plain(id: &str, name: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            tree: None,
+            pause_kind: None,
+        }
+    }
+}
+
+/// A breakpoint set through `addBreakpointWithScriptUri`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockBreakpoint {
+    pub id: String,
+    pub isolate_id: String,
+    pub script_uri: String,
+    pub line: u64,
+}
+
+#[derive(Default)]
+struct MockState {
+    isolates: Vec<MockIsolate>,
+    breakpoints: Vec<MockBreakpoint>,
+    next_breakpoint: u64,
+    /// Every request received, in order, as (method, params).
+    requests: Vec<(String, Value)>,
+    /// The node selected through `setSelectionById`.
+    selected: Option<String>,
+}
+
+/// A running mock VM service. The server stops when this is dropped.
+pub struct MockVmService {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    /// Events for the connected clients, as (stream id, event).
+    tx_events: broadcast::Sender<(String, Value)>,
+    server: JoinHandle<()>,
+}
+
+impl MockVmService {
+    /// Starts a VM with a Flutter `main` isolate showing a 50 node tree.
+    pub async fn start() -> Result<Self> {
+        Self::with_isolates(vec![MockIsolate::flutter("isolates/1", TreeShape::new(50))]).await
+    }
+
+    /// Starts a VM running `isolates`, listening on a free port of localhost.
+    pub async fn with_isolates(isolates: Vec<MockIsolate>) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .context("Failed to bind mock VM service")?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(MockState {
+            isolates,
+            ..Default::default()
+        }));
+        let (tx_events, _) = broadcast::channel(100);
+
+        let server = {
+            let state = state.clone();
+            let tx_events = tx_events.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let state = state.clone();
+                    let tx_events = tx_events.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = serve(stream, state, tx_events).await {
+                            log::error!("Mock VM service connection failed: {}", e);
+                        }
+                    });
+                }
+            })
+        };
+
+        Ok(Self {
+            addr,
+            state,
+            tx_events,
+            server,
+        })
+    }
+
+    /// The WebSocket URI to connect to, as `flutter run` prints it.
+    pub fn uri(&self) -> String {
+        format!("ws://{}/ws", self.addr)
+    }
+
+    /// Every request received so far, as (method, params).
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn breakpoints(&self) -> Vec<MockBreakpoint> {
+        self.state.lock().unwrap().breakpoints.clone()
+    }
+
+    /// The node selected through `setSelectionById`.
+    pub fn selected(&self) -> Option<String> {
+        self.state.lock().unwrap().selected.clone()
+    }
+
+    /// Sends an event to the clients listening to `stream_id`. `event` gets its `type` and
+    /// a timestamp filled in when missing.
+    pub fn send_event(&self, stream_id: &str, mut event: Value) {
+        if let Some(fields) = event.as_object_mut() {
+            fields.entry("type").or_insert(json!("Event"));
+            fields
+                .entry("timestamp")
+                .or_insert(json!(chrono::Utc::now().timestamp_millis()));
+        }
+        // No receivers just means no client is connected
+        let _ = self.tx_events.send((stream_id.to_string(), event));
+    }
+
+    /// Starts a new isolate, announcing it on the Isolate stream.
+    pub fn start_isolate(&self, isolate: MockIsolate) {
+        let isolate_ref = isolate_ref(&isolate);
+        self.state.lock().unwrap().isolates.push(isolate);
+        self.send_event(
+            "Isolate",
+            json!({ "kind": "IsolateStart", "isolate": isolate_ref }),
+        );
+        self.send_event(
+            "Isolate",
+            json!({ "kind": "IsolateRunnable", "isolate": isolate_ref }),
+        );
+    }
+
+    /// Ends an isolate, announcing it on the Isolate stream.
+    pub fn exit_isolate(&self, isolate_id: &str) {
+        let removed = {
+            let mut state = self.state.lock().unwrap();
+            let index = state.isolates.iter().position(|i| i.id == isolate_id);
+            index.map(|index| state.isolates.remove(index))
+        };
+        if let Some(isolate) = removed {
+            self.send_event(
+                "Isolate",
+                json!({ "kind": "IsolateExit", "isolate": isolate_ref(&isolate) }),
+            );
+        }
+    }
+
+    /// Pauses an isolate as if it hit its first breakpoint, announcing it on the Debug
+    /// stream.
+    pub fn hit_breakpoint(&self, isolate_id: &str) {
+        let (isolate_ref, breakpoint) = {
+            let mut state = self.state.lock().unwrap();
+            let breakpoint = state
+                .breakpoints
+                .iter()
+                .find(|b| b.isolate_id == isolate_id)
+                .map(breakpoint_json);
+            let Some(isolate) = state.isolates.iter_mut().find(|i| i.id == isolate_id) else {
+                return;
+            };
+            isolate.pause_kind = Some("PauseBreakpoint".to_string());
+            (isolate_ref(isolate), breakpoint)
+        };
+        let mut event = json!({ "kind": "PauseBreakpoint", "isolate": isolate_ref });
+        if let Some(breakpoint) = breakpoint {
+            event["pauseBreakpoints"] = json!([breakpoint]);
+        }
+        self.send_event("Debug", event);
+    }
+}
+
+impl Drop for MockVmService {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+fn isolate_ref(isolate: &MockIsolate) -> Value {
+    json!({ "type": "@Isolate", "id": isolate.id, "name": isolate.name })
+}
+
+fn breakpoint_json(breakpoint: &MockBreakpoint) -> Value {
+    json!({
+        "type": "Breakpoint",
+        "id": breakpoint.id,
+        "resolved": true,
+        "location": {
+            "type": "SourceLocation",
+            "script": { "type": "@Script", "id": "scripts/1", "uri": breakpoint.script_uri },
+            "line": breakpoint.line,
+        },
+    })
+}
+
+/// One client connection: answers its requests and forwards the events of the streams it
+/// listens to.
+async fn serve(
+    stream: TcpStream,
+    state: Arc<Mutex<MockState>>,
+    tx_events: broadcast::Sender<(String, Value)>,
+) -> Result<()> {
+    let mut ws_stream = tokio_tungstenite::accept_async(stream).await?;
+    let mut rx_events = tx_events.subscribe();
+    let mut streams = HashSet::new();
+
+    loop {
+        tokio::select! {
+            msg = ws_stream.next() => {
+                let text = match msg {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(e.into()),
+                };
+                let request: Value = serde_json::from_str(&text)?;
+                let method = request["method"].as_str().unwrap_or_default().to_string();
+                let params = request.get("params").cloned().unwrap_or(json!({}));
+                state.lock().unwrap().requests.push((method.clone(), params.clone()));
+
+                let result = handle(&state, &mut streams, &method, &params);
+                let response = match result {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": request["id"] }),
+                    Err((code, message)) => json!({
+                        "jsonrpc": "2.0",
+                        "error": { "code": code, "message": message },
+                        "id": request["id"],
+                    }),
+                };
+                ws_stream.send(Message::Text(response.to_string())).await?;
+            }
+            event = rx_events.recv() => {
+                let (stream_id, event) = match event {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if !streams.contains(&stream_id) {
+                    continue;
+                }
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "streamNotify",
+                    "params": { "streamId": stream_id, "event": event },
+                });
+                ws_stream.send(Message::Text(notification.to_string())).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+type RpcResult = std::result::Result<Value, (i64, String)>;
+
+fn handle(
+    state: &Mutex<MockState>,
+    streams: &mut HashSet<String>,
+    method: &str,
+    params: &Value,
+) -> RpcResult {
+    let mut state = state.lock().unwrap();
+    let string_param = |name: &str| {
+        params[name].as_str().map(str::to_string).ok_or_else(|| {
+            (
+                INVALID_PARAMS,
+                format!("{}: missing parameter {}", method, name),
+            )
+        })
+    };
+
+    match method {
+        "getVersion" => Ok(json!({ "type": "Version", "major": 4, "minor": 16 })),
+        "getVM" => Ok(json!({
+            "type": "VM",
+            "isolates": state.isolates.iter().map(isolate_ref).collect::<Vec<_>>(),
+            "isolateGroups": [],
+            "version": "3.5.0 (mock)",
+            "operatingSystem": "linux",
+            "targetCPU": "x64",
+        })),
+        "streamListen" => {
+            let stream_id = string_param("streamId")?;
+            if streams.insert(stream_id) {
+                Ok(json!({ "type": "Success" }))
+            } else {
+                Err((
+                    STREAM_ALREADY_SUBSCRIBED,
+                    "Stream already subscribed".to_string(),
+                ))
+            }
+        }
+        "streamCancel" => {
+            if streams.remove(&string_param("streamId")?) {
+                Ok(json!({ "type": "Success" }))
+            } else {
+                Err((STREAM_NOT_SUBSCRIBED, "Stream not subscribed".to_string()))
+            }
+        }
+        _ => {
+            // Everything else runs on an isolate, which a collected isolate answers with
+            // a Sentinel
+            let isolate_id = string_param("isolateId")?;
+            let Some(isolate) = state.isolates.iter().find(|i| i.id == isolate_id).cloned() else {
+                return Ok(json!({
+                    "type": "Sentinel",
+                    "kind": "Collected",
+                    "valueAsString": "<collected>",
+                }));
+            };
+            if method.starts_with("ext.flutter.inspector.") {
+                let tree = isolate
+                    .tree
+                    .as_ref()
+                    .ok_or_else(|| (METHOD_NOT_FOUND, format!("Unknown method \"{}\"", method)))?;
+                return inspector(&mut state, tree, method, params).map(|result| {
+                    json!({ "type": "_extensionType", "method": method, "result": result })
+                });
+            }
+            isolate_method(&mut state, &isolate, method, params)
+        }
+    }
+}
+
+fn isolate_method(
+    state: &mut MockState,
+    isolate: &MockIsolate,
+    method: &str,
+    params: &Value,
+) -> RpcResult {
+    match method {
+        "getIsolate" => {
+            let extension_rpcs: &[&str] = if isolate.tree.is_some() {
+                &INSPECTOR_EXTENSIONS
+            } else {
+                &[]
+            };
+            let root_lib = json!({
+                "type": "@Library",
+                "id": "libraries/1",
+                "uri": "package:app/main.dart",
+            });
+            Ok(json!({
+                "type": "Isolate",
+                "id": isolate.id,
+                "name": isolate.name,
+                "extensionRPCs": extension_rpcs,
+                "libraries": [root_lib],
+                "rootLib": root_lib,
+                "pauseEvent": { "type": "Event", "kind": isolate.pause_kind.as_deref().unwrap_or("Resume") },
+                "livePorts": 1,
+            }))
+        }
+        "addBreakpointWithScriptUri" => {
+            let (Some(script_uri), Some(line)) =
+                (params["scriptUri"].as_str(), params["line"].as_u64())
+            else {
+                return Err((
+                    INVALID_PARAMS,
+                    "scriptUri and line are required".to_string(),
+                ));
+            };
+            state.next_breakpoint += 1;
+            let breakpoint = MockBreakpoint {
+                id: format!("breakpoints/{}", state.next_breakpoint),
+                isolate_id: isolate.id.clone(),
+                script_uri: script_uri.to_string(),
+                line,
+            };
+            let result = breakpoint_json(&breakpoint);
+            state.breakpoints.push(breakpoint);
+            Ok(result)
+        }
+        "removeBreakpoint" => {
+            let id = params["breakpointId"].as_str().unwrap_or_default();
+            let count = state.breakpoints.len();
+            state
+                .breakpoints
+                .retain(|b| !(b.id == id && b.isolate_id == isolate.id));
+            if state.breakpoints.len() < count {
+                Ok(json!({ "type": "Success" }))
+            } else {
+                Err((INVALID_PARAMS, format!("Unknown breakpoint {}", id)))
+            }
+        }
+        "pause" | "resume" => {
+            let pause_kind = (method == "pause").then(|| "PauseInterrupted".to_string());
+            if let Some(isolate) = state.isolates.iter_mut().find(|i| i.id == isolate.id) {
+                isolate.pause_kind = pause_kind;
+            }
+            Ok(json!({ "type": "Success" }))
+        }
+        "getStack" => Ok(json!({ "type": "Stack", "frames": [], "messages": [] })),
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method \"{}\"", method))),
+    }
+}
+
+/// The inspector extensions, answered from the isolate's `tree`.
+fn inspector(state: &mut MockState, tree: &Value, method: &str, params: &Value) -> RpcResult {
+    let node = |id: &str| {
+        find_node(tree, id).ok_or_else(|| (INVALID_PARAMS, format!("Unknown object {}", id)))
+    };
+    let arg = params["arg"].as_str().unwrap_or_default();
+
+    match method.trim_start_matches("ext.flutter.inspector.") {
+        "getRootWidgetSummaryTree" | "getRootWidgetTree" | "getRootWidget" => Ok(tree.clone()),
+        "getChildrenSummaryTree" | "getChildrenDetailsSubtree" => {
+            Ok(node(arg)?.get("children").cloned().unwrap_or(json!([])))
+        }
+        "getDetailsSubtree" => {
+            let depth = params["subtreeDepth"].as_u64().unwrap_or(2);
+            let mut details = node(arg)?.clone();
+            truncate(&mut details, depth);
+            Ok(details)
+        }
+        "getProperties" => Ok(node(arg)?.get("properties").cloned().unwrap_or(json!([]))),
+        "getSelectedSummaryWidget" => Ok(match &state.selected {
+            Some(id) => node(id)?.clone(),
+            None => Value::Null,
+        }),
+        "setSelectionById" => {
+            node(arg)?;
+            state.selected = Some(arg.to_string());
+            Ok(json!(true))
+        }
+        "disposeGroup" | "trackRebuildDirtyWidgets" | "trackRepaintWidgets" => Ok(json!(true)),
+        _ => Err((METHOD_NOT_FOUND, format!("Unknown method \"{}\"", method))),
+    }
+}
+
+fn find_node<'a>(node: &'a Value, id: &str) -> Option<&'a Value> {
+    if node["valueId"].as_str() == Some(id) || node["objectId"].as_str() == Some(id) {
+        return Some(node);
+    }
+    node["children"]
+        .as_array()?
+        .iter()
+        .find_map(|child| find_node(child, id))
+}
+
+/// Cuts the children below `depth`, marking the cut nodes like the framework does.
+fn truncate(node: &mut Value, depth: u64) {
+    let Some(children) = node.get_mut("children").and_then(Value::as_array_mut) else {
+        return;
+    };
+    if depth == 0 {
+        if !children.is_empty() {
+            children.clear();
+            node["truncated"] = json!(true);
+        }
+        return;
+    }
+    for child in children {
+        truncate(child, depth - 1);
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Headers the replay leaves to the host's HTTP client: they describe the connection or
+/// the encoding rather than the request, and a compressed response couldn't be compared.
+const HOP_HEADERS: [&str; 5] = [
+    "host",
+    "content-length",
+    "connection",
+    "accept-encoding",
+    "transfer-encoding",
+];
+/// Bodies longer than this many lines are compared by size only.
+const MAX_DIFF_LINES: usize = 1000;
+
+/// An entry of `ext.dart.io.getHttpProfile`, with the bodies once fetched with
+/// `ext.dart.io.getHttpProfileRequest`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HttpProfileRequest {
+    #[serde(deserialize_with = "string_or_number")]
+    pub id: String,
+    pub method: String,
+    pub uri: String,
+    /// Microseconds since the epoch.
+    #[serde(rename = "startTime")]
+    pub start_time: i64,
+    #[serde(rename = "endTime")]
+    pub end_time: Option<i64>,
+    pub request: Option<HttpRequestData>,
+    pub response: Option<HttpResponseData>,
+    #[serde(rename = "requestBody")]
+    pub request_body: Option<Vec<u8>>,
+    #[serde(rename = "responseBody")]
+    pub response_body: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HttpRequestData {
+    #[serde(default)]
+    pub headers: BTreeMap<String, Vec<String>>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HttpResponseData {
+    #[serde(rename = "statusCode")]
+    pub status_code: Option<u16>,
+    /// -1 when the response didn't say.
+    #[serde(rename = "contentLength")]
+    pub content_length: Option<i64>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, Vec<String>>,
+    pub error: Option<String>,
+}
+
+/// Older VMs send request ids as numbers.
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(id) => id,
+        other => other.to_string(),
+    })
+}
+
+impl HttpProfileRequest {
+    pub fn duration(&self) -> Option<Duration> {
+        let micros = self.end_time? - self.start_time;
+        Some(Duration::from_micros(micros.max(0) as u64))
+    }
+
+    pub fn status_code(&self) -> Option<u16> {
+        self.response.as_ref()?.status_code
+    }
+
+    /// Size of the response body: what was received once the bodies are fetched, else
+    /// what the response announced.
+    pub fn response_size(&self) -> Option<u64> {
+        if let Some(body) = &self.response_body {
+            return Some(body.len() as u64);
+        }
+        let length = self.response.as_ref()?.content_length?;
+        u64::try_from(length).ok()
+    }
+
+    /// Why the request failed, when it did before or while receiving the response.
+    pub fn error(&self) -> Option<&str> {
+        let request_error = self.request.as_ref().and_then(|r| r.error.as_deref());
+        request_error.or(self.response.as_ref().and_then(|r| r.error.as_deref()))
+    }
+}
+
+/// One line of a replay report.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Heading(String),
+    Same(String),
+    /// Only in what the app received.
+    Removed(String),
+    /// Only in the replayed response.
+    Added(String),
+}
+
+/// Re-issues a captured GET from the host and compares the response with the one the app
+/// received: status, headers and body.
+pub async fn replay(request: &HttpProfileRequest) -> Result<Vec<DiffLine>> {
+    if request.method != "GET" {
+        bail!(
+            "Only GET requests are replayed, this is a {}",
+            request.method
+        );
+    }
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let mut builder = client.get(&request.uri);
+    for (name, values) in request.request.iter().flat_map(|data| &data.headers) {
+        if HOP_HEADERS.contains(&name.to_lowercase().as_str()) {
+            continue;
+        }
+        for value in values {
+            builder = builder.header(name, value);
+        }
+    }
+    let response = builder
+        .send()
+        .await
+        .with_context(|| format!("GET {} failed", request.uri))?;
+
+    let status = response.status().as_u16();
+    let mut headers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, value) in response.headers() {
+        headers
+            .entry(name.as_str().to_string())
+            .or_default()
+            .push(String::from_utf8_lossy(value.as_bytes()).into_owned());
+    }
+    let body = response.bytes().await?;
+
+    let original = request.response.clone().unwrap_or_default();
+    let mut report = vec![DiffLine::Heading("Status".to_string())];
+    match original.status_code {
+        Some(code) if code == status => report.push(DiffLine::Same(code.to_string())),
+        code => {
+            report.push(DiffLine::Removed(
+                code.map_or("(none)".to_string(), |code| code.to_string()),
+            ));
+            report.push(DiffLine::Added(status.to_string()));
+        }
+    }
+    report.push(DiffLine::Heading("Headers".to_string()));
+    report.extend(diff_headers(&original.headers, &headers));
+    report.push(DiffLine::Heading("Body".to_string()));
+    report.extend(diff_bodies(
+        request.response_body.as_deref().unwrap_or_default(),
+        &body,
+    ));
+    Ok(report)
+}
+
+fn diff_headers(
+    original: &BTreeMap<String, Vec<String>>,
+    replayed: &BTreeMap<String, Vec<String>>,
+) -> Vec<DiffLine> {
+    // Header names are case-insensitive and the two sides don't agree on a case
+    let normalize = |headers: &BTreeMap<String, Vec<String>>| -> BTreeMap<String, String> {
+        headers
+            .iter()
+            .map(|(name, values)| (name.to_lowercase(), values.join(", ")))
+            .collect()
+    };
+    let (original, replayed) = (normalize(original), normalize(replayed));
+    let mut names: Vec<&String> = original.keys().chain(replayed.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let mut lines = Vec::new();
+    for name in names {
+        match (original.get(name), replayed.get(name)) {
+            (Some(a), Some(b)) if a == b => lines.push(DiffLine::Same(format!("{}: {}", name, a))),
+            (a, b) => {
+                if let Some(a) = a {
+                    lines.push(DiffLine::Removed(format!("{}: {}", name, a)));
+                }
+                if let Some(b) = b {
+                    lines.push(DiffLine::Added(format!("{}: {}", name, b)));
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// A line diff of two bodies, or a size comparison for binary and very long ones.
+fn diff_bodies(original: &[u8], replayed: &[u8]) -> Vec<DiffLine> {
+    if original == replayed {
+        return vec![DiffLine::Same(format!(
+            "identical ({} bytes)",
+            original.len()
+        ))];
+    }
+    let (Ok(a), Ok(b)) = (std::str::from_utf8(original), std::str::from_utf8(replayed)) else {
+        return vec![DiffLine::Heading(format!(
+            "binary bodies differ: {} vs {} bytes",
+            original.len(),
+            replayed.len()
+        ))];
+    };
+    let a: Vec<&str> = a.lines().collect();
+    let b: Vec<&str> = b.lines().collect();
+    if a.len() > MAX_DIFF_LINES || b.len() > MAX_DIFF_LINES {
+        return vec![DiffLine::Heading(format!(
+            "bodies differ: {} vs {} bytes, too long to diff",
+            original.len(),
+            replayed.len()
+        ))];
+    }
+    diff_lines(&a, &b)
+}
+
+/// Longest common subsequence diff.
+fn diff_lines(a: &[&str], b: &[&str]) -> Vec<DiffLine> {
+    let mut common = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            common[i][j] = if a[i] == b[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push(DiffLine::Same(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || common[i][j + 1] >= common[i + 1][j]) {
+            lines.push(DiffLine::Added(b[j].to_string()));
+            j += 1;
+        } else {
+            lines.push(DiffLine::Removed(a[i].to_string()));
+            i += 1;
+        }
+    }
+    lines
+}
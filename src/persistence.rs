@@ -0,0 +1,209 @@
+use crate::app_state::{AppState, Tab};
+use crate::vm_service::RemoteDiagnosticsNode;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+pub const STATE_DIR: &str = ".flutter-tui";
+const STATE_FILE: &str = "state.json";
+
+/// UI state that survives between sessions of the same project.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistedState {
+    pub current_tab: Option<Tab>,
+    pub show_logs: Option<bool>,
+    pub log_panel_height: Option<u16>,
+
+    // Inspector
+    /// Creation-location hashes of expanded widgets (see [`location_hash`]).
+    pub expanded_locations: HashSet<String>,
+    pub tree_scroll_offset: usize,
+    pub tree_horizontal_scroll: usize,
+    pub selected_widget: Option<WidgetFingerprint>,
+    pub inspector_split: Option<[u16; 2]>,
+
+    // Debugger
+    pub debugger_expanded_ids: HashSet<String>,
+    pub debugger_tree_scroll_offset: usize,
+    pub open_file_path: Option<String>,
+    pub source_scroll_offset: usize,
+    pub source_selected_line: Option<usize>,
+    pub right_panel_split: Option<[u16; 3]>,
+    pub debugger_split: Option<[u16; 3]>,
+
+    /// Labels given to isolates, by isolate name (numbered for isolates sharing one) since
+    /// ids change with every restart.
+    pub isolate_labels: HashMap<String, String>,
+}
+
+pub fn state_path(project_root: &Path) -> PathBuf {
+    project_root.join(STATE_DIR).join(STATE_FILE)
+}
+
+pub fn load(project_root: &Path) -> Option<PersistedState> {
+    let content = std::fs::read_to_string(state_path(project_root)).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(state) => Some(state),
+        Err(e) => {
+            log::error!("Ignoring unreadable UI state: {}", e);
+            None
+        }
+    }
+}
+
+pub fn save(project_root: &Path, state: &PersistedState) -> Result<()> {
+    let content = serde_json::to_vec_pretty(state)?;
+    write_atomic(&state_path(project_root), &content).context("Failed to write UI state")
+}
+
+/// Replaces the file at `path` by writing a sibling and renaming it over, so a crash while
+/// writing leaves the previous content rather than half of the new one.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).context("Failed to create state directory")?;
+    }
+    let temp = path.with_extension("tmp");
+    std::fs::write(&temp, content)?;
+    std::fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// Writes state files as they change instead of only on a clean exit, so a panic or a
+/// kill loses at most the changes since the last write.
+#[derive(Default)]
+pub struct Journal {
+    written: HashMap<PathBuf, Vec<u8>>,
+}
+
+impl Journal {
+    /// Writes `content` to `path` unless it is what was last written there. A failed write
+    /// isn't retried until the content changes again, to report it once.
+    pub fn write(&mut self, path: PathBuf, content: Vec<u8>) -> Result<()> {
+        if self.written.get(&path) == Some(&content) {
+            return Ok(());
+        }
+        let result = write_atomic(&path, &content)
+            .with_context(|| format!("Failed to write {}", path.display()));
+        self.written.insert(path, content);
+        result
+    }
+}
+
+/// What tells the selected widget apart once a restart gave every node a new id: where it
+/// was created and the widgets above it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WidgetFingerprint {
+    /// [`location_hash`] of the widget, if it has a creation location.
+    pub location: Option<String>,
+    pub widget: String,
+    /// Widget types from the root down to the parent.
+    pub ancestors: Vec<String>,
+}
+
+impl WidgetFingerprint {
+    /// Fingerprint of the node with `id` under `root`.
+    pub fn of(root: &RemoteDiagnosticsNode, id: &str) -> Option<Self> {
+        let mut path = Vec::new();
+        if !path_to(root, id, &mut path) {
+            return None;
+        }
+        let node = path.pop()?;
+        Some(Self {
+            location: location_hash(node),
+            widget: widget_type(node).to_string(),
+            ancestors: path
+                .into_iter()
+                .map(|n| widget_type(n).to_string())
+                .collect(),
+        })
+    }
+
+    /// The node under `root` of the same type and location with the most ancestor types in
+    /// common, counted from the parent up; the first in tree order on a tie. One without a
+    /// location only matches a node with the very same ancestors.
+    pub fn find<'a>(&self, root: &'a RemoteDiagnosticsNode) -> Option<&'a RemoteDiagnosticsNode> {
+        let mut best = None;
+        self.find_in(root, &mut Vec::new(), &mut best);
+        best.map(|(node, _)| node)
+    }
+
+    fn find_in<'a>(
+        &self,
+        node: &'a RemoteDiagnosticsNode,
+        ancestors: &mut Vec<&'a str>,
+        best: &mut Option<(&'a RemoteDiagnosticsNode, usize)>,
+    ) {
+        let widget = widget_type(node);
+        if widget == self.widget && location_hash(node) == self.location {
+            let common = ancestors
+                .iter()
+                .rev()
+                .zip(self.ancestors.iter().rev())
+                .take_while(|(a, b)| **a == b.as_str())
+                .count();
+            let exact = common == ancestors.len() && common == self.ancestors.len();
+            if (self.location.is_some() || exact) && best.is_none_or(|(_, most)| common > most) {
+                *best = Some((node, common));
+            }
+        }
+        ancestors.push(widget);
+        for child in node.children.iter().flatten() {
+            self.find_in(child, ancestors, best);
+        }
+        ancestors.pop();
+    }
+}
+
+fn widget_type(node: &RemoteDiagnosticsNode) -> &str {
+    node.widget_runtime_type
+        .as_deref()
+        .or(node.description.as_deref())
+        .unwrap_or_default()
+}
+
+/// Pushes the nodes from `node` down to the one with `id` onto `path`.
+fn path_to<'a>(
+    node: &'a RemoteDiagnosticsNode,
+    id: &str,
+    path: &mut Vec<&'a RemoteDiagnosticsNode>,
+) -> bool {
+    path.push(node);
+    if AppState::get_node_id(node).as_deref() == Some(id) {
+        return true;
+    }
+    if node
+        .children
+        .iter()
+        .flatten()
+        .any(|child| path_to(child, id, path))
+    {
+        return true;
+    }
+    path.pop();
+    false
+}
+
+/// A stable identifier for a widget across sessions, derived from where it was created.
+///
+/// Inspector object IDs change every time the app restarts, so expansion state is keyed on
+/// the creation location (file, line, column and widget type) instead. Uses FNV-1a so the
+/// value doesn't depend on the Rust version's default hasher.
+pub fn location_hash(node: &RemoteDiagnosticsNode) -> Option<String> {
+    let location = node.creation_location.as_ref()?;
+    let key = format!(
+        "{}:{}:{}:{}",
+        location.file,
+        location.line,
+        location.column,
+        node.widget_runtime_type.as_deref().unwrap_or("")
+    );
+
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in key.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    Some(format!("{:016x}", hash))
+}
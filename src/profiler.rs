@@ -0,0 +1,221 @@
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// Windows the Profiler tab offers, in seconds back from now.
+pub const PROFILE_WINDOWS: [u64; 5] = [1, 5, 10, 30, 60];
+
+/// The result of `getCpuSamples`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CpuSamples {
+    /// Microseconds between samples.
+    #[serde(rename = "samplePeriod")]
+    pub sample_period: u64,
+    #[serde(rename = "timeExtentMicros")]
+    pub time_extent_micros: u64,
+    #[serde(default)]
+    pub functions: Vec<ProfileFunction>,
+    #[serde(default)]
+    pub samples: Vec<CpuSample>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileFunction {
+    /// `Dart`, `Native`, `Tag`, `Stub` or `Collected`.
+    pub kind: String,
+    #[serde(rename = "resolvedUrl", default)]
+    pub resolved_url: String,
+    /// A `@Function` or a `NativeFunction`.
+    pub function: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CpuSample {
+    /// Indices into `functions`, innermost frame first.
+    #[serde(default)]
+    pub stack: Vec<usize>,
+}
+
+impl ProfileFunction {
+    /// `Class.method` for methods, the bare name for everything else.
+    fn display_name(&self) -> String {
+        let name = self
+            .function
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or("<unknown>");
+        let owner = self
+            .function
+            .get("owner")
+            .filter(|owner| owner.get("type").and_then(|t| t.as_str()) == Some("@Class"))
+            .and_then(|owner| owner.get("name"))
+            .and_then(|n| n.as_str());
+        match owner {
+            Some(owner) => format!("{}.{}", owner, name),
+            None if self.kind == "Dart" => name.to_string(),
+            None => format!("[{}] {}", self.kind, name),
+        }
+    }
+}
+
+/// A function's share of the samples.
+#[derive(Debug, Clone)]
+pub struct FunctionStats {
+    pub name: String,
+    pub url: String,
+    /// Samples with the function on top of the stack.
+    pub self_ticks: u64,
+    /// Samples with the function anywhere on the stack.
+    pub total_ticks: u64,
+}
+
+/// A node of a call tree: in the top-down tree its children are the functions it called,
+/// in the bottom-up tree the functions that called it.
+#[derive(Debug, Clone)]
+pub struct CallNode {
+    pub function: usize,
+    pub ticks: u64,
+    /// Samples that ended in this node, only meaningful in the top-down tree.
+    pub self_ticks: u64,
+    /// Sorted by ticks, heaviest first.
+    pub children: Vec<CallNode>,
+}
+
+impl CallNode {
+    fn new(function: usize) -> Self {
+        Self {
+            function,
+            ticks: 0,
+            self_ticks: 0,
+            children: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, path: impl Iterator<Item = usize>) {
+        self.ticks += 1;
+        let mut node = self;
+        for function in path {
+            let index = match node.children.iter().position(|c| c.function == function) {
+                Some(index) => index,
+                None => {
+                    node.children.push(CallNode::new(function));
+                    node.children.len() - 1
+                }
+            };
+            node = &mut node.children[index];
+            node.ticks += 1;
+        }
+        node.self_ticks += 1;
+    }
+
+    fn sort(&mut self) {
+        self.children
+            .sort_by_key(|child| std::cmp::Reverse(child.ticks));
+        for child in &mut self.children {
+            child.sort();
+        }
+    }
+
+    /// The node at `path`, a list of child indices from this one.
+    pub fn at(&self, path: &[usize]) -> Option<&CallNode> {
+        path.iter()
+            .try_fold(self, |node, &index| node.children.get(index))
+    }
+}
+
+/// CPU samples aggregated for the Profiler tab.
+#[derive(Debug, Clone)]
+pub struct CpuProfile {
+    pub window_secs: u64,
+    pub sample_count: u64,
+    pub sample_period_micros: u64,
+    pub time_extent_micros: u64,
+    /// Indexed like `ProfileFunction`s, for `CallNode::function`.
+    pub names: Vec<String>,
+    /// Sorted by self ticks, heaviest first.
+    pub functions: Vec<FunctionStats>,
+    /// Rooted at a synthetic node covering all samples.
+    pub top_down: CallNode,
+    pub bottom_up: CallNode,
+}
+
+/// Index of the synthetic root node's "function".
+pub const ROOT_FUNCTION: usize = usize::MAX;
+
+impl CpuProfile {
+    pub fn new(window_secs: u64, samples: CpuSamples) -> Self {
+        let names: Vec<String> = samples
+            .functions
+            .iter()
+            .map(ProfileFunction::display_name)
+            .collect();
+        let mut functions: Vec<FunctionStats> = samples
+            .functions
+            .iter()
+            .zip(&names)
+            .map(|(function, name)| FunctionStats {
+                name: name.clone(),
+                url: function.resolved_url.clone(),
+                self_ticks: 0,
+                total_ticks: 0,
+            })
+            .collect();
+
+        let mut top_down = CallNode::new(ROOT_FUNCTION);
+        let mut bottom_up = CallNode::new(ROOT_FUNCTION);
+        for sample in &samples.samples {
+            let stack: Vec<usize> = sample
+                .stack
+                .iter()
+                .copied()
+                .filter(|&index| index < functions.len())
+                .collect();
+            if let Some(&top) = stack.first() {
+                functions[top].self_ticks += 1;
+            }
+            // Recursive functions count once per sample
+            let mut seen = HashSet::new();
+            for &index in &stack {
+                if seen.insert(index) {
+                    functions[index].total_ticks += 1;
+                }
+            }
+            top_down.add(stack.iter().rev().copied());
+            bottom_up.add(stack.iter().copied());
+        }
+        top_down.sort();
+        bottom_up.sort();
+        functions.retain(|function| function.total_ticks > 0);
+        functions.sort_by(|a, b| {
+            b.self_ticks
+                .cmp(&a.self_ticks)
+                .then(b.total_ticks.cmp(&a.total_ticks))
+        });
+
+        Self {
+            window_secs,
+            sample_count: samples.samples.len() as u64,
+            sample_period_micros: samples.sample_period,
+            time_extent_micros: samples.time_extent_micros,
+            names,
+            functions,
+            top_down,
+            bottom_up,
+        }
+    }
+
+    pub fn name(&self, function: usize) -> &str {
+        self.names
+            .get(function)
+            .map(String::as_str)
+            .unwrap_or("all")
+    }
+
+    /// Share of all samples, in percent.
+    pub fn percent(&self, ticks: u64) -> f64 {
+        if self.sample_count == 0 {
+            0.0
+        } else {
+            ticks as f64 * 100.0 / self.sample_count as f64
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Number of recent frames kept per location.
+const HISTORY_FRAMES: usize = 8;
+/// Locations that haven't rebuilt for this long stop being highlighted.
+const ACTIVITY_TIMEOUT: Duration = Duration::from_secs(3);
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Per-location rebuild history built from `Flutter.RebuildDirtyWidgets` extension events,
+/// which `ext.flutter.inspector.trackRebuildDirtyWidgets` makes the framework post once
+/// per frame. `Flutter.RepaintWidgets` events have the same shape and get a tracker of
+/// their own.
+#[derive(Debug, Default)]
+pub struct RebuildTracker {
+    /// Location ids assigned by the framework -> location key (see [`location_key`]).
+    locations: HashMap<i64, String>,
+    history: HashMap<String, LocationHistory>,
+    /// Rebuilds per location since tracking started.
+    totals: HashMap<String, u64>,
+}
+
+/// One row of the rebuild stats: a location and how often widgets created there rebuilt.
+#[derive(Debug, Clone)]
+pub struct RebuildStat {
+    pub key: String,
+    pub total: u64,
+    /// Rebuilds over the recent frames.
+    pub recent: u32,
+    /// Repaints of render objects created at the location since tracking started.
+    pub paints: u64,
+    /// Widget type created at the location and a tree node of it, when it is in the tree.
+    pub widget: Option<String>,
+    pub node_id: Option<String>,
+}
+
+#[derive(Debug)]
+struct LocationHistory {
+    /// Rebuild counts of the most recent frames, oldest first.
+    counts: VecDeque<u32>,
+    last_rebuild: Instant,
+}
+
+pub fn location_key(file: &str, line: u32, column: u32) -> String {
+    format!("{}:{}:{}", file, line, column)
+}
+
+impl RebuildTracker {
+    pub fn clear(&mut self) {
+        self.locations.clear();
+        self.history.clear();
+        self.totals.clear();
+    }
+
+    /// Starts counting from zero again, e.g. after a hot reload. Locations the framework
+    /// already announced stay known, it doesn't send them again.
+    pub fn reset_counts(&mut self) {
+        self.history.clear();
+        self.totals.clear();
+    }
+
+    /// Records the `extensionData` of one `Flutter.RebuildDirtyWidgets` event.
+    pub fn record(&mut self, data: &Value) {
+        // {"newLocations": {"file:///...": [id, line, column, id, line, column, ...]}}
+        if let Some(new_locations) = data.get("newLocations").and_then(|l| l.as_object()) {
+            for (file, entries) in new_locations {
+                let Some(entries) = entries.as_array() else {
+                    continue;
+                };
+                for entry in entries.chunks_exact(3) {
+                    if let (Some(id), Some(line), Some(column)) =
+                        (entry[0].as_i64(), entry[1].as_u64(), entry[2].as_u64())
+                    {
+                        self.locations
+                            .insert(id, location_key(file, line as u32, column as u32));
+                    }
+                }
+            }
+        }
+
+        // {"events": [id, count, id, count, ...]}
+        let mut frame: HashMap<&str, u32> = HashMap::new();
+        if let Some(events) = data.get("events").and_then(|e| e.as_array()) {
+            for pair in events.chunks_exact(2) {
+                let (Some(id), Some(count)) = (pair[0].as_i64(), pair[1].as_u64()) else {
+                    continue;
+                };
+                if let Some(key) = self.locations.get(&id) {
+                    *frame.entry(key.as_str()).or_default() += count as u32;
+                }
+            }
+        }
+
+        let now = Instant::now();
+        for (key, count) in &frame {
+            *self.totals.entry(key.to_string()).or_default() += *count as u64;
+        }
+        for key in frame.keys() {
+            self.history
+                .entry(key.to_string())
+                .or_insert_with(|| LocationHistory {
+                    counts: VecDeque::new(),
+                    last_rebuild: now,
+                })
+                .last_rebuild = now;
+        }
+        // Every tracked location gets an entry for this frame, zero if it didn't rebuild
+        for (key, history) in self.history.iter_mut() {
+            history
+                .counts
+                .push_back(frame.get(key.as_str()).copied().unwrap_or(0));
+            while history.counts.len() > HISTORY_FRAMES {
+                history.counts.pop_front();
+            }
+        }
+        self.history
+            .retain(|_, history| history.last_rebuild.elapsed() < ACTIVITY_TIMEOUT);
+    }
+
+    fn active_history(&self, key: &str) -> Option<&LocationHistory> {
+        self.history
+            .get(key)
+            .filter(|history| history.last_rebuild.elapsed() < ACTIVITY_TIMEOUT)
+    }
+
+    /// Total rebuilds at a location over the recent frames, if it rebuilt lately.
+    pub fn recent_rebuilds(&self, key: &str) -> Option<u32> {
+        let total = self.active_history(key)?.counts.iter().sum();
+        (total > 0).then_some(total)
+    }
+
+    /// Rebuilds at a location since tracking started.
+    pub fn total_rebuilds(&self, key: &str) -> Option<u64> {
+        self.totals.get(key).copied()
+    }
+
+    /// Every location that rebuilt since tracking started, unsorted.
+    pub fn stats(&self) -> Vec<RebuildStat> {
+        self.totals
+            .iter()
+            .map(|(key, &total)| RebuildStat {
+                key: key.clone(),
+                total,
+                recent: self.recent_rebuilds(key).unwrap_or(0),
+                paints: 0,
+                widget: None,
+                node_id: None,
+            })
+            .collect()
+    }
+
+    /// A small bar chart of the recent rebuild counts at a location, if it rebuilt lately.
+    pub fn sparkline(&self, key: &str) -> Option<String> {
+        let history = self.active_history(key)?;
+        let max = history.counts.iter().copied().max().unwrap_or(0);
+        if max == 0 {
+            return None;
+        }
+        Some(
+            history
+                .counts
+                .iter()
+                .map(|&count| {
+                    if count == 0 {
+                        ' '
+                    } else {
+                        BARS[((count * BARS.len() as u32 - 1) / max) as usize]
+                    }
+                })
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,61 @@
+use crate::vm_service::ScriptRef;
+use std::collections::{BTreeMap, HashMap};
+
+/// Script ids of an isolate by script URI, what the next hot reload is compared against.
+#[derive(Debug, Default)]
+pub struct ScriptSnapshot {
+    pub isolate_id: String,
+    ids: HashMap<String, String>,
+}
+
+impl ScriptSnapshot {
+    pub fn new(isolate_id: String, scripts: Vec<ScriptRef>) -> Self {
+        Self {
+            isolate_id,
+            ids: scripts
+                .into_iter()
+                .map(|script| (script.uri, script.id))
+                .collect(),
+        }
+    }
+}
+
+/// Libraries a hot reload actually recompiled, grouped by package.
+#[derive(Debug, Default)]
+pub struct ReloadScope {
+    /// Local time of the reload.
+    pub time: String,
+    /// Package name -> URIs of its reloaded libraries, both sorted.
+    pub packages: BTreeMap<String, Vec<String>>,
+}
+
+impl ReloadScope {
+    /// Scripts of `after` that are new or got a new id since `before`.
+    pub fn between(before: &ScriptSnapshot, after: &ScriptSnapshot, time: String) -> Self {
+        let mut packages: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (uri, id) in &after.ids {
+            if before.ids.get(uri) != Some(id) {
+                packages
+                    .entry(package_of(uri).to_string())
+                    .or_default()
+                    .push(uri.clone());
+            }
+        }
+        for uris in packages.values_mut() {
+            uris.sort();
+        }
+        Self { time, packages }
+    }
+
+    pub fn library_count(&self) -> usize {
+        self.packages.values().map(Vec::len).sum()
+    }
+}
+
+/// `package:app/src/home.dart` -> `app`, `dart:core` -> `dart`, `file:///...` -> `file`.
+pub fn package_of(uri: &str) -> &str {
+    match uri.strip_prefix("package:") {
+        Some(path) => path.split('/').next().unwrap_or(path),
+        None => uri.split(':').next().unwrap_or(uri),
+    }
+}
@@ -0,0 +1,76 @@
+use crate::persistence::STATE_DIR;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+const SCREENSHOT_DIR: &str = "screenshots";
+
+/// A widget screenshot from `ext.flutter.inspector.screenshot`: the PNG as received, for
+/// saving, and its pixels, for drawing in the terminal.
+pub struct Screenshot {
+    pub widget: String,
+    pub png: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Screenshot {
+    pub fn decode(widget: String, png: Vec<u8>) -> Result<Self> {
+        let mut decoder = png::Decoder::new(png.as_slice());
+        // Palettes, 16-bit channels and missing alpha all end up as 8-bit RGB(A)
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().context("Invalid PNG")?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).context("Invalid PNG")?;
+        buffer.truncate(info.buffer_size());
+
+        let rgba = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|g| [*g, *g, *g, 255]).collect(),
+            png::ColorType::Indexed => anyhow::bail!("Unexpected indexed PNG"),
+        };
+        Ok(Self {
+            widget,
+            png,
+            width: info.width,
+            height: info.height,
+            rgba,
+        })
+    }
+
+    /// The color of a pixel over a black background.
+    pub fn pixel(&self, x: u32, y: u32) -> (u8, u8, u8) {
+        let index = ((y * self.width + x) * 4) as usize;
+        let Some(&[r, g, b, a]) = self.rgba.get(index..index + 4) else {
+            return (0, 0, 0);
+        };
+        let blend = |c: u8| (c as u16 * a as u16 / 255) as u8;
+        (blend(r), blend(g), blend(b))
+    }
+
+    /// Writes the PNG to `.flutter-tui/screenshots/` of the project.
+    pub fn save(&self, project_root: &Path) -> Result<PathBuf> {
+        let dir = project_root.join(STATE_DIR).join(SCREENSHOT_DIR);
+        std::fs::create_dir_all(&dir).context("Failed to create screenshot directory")?;
+        let name: String = self
+            .widget
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = dir.join(format!(
+            "{}-{}.png",
+            name,
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        std::fs::write(&path, &self.png).context("Failed to write screenshot")?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::vm_service::RemoteDiagnosticsNode;
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+/// Number of snapshots kept for the chart.
+const MAX_SNAPSHOTS: usize = 120;
+/// Snapshots in a row whose widget count never drops before growth counts as a leak.
+const LEAK_WINDOW: usize = 5;
+/// How many of the fastest growing widget types a leak report names.
+const MAX_SUSPECTS: usize = 3;
+
+/// Widget count and memory of the app at one point in time, for leak hunting.
+#[derive(Debug, Clone)]
+pub struct TreeSnapshot {
+    pub taken_at: Instant,
+    pub widgets: usize,
+    /// Dart heap plus external memory of the inspected isolate, in bytes.
+    pub memory: u64,
+    pub by_type: HashMap<String, usize>,
+}
+
+impl TreeSnapshot {
+    /// Counts the widgets of a full (not summary) widget tree by type.
+    pub fn new(root: &RemoteDiagnosticsNode, memory: u64) -> Self {
+        let mut by_type = HashMap::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            let widget = node
+                .widget_runtime_type
+                .as_deref()
+                .or(node.description.as_deref())
+                .unwrap_or("?");
+            *by_type.entry(widget.to_string()).or_default() += 1;
+            stack.extend(node.children.iter().flatten());
+        }
+        Self {
+            taken_at: Instant::now(),
+            widgets: by_type.values().sum(),
+            memory,
+            by_type,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SnapshotHistory {
+    snapshots: VecDeque<TreeSnapshot>,
+}
+
+impl SnapshotHistory {
+    pub fn push(&mut self, snapshot: TreeSnapshot) {
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > MAX_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn latest(&self) -> Option<&TreeSnapshot> {
+        self.snapshots.back()
+    }
+
+    pub fn widget_counts(&self) -> Vec<u64> {
+        self.snapshots.iter().map(|s| s.widgets as u64).collect()
+    }
+
+    pub fn memory(&self) -> Vec<u64> {
+        self.snapshots.iter().map(|s| s.memory).collect()
+    }
+
+    /// When the widget count grew over the last few snapshots without ever dropping, the
+    /// widget types that grew the most in that window with how many instances they gained.
+    pub fn leak_suspects(&self) -> Option<Vec<(String, i64)>> {
+        if self.snapshots.len() < LEAK_WINDOW {
+            return None;
+        }
+        let window: Vec<&TreeSnapshot> = self
+            .snapshots
+            .iter()
+            .skip(self.snapshots.len() - LEAK_WINDOW)
+            .collect();
+        let monotonic = window
+            .windows(2)
+            .all(|pair| pair[1].widgets >= pair[0].widgets);
+        let (first, last) = (window[0], window[LEAK_WINDOW - 1]);
+        if !monotonic || last.widgets <= first.widgets {
+            return None;
+        }
+
+        let mut growth: Vec<(String, i64)> = last
+            .by_type
+            .iter()
+            .map(|(widget, &count)| {
+                let before = first.by_type.get(widget).copied().unwrap_or(0);
+                (widget.clone(), count as i64 - before as i64)
+            })
+            .filter(|(_, grown)| *grown > 0)
+            .collect();
+        growth.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        growth.truncate(MAX_SUSPECTS);
+        Some(growth)
+    }
+}
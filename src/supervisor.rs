@@ -0,0 +1,162 @@
+use anyhow::Result;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::{Id, JoinSet};
+
+/// Tasks that ran at least this long before failing get a fresh restart budget.
+const HEALTHY_RUN: Duration = Duration::from_secs(60);
+
+/// What to do when a supervised task ends.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RestartPolicy {
+    /// One-shot work, e.g. reading the Flutter version.
+    Never,
+    /// Restart with backoff after errors and panics, giving up after `max_restarts` in a
+    /// row. Returning `Ok` ends the task for good.
+    OnFailure { max_restarts: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskStatus {
+    Running,
+    Finished,
+    Restarting { attempt: u32 },
+    Failed(String),
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Running => write!(f, "running"),
+            Self::Finished => write!(f, "finished"),
+            Self::Restarting { attempt } => write!(f, "restarting (attempt {})", attempt),
+            Self::Failed(error) => write!(f, "failed: {}", error),
+        }
+    }
+}
+
+type TaskFactory = Box<dyn FnMut() -> BoxFuture<'static, Result<()>> + Send>;
+
+struct Task {
+    policy: RestartPolicy,
+    factory: TaskFactory,
+    failures: u32,
+}
+
+/// Runs the long-lived subsystems (VM service session, daemon, file watcher) in a
+/// `JoinSet`, restarts them according to their policy and reports every status change.
+///
+/// Tasks are created from factories so they can be started again; state that must
+/// survive a restart, like channel receivers, lives behind a mutex the factory captures.
+pub struct Supervisor {
+    tasks: HashMap<&'static str, Task>,
+    running: HashMap<Id, (&'static str, Instant)>,
+    set: JoinSet<Result<()>>,
+    tx_status: mpsc::UnboundedSender<(&'static str, TaskStatus)>,
+}
+
+impl Supervisor {
+    pub fn new(tx_status: mpsc::UnboundedSender<(&'static str, TaskStatus)>) -> Self {
+        Self {
+            tasks: HashMap::new(),
+            running: HashMap::new(),
+            set: JoinSet::new(),
+            tx_status,
+        }
+    }
+
+    /// Registers a task under `name` and starts it.
+    pub fn spawn<F>(&mut self, name: &'static str, policy: RestartPolicy, factory: F)
+    where
+        F: FnMut() -> BoxFuture<'static, Result<()>> + Send + 'static,
+    {
+        self.tasks.insert(
+            name,
+            Task {
+                policy,
+                factory: Box::new(factory),
+                failures: 0,
+            },
+        );
+        self.start(name, Duration::ZERO);
+    }
+
+    fn start(&mut self, name: &'static str, delay: Duration) {
+        let Some(task) = self.tasks.get_mut(name) else {
+            return;
+        };
+        let future = (task.factory)();
+        let tx_status = self.tx_status.clone();
+        let handle = self.set.spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _ = tx_status.send((name, TaskStatus::Running));
+            future.await
+        });
+        self.running.insert(handle.id(), (name, Instant::now()));
+    }
+
+    fn report(&self, name: &'static str, status: TaskStatus) {
+        let _ = self.tx_status.send((name, status));
+    }
+
+    /// Watches the tasks until the app exits. Names sent on `rx_restart` are started again
+    /// if they aren't running, with their restart budget reset.
+    pub async fn run(mut self, mut rx_restart: mpsc::Receiver<&'static str>) {
+        loop {
+            tokio::select! {
+                Some(joined) = self.set.join_next_with_id() => {
+                    let (id, result) = match joined {
+                        Ok((id, result)) => (id, result),
+                        Err(e) if e.is_panic() => (e.id(), Err(anyhow::anyhow!("panicked"))),
+                        Err(e) => (e.id(), Err(anyhow::anyhow!("cancelled"))),
+                    };
+                    if let Some((name, started)) = self.running.remove(&id) {
+                        self.on_exit(name, started, result);
+                    }
+                }
+                Some(name) = rx_restart.recv() => {
+                    if self.running.values().any(|(running, _)| *running == name) {
+                        continue;
+                    }
+                    if let Some(task) = self.tasks.get_mut(name) {
+                        log::info!("Restarting {}", name);
+                        task.failures = 0;
+                        self.start(name, Duration::ZERO);
+                    }
+                }
+                else => break,
+            }
+        }
+    }
+
+    fn on_exit(&mut self, name: &'static str, started: Instant, result: Result<()>) {
+        let Some(task) = self.tasks.get_mut(name) else {
+            return;
+        };
+        let error = match result {
+            Ok(()) => {
+                log::info!("{} finished", name);
+                self.report(name, TaskStatus::Finished);
+                return;
+            }
+            Err(e) => e.to_string(),
+        };
+        log::error!("{} stopped: {}", name, error);
+
+        if started.elapsed() >= HEALTHY_RUN {
+            task.failures = 0;
+        }
+        task.failures += 1;
+        match task.policy {
+            RestartPolicy::OnFailure { max_restarts } if task.failures <= max_restarts => {
+                let attempt = task.failures;
+                self.report(name, TaskStatus::Restarting { attempt });
+                self.start(name, Duration::from_secs(1 << attempt.min(5)));
+            }
+            _ => self.report(name, TaskStatus::Failed(error)),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::vm_service::{CreationLocation, RemoteDiagnosticsNode};
+
+/// Single-child widgets, which real Flutter trees are mostly made of.
+const WRAPPER_TYPES: [&str; 9] = [
+    "Padding",
+    "Container",
+    "Align",
+    "SizedBox",
+    "Center",
+    "DecoratedBox",
+    "GestureDetector",
+    "Builder",
+    "Expanded",
+];
+const MULTI_CHILD_TYPES: [&str; 4] = ["Column", "Row", "Stack", "ListView"];
+const LEAF_TYPES: [&str; 3] = ["Text", "Icon", "Image"];
+/// Source files the widgets are "created" in.
+const SOURCE_FILES: usize = 20;
+
+/// The shape of a synthetic widget tree. The same shape always generates the same tree, so
+/// a shape is enough to reproduce a tree for a benchmark or a bug report.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TreeShape {
+    /// Total number of nodes, the root included.
+    pub nodes: usize,
+    /// Most children a multi-child widget gets.
+    pub max_children: usize,
+    pub seed: u64,
+}
+
+impl TreeShape {
+    pub fn new(nodes: usize) -> Self {
+        Self {
+            nodes,
+            max_children: 8,
+            seed: 1,
+        }
+    }
+
+    /// Builds the tree: every node has an id and a description, most have a creation
+    /// location in the project, and the ids are numbered in pre-order from
+    /// `inspector-0` (the root).
+    pub fn generate(&self) -> RemoteDiagnosticsNode {
+        let mut generator = Generator {
+            rng: XorShift(self.seed.max(1)),
+            max_children: self.max_children.max(2),
+            next_id: 0,
+        };
+        generator.node(self.nodes.max(1))
+    }
+}
+
+struct Generator {
+    rng: XorShift,
+    max_children: usize,
+    next_id: usize,
+}
+
+impl Generator {
+    /// A subtree of exactly `size` nodes.
+    fn node(&mut self, size: usize) -> RemoteDiagnosticsNode {
+        let id = self.next_id;
+        self.next_id += 1;
+        let remaining = size - 1;
+
+        let widget_type = if remaining == 0 {
+            LEAF_TYPES[self.rng.below(LEAF_TYPES.len())]
+        } else if remaining > 1 && self.rng.below(10) < 4 {
+            MULTI_CHILD_TYPES[self.rng.below(MULTI_CHILD_TYPES.len())]
+        } else {
+            WRAPPER_TYPES[self.rng.below(WRAPPER_TYPES.len())]
+        };
+
+        // Split the rest between the children, each getting at least one node
+        let child_count = if MULTI_CHILD_TYPES.contains(&widget_type) {
+            2 + self.rng.below(self.max_children.min(remaining) - 1)
+        } else {
+            remaining.min(1)
+        };
+        let mut sizes = vec![1; child_count];
+        for _ in child_count..remaining {
+            let child = self.rng.below(child_count);
+            sizes[child] += 1;
+        }
+        let children: Vec<_> = sizes.into_iter().map(|size| self.node(size)).collect();
+
+        let description = if widget_type == "Text" {
+            format!("Text \"Label {}\"", id)
+        } else {
+            widget_type.to_string()
+        };
+        // Framework-created widgets have no location in the project
+        let creation_location = (self.rng.below(10) < 7).then(|| CreationLocation {
+            file: format!(
+                "file:///app/lib/screen_{}.dart",
+                self.rng.below(SOURCE_FILES)
+            ),
+            line: 1 + self.rng.below(500) as u32,
+            column: 1 + self.rng.below(40) as u32,
+            name: Some(widget_type.to_string()),
+        });
+
+        RemoteDiagnosticsNode {
+            description: Some(description),
+            node_type: Some("_ElementDiagnosticableTreeNode".to_string()),
+            widget_runtime_type: Some(widget_type.to_string()),
+            object_id: Some(format!("inspector-{}", id)),
+            value_id: Some(format!("inspector-{}", id)),
+            has_children: Some(!children.is_empty()),
+            children: Some(children),
+            creation_location,
+            ..Default::default()
+        }
+    }
+}
+
+/// A small deterministic PRNG; the trees only need to look random, and must not change
+/// between versions of a dependency.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`, `n` > 0.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
@@ -0,0 +1,325 @@
+use crate::persistence::STATE_DIR;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const CAPTURE_DIR: &str = "captures";
+/// Bounds the buffer however busy the timeline gets.
+const MAX_EVENTS: usize = 200_000;
+/// Timeline streams recorded while the TUI is connected.
+pub const RECORDED_STREAMS: [&str; 3] = ["Dart", "Embedder", "GC"];
+/// Windows a timeline capture of the Performance tab offers, in seconds back from now.
+pub const CAPTURE_WINDOWS: [u64; 4] = [1, 2, 5, 10];
+
+/// Build and raster times of one frame, from a `Flutter.Frame` event.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameTiming {
+    pub number: u64,
+    /// On the timeline clock, in microseconds.
+    pub start_micros: i64,
+    pub elapsed: Duration,
+    pub build: Duration,
+    pub raster: Duration,
+}
+
+impl FrameTiming {
+    pub fn parse(data: &Value) -> Option<Self> {
+        let micros = |key: &str| data.get(key).and_then(|v| v.as_i64());
+        let duration = |key: &str| Duration::from_micros(micros(key).unwrap_or(0).max(0) as u64);
+        Some(Self {
+            number: data.get("number")?.as_u64()?,
+            start_micros: micros("startTime")?,
+            elapsed: duration("elapsed"),
+            build: duration("build"),
+            raster: duration("raster"),
+        })
+    }
+
+    /// Build plus raster time, what the frame cost the UI and raster threads.
+    pub fn work(&self) -> Duration {
+        self.build + self.raster
+    }
+}
+
+/// What the VM task forwards to the recorder.
+#[derive(Debug)]
+pub enum TimelineUpdate {
+    Frame(FrameTiming),
+    /// Trace events of the `Timeline` stream, in Chrome's trace event format.
+    Events(Vec<Value>),
+    /// A `Flutter.Error` event, posted for errors while structured errors are on.
+    Error,
+}
+
+/// The last few seconds of frames and timeline events, kept so a hitch can be captured
+/// after it happened: by hand, or automatically on a slow frame or a Flutter error.
+pub struct TimelineRecorder {
+    window: Duration,
+    frames: VecDeque<FrameTiming>,
+    events: VecDeque<Value>,
+    /// Newest timestamp seen, on the timeline clock.
+    latest_micros: i64,
+    /// Frames slower than this trigger a capture.
+    pub jank_trigger: Option<Duration>,
+    pub error_trigger: bool,
+    last_auto_capture: Option<Instant>,
+}
+
+impl TimelineRecorder {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            frames: VecDeque::new(),
+            events: VecDeque::new(),
+            latest_micros: 0,
+            jank_trigger: None,
+            error_trigger: false,
+            last_auto_capture: None,
+        }
+    }
+
+    /// Adds an update to the buffer. Returns why a capture should be taken when it fires
+    /// a trigger. Triggers don't fire again until a window later, so one janky scroll
+    /// doesn't produce a capture per frame.
+    pub fn record(&mut self, update: TimelineUpdate) -> Option<String> {
+        let trigger = match update {
+            TimelineUpdate::Frame(frame) => {
+                self.latest_micros = self.latest_micros.max(frame.start_micros);
+                self.frames.push_back(frame);
+                self.jank_trigger
+                    .filter(|threshold| frame.elapsed > *threshold)
+                    .map(|_| format!("jank-frame-{}", frame.number))
+            }
+            TimelineUpdate::Events(events) => {
+                for event in events {
+                    if let Some(ts) = event_micros(&event) {
+                        self.latest_micros = self.latest_micros.max(ts);
+                    }
+                    self.events.push_back(event);
+                }
+                None
+            }
+            TimelineUpdate::Error => self.error_trigger.then(|| "flutter-error".to_string()),
+        };
+        self.prune();
+
+        let trigger = trigger?;
+        if self
+            .last_auto_capture
+            .is_some_and(|at| at.elapsed() < self.window)
+        {
+            return None;
+        }
+        self.last_auto_capture = Some(Instant::now());
+        Some(trigger)
+    }
+
+    fn prune(&mut self) {
+        let oldest = self.latest_micros - self.window.as_micros() as i64;
+        while self
+            .frames
+            .front()
+            .is_some_and(|frame| frame.start_micros < oldest)
+        {
+            self.frames.pop_front();
+        }
+        while self.events.len() > MAX_EVENTS
+            || self
+                .events
+                .front()
+                .is_some_and(|event| event_micros(event).unwrap_or(0) < oldest)
+        {
+            self.events.pop_front();
+        }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Writes the buffered window to `.flutter-tui/captures/` as a Chrome trace, which
+    /// Perfetto and chrome://tracing open, with the frames as slices of their own.
+    pub fn save(&self, project_root: &Path, reason: &str) -> Result<PathBuf> {
+        let mut trace_events: Vec<Value> = self.events.iter().cloned().collect();
+        trace_events.push(json!({
+            "name": "thread_name",
+            "ph": "M",
+            "pid": 0,
+            "tid": 0,
+            "args": { "name": "Flutter frames" }
+        }));
+        for frame in &self.frames {
+            trace_events.push(json!({
+                "name": format!("Frame {}", frame.number),
+                "cat": "Flutter",
+                "ph": "X",
+                "pid": 0,
+                "tid": 0,
+                "ts": frame.start_micros,
+                "dur": frame.elapsed.as_micros() as u64,
+                "args": {
+                    "buildMicros": frame.build.as_micros() as u64,
+                    "rasterMicros": frame.raster.as_micros() as u64
+                }
+            }));
+        }
+        let trace = json!({
+            "traceEvents": trace_events,
+            "displayTimeUnit": "ms",
+            "otherData": {
+                "reason": reason,
+                "windowSeconds": self.window.as_secs(),
+                "frames": self.frames.len()
+            }
+        });
+
+        let dir = project_root.join(STATE_DIR).join(CAPTURE_DIR);
+        std::fs::create_dir_all(&dir).context("Failed to create capture directory")?;
+        let path = dir.join(format!(
+            "{}-{}.json",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            reason
+        ));
+        std::fs::write(&path, serde_json::to_vec(&trace)?).context("Failed to write capture")?;
+        Ok(path)
+    }
+}
+
+/// A timed event of a captured timeline, its begin and end paired up.
+#[derive(Debug, Clone)]
+pub struct TimelineSpan {
+    pub name: String,
+    pub thread: String,
+    /// Since the start of the capture.
+    pub offset: Duration,
+    pub duration: Duration,
+}
+
+/// The spans of one category of a capture, e.g. `Dart` or `GC`.
+#[derive(Debug, Clone)]
+pub struct TimelineCategory {
+    pub name: String,
+    pub total: Duration,
+    /// Longest first.
+    pub spans: Vec<TimelineSpan>,
+}
+
+/// A window of the VM's timeline from `getVMTimeline`, grouped for triage.
+#[derive(Debug, Clone)]
+pub struct TimelineCapture {
+    pub window_secs: u64,
+    pub event_count: usize,
+    /// Most time spent first.
+    pub categories: Vec<TimelineCategory>,
+}
+
+impl TimelineCapture {
+    pub fn new(window_secs: u64, origin_micros: i64, events: &[Value]) -> Self {
+        let str_field = |event: &Value, key: &str| -> String {
+            event
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        // Both ids are numbers, threads are told apart by the pair
+        let thread_of = |event: &Value| (event.get("pid").cloned(), event.get("tid").cloned());
+
+        let mut thread_names = HashMap::new();
+        for event in events {
+            if event.get("ph").and_then(|p| p.as_str()) == Some("M")
+                && event.get("name").and_then(|n| n.as_str()) == Some("thread_name")
+            {
+                if let Some(name) = event.pointer("/args/name").and_then(|n| n.as_str()) {
+                    thread_names.insert(thread_of(event), name.to_string());
+                }
+            }
+        }
+
+        // (category, span) of every paired event
+        let mut spans: Vec<(String, TimelineSpan)> = Vec::new();
+        let mut span = |event: &Value, start: i64, end: i64| {
+            let thread = thread_names
+                .get(&thread_of(event))
+                .cloned()
+                .unwrap_or_default();
+            spans.push((
+                str_field(event, "cat"),
+                TimelineSpan {
+                    name: str_field(event, "name"),
+                    thread,
+                    offset: Duration::from_micros((start - origin_micros).max(0) as u64),
+                    duration: Duration::from_micros((end - start).max(0) as u64),
+                },
+            ));
+        };
+        // Synchronous events nest per thread, asynchronous ones are matched by id
+        let mut open_sync: HashMap<_, Vec<&Value>> = HashMap::new();
+        let mut open_async: HashMap<(String, String, String), &Value> = HashMap::new();
+        for event in events {
+            let Some(ts) = event_micros(event) else {
+                continue;
+            };
+            match event.get("ph").and_then(|p| p.as_str()) {
+                Some("X") => {
+                    let dur = event.get("dur").and_then(|d| d.as_i64()).unwrap_or(0);
+                    span(event, ts, ts + dur);
+                }
+                Some("B") => open_sync.entry(thread_of(event)).or_default().push(event),
+                Some("E") => {
+                    if let Some(begin) = open_sync.get_mut(&thread_of(event)).and_then(Vec::pop) {
+                        span(begin, event_micros(begin).unwrap_or(ts), ts);
+                    }
+                }
+                Some(phase @ ("b" | "e")) => {
+                    let key = (
+                        str_field(event, "cat"),
+                        str_field(event, "name"),
+                        event.get("id").map(|id| id.to_string()).unwrap_or_default(),
+                    );
+                    if phase == "b" {
+                        open_async.insert(key, event);
+                    } else if let Some(begin) = open_async.remove(&key) {
+                        span(begin, event_micros(begin).unwrap_or(ts), ts);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut categories: Vec<TimelineCategory> = Vec::new();
+        for (category, span) in spans {
+            let index = match categories.iter().position(|c| c.name == category) {
+                Some(index) => index,
+                None => {
+                    categories.push(TimelineCategory {
+                        name: category,
+                        total: Duration::ZERO,
+                        spans: Vec::new(),
+                    });
+                    categories.len() - 1
+                }
+            };
+            categories[index].total += span.duration;
+            categories[index].spans.push(span);
+        }
+        for category in &mut categories {
+            category
+                .spans
+                .sort_by_key(|span| std::cmp::Reverse(span.duration));
+        }
+        categories.sort_by_key(|category| std::cmp::Reverse(category.total));
+
+        Self {
+            window_secs,
+            event_count: events.len(),
+            categories,
+        }
+    }
+}
+
+fn event_micros(event: &Value) -> Option<i64> {
+    event.get("ts")?.as_i64()
+}
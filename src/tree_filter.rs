@@ -0,0 +1,133 @@
+use crate::vm_service::RemoteDiagnosticsNode;
+use std::collections::HashSet;
+
+/// A parsed inspector filter query.
+///
+/// The query is a whitespace separated list of terms that must all match:
+/// - `text` matches the widget type or description (case-insensitive substring)
+/// - `type:Text` / `t:Text` only matches the widget type
+/// - `desc:hello` / `d:hello` only matches the description
+/// - `name:child` / `n:child` only matches the diagnostics name
+/// - a leading `!` negates any of the above, e.g. `!type:Padding`
+#[derive(Debug, Clone, Default)]
+pub struct TreeFilter {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Any,
+    Type,
+    Description,
+    Name,
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    field: Field,
+    needle: String,
+    negated: bool,
+}
+
+impl TreeFilter {
+    pub fn parse(query: &str) -> Self {
+        let terms = query
+            .split_whitespace()
+            .filter_map(|raw| {
+                let (negated, raw) = match raw.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, raw),
+                };
+
+                let (field, needle) = match raw.split_once(':') {
+                    Some(("type" | "t", v)) => (Field::Type, v),
+                    Some(("desc" | "d", v)) => (Field::Description, v),
+                    Some(("name" | "n", v)) => (Field::Name, v),
+                    _ => (Field::Any, raw),
+                };
+
+                if needle.is_empty() {
+                    return None;
+                }
+
+                Some(Term {
+                    field,
+                    needle: needle.to_lowercase(),
+                    negated,
+                })
+            })
+            .collect();
+
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, node: &RemoteDiagnosticsNode) -> bool {
+        self.terms.iter().all(|term| term.matches(node))
+    }
+
+    /// Collects the IDs of every node that matches, plus the IDs of all their ancestors,
+    /// so the filtered tree can still be navigated from the root.
+    /// Also returns the number of nodes that matched directly.
+    pub fn visible_ids(&self, root: &RemoteDiagnosticsNode) -> (HashSet<String>, usize) {
+        let mut ids = HashSet::new();
+        let mut match_count = 0;
+        self.collect_visible(root, &mut ids, &mut match_count);
+        (ids, match_count)
+    }
+
+    /// Returns true if this node or any of its descendants matched.
+    fn collect_visible(
+        &self,
+        node: &RemoteDiagnosticsNode,
+        ids: &mut HashSet<String>,
+        match_count: &mut usize,
+    ) -> bool {
+        let mut keep = self.matches(node);
+        if keep {
+            *match_count += 1;
+        }
+
+        if let Some(children) = &node.children {
+            for child in children {
+                if self.collect_visible(child, ids, match_count) {
+                    keep = true;
+                }
+            }
+        }
+
+        if keep {
+            if let Some(id) = crate::app_state::AppState::get_node_id(node) {
+                ids.insert(id);
+            }
+        }
+        keep
+    }
+}
+
+impl Term {
+    fn matches(&self, node: &RemoteDiagnosticsNode) -> bool {
+        let contains = |value: Option<&str>| {
+            value
+                .map(|v| v.to_lowercase().contains(&self.needle))
+                .unwrap_or(false)
+        };
+
+        let widget_type = node
+            .widget_runtime_type
+            .as_deref()
+            .or(node.node_type.as_deref());
+
+        let found = match self.field {
+            Field::Any => contains(widget_type) || contains(node.description.as_deref()),
+            Field::Type => contains(widget_type),
+            Field::Description => contains(node.description.as_deref()),
+            Field::Name => contains(node.name.as_deref()),
+        };
+
+        found != self.negated
+    }
+}
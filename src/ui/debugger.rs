@@ -1,4 +1,7 @@
+use super::highlight;
+use super::theme::theme;
 use crate::app_state::AppState;
+use crate::t;
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     style::{Color, Style},
@@ -10,11 +13,12 @@ pub fn draw(f: &mut Frame, area: ratatui::layout::Rect, state: &AppState) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Percentage(20), // File Explorer
-            Constraint::Percentage(50), // Source Code
-            Constraint::Percentage(30), // Breakpoints/Stack
+            Constraint::Percentage(state.debugger_split[0]), // File Explorer
+            Constraint::Percentage(state.debugger_split[1]), // Source Code
+            Constraint::Percentage(state.debugger_split[2]), // Breakpoints/Stack
         ])
         .split(area);
+    super::record_split(state, area, &chunks);
 
     // File Explorer
     state.debugger_tree_area.replace(chunks[0]);
@@ -25,11 +29,18 @@ pub fn draw(f: &mut Frame, area: ratatui::layout::Rect, state: &AppState) {
         f,
         chunks[0],
         state.file_tree.as_ref(),
+        &crate::ui::tree::TreeRows::new(
+            state.file_tree.as_ref(),
+            &state.debugger_expanded_ids,
+            None,
+        ),
         state.debugger_selected_index,
-        &state.debugger_expanded_ids,
+        None,
+        None,
+        None,
         state.debugger_tree_scroll_offset,
         state.debugger_tree_horizontal_scroll,
-        "Files",
+        t!("debugger.files"),
         state.focus == crate::app_state::Focus::DebuggerFiles,
     );
     state.debugger_visible_count.replace(count);
@@ -57,14 +68,12 @@ pub fn draw(f: &mut Frame, area: ratatui::layout::Rect, state: &AppState) {
             )
         };
 
-        let search_block = Block::default()
-            .title("Search Files")
-            .borders(Borders::ALL)
-            .border_style(if state.focus == crate::app_state::Focus::DebuggerSearch {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default()
-            });
+        let search_block = super::focus_border(
+            Block::default()
+                .title(t!("debugger.search_files"))
+                .borders(Borders::ALL),
+            state.focus == crate::app_state::Focus::DebuggerSearch,
+        );
 
         let p = Paragraph::new(search_text).block(search_block);
         f.render_widget(p, search_area);
@@ -72,14 +81,19 @@ pub fn draw(f: &mut Frame, area: ratatui::layout::Rect, state: &AppState) {
 
     // Source Code
     state.debugger_source_area.replace(chunks[1]);
-    let source_block = Block::default().title("Source Code").borders(Borders::ALL);
+    let source_block = super::focus_border(
+        Block::default()
+            .title(t!("debugger.source"))
+            .borders(Borders::ALL),
+        state.focus == crate::app_state::Focus::DebuggerSource,
+    );
     let source_area = chunks[1];
     f.render_widget(source_block.clone(), source_area);
 
     let inner_source_area = source_block.inner(source_area);
 
     if let Some(content) = &state.open_file_content {
-        // Simple rendering for now: line numbers + content
+        let mut lex_state = highlight::state_before(content, state.source_scroll_offset);
         let lines: Vec<ratatui::widgets::ListItem> = content
             .iter()
             .enumerate()
@@ -91,76 +105,238 @@ pub fn draw(f: &mut Frame, area: ratatui::layout::Rect, state: &AppState) {
                 let path = state.open_file_path.as_deref().unwrap_or("");
                 let bp_key = format!("{}:{}", path, line_num);
                 let is_bp = state.breakpoints.contains(&bp_key);
+                let is_logpoint = state.logpoints.contains_key(&bp_key);
+                let is_disabled = state.disabled_breakpoints.contains(&bp_key);
 
                 let is_selected = state.source_selected_line == Some(i);
+                let is_execution =
+                    state
+                        .execution_line
+                        .as_ref()
+                        .is_some_and(|(exec_path, exec_line)| {
+                            exec_path == path && *exec_line == line_num
+                        });
 
-                let prefix = if is_bp { "●" } else { " " };
+                let prefix = if super::accessible() {
+                    // Breakpoint kind, then where execution and the cursor are
+                    let kind = if is_bp && is_disabled {
+                        'b'
+                    } else if is_logpoint {
+                        'L'
+                    } else if is_bp {
+                        'B'
+                    } else {
+                        ' '
+                    };
+                    let position = if is_execution {
+                        '='
+                    } else if is_selected {
+                        '>'
+                    } else {
+                        ' '
+                    };
+                    format!("{}{}", kind, position)
+                } else if is_execution {
+                    "▶".to_string()
+                } else if is_bp && is_disabled {
+                    "○".to_string()
+                } else if is_logpoint {
+                    "◆".to_string()
+                } else if is_bp {
+                    "●".to_string()
+                } else {
+                    " ".to_string()
+                };
                 let mut style = Style::default();
-                if is_bp {
-                    style = style.fg(Color::Red);
+                if is_bp && is_disabled {
+                    style = style.fg(theme().disabled_breakpoint);
+                } else if is_logpoint {
+                    style = style.fg(theme().logpoint);
+                } else if is_bp {
+                    style = style.fg(theme().breakpoint);
+                } else if is_execution {
+                    style = style.fg(theme().execution_line.bg.unwrap_or(Color::Yellow));
                 }
                 if is_selected {
-                    style = style.bg(Color::DarkGray);
+                    style = super::selection_style(style.patch(theme().line_selection));
+                }
+
+                // Highlight every line so multi-line comments/strings stay in sync, but the
+                // execution line keeps its solid marker style
+                let highlighted = highlight::highlight_line(line, &mut lex_state);
+                let mut spans = vec![ratatui::text::Span::styled(
+                    format!("{} {:4} ", prefix, line_num),
+                    style,
+                )];
+                if is_execution {
+                    spans.push(ratatui::text::Span::styled(line, theme().execution_line));
+                } else {
+                    spans.extend(highlighted);
                 }
 
-                ratatui::widgets::ListItem::new(ratatui::text::Line::from(vec![
-                    ratatui::text::Span::styled(format!("{} {:4} ", prefix, line_num), style),
-                    ratatui::text::Span::raw(line),
-                ]))
+                ratatui::widgets::ListItem::new(ratatui::text::Line::from(spans))
             })
             .collect();
 
         let list = ratatui::widgets::List::new(lines);
         f.render_widget(list, inner_source_area);
     } else {
-        let p = Paragraph::new("No file open").alignment(ratatui::layout::Alignment::Center);
+        let p =
+            Paragraph::new(t!("debugger.no_file")).alignment(ratatui::layout::Alignment::Center);
         f.render_widget(p, inner_source_area);
     }
 
     // Right Panel
     let right_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .constraints([
+            Constraint::Percentage(state.right_panel_split[0]), // Breakpoints
+            Constraint::Percentage(state.right_panel_split[1]), // Call Stack
+            Constraint::Percentage(state.right_panel_split[2]), // Locals
+        ])
         .split(chunks[2]);
 
+    let marked_breakpoints = state.marked_breakpoints();
     let breakpoints_list: Vec<ratatui::widgets::ListItem> = state
-        .breakpoints
-        .iter()
-        .map(|bp| ratatui::widgets::ListItem::new(bp.as_str()))
+        .sorted_breakpoints()
+        .into_iter()
+        .enumerate()
+        .map(|(index, bp)| {
+            let mut text = bp.clone();
+            if let Some(condition) = state.breakpoint_conditions.get(bp) {
+                text.push_str(&format!(
+                    " {}",
+                    t!("debugger.breakpoint_condition", condition = condition)
+                ));
+            }
+            let mut style = Style::default();
+            if let Some(template) = state.logpoints.get(bp) {
+                text.push_str(&format!(
+                    " {}",
+                    t!("debugger.breakpoint_log", template = template)
+                ));
+                style = style.fg(theme().logpoint);
+            }
+            if state.disabled_breakpoints.contains(bp) {
+                text.push_str(&format!(" {}", t!("debugger.breakpoint_disabled")));
+                style = style.fg(theme().disabled_breakpoint);
+            }
+            if marked_breakpoints
+                .as_ref()
+                .is_some_and(|marked| marked.contains(&index))
+            {
+                style = super::mark_style(style.patch(theme().mark));
+            }
+            ratatui::widgets::ListItem::new(text).style(style)
+        })
         .collect();
 
+    let breakpoints_focused = state.focus == crate::app_state::Focus::DebuggerBreakpoints;
+    let has_breakpoints = !breakpoints_list.is_empty();
     let breakpoints = ratatui::widgets::List::new(breakpoints_list)
-        .block(Block::default().title("Breakpoints").borders(Borders::ALL));
-    f.render_widget(breakpoints, right_chunks[0]);
+        .block(super::focus_border(
+            Block::default()
+                .title(if breakpoints_focused {
+                    t!("debugger.breakpoints_focused")
+                } else {
+                    t!("debugger.breakpoints")
+                })
+                .borders(Borders::ALL),
+            breakpoints_focused,
+        ))
+        .highlight_style(super::selection_style(theme().selection))
+        .highlight_symbol("> ");
+    let mut breakpoints_state = ratatui::widgets::ListState::default();
+    if breakpoints_focused && has_breakpoints {
+        breakpoints_state.select(Some(state.breakpoints_selected_index));
+    }
+    f.render_stateful_widget(breakpoints, right_chunks[0], &mut breakpoints_state);
 
+    let stack_focused = state.focus == crate::app_state::Focus::DebuggerStack;
     let mut stack_items = Vec::new();
-    match &state.debug_state {
-        crate::app_state::DebugState::Paused { reason, .. } => {
-            stack_items.push(ratatui::widgets::ListItem::new(format!(
-                "Paused: {}",
-                reason
-            )));
-            if let Some(stack) = &state.stack_trace {
-                if let Some(frames) = stack.get("frames").and_then(|f| f.as_array()) {
-                    for frame in frames {
-                        if let Some(func) = frame
-                            .get("function")
-                            .and_then(|f| f.get("name"))
-                            .and_then(|n| n.as_str())
-                        {
-                            stack_items
-                                .push(ratatui::widgets::ListItem::new(format!("- {}", func)));
-                        }
-                    }
-                }
+    let stack_title = match &state.debug_state {
+        crate::app_state::DebugState::Paused {
+            isolate_id, reason, ..
+        } => {
+            for frame in state.stack_frames() {
+                stack_items.push(ratatui::widgets::ListItem::new(
+                    frame.function_name().to_string(),
+                ));
+            }
+            if reason == "PauseStart" {
+                stack_items.push(
+                    ratatui::widgets::ListItem::new(t!(
+                        "debugger.paused_at_start",
+                        key = state.keymap.key(crate::keymap::Action::TogglePause)
+                    ))
+                    .style(Style::default().fg(Color::Green)),
+                );
             }
+            let mut title = t!(
+                "debugger.stack_paused",
+                isolate = state.isolate_name(isolate_id),
+                reason = reason
+            );
+            // The isolate picker switches to them
+            let others = state.paused_isolates.len().saturating_sub(1);
+            if others > 0 {
+                title.push_str(&t!("debugger.stack_others_paused", count = others));
+            }
+            title
         }
         crate::app_state::DebugState::Running => {
-            stack_items.push(ratatui::widgets::ListItem::new("Running..."));
+            stack_items.push(ratatui::widgets::ListItem::new(t!("debugger.running")));
+            t!("debugger.stack").to_string()
         }
     };
 
     let stack_list = ratatui::widgets::List::new(stack_items)
-        .block(Block::default().title("Call Stack").borders(Borders::ALL));
-    f.render_widget(stack_list, right_chunks[1]);
+        .block(super::focus_border(
+            Block::default().title(stack_title).borders(Borders::ALL),
+            stack_focused,
+        ))
+        .highlight_style(super::selection_style(theme().selection))
+        .highlight_symbol("> ");
+    let mut stack_state = ratatui::widgets::ListState::default();
+    if matches!(
+        state.debug_state,
+        crate::app_state::DebugState::Paused { .. }
+    ) {
+        stack_state.select(Some(state.stack_selected_index));
+    }
+    f.render_stateful_widget(stack_list, right_chunks[1], &mut stack_state);
+
+    // Locals
+    let locals_focused = state.focus == crate::app_state::Focus::DebuggerLocals;
+    state
+        .locals_tree_height
+        .replace(right_chunks[2].height as usize);
+    if state.locals_root.is_some() {
+        crate::ui::tree::draw(
+            f,
+            right_chunks[2],
+            state.locals_root.as_ref(),
+            &crate::ui::tree::TreeRows::new(
+                state.locals_root.as_ref(),
+                &state.locals_expanded_ids,
+                None,
+            ),
+            state.locals_selected_index,
+            None,
+            None,
+            None,
+            state.locals_scroll_offset,
+            0,
+            t!("debugger.locals"),
+            locals_focused,
+        );
+    } else {
+        let p = Paragraph::new(t!("debugger.not_paused")).block(super::focus_border(
+            Block::default()
+                .title(t!("debugger.locals"))
+                .borders(Borders::ALL),
+            locals_focused,
+        ));
+        f.render_widget(p, right_chunks[2]);
+    }
 }
@@ -1,60 +1,119 @@
 use crate::app_state::AppState;
+use crate::t;
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Direction, Layout, Rect},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 
 pub fn draw(f: &mut Frame, area: Rect, state: &AppState) {
-    let border_style = if state.focus == crate::app_state::Focus::Details {
-        ratatui::style::Style::default().fg(ratatui::style::Color::Yellow)
-    } else {
-        ratatui::style::Style::default()
-    };
-    let block = Block::default()
-        .title("Details")
-        .borders(Borders::ALL)
-        .border_style(border_style);
+    // Only the tree view takes clicks
+    state.details_tree_area.replace(Rect::default());
+    let block = super::focus_border(
+        Block::default()
+            .title(t!("details.title"))
+            .borders(Borders::ALL),
+        state.focus == crate::app_state::Focus::Details,
+    );
 
-    let content = if let Some(details) = &state.selected_node_details {
-        let mut text = format!(
-            "Type: {}\nDescription: {}\nObject ID: {}\nValue ID: {}\n\nProperties:\n",
-            details.widget_runtime_type.as_deref().unwrap_or("Unknown"),
-            details.description.as_deref().unwrap_or("-"),
-            details.object_id.as_deref().unwrap_or("-"),
-            details.value_id.as_deref().unwrap_or("-")
-        );
+    if let (true, Some(_), Some(json)) = (
+        state.show_raw_json,
+        &state.selected_node_details,
+        &state.details_raw_json,
+    ) {
+        // Plain text so it can be selected and copied from the terminal
+        let paragraph = Paragraph::new(json.as_str())
+            .block(block.title(t!("details.title_raw_json")))
+            .scroll((state.raw_json_scroll.min(u16::MAX as usize) as u16, 0));
+        f.render_widget(paragraph, area);
+        return;
+    }
 
-        if let Some(props) = &details.properties {
-            for prop in props {
-                let name = prop.name.as_deref().unwrap_or("");
-                let desc = prop.description.as_deref().unwrap_or("");
-                if !name.is_empty() || !desc.is_empty() {
-                    text.push_str(&format!("- {}: {}\n", name, desc));
-                }
-            }
+    if state.selected_node_details.is_some() {
+        let searching = state.focus == crate::app_state::Focus::DetailsSearch;
+        let show_search_bar = searching || !state.details_search_query.is_empty();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),
+                Constraint::Length(if show_search_bar { 3 } else { 0 }),
+            ])
+            .split(area);
+        if show_search_bar {
+            let block = super::focus_border(
+                Block::default()
+                    .title(if searching {
+                        t!("details.search_editing")
+                    } else {
+                        t!("details.search")
+                    })
+                    .borders(Borders::ALL),
+                searching,
+            );
+            let text = t!(
+                "common.query_matches",
+                query = state.details_search_query,
+                count = state.details_search_matches
+            );
+            f.render_widget(Paragraph::new(text).block(block), chunks[1]);
         }
-        text
-    } else if let Some(root) = &state.root_node {
+
+        // One tree for everything so nested values can be drilled into and nothing clips
+        state.details_tree_height.replace(chunks[0].height as usize);
+        state
+            .details_tree_area
+            .replace(Block::default().borders(Borders::ALL).inner(chunks[0]));
+        crate::ui::tree::draw(
+            f,
+            chunks[0],
+            state.shown_details(),
+            &crate::ui::tree::TreeRows::new(
+                state.shown_details(),
+                &state.details_expanded_ids,
+                None,
+            ),
+            state.details_selected_index,
+            None,
+            None,
+            None,
+            state.details_scroll_offset,
+            0,
+            t!("details.title"),
+            state.focus == crate::app_state::Focus::Details,
+        );
+        return;
+    }
+
+    let content = if let Some(root) = &state.root_node {
         // Fallback to tree node if details not yet loaded
         // ... (existing logic)
         let mut lines = Vec::new();
         let mut visible_nodes = Vec::new();
-        flatten_tree(root, 0, &mut lines, &mut visible_nodes);
+        flatten_tree(root, &mut lines, &mut visible_nodes);
 
         if let Some(node) = visible_nodes.get(state.selected_index) {
-            format!(
-                "Type: {}\nDescription: {}\nObject ID: {}\nValue ID: {}\n\n(Fetching details...)",
-                node.widget_runtime_type.as_deref().unwrap_or("Unknown"),
-                node.description.as_deref().unwrap_or("-"),
-                node.object_id.as_deref().unwrap_or("-"),
-                node.value_id.as_deref().unwrap_or("-")
+            let expired = AppState::get_node_id(node).is_some()
+                && AppState::get_node_id(node) == state.expired_details_id;
+            t!(
+                "details.summary",
+                widget_type = node
+                    .widget_runtime_type
+                    .as_deref()
+                    .unwrap_or(t!("common.unknown")),
+                description = node.description.as_deref().unwrap_or("-"),
+                object_id = node.object_id.as_deref().unwrap_or("-"),
+                value_id = node.value_id.as_deref().unwrap_or("-"),
+                status = if expired {
+                    t!("details.expired")
+                } else {
+                    t!("details.fetching")
+                }
             )
         } else {
-            "No node selected".to_string()
+            t!("details.no_selection").to_string()
         }
     } else {
-        "No data".to_string()
+        t!("details.no_data").to_string()
     };
 
     let paragraph = Paragraph::new(content).block(block);
@@ -65,7 +124,6 @@ pub fn draw(f: &mut Frame, area: Rect, state: &AppState) {
 use crate::vm_service::RemoteDiagnosticsNode;
 fn flatten_tree<'a>(
     node: &'a RemoteDiagnosticsNode,
-    depth: usize,
     lines: &mut Vec<String>,
     nodes: &mut Vec<&'a RemoteDiagnosticsNode>,
 ) {
@@ -73,7 +131,7 @@ fn flatten_tree<'a>(
     nodes.push(node);
     if let Some(children) = &node.children {
         for child in children {
-            flatten_tree(child, depth + 1, lines, nodes);
+            flatten_tree(child, lines, nodes);
         }
     }
 }
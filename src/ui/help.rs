@@ -0,0 +1,293 @@
+use crate::app_state::{AppState, Focus, Tab};
+use crate::i18n::text;
+use crate::keymap::{Action, KeyBinding};
+use crate::t;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// What triggers an entry: a remappable action, shown with its current key, or keys the
+/// main loop handles as they are, with the second ones in use when vim navigation is on.
+enum Keys {
+    Action(Action),
+    Fixed(&'static str),
+    Vim(&'static str, &'static str),
+}
+
+/// A group of the cheat-sheet, titled and described by catalog keys. Statics, so the
+/// section in focus can be told apart by address.
+struct Section {
+    title: &'static str,
+    entries: &'static [(Keys, &'static str)],
+}
+
+static GLOBAL: Section = Section {
+    title: "help.global",
+    entries: &[
+        (Keys::Action(Action::InspectorTab), "help.inspector_tab"),
+        (Keys::Action(Action::DebuggerTab), "help.debugger_tab"),
+        (Keys::Action(Action::LogsTab), "help.logs_tab"),
+        (Keys::Action(Action::MemoryTab), "help.memory_tab"),
+        (Keys::Action(Action::ProfilerTab), "help.profiler_tab"),
+        (Keys::Action(Action::PerformanceTab), "help.performance_tab"),
+        (Keys::Action(Action::NetworkTab), "help.network_tab"),
+        (Keys::Action(Action::CycleFocus), "help.cycle_focus"),
+        (Keys::Action(Action::HotReload), "help.hot_reload"),
+        (Keys::Action(Action::HotRestart), "help.hot_restart"),
+        (Keys::Action(Action::ToggleAutoReload), "help.auto_reload"),
+        (Keys::Action(Action::ReloadScope), "help.reload_scope"),
+        (Keys::Action(Action::Reassemble), "help.reassemble"),
+        (Keys::Action(Action::Refresh), "help.refresh"),
+        (Keys::Fixed("Ctrl+R"), "help.refresh"),
+        (Keys::Action(Action::TogglePause), "help.pause"),
+        (Keys::Action(Action::SelectIsolate), "help.select_isolate"),
+        (
+            Keys::Action(Action::PreviousIsolate),
+            "help.previous_isolate",
+        ),
+        (Keys::Action(Action::NextIsolate), "help.next_isolate"),
+        (Keys::Action(Action::ToggleLogs), "help.toggle_logs"),
+        (Keys::Action(Action::GrowLogs), "help.grow_logs"),
+        (Keys::Fixed("="), "help.grow_logs"),
+        (Keys::Action(Action::ShrinkLogs), "help.shrink_logs"),
+        (
+            Keys::Action(Action::CycleLogHeight),
+            "help.cycle_log_height",
+        ),
+        (Keys::Action(Action::Search), "help.search"),
+        (Keys::Action(Action::Copy), "help.copy"),
+        (Keys::Action(Action::CommandPalette), "help.command_palette"),
+        (Keys::Action(Action::Toggles), "help.toggles"),
+        (Keys::Action(Action::AppInfo), "help.app_info"),
+        (Keys::Action(Action::IsolateMetrics), "help.isolate_metrics"),
+        (Keys::Action(Action::DeviceInput), "help.device_input"),
+        (Keys::Action(Action::SaveTimeline), "help.save_timeline"),
+        (Keys::Action(Action::Help), "help.help"),
+        (Keys::Action(Action::Quit), "help.quit"),
+        (Keys::Fixed("Ctrl+C"), "help.quit"),
+        (Keys::Fixed("Ctrl+Z"), "help.suspend"),
+        (Keys::Fixed("Ctrl+←/→"), "help.resize_pane"),
+        (Keys::Fixed("Ctrl+Home/End"), "help.jump_to_edge"),
+        (Keys::Fixed("hjkl gg/G Ctrl+D/U"), "help.vim"),
+    ],
+};
+
+static INSPECTOR: Section = Section {
+    title: "help.inspector",
+    entries: &[
+        (Keys::Fixed("↑/↓"), "help.move"),
+        (Keys::Fixed("Shift+↑/↓"), "help.extend_selection"),
+        (Keys::Fixed("←/→"), "help.collapse_expand"),
+        (Keys::Fixed("e/E"), "help.expand_collapse_subtree"),
+        (Keys::Fixed("z"), "help.focus_subtree"),
+        (Keys::Fixed("D"), "help.expand_to_depth"),
+        (Keys::Fixed("f"), "help.scroll_to_node"),
+        (Keys::Fixed("F"), "help.filter"),
+        (Keys::Fixed("n/N"), "help.next_match"),
+        (Keys::Vim("g", "gd"), "help.open_source"),
+        (Keys::Fixed("J"), "help.raw_json"),
+        (Keys::Fixed("P"), "help.screenshot"),
+        (Keys::Fixed("Y"), "help.copy_as_dart"),
+        (Keys::Action(Action::ToggleSummaryTree), "help.summary_tree"),
+        (Keys::Action(Action::WidgetSelectMode), "help.select_mode"),
+        (
+            Keys::Action(Action::ToggleRebuildTracking),
+            "help.rebuild_tracking",
+        ),
+        (Keys::Action(Action::RebuildStats), "help.rebuild_stats"),
+        (Keys::Action(Action::LayoutWarnings), "help.layout_warnings"),
+        (Keys::Action(Action::FocusTree), "help.focus_tree"),
+    ],
+};
+
+static DETAILS: Section = Section {
+    title: "help.details",
+    entries: &[
+        (Keys::Fixed("↑/↓ PgUp/PgDn"), "help.move"),
+        (Keys::Fixed("←/→ Enter/Space"), "help.collapse_expand"),
+        (Keys::Fixed("/"), "help.search_properties"),
+    ],
+};
+
+static SOURCE: Section = Section {
+    title: "help.source",
+    entries: &[
+        (Keys::Fixed("↑/↓"), "help.move"),
+        (
+            Keys::Action(Action::ToggleBreakpoint),
+            "help.toggle_breakpoint",
+        ),
+        (Keys::Fixed("C"), "help.breakpoint_condition"),
+        (Keys::Fixed("L"), "help.logpoint"),
+        (Keys::Fixed("/"), "help.search_text"),
+        (Keys::Fixed("n/N"), "help.next_match"),
+        (Keys::Action(Action::Resume), "help.resume"),
+        (Keys::Action(Action::StepOver), "help.step_over"),
+        (Keys::Action(Action::StepInto), "help.step_into"),
+        (Keys::Action(Action::StepOut), "help.step_out"),
+        (Keys::Fixed("Esc"), "help.back_to_files"),
+    ],
+};
+
+static DEBUGGER: Section = Section {
+    title: "help.debugger",
+    entries: &[
+        (Keys::Fixed("↑/↓"), "help.move"),
+        (Keys::Fixed("←/→"), "help.collapse_expand"),
+        (Keys::Fixed("Enter/Space"), "help.open"),
+        (Keys::Fixed("/"), "help.search_files"),
+        (Keys::Fixed("n/N"), "help.next_match"),
+        (Keys::Fixed("Shift+↑/↓"), "help.extend_breakpoints"),
+        (Keys::Fixed("d/Del"), "help.remove_breakpoint"),
+        (Keys::Fixed("Ctrl+↑/↓"), "help.resize_panel"),
+        (Keys::Action(Action::Resume), "help.resume"),
+    ],
+};
+
+static LOGS: Section = Section {
+    title: "help.logs",
+    entries: &[
+        (Keys::Fixed("↑/↓ PgUp/PgDn"), "help.move"),
+        (Keys::Fixed("Shift+↑/↓"), "help.extend_selection"),
+        (Keys::Fixed("Enter/Space"), "help.expand_entry"),
+        (Keys::Fixed("/"), "help.search_text"),
+        (Keys::Fixed("n/N"), "help.next_match"),
+        (Keys::Fixed("b"), "help.break_at_error"),
+        (Keys::Fixed("[/]"), "help.log_source"),
+        (Keys::Fixed("v"), "help.log_level"),
+    ],
+};
+
+static MEMORY: Section = Section {
+    title: "help.memory",
+    entries: &[
+        (Keys::Fixed("s"), "help.sort"),
+        (Keys::Fixed("G"), "help.collect_garbage"),
+        (Keys::Fixed("W"), "help.leak_hunt"),
+        (Keys::Fixed("j"), "help.leak_suspect"),
+    ],
+};
+
+static PROFILER: Section = Section {
+    title: "help.profiler",
+    entries: &[
+        (Keys::Fixed("c"), "help.capture"),
+        (Keys::Fixed("[/]"), "help.window"),
+        (Keys::Fixed("v"), "help.view"),
+        (Keys::Fixed("Enter/Space"), "help.collapse_expand"),
+    ],
+};
+
+static PERFORMANCE: Section = Section {
+    title: "help.performance",
+    entries: &[
+        (Keys::Fixed("c"), "help.capture"),
+        (Keys::Fixed("[/]"), "help.window"),
+        (Keys::Fixed("f"), "help.category"),
+        (Keys::Fixed("/"), "help.filter_events"),
+    ],
+};
+
+static NETWORK: Section = Section {
+    title: "help.network",
+    entries: &[
+        (Keys::Fixed("Enter/Space"), "help.request_details"),
+        (Keys::Fixed("d"), "help.replay"),
+    ],
+};
+
+/// The section of the pane or tab in focus, which the cheat-sheet starts with.
+fn current_section(state: &AppState) -> &'static Section {
+    match (state.current_tab, state.focus) {
+        (_, Focus::Details | Focus::DetailsSearch) => &DETAILS,
+        (_, Focus::Logs) | (Tab::Logs, _) => &LOGS,
+        (Tab::Inspector, _) => &INSPECTOR,
+        (Tab::Debugger, Focus::DebuggerSource) => &SOURCE,
+        (Tab::Debugger, _) => &DEBUGGER,
+        (Tab::Memory, _) => &MEMORY,
+        (Tab::Profiler, _) => &PROFILER,
+        (Tab::Performance, _) => &PERFORMANCE,
+        (Tab::Network, _) => &NETWORK,
+    }
+}
+
+fn section_lines(state: &AppState, section: &Section, current: bool, lines: &mut Vec<Line>) {
+    let mut title = text(section.title).to_string();
+    if current {
+        title.push_str(&format!(" {}", t!("help.current")));
+    }
+    let title_style = if current {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD)
+    };
+    lines.push(Line::styled(title, title_style));
+
+    for (keys, description) in section.entries {
+        let keys = match keys {
+            Keys::Action(action) => state.keymap.key(*action).to_string(),
+            Keys::Vim(_, keys) if state.vim_keys => keys.to_string(),
+            Keys::Fixed(keys) | Keys::Vim(keys, _) => {
+                // A remapped action that took this key shadows what it does here
+                let taken = keys
+                    .parse::<KeyBinding>()
+                    .is_ok_and(|key| state.keymap.is_remapped_key(key));
+                if taken {
+                    continue;
+                }
+                keys.to_string()
+            }
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<16}", keys), Style::default().fg(Color::Green)),
+            Span::raw(text(description)),
+        ]));
+    }
+    lines.push(Line::raw(""));
+}
+
+pub fn draw(f: &mut Frame, area: Rect, state: &AppState) {
+    let current = current_section(state);
+    let mut lines = Vec::new();
+    section_lines(state, current, true, &mut lines);
+    section_lines(state, &GLOBAL, false, &mut lines);
+    for section in [
+        &INSPECTOR,
+        &DETAILS,
+        &SOURCE,
+        &DEBUGGER,
+        &LOGS,
+        &MEMORY,
+        &PROFILER,
+        &PERFORMANCE,
+        &NETWORK,
+    ] {
+        if !std::ptr::eq(current, section) {
+            section_lines(state, section, false, &mut lines);
+        }
+    }
+
+    let block = Block::default()
+        .title(t!("help.title"))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::DarkGray));
+    let height = block.inner(area).height as usize;
+    let max_scroll = lines.len().saturating_sub(height);
+    state.help_max_scroll.replace(max_scroll);
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((state.help_scroll.min(max_scroll) as u16, 0)),
+        area,
+    );
+}
@@ -0,0 +1,248 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+};
+
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "as",
+    "assert",
+    "async",
+    "await",
+    "base",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "covariant",
+    "default",
+    "deferred",
+    "do",
+    "dynamic",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "extension",
+    "external",
+    "factory",
+    "false",
+    "final",
+    "finally",
+    "for",
+    "get",
+    "hide",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "interface",
+    "is",
+    "late",
+    "library",
+    "mixin",
+    "new",
+    "null",
+    "on",
+    "operator",
+    "part",
+    "required",
+    "rethrow",
+    "return",
+    "sealed",
+    "set",
+    "show",
+    "static",
+    "super",
+    "switch",
+    "sync",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "var",
+    "void",
+    "when",
+    "while",
+    "with",
+    "yield",
+];
+
+const KEYWORD_STYLE: Style = Style::new().fg(Color::Magenta);
+const TYPE_STYLE: Style = Style::new().fg(Color::Cyan);
+const STRING_STYLE: Style = Style::new().fg(Color::Green);
+const NUMBER_STYLE: Style = Style::new().fg(Color::LightYellow);
+const COMMENT_STYLE: Style = Style::new().fg(Color::DarkGray);
+const ANNOTATION_STYLE: Style = Style::new().fg(Color::LightBlue);
+
+/// Lexer state carried from one line to the next.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LineState {
+    #[default]
+    Code,
+    /// Inside a (possibly nested) `/* */` comment.
+    BlockComment(u32),
+    /// Inside a `'''` or `"""` string.
+    MultilineString(char),
+}
+
+/// Returns the lexer state at the start of line `index`.
+pub fn state_before(lines: &[String], index: usize) -> LineState {
+    let mut state = LineState::default();
+    for line in lines.iter().take(index) {
+        highlight_line(line, &mut state);
+    }
+    state
+}
+
+/// Splits a line of Dart source into colored spans, updating `state` for the next line.
+///
+/// This is a small lexer rather than a parser: types are guessed from capitalization.
+pub fn highlight_line<'a>(line: &'a str, state: &mut LineState) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    let bytes = line.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+        match *state {
+            LineState::BlockComment(depth) => {
+                i = scan_block_comment(bytes, i, depth, state);
+                spans.push(Span::styled(&line[start..i], COMMENT_STYLE));
+            }
+            LineState::MultilineString(quote) => {
+                let end = find_triple(bytes, i, quote as u8);
+                i = match end {
+                    Some(end) => {
+                        *state = LineState::Code;
+                        end + 3
+                    }
+                    None => bytes.len(),
+                };
+                spans.push(Span::styled(&line[start..i], STRING_STYLE));
+            }
+            LineState::Code => {
+                let c = bytes[i];
+                if line[i..].starts_with("//") {
+                    spans.push(Span::styled(&line[i..], COMMENT_STYLE));
+                    break;
+                } else if line[i..].starts_with("/*") {
+                    i = scan_block_comment(bytes, i + 2, 1, state);
+                    spans.push(Span::styled(&line[start..i], COMMENT_STYLE));
+                } else if c == b'\'' || c == b'"' || (c == b'r' && next_is_quote(bytes, i)) {
+                    let raw = c == b'r';
+                    let quote_at = if raw { i + 1 } else { i };
+                    let quote = bytes[quote_at];
+                    if bytes[quote_at..].starts_with(&[quote; 3]) {
+                        match find_triple(bytes, quote_at + 3, quote) {
+                            Some(end) => i = end + 3,
+                            None => {
+                                *state = LineState::MultilineString(quote as char);
+                                i = bytes.len();
+                            }
+                        }
+                    } else {
+                        i = scan_string(bytes, quote_at + 1, quote, raw);
+                    }
+                    spans.push(Span::styled(&line[start..i], STRING_STYLE));
+                } else if c == b'@' {
+                    i += 1;
+                    while i < bytes.len() && is_ident(bytes[i]) {
+                        i += 1;
+                    }
+                    spans.push(Span::styled(&line[start..i], ANNOTATION_STYLE));
+                } else if c.is_ascii_digit() {
+                    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.')
+                    {
+                        i += 1;
+                    }
+                    spans.push(Span::styled(&line[start..i], NUMBER_STYLE));
+                } else if is_ident(c) {
+                    while i < bytes.len() && is_ident(bytes[i]) {
+                        i += 1;
+                    }
+                    let word = &line[start..i];
+                    let style = if KEYWORDS.contains(&word) {
+                        KEYWORD_STYLE
+                    } else if word
+                        .trim_start_matches(['_', '$'])
+                        .starts_with(|c: char| c.is_ascii_uppercase())
+                    {
+                        TYPE_STYLE
+                    } else {
+                        Style::default()
+                    };
+                    spans.push(Span::styled(word, style));
+                } else {
+                    // Plain run up to the next character that could start a token
+                    i += line[i..].chars().next().map_or(1, char::len_utf8);
+                    while i < bytes.len() && !starts_token(bytes, i) {
+                        i += 1;
+                    }
+                    spans.push(Span::raw(&line[start..i]));
+                }
+            }
+        }
+    }
+
+    spans
+}
+
+fn is_ident(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_' || c == b'$'
+}
+
+fn next_is_quote(bytes: &[u8], i: usize) -> bool {
+    let prev_is_ident = i > 0 && is_ident(bytes[i - 1]);
+    !prev_is_ident && matches!(bytes.get(i + 1), Some(b'\'' | b'"'))
+}
+
+fn starts_token(bytes: &[u8], i: usize) -> bool {
+    let c = bytes[i];
+    is_ident(c)
+        || c == b'\''
+        || c == b'"'
+        || c == b'@'
+        || (c == b'/' && matches!(bytes.get(i + 1), Some(b'/' | b'*')))
+        || !c.is_ascii()
+}
+
+/// Returns the index just past the closing quote, or the end of the line.
+fn scan_string(bytes: &[u8], mut i: usize, quote: u8, raw: bool) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if !raw => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn find_triple(bytes: &[u8], from: usize, quote: u8) -> Option<usize> {
+    (from..bytes.len().saturating_sub(2)).find(|&i| bytes[i..].starts_with(&[quote; 3]))
+}
+
+/// Scans a block comment body starting at `i`, returning where the comment (or line) ends.
+fn scan_block_comment(bytes: &[u8], mut i: usize, mut depth: u32, state: &mut LineState) -> usize {
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"/*") {
+            depth += 1;
+            i += 2;
+        } else if bytes[i..].starts_with(b"*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                *state = LineState::Code;
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    *state = LineState::BlockComment(depth);
+    bytes.len()
+}
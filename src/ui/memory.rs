@@ -0,0 +1,342 @@
+use crate::app_state::{AppState, Focus, MemoryTabState};
+use crate::t;
+use crate::ui::theme::theme;
+use crate::ui::{focus_border, selection_style};
+use crate::vm_service::MemoryUsage;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Sparkline},
+    Frame,
+};
+
+/// Rows of the heap chart, borders included.
+const HEAP_CHART_HEIGHT: u16 = 7;
+
+pub fn draw(f: &mut Frame, area: Rect, state: &AppState) {
+    let Some(memory) = &state.memory else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(memory.isolates.len().max(1) as u16 + 6),
+            Constraint::Length(HEAP_CHART_HEIGHT),
+            Constraint::Min(0),
+        ])
+        .split(area);
+    draw_isolates(f, chunks[0], state, memory);
+    draw_heap_chart(f, chunks[1], memory);
+    if state.snapshot_interval.is_none() && state.snapshots.is_empty() {
+        draw_allocations(f, chunks[2], state, memory);
+        return;
+    }
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(chunks[2]);
+    draw_allocations(f, bottom[0], state, memory);
+    draw_snapshots(f, bottom[1], state);
+}
+
+/// Dart heap usage of the inspected isolate since the tab was opened.
+fn draw_heap_chart(f: &mut Frame, area: Rect, memory: &MemoryTabState) {
+    let title = match (memory.heap_history.back(), memory.heap_history.iter().max()) {
+        (Some(latest), Some(peak)) => t!(
+            "memory.heap_title",
+            heap = format_bytes(*latest),
+            peak = format_bytes(*peak)
+        ),
+        _ => t!("memory.heap_title_empty").to_string(),
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let data: Vec<u64> = memory.heap_history.iter().copied().collect();
+    f.render_widget(
+        Sparkline::default()
+            .block(block)
+            .data(relative(data))
+            .style(Style::default().fg(Color::Green)),
+        area,
+    );
+}
+
+/// Live instances and bytes per class, from the allocation profile.
+fn draw_allocations(f: &mut Frame, area: Rect, state: &AppState, memory: &MemoryTabState) {
+    let block = focus_border(
+        Block::default()
+            .title(t!(
+                "memory.allocations_title",
+                sort = memory.allocation_sort.label()
+            ))
+            .borders(Borders::ALL),
+        state.focus == Focus::Memory,
+    );
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let classes = match &memory.allocations {
+        None => {
+            f.render_widget(Paragraph::new(t!("common.loading")), inner_area);
+            return;
+        }
+        Some(Err(e)) => {
+            f.render_widget(
+                Paragraph::new(e.as_str()).style(Style::default().fg(Color::Red)),
+                inner_area,
+            );
+            return;
+        }
+        Some(Ok(_)) => memory.sorted_allocations(),
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner_area);
+    f.render_widget(
+        Paragraph::new(format!(
+            "   {:<32} {:>10} {:>12} {:>10}",
+            t!("memory.class"),
+            t!("memory.instances"),
+            t!("memory.bytes"),
+            t!("memory.allocated")
+        ))
+        .style(Style::default().fg(Color::Cyan)),
+        chunks[0],
+    );
+    let items: Vec<ListItem> = classes
+        .iter()
+        .map(|stats| {
+            ListItem::new(format!(
+                "{:<32} {:>10} {:>12} {:>10}",
+                truncate(&stats.class.name, 32),
+                stats.instances_current,
+                format_bytes(stats.bytes_current),
+                stats.instances_accumulated
+            ))
+        })
+        .collect();
+    let count = items.len();
+    let list = List::new(items)
+        .highlight_style(selection_style(theme().selection))
+        .highlight_symbol(">> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(
+        memory
+            .allocation_selected_index
+            .min(count.saturating_sub(1)),
+    ));
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+fn draw_isolates(f: &mut Frame, area: Rect, state: &AppState, memory: &MemoryTabState) {
+    let block = Block::default()
+        .title(t!("memory.isolates_title"))
+        .borders(Borders::ALL);
+
+    if memory.isolates.is_empty() {
+        let text = if state.vm_service_client.is_some() {
+            t!("memory.sampling")
+        } else {
+            t!("common.not_connected")
+        };
+        f.render_widget(Paragraph::new(text).block(block), area);
+        return;
+    }
+
+    let mut lines = vec![Line::styled(
+        format!(
+            "  {:<24} {:>22} {:>12} {:>22} {:>12}",
+            t!("memory.isolate"),
+            t!("memory.dart_heap"),
+            t!("memory.capacity"),
+            t!("memory.external"),
+            t!("memory.total")
+        ),
+        Style::default().fg(Color::Cyan),
+    )];
+
+    let mut total = MemoryUsage::default();
+    for sample in &memory.isolates {
+        let usage = sample.usage;
+        total.heap_usage += usage.heap_usage;
+        total.heap_capacity += usage.heap_capacity;
+        total.external_usage += usage.external_usage;
+
+        let inspected = state
+            .inspected_isolate
+            .as_ref()
+            .is_some_and(|isolate| isolate.id == sample.isolate_id);
+        let previous = sample.previous;
+        let mut spans = vec![
+            Span::raw(if inspected { "* " } else { "  " }),
+            Span::raw(format!("{:<24}", truncate(&sample.name, 24))),
+        ];
+        spans.extend(with_change(
+            usage.heap_usage,
+            previous.map(|p| p.heap_usage),
+        ));
+        spans.push(Span::raw(format!(
+            " {:>12}",
+            format_bytes(usage.heap_capacity)
+        )));
+        // External memory apart from the heap: images, textures and other native buffers
+        spans.extend(with_change(
+            usage.external_usage,
+            previous.map(|p| p.external_usage),
+        ));
+        spans.push(Span::raw(format!(
+            " {:>12}",
+            format_bytes(usage.heap_usage + usage.external_usage)
+        )));
+        lines.push(Line::from(spans));
+    }
+
+    if memory.isolates.len() > 1 {
+        lines.push(Line::styled(
+            format!(
+                "  {:<24} {:>22} {:>12} {:>22} {:>12}",
+                t!("memory.all_isolates"),
+                format_bytes(total.heap_usage),
+                format_bytes(total.heap_capacity),
+                format_bytes(total.external_usage),
+                format_bytes(total.heap_usage + total.external_usage)
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+    }
+    lines.push(Line::raw(""));
+    lines.push(Line::styled(
+        t!("memory.legend"),
+        Style::default().fg(Color::DarkGray),
+    ));
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Widget count and memory over time, with a warning when the widget count keeps growing.
+fn draw_snapshots(f: &mut Frame, area: Rect, state: &AppState) {
+    let title = match state.snapshot_interval {
+        Some(interval) => t!("memory.leak_hunt_title", secs = interval.as_secs()),
+        None => t!("memory.leak_hunt_stopped").to_string(),
+    };
+    let block = Block::default().title(title).borders(Borders::ALL);
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let Some(latest) = state.snapshots.latest() else {
+        f.render_widget(Paragraph::new(t!("memory.first_snapshot")), inner_area);
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Length(2),
+        ])
+        .split(inner_area);
+
+    f.render_widget(
+        Paragraph::new(t!(
+            "memory.snapshots",
+            count = state.snapshots.widget_counts().len(),
+            secs = latest.taken_at.elapsed().as_secs()
+        ))
+        .style(Style::default().fg(Color::DarkGray)),
+        chunks[0],
+    );
+    // Both charts share the time axis, one snapshot per column
+    f.render_widget(
+        Paragraph::new(t!("memory.widgets", count = latest.widgets))
+            .style(Style::default().fg(Color::Cyan)),
+        chunks[1],
+    );
+    f.render_widget(
+        Sparkline::default()
+            .data(relative(state.snapshots.widget_counts()))
+            .style(Style::default().fg(Color::Cyan)),
+        chunks[2],
+    );
+    f.render_widget(
+        Paragraph::new(t!("memory.memory", bytes = format_bytes(latest.memory)))
+            .style(Style::default().fg(Color::Magenta)),
+        chunks[3],
+    );
+    f.render_widget(
+        Sparkline::default()
+            .data(relative(state.snapshots.memory()))
+            .style(Style::default().fg(Color::Magenta)),
+        chunks[4],
+    );
+
+    let verdict = match state.snapshots.leak_suspects() {
+        Some(suspects) => {
+            let grown: Vec<String> = suspects
+                .iter()
+                .map(|(widget, grown)| format!("{} +{}", widget, grown))
+                .collect();
+            Line::styled(
+                t!("memory.probable_leak", widgets = grown.join(", ")),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )
+        }
+        None => Line::raw(t!("memory.no_leak")),
+    };
+    f.render_widget(Paragraph::new(verdict), chunks[5]);
+}
+
+/// Shifts a series so its minimum sits at the bottom, leaks are small changes on top of
+/// a big baseline.
+fn relative(values: Vec<u64>) -> Vec<u64> {
+    let min = values.iter().copied().min().unwrap_or(0);
+    values.into_iter().map(|value| value - min + 1).collect()
+}
+
+/// A 22 column cell: the value and how much it changed since the previous sample.
+fn with_change(value: u64, previous: Option<u64>) -> Vec<Span<'static>> {
+    let change = previous.map(|previous| value as i64 - previous as i64);
+    let (text, style) = match change {
+        Some(change) if change > 0 => (
+            format!("+{}", format_bytes(change as u64)),
+            Style::default().fg(Color::Red),
+        ),
+        Some(change) if change < 0 => (
+            format!("-{}", format_bytes(change.unsigned_abs())),
+            Style::default().fg(Color::Green),
+        ),
+        _ => (String::new(), Style::default()),
+    };
+    vec![
+        Span::raw(format!(" {:>12}", format_bytes(value))),
+        Span::styled(format!(" {:>9}", text), style),
+    ]
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(width - 3).collect();
+        truncated.push_str("...");
+        truncated
+    }
+}
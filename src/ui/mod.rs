@@ -1,207 +1,812 @@
 pub mod debugger;
 pub mod details;
+pub mod help;
+pub mod highlight;
+pub mod memory;
+pub mod network;
+pub mod palette;
+pub mod performance;
+pub mod profiler;
+pub mod theme;
 pub mod tree;
 
-use crate::app_state::{AppState, Tab};
+use crate::app_state::{AppBarButton, AppState, ConnectionStatus, PauseControl, Tab, ToggleState};
+use crate::keymap::Action;
+use crate::t;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    widgets::{Block, Borders, Clear, Paragraph},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph},
     Frame,
 };
+use std::sync::atomic::{AtomicBool, Ordering};
+use theme::theme;
+
+/// High-contrast rendering for screen readers and limited terminals (`--accessible`): no
+/// glyphs, and cues otherwise carried by color alone get a textual or shape marker.
+static ACCESSIBLE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_accessible(enabled: bool) {
+    ACCESSIBLE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn accessible() -> bool {
+    ACCESSIBLE.load(Ordering::Relaxed)
+}
+
+/// Marker in front of tree rows and log entries that can be expanded.
+pub fn expander(expandable: bool, expanded: bool) -> &'static str {
+    match (expandable, expanded, accessible()) {
+        (false, _, false) => "  ",
+        (false, _, true) => "    ",
+        (true, true, false) => "▼ ",
+        (true, false, false) => "▶ ",
+        (true, true, true) => "[-] ",
+        (true, false, true) => "[+] ",
+    }
+}
+
+/// Style of a selected row: `colored` normally, reversed video in accessible mode.
+pub fn selection_style(colored: Style) -> Style {
+    if accessible() {
+        Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        colored
+    }
+}
+
+/// Rows of a Shift+arrow selection other than the cursor's, underlined in accessible mode.
+pub fn mark_style(colored: Style) -> Style {
+    if accessible() {
+        Style::default().add_modifier(Modifier::UNDERLINED)
+    } else {
+        colored
+    }
+}
+
+/// Highlights the border of the focused pane, doubled in accessible mode.
+pub fn focus_border(block: Block<'_>, focused: bool) -> Block<'_> {
+    if !focused {
+        return block;
+    }
+    let block = block.border_style(Style::default().fg(theme().focus_border));
+    if accessible() {
+        block.border_type(BorderType::Double)
+    } else {
+        block
+    }
+}
+
+/// Rows of the status bar at the bottom of the screen.
+pub const STATUS_BAR_HEIGHT: u16 = 1;
 
 pub fn draw(f: &mut Frame, state: &AppState) {
+    state
+        .log_panel_limit
+        .replace(f.area().height.saturating_sub(3 + STATUS_BAR_HEIGHT));
+    state.logs_area.replace(Rect::default());
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // App Bar
-            Constraint::Min(0),    // Main Content
-            if state.show_logs {
-                Constraint::Length(10)
-            } else {
-                Constraint::Length(0)
-            }, // Logs
+            Constraint::Length(3),                      // App Bar
+            Constraint::Min(0),                         // Main Content
+            Constraint::Length(state.log_panel_rows()), // Logs
+            Constraint::Length(STATUS_BAR_HEIGHT),      // Status Bar
         ])
         .split(f.area());
 
     // App Bar
-    let app_bar_block = Block::default().borders(Borders::ALL).title("Controls");
+    let app_bar_title = match &state.inspected_isolate {
+        Some(isolate) if state.available_isolates.len() > 1 => {
+            t!(
+                "app.controls_isolates",
+                isolate = state.isolate_label(&isolate.id, &isolate.name),
+                previous = state.keymap.key(Action::PreviousIsolate),
+                next = state.keymap.key(Action::NextIsolate),
+                list = state.keymap.key(Action::SelectIsolate)
+            )
+        }
+        Some(isolate) => t!(
+            "app.controls_isolate",
+            isolate = state.isolate_label(&isolate.id, &isolate.name)
+        ),
+        None => t!("app.controls").to_string(),
+    };
+    let app_bar_block = Block::default().borders(Borders::ALL).title(app_bar_title);
     let app_bar_area = chunks[0];
 
     f.render_widget(app_bar_block, app_bar_area);
 
-    let button_titles = [
-        "Inspector (1)",
-        "Debugger (2)",
-        "Hot Reload (r)",
-        "Hot Restart (R)",
-        "Auto (a)",
-        "Refresh (F5)",
-        "Logs (l)",
-        "Quit (q)",
-    ];
-    for (i, title) in button_titles.iter().enumerate() {
-        let button_style = if i == 4 {
-            // Auto Toggle
-            if state.auto_reload {
-                Style::default().fg(Color::Green).bg(Color::Black)
-            } else {
-                Style::default().fg(Color::Red).bg(Color::Black)
+    draw_app_bar_buttons(f, app_bar_area, state);
+
+    let main_area = chunks[1];
+    // Only a tab drawn with a resizable split has borders to drag
+    state.split_borders.borrow_mut().clear();
+
+    if state.logs_only || state.current_tab == Tab::Logs {
+        // `logs` subcommand or the Logs tab: the logs get the whole screen
+        draw_logs(f, main_area, state);
+    } else {
+        match state.current_tab {
+            Tab::Inspector => {
+                let main_chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(state.inspector_split.map(Constraint::Percentage))
+                    .split(main_area);
+                record_split(state, main_area, &main_chunks);
+
+                // Left: Widget Tree (with the filter bar underneath while a filter is active)
+                let show_filter_bar = state.focus == crate::app_state::Focus::Filter
+                    || !state.filter_query.is_empty();
+                let tree_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(1),
+                        Constraint::Min(0),
+                        Constraint::Length(if show_filter_bar { 3 } else { 0 }),
+                    ])
+                    .split(main_chunks[0]);
+
+                draw_breadcrumb(f, tree_chunks[0], state);
+                state.inspector_tree_area.replace(tree_chunks[1]);
+                state
+                    .inspector_tree_height
+                    .replace(tree_chunks[1].height as usize);
+                let mut title = if state.summary_tree {
+                    t!("tree.title").to_string()
+                } else {
+                    t!("tree.title_all").to_string()
+                };
+                if state.filter_visible_ids.is_some() {
+                    title.push_str(&format!(" {}", t!("tree.filtered")));
+                }
+                if state.subtree_focus_saved.is_some() {
+                    title.push_str(&format!(" {}", t!("tree.subtree_focus")));
+                }
+                for name in state.failed_subsystems() {
+                    title.push_str(&format!(" {}", t!("tree.subsystem_failed", name = name)));
+                }
+                if state.is_paused() {
+                    title.push_str(&format!(" {}", t!("tree.app_paused")));
+                }
+                if state.rebuild_tracking {
+                    title.push_str(&format!(" {}", t!("tree.tracking_rebuilds")));
+                }
+                if state.widget_select_mode {
+                    title.push_str(&format!(" {}", t!("tree.select_mode")));
+                }
+                if state.tree_refreshing {
+                    const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+                    let spinner = SPINNER[state.spinner_tick % SPINNER.len()];
+                    title.push_str(&format!(" {}", t!("tree.refreshing", spinner = spinner)));
+                } else if let Some(updated_at) = &state.tree_updated_at {
+                    title.push_str(&format!(" {}", t!("tree.as_of", time = updated_at)));
+                }
+                let rebuild_sparklines = state.rebuild_sparklines();
+                let count = tree::draw(
+                    f,
+                    tree_chunks[1],
+                    state.root_node.as_ref(),
+                    state.tree_rows(),
+                    state.selected_index,
+                    state.marked_tree_rows(),
+                    Some(&state.search_matches),
+                    rebuild_sparklines.as_ref(),
+                    state.tree_scroll_offset,
+                    state.tree_horizontal_scroll,
+                    &title,
+                    state.focus == crate::app_state::Focus::Tree
+                        || state.focus == crate::app_state::Focus::Search
+                        || state.focus == crate::app_state::Focus::Filter,
+                );
+                state.inspector_visible_count.replace(count);
+
+                if show_filter_bar {
+                    draw_filter_bar(f, tree_chunks[2], state);
+                }
+
+                // Right: Details
+                details::draw(f, main_chunks[1], state);
             }
-        } else if i == 0 && state.current_tab == Tab::Inspector {
-            Style::default().fg(Color::Yellow).bg(Color::Black)
-        } else if i == 1 && state.current_tab == Tab::Debugger {
-            Style::default().fg(Color::Yellow).bg(Color::Black)
-        } else if i == 6 {
-            if state.show_logs {
-                Style::default().fg(Color::Green).bg(Color::Black)
-            } else {
-                Style::default().fg(Color::Red).bg(Color::Black)
+            Tab::Debugger => {
+                debugger::draw(f, main_area, state);
             }
-        } else {
-            Style::default().fg(Color::Cyan).bg(Color::Black)
-        };
-
-        let display_title = if i == 4 {
-            if state.auto_reload {
-                "Auto (a): ON"
-            } else {
-                "Auto (a): OFF"
+            Tab::Memory => {
+                memory::draw(f, main_area, state);
             }
-        } else if i == 6 {
-            if state.show_logs {
-                "Logs (l): ON"
-            } else {
-                "Logs (l): OFF"
+            Tab::Profiler => {
+                profiler::draw(f, main_area, state);
             }
-        } else {
-            title
-        };
+            Tab::Performance => {
+                performance::draw(f, main_area, state);
+            }
+            Tab::Network => {
+                network::draw(f, main_area, state);
+            }
+            Tab::Logs => {}
+        }
+    }
 
-        let button = Paragraph::new(display_title)
-            .style(button_style)
-            .alignment(ratatui::layout::Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
-        f.render_widget(
-            button,
-            Rect {
-                x: app_bar_area.x + (i as u16 * 20),
-                y: app_bar_area.y,
-                width: 20,
-                height: 3,
-            },
-        );
+    // Bottom: Logs
+    if state.log_panel_rows() > 0 {
+        draw_logs(f, chunks[2], state);
     }
 
-    let main_area = chunks[1];
+    draw_status_bar(f, chunks[3], state);
 
-    match state.current_tab {
-        Tab::Inspector => {
-            let main_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(75), Constraint::Percentage(25)])
-                .split(main_area);
+    // Isolate Selection Popup
+    if state.show_isolate_selection {
+        draw_isolate_selection_popup(f, state);
+    }
 
-            // Left: Widget Tree
-            state.inspector_tree_area.replace(main_chunks[0]);
-            state
-                .inspector_tree_height
-                .replace(main_chunks[0].height as usize);
-            let count = tree::draw(
-                f,
-                main_chunks[0],
-                state.root_node.as_ref(),
-                state.selected_index,
-                &state.expanded_ids,
-                state.tree_scroll_offset,
-                state.tree_horizontal_scroll,
-                "Widget Tree",
-                state.focus == crate::app_state::Focus::Tree
-                    || state.focus == crate::app_state::Focus::Search,
-            );
-            state.inspector_visible_count.replace(count);
+    if state.show_app_info {
+        draw_app_info_popup(f, state);
+    }
 
-            // Right: Details
-            details::draw(f, main_chunks[1], state);
-        }
-        Tab::Debugger => {
-            debugger::draw(f, main_area, state);
-        }
+    if state.show_isolate_metrics {
+        draw_isolate_metrics_popup(f, state);
     }
 
-    // Bottom: Logs
-    if state.show_logs {
-        let border_style = if state.focus == crate::app_state::Focus::Logs {
-            ratatui::style::Style::default().fg(ratatui::style::Color::Yellow)
-        } else {
-            ratatui::style::Style::default()
-        };
+    if state.show_toggles {
+        draw_toggles_popup(f, state);
+    }
 
-        let log_block = ratatui::widgets::Block::default()
-            .title("Logs")
-            .borders(ratatui::widgets::Borders::ALL)
-            .border_style(border_style);
-        let log_area = chunks[2];
-        let log_height = log_area.height as usize;
+    if state.show_command_palette {
+        draw_command_palette(f, state);
+    }
 
-        // Calculate scroll offset
-        let scroll_offset = if state.log_auto_scroll {
-            state
-                .logs
-                .len()
-                .saturating_sub(log_height.saturating_sub(2)) // -2 for borders
-        } else {
-            state.log_scroll_state
-        };
+    if state.eval_result.is_some() {
+        draw_eval_result_popup(f, state);
+    }
 
-        // Ensure scroll_offset is valid
-        let scroll_offset = scroll_offset.min(state.logs.len().saturating_sub(1));
+    if state.show_screenshot {
+        draw_screenshot_popup(f, state);
+    }
 
-        let logs: Vec<ratatui::widgets::ListItem> = state
-            .logs
-            .iter()
-            .skip(scroll_offset)
-            .take(log_height.saturating_sub(2))
-            .map(|s| ratatui::widgets::ListItem::new(ratatui::text::Line::from(s.as_str())))
-            .collect();
+    if state.http_replay.is_some() {
+        draw_http_replay_popup(f, state);
+    }
 
-        let logs_list = ratatui::widgets::List::new(logs).block(log_block);
-        f.render_widget(logs_list, log_area);
+    if state.show_rebuild_stats {
+        draw_rebuild_stats_popup(f, state);
+    }
+    if state.show_layout_warnings {
+        draw_layout_warnings_popup(f, state);
+    }
+    if state.show_reload_scope {
+        draw_reload_scope_popup(f, state);
     }
 
-    // Isolate Selection Popup
-    if state.show_isolate_selection {
-        draw_isolate_selection_popup(f, state);
+    if state.show_focus_tree {
+        draw_focus_tree_popup(f, state);
+    }
+
+    if state.show_app_bar_menu {
+        draw_app_bar_menu(f, state);
+    }
+
+    if state.show_help {
+        help::draw(f, centered_rect(70, 80, f.area()), state);
     }
 
     // Draw Search Input if active
     if state.focus == crate::app_state::Focus::Search {
         let area = centered_rect(60, 20, f.area());
         let block = Block::default()
-            .title("Search")
+            .title(t!("search.title"))
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Yellow));
 
-        let text = format!(
-            "Query: {}\nMatches: {}/{}\n\n(Enter: Next, Shift+Enter: Prev, Esc: Cancel)",
-            state.search_query,
-            if state.search_results.is_empty() {
+        let text = t!(
+            "search.prompt",
+            query = state.search_query,
+            current = if state.search_results.is_empty() {
                 0
             } else {
                 state.current_match_index + 1
             },
-            state.search_results.len()
+            total = state.search_results.len()
+        );
+        let paragraph = Paragraph::new(text).block(block);
+        f.render_widget(Clear, area); // Clear background
+        f.render_widget(paragraph, area);
+    }
+
+    // Logpoint message input
+    if state.focus == crate::app_state::Focus::LogpointInput {
+        let area = centered_rect(60, 20, f.area());
+        let block = Block::default()
+            .title(t!("logpoint.title"))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta));
+
+        let text = t!("logpoint.prompt", message = state.logpoint_input);
+        let paragraph = Paragraph::new(text).block(block);
+        f.render_widget(Clear, area); // Clear background
+        f.render_widget(paragraph, area);
+    }
+
+    // Isolate label input
+    if state.focus == crate::app_state::Focus::IsolateLabelInput {
+        let area = centered_rect(50, 20, f.area());
+        let block = Block::default()
+            .title(t!("isolate_label.title"))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let name = state
+            .available_isolates
+            .get(state.selected_isolate_index)
+            .map_or("", |isolate| isolate.name.as_str());
+        let text = t!(
+            "isolate_label.prompt",
+            name = name,
+            label = state.isolate_label_input
         );
         let paragraph = Paragraph::new(text).block(block);
         f.render_widget(Clear, area); // Clear background
         f.render_widget(paragraph, area);
     }
+
+    // Device input form
+    if state.focus == crate::app_state::Focus::DeviceInput {
+        let area = centered_rect(60, 20, f.area());
+        let block = Block::default()
+            .title(t!("device_input.title"))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let text = t!("device_input.prompt", input = state.device_input);
+        let paragraph = Paragraph::new(text).block(block);
+        f.render_widget(Clear, area); // Clear background
+        f.render_widget(paragraph, area);
+    }
+
+    // Text search of the logs or the source view
+    if state.focus == crate::app_state::Focus::TextSearch {
+        let area = centered_rect(50, 20, f.area());
+        let block = Block::default()
+            .title(t!("text_search.title"))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let text = t!("text_search.prompt", query = state.text_search_input);
+        let paragraph = Paragraph::new(text).block(block);
+        f.render_widget(Clear, area); // Clear background
+        f.render_widget(paragraph, area);
+    }
+
+    // Expand-to-depth input
+    if state.focus == crate::app_state::Focus::DepthInput {
+        let area = centered_rect(40, 20, f.area());
+        let block = Block::default()
+            .title(t!("depth.title"))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let text = t!("depth.prompt", depth = state.depth_input);
+        let paragraph = Paragraph::new(text).block(block);
+        f.render_widget(Clear, area); // Clear background
+        f.render_widget(paragraph, area);
+    }
+
+    // Breakpoint condition input
+    if state.focus == crate::app_state::Focus::ConditionInput {
+        let area = centered_rect(60, 20, f.area());
+        let block = Block::default()
+            .title(t!("condition.title"))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let text = t!("condition.prompt", condition = state.condition_input);
+        let paragraph = Paragraph::new(text).block(block);
+        f.render_widget(Clear, area); // Clear background
+        f.render_widget(paragraph, area);
+    }
+
+    palette::apply(f.buffer_mut());
+}
+
+/// Remembers where the panes of a resizable split were drawn, for dragging their borders.
+fn record_split(state: &AppState, area: Rect, panes: &[Rect]) {
+    state.split_area.replace(area);
+    state
+        .split_borders
+        .replace(panes.iter().skip(1).map(|pane| pane.x).collect());
+}
+
+/// One line of what's otherwise only found in the logs: the connection, the device, the
+/// inspected isolate, whether it is paused, and how the last reload went.
+fn draw_status_bar(f: &mut Frame, area: Rect, state: &AppState) {
+    let separator = || Span::styled(" │ ", Style::default().fg(Color::DarkGray));
+    let connection_style = match state.connection_status {
+        ConnectionStatus::Connected => Style::default().fg(Color::Green),
+        ConnectionStatus::Connecting | ConnectionStatus::Reconnecting { .. } => {
+            Style::default().fg(Color::Yellow)
+        }
+        ConnectionStatus::Disconnected { .. } => Style::default().fg(Color::Red),
+    };
+    let isolate = match &state.inspected_isolate {
+        Some(isolate) => t!(
+            "status.isolate",
+            isolate = state.isolate_label(&isolate.id, &isolate.name)
+        ),
+        None => t!("status.no_isolate").to_string(),
+    };
+    let (debug_state, debug_style) = match &state.debug_state {
+        crate::app_state::DebugState::Paused { reason, .. } => (
+            t!("status.paused", reason = reason),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ),
+        crate::app_state::DebugState::Running => {
+            (t!("status.running").to_string(), Style::default())
+        }
+    };
+    let (auto_reload, auto_reload_style) = if state.auto_reload {
+        (
+            t!("status.auto_reload_on"),
+            Style::default().fg(Color::Green),
+        )
+    } else {
+        (
+            t!("status.auto_reload_off"),
+            Style::default().fg(Color::Red),
+        )
+    };
+    let mut last_reload = match &state.last_reload {
+        Some((time, true)) => t!("status.restarted", time = time),
+        Some((time, false)) => t!("status.reloaded", time = time),
+        None => t!("status.not_reloaded").to_string(),
+    };
+    // A reload that changed nothing is what needs pointing out
+    let mut last_reload_style = Style::default();
+    if let Some(scope) = &state.reload_scope {
+        let key = state.keymap.key(Action::ReloadScope);
+        match scope.library_count() {
+            0 => {
+                last_reload.push_str(&t!("status.reload_unchanged", key = key));
+                last_reload_style = Style::default().fg(theme().log_warn);
+            }
+            count => last_reload.push_str(&t!(
+                "status.reload_scope",
+                count = count,
+                packages = scope.packages.len(),
+                key = key
+            )),
+        }
+    }
+
+    let mut line = Line::from(vec![
+        Span::styled(
+            format!(" {}", state.connection_status.label()),
+            connection_style,
+        ),
+        separator(),
+        Span::raw(
+            state
+                .app_info
+                .device
+                .as_deref()
+                .unwrap_or(t!("status.no_device")),
+        ),
+        separator(),
+        Span::raw(isolate),
+        separator(),
+        Span::styled(debug_state, debug_style),
+        separator(),
+        Span::styled(auto_reload, auto_reload_style),
+        separator(),
+        Span::styled(last_reload, last_reload_style),
+    ]);
+    // Shown only once there is something to look at, it points at the popup
+    if !state.layout_warnings.is_empty() {
+        line.push_span(separator());
+        line.push_span(Span::styled(
+            t!(
+                "status.layout_warnings",
+                count = state.layout_warnings.len(),
+                key = state.keymap.key(Action::LayoutWarnings)
+            ),
+            Style::default().fg(theme().log_warn),
+        ));
+    }
+    f.render_widget(
+        Paragraph::new(line).style(Style::default().bg(Color::Black)),
+        area,
+    );
+}
+
+/// Text of an app bar button, with the key it is bound to.
+fn app_bar_label(state: &AppState, button: AppBarButton) -> String {
+    // Labels name the keys as remapped in the config
+    let key = |action| state.keymap.key(action);
+    let label = match button {
+        AppBarButton::Inspector => t!("app.inspector", key = key(Action::InspectorTab)),
+        AppBarButton::Debugger => t!("app.debugger", key = key(Action::DebuggerTab)),
+        AppBarButton::HotReload => t!("app.hot_reload", key = key(Action::HotReload)),
+        AppBarButton::HotRestart => t!("app.hot_restart", key = key(Action::HotRestart)),
+        AppBarButton::AutoReload if state.auto_reload => {
+            t!("app.auto_on", key = key(Action::ToggleAutoReload))
+        }
+        AppBarButton::AutoReload => t!("app.auto_off", key = key(Action::ToggleAutoReload)),
+        AppBarButton::Refresh => t!("app.refresh", key = key(Action::Refresh)),
+        AppBarButton::Logs if state.show_logs => t!("app.logs_on", key = key(Action::ToggleLogs)),
+        AppBarButton::Logs => t!("app.logs_off", key = key(Action::ToggleLogs)),
+        AppBarButton::Pause => match state.pause_control() {
+            PauseControl::Start => t!("app.start", key = key(Action::TogglePause)),
+            PauseControl::Resume => t!("app.resume", key = key(Action::TogglePause)),
+            PauseControl::Pause => t!("app.pause", key = key(Action::TogglePause)),
+        },
+        AppBarButton::Toggles => t!("app.toggles", key = key(Action::Toggles)),
+        AppBarButton::Quit => t!("app.quit", key = key(Action::Quit)),
+        AppBarButton::More => t!("app.more").to_string(),
+    };
+    let is_active_tab = matches!(
+        (button, state.current_tab),
+        (AppBarButton::Inspector, Tab::Inspector) | (AppBarButton::Debugger, Tab::Debugger)
+    );
+    if accessible() && is_active_tab {
+        format!("[{}]", label)
+    } else {
+        label.to_string()
+    }
+}
+
+fn app_bar_style(state: &AppState, button: AppBarButton) -> Style {
+    match button {
+        AppBarButton::AutoReload | AppBarButton::Logs => {
+            let on = if button == AppBarButton::AutoReload {
+                state.auto_reload
+            } else {
+                state.show_logs
+            };
+            Style::default()
+                .fg(if on { Color::Green } else { Color::Red })
+                .bg(Color::Black)
+        }
+        AppBarButton::Inspector if state.current_tab == Tab::Inspector => {
+            Style::default().fg(Color::Yellow).bg(Color::Black)
+        }
+        AppBarButton::Debugger if state.current_tab == Tab::Debugger => {
+            Style::default().fg(Color::Yellow).bg(Color::Black)
+        }
+        AppBarButton::Toggles if state.show_toggles => {
+            Style::default().fg(Color::Yellow).bg(Color::Black)
+        }
+        AppBarButton::More if state.show_app_bar_menu => {
+            Style::default().fg(Color::Yellow).bg(Color::Black)
+        }
+        AppBarButton::Pause if state.paused_at_start() => Style::default()
+            .fg(Color::Black)
+            .bg(Color::Green)
+            .add_modifier(Modifier::BOLD),
+        AppBarButton::Pause if state.is_paused() => {
+            Style::default().fg(Color::Black).bg(Color::Yellow)
+        }
+        _ => Style::default().fg(Color::Cyan).bg(Color::Black),
+    }
+}
+
+/// Lays the buttons out left to right, each as wide as its label. When they don't all fit,
+/// the ones that don't go into a menu behind a More button in the last slot. Where each
+/// button ended up is recorded for mouse hit-testing.
+fn draw_app_bar_buttons(f: &mut Frame, area: Rect, state: &AppState) {
+    let width = |button| {
+        unicode_width::UnicodeWidthStr::width(app_bar_label(state, button).as_str()) as u16 + 4
+    };
+    let mut buttons = AppBarButton::ALL.to_vec();
+    if state.paused_at_start() {
+        // The app waits for it, it mustn't end up in the menu
+        buttons.retain(|button| *button != AppBarButton::Pause);
+        buttons.insert(0, AppBarButton::Pause);
+    }
+    let total: u16 = buttons.iter().map(|button| width(*button)).sum();
+    let mut shown = Vec::new();
+    let mut overflow = Vec::new();
+    if total <= area.width {
+        shown.extend(buttons);
+    } else {
+        // Keep the order: once one button overflows, so do all after it
+        let mut left = area.width.saturating_sub(width(AppBarButton::More));
+        for button in buttons {
+            if overflow.is_empty() && width(button) <= left {
+                left -= width(button);
+                shown.push(button);
+            } else {
+                overflow.push(button);
+            }
+        }
+        shown.push(AppBarButton::More);
+    }
+
+    let mut areas = Vec::new();
+    let mut x = area.x;
+    for button in shown {
+        let button_area = Rect {
+            x,
+            y: area.y,
+            width: width(button).min(area.right().saturating_sub(x)),
+            height: area.height.min(3),
+        };
+        if button_area.width == 0 {
+            break;
+        }
+        let paragraph = Paragraph::new(app_bar_label(state, button))
+            .style(app_bar_style(state, button))
+            .alignment(ratatui::layout::Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(paragraph, button_area);
+        areas.push((button_area, button));
+        x += button_area.width;
+    }
+    state.app_bar_areas.replace(areas);
+    state.app_bar_overflow.replace(overflow);
+}
+
+/// The buttons that didn't fit the app bar, dropped down under its More button.
+fn draw_app_bar_menu(f: &mut Frame, state: &AppState) {
+    let anchor = state
+        .app_bar_areas
+        .borrow()
+        .iter()
+        .find(|(_, button)| *button == AppBarButton::More)
+        .map(|(area, _)| *area);
+    let Some(anchor) = anchor else {
+        // Everything fits again
+        state.app_bar_menu_areas.replace(Vec::new());
+        return;
+    };
+    let overflow = state.app_bar_overflow.borrow().clone();
+    let labels: Vec<String> = overflow
+        .iter()
+        .map(|button| app_bar_label(state, *button))
+        .collect();
+    let screen = f.area();
+    let width = labels
+        .iter()
+        .map(|label| unicode_width::UnicodeWidthStr::width(label.as_str()) as u16 + 4)
+        .max()
+        .unwrap_or(0)
+        .min(screen.width);
+    let area = Rect {
+        x: anchor.x.min(screen.right().saturating_sub(width)),
+        y: anchor.bottom(),
+        width,
+        height: (labels.len() as u16 + 2).min(screen.bottom().saturating_sub(anchor.bottom())),
+    };
+
+    let items: Vec<ListItem> = overflow
+        .iter()
+        .zip(labels)
+        .map(|(button, label)| ListItem::new(label).style(app_bar_style(state, *button)))
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL))
+        .highlight_style(selection_style(theme().selection));
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.app_bar_menu_selected_index));
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut list_state);
+
+    let rows = area.height.saturating_sub(2) as usize;
+    let areas = overflow
+        .into_iter()
+        .take(rows)
+        .enumerate()
+        .map(|(i, button)| {
+            let row = Rect {
+                x: area.x,
+                y: area.y + 1 + i as u16,
+                width: area.width,
+                height: 1,
+            };
+            (row, button)
+        })
+        .collect();
+    state.app_bar_menu_areas.replace(areas);
+}
+
+/// Ancestors of the selected widget on one line, `Home > Scaffold > Column`. When the chain
+/// is too long the outermost ancestors give way to `... > `.
+fn draw_breadcrumb(f: &mut Frame, area: Rect, state: &AppState) {
+    const SEPARATOR: &str = " > ";
+    const ELLIPSIS: &str = "... > ";
+    let crumbs = state.selected_breadcrumb();
+    let mut areas = Vec::new();
+
+    // Keep as many of the innermost segments as fit
+    let width = area.width as usize;
+    let mut used = 0;
+    let mut first = crumbs.len();
+    for (i, (_, label)) in crumbs.iter().enumerate().rev() {
+        let needed = label.chars().count()
+            + if i + 1 < crumbs.len() {
+                SEPARATOR.len()
+            } else {
+                0
+            };
+        let reserve = if i > 0 { ELLIPSIS.len() } else { 0 };
+        if used + needed + reserve > width && first < crumbs.len() {
+            break;
+        }
+        used += needed;
+        first = i;
+    }
+
+    let mut spans = Vec::new();
+    let mut x = area.x;
+    if first > 0 {
+        spans.push(Span::styled(ELLIPSIS, Style::default().fg(Color::DarkGray)));
+        x += ELLIPSIS.len() as u16;
+    }
+    for (i, (id, label)) in crumbs.iter().enumerate().skip(first) {
+        if i > first {
+            spans.push(Span::styled(
+                SEPARATOR,
+                Style::default().fg(Color::DarkGray),
+            ));
+            x += SEPARATOR.len() as u16;
+        }
+        let style = if i + 1 == crumbs.len() {
+            selection_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Style::default().fg(Color::Cyan)
+        };
+        let label_width = label.chars().count() as u16;
+        let right = area.x + area.width;
+        if x < right {
+            areas.push((
+                Rect::new(x, area.y, label_width.min(right - x), 1),
+                id.clone(),
+            ));
+        }
+        spans.push(Span::styled(label.clone(), style));
+        x += label_width;
+    }
+
+    state.breadcrumb_areas.replace(areas);
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn draw_filter_bar(f: &mut Frame, area: Rect, state: &AppState) {
+    let is_focused = state.focus == crate::app_state::Focus::Filter;
+    let text = if state.filter_visible_ids.is_some() {
+        t!(
+            "common.query_matches",
+            query = state.filter_query,
+            count = state.filter_match_count
+        )
+    } else {
+        state.filter_query.clone()
+    };
+
+    let block = focus_border(
+        Block::default()
+            .title(if is_focused {
+                t!("filter.title_editing")
+            } else {
+                t!("filter.title")
+            })
+            .borders(Borders::ALL),
+        is_focused,
+    );
+
+    f.render_widget(Paragraph::new(text).block(block), area);
 }
 
 fn draw_isolate_selection_popup(f: &mut Frame, state: &AppState) {
     let area = centered_rect(60, 40, f.area());
     let block = ratatui::widgets::Block::default()
-        .title("Select Isolate")
+        .title(if state.inspected_isolate.is_some() {
+            t!("isolates.select_keep")
+        } else {
+            t!("isolates.select_first")
+        })
         .borders(ratatui::widgets::Borders::ALL)
         .style(ratatui::style::Style::default().bg(ratatui::style::Color::DarkGray));
 
@@ -212,7 +817,21 @@ fn draw_isolate_selection_popup(f: &mut Frame, state: &AppState) {
         .available_isolates
         .iter()
         .map(|iso| {
-            let content = format!("{} ({})", iso.name, iso.id);
+            let mut content = match state
+                .isolate_labels
+                .get(&state.isolate_label_key(&iso.id, &iso.name))
+            {
+                Some(label) => format!("{} - {} ({})", label, iso.name, iso.id),
+                None => format!("{} ({})", iso.name, iso.id),
+            };
+            if state.inspector_isolates.contains(&iso.id) {
+                content.push_str(&format!(" {}", t!("isolates.flutter_ui")));
+            }
+            if let Some((crate::app_state::DebugState::Paused { reason, .. }, _)) =
+                state.paused_isolates.get(&iso.id)
+            {
+                content.push_str(&format!(" {}", t!("isolates.paused", reason = reason)));
+            }
             ratatui::widgets::ListItem::new(content)
         })
         .collect();
@@ -233,6 +852,754 @@ fn draw_isolate_selection_popup(f: &mut Frame, state: &AppState) {
     f.render_stateful_widget(list, inner_area, &mut list_state);
 }
 
+fn draw_toggles_popup(f: &mut Frame, state: &AppState) {
+    let area = centered_rect(50, 60, f.area());
+    let block = ratatui::widgets::Block::default()
+        .title(t!("toggles.title"))
+        .borders(ratatui::widgets::Borders::ALL)
+        .style(ratatui::style::Style::default().bg(ratatui::style::Color::DarkGray));
+
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(block.clone(), area);
+
+    let items: Vec<ratatui::widgets::ListItem> = crate::app_state::toggle_rows()
+        .zip(crate::app_state::TOGGLE_KEYS)
+        .map(|(row, key)| {
+            // Unknown until queried; profile builds don't register these extensions
+            let content = match state.toggle_states.get(row.method) {
+                Some(ToggleState::Enabled(true)) => format!("{}  [x] {}", key, row.label),
+                Some(ToggleState::Enabled(false)) => format!("{}  [ ] {}", key, row.label),
+                Some(ToggleState::Value(value)) => {
+                    format!("{}  [~] {}: {}", key, row.label, value)
+                }
+                None => format!("{}  [?] {} {}", key, row.label, t!("toggles.unavailable")),
+            };
+            ratatui::widgets::ListItem::new(content)
+        })
+        .collect();
+
+    let list = ratatui::widgets::List::new(items)
+        .block(ratatui::widgets::Block::default().borders(ratatui::widgets::Borders::NONE))
+        .highlight_style(
+            ratatui::style::Style::default()
+                .fg(ratatui::style::Color::Black)
+                .bg(ratatui::style::Color::White),
+        )
+        .highlight_symbol(">> ");
+
+    let mut list_state = ratatui::widgets::ListState::default();
+    list_state.select(Some(state.toggles_selected_index));
+
+    let inner_area = block.inner(area);
+    f.render_stateful_widget(list, inner_area, &mut list_state);
+}
+
+fn draw_command_palette(f: &mut Frame, state: &AppState) {
+    let area = centered_rect(60, 40, f.area());
+    let block = Block::default()
+        .title(t!("snippets.title"))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::DarkGray));
+    f.render_widget(Clear, area);
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(inner_area);
+    f.render_widget(
+        Paragraph::new(format!(": {}", state.palette_query))
+            .style(Style::default().fg(Color::Yellow)),
+        chunks[0],
+    );
+
+    if state.snippets.is_empty() {
+        f.render_widget(
+            Paragraph::new(t!("snippets.none")).wrap(ratatui::widgets::Wrap { trim: false }),
+            chunks[1],
+        );
+        return;
+    }
+
+    let items: Vec<ratatui::widgets::ListItem> = state
+        .palette_snippets()
+        .iter()
+        .map(|snippet| {
+            ratatui::widgets::ListItem::new(Line::from(vec![
+                Span::raw(snippet.name.clone()),
+                Span::styled(
+                    format!("  {}", snippet.expression),
+                    Style::default().fg(Color::Gray),
+                ),
+            ]))
+        })
+        .collect();
+    let list = ratatui::widgets::List::new(items)
+        .highlight_style(selection_style(theme().selection))
+        .highlight_symbol(">> ");
+    let mut list_state = ratatui::widgets::ListState::default();
+    list_state.select(Some(state.palette_selected_index));
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+fn draw_eval_result_popup(f: &mut Frame, state: &AppState) {
+    let Some(result) = &state.eval_result else {
+        return;
+    };
+    let area = centered_rect(80, 60, f.area());
+    let block = Block::default()
+        .title(t!("eval.title", name = result.name))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::DarkGray));
+
+    let mut lines = vec![
+        Line::styled(result.expression.clone(), Style::default().fg(Color::Cyan)),
+        Line::raw(""),
+    ];
+    match &result.output {
+        None => lines.push(Line::raw(t!("eval.evaluating"))),
+        Some(Ok(output)) => lines.extend(output.lines().map(|line| Line::raw(line.to_string()))),
+        Some(Err(error)) => lines.extend(
+            error
+                .lines()
+                .map(|line| Line::styled(line.to_string(), Style::default().fg(Color::Red))),
+        ),
+    }
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .scroll((state.eval_result_scroll.min(u16::MAX as usize) as u16, 0)),
+        area,
+    );
+}
+
+/// The screenshot in half blocks: every cell shows two pixels, the upper one as the
+/// foreground of `▀` and the lower one as its background.
+fn draw_screenshot_popup(f: &mut Frame, state: &AppState) {
+    let area = centered_rect(80, 80, f.area());
+    let title = match &state.screenshot {
+        Some(Ok(screenshot)) => t!(
+            "screenshot.title",
+            widget = screenshot.widget,
+            width = screenshot.width,
+            height = screenshot.height
+        ),
+        _ => t!("screenshot.title_empty").to_string(),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+    f.render_widget(Clear, area);
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let screenshot = match &state.screenshot {
+        None => {
+            f.render_widget(Paragraph::new(t!("screenshot.taking")), inner_area);
+            return;
+        }
+        Some(Err(error)) => {
+            f.render_widget(
+                Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red)),
+                inner_area,
+            );
+            return;
+        }
+        Some(Ok(screenshot)) if screenshot.width == 0 || screenshot.height == 0 => return,
+        Some(Ok(screenshot)) => screenshot,
+    };
+
+    // Fit the image keeping its aspect ratio, a cell being one pixel wide and two high
+    let scale = (inner_area.width as f64 / screenshot.width as f64)
+        .min(inner_area.height as f64 * 2.0 / screenshot.height as f64);
+    let columns = ((screenshot.width as f64 * scale) as u16).max(1);
+    let rows = ((screenshot.height as f64 * scale / 2.0) as u16).max(1);
+    let x0 = inner_area.x + (inner_area.width - columns.min(inner_area.width)) / 2;
+    let y0 = inner_area.y + (inner_area.height - rows.min(inner_area.height)) / 2;
+
+    let sample = |column: u16, half_row: u16| {
+        let x = ((column as f64 + 0.5) / scale) as u32;
+        let y = ((half_row as f64 + 0.5) / scale) as u32;
+        let (r, g, b) = screenshot.pixel(x.min(screenshot.width - 1), y.min(screenshot.height - 1));
+        Color::Rgb(r, g, b)
+    };
+    let buffer = f.buffer_mut();
+    for row in 0..rows.min(inner_area.height) {
+        for column in 0..columns.min(inner_area.width) {
+            if let Some(cell) = buffer.cell_mut((x0 + column, y0 + row)) {
+                cell.set_symbol("▀")
+                    .set_fg(sample(column, row * 2))
+                    .set_bg(sample(column, row * 2 + 1));
+            }
+        }
+    }
+}
+
+fn draw_http_replay_popup(f: &mut Frame, state: &AppState) {
+    let Some(replay) = &state.http_replay else {
+        return;
+    };
+    let area = centered_rect(80, 70, f.area());
+    let block = Block::default()
+        .title(t!("replay.title", uri = replay.uri))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::Black));
+
+    let lines: Vec<Line> = match &replay.report {
+        None => vec![Line::raw(t!("replay.replaying"))],
+        Some(Err(error)) => vec![Line::styled(error.clone(), Style::default().fg(Color::Red))],
+        Some(Ok(report)) => report
+            .iter()
+            .map(|line| match line {
+                crate::network::DiffLine::Heading(text) => Line::styled(
+                    text.clone(),
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                crate::network::DiffLine::Same(text) => Line::raw(format!("  {}", text)),
+                crate::network::DiffLine::Removed(text) => {
+                    Line::styled(format!("- {}", text), Style::default().fg(Color::Red))
+                }
+                crate::network::DiffLine::Added(text) => {
+                    Line::styled(format!("+ {}", text), Style::default().fg(Color::Green))
+                }
+            })
+            .collect(),
+    };
+
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((state.http_replay_scroll.min(u16::MAX as usize) as u16, 0)),
+        area,
+    );
+}
+
+fn draw_focus_tree_popup(f: &mut Frame, state: &AppState) {
+    let area = centered_rect(80, 70, f.area());
+    let block = Block::default()
+        .title(t!("focus_tree.title"))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::DarkGray));
+
+    let Some(dump) = &state.focus_tree else {
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(t!("common.fetching")).block(block), area);
+        return;
+    };
+
+    // The path to the focused node is what explains where key events go
+    let lines: Vec<ratatui::text::Line> = dump
+        .lines()
+        .map(|line| {
+            let style = if line.contains("PRIMARY FOCUS") {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+            } else if line.contains("IN FOCUS PATH") {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ratatui::text::Line::styled(line.to_string(), style)
+        })
+        .collect();
+    let scroll = state
+        .focus_tree_scroll
+        .min(lines.len().saturating_sub(1))
+        .min(u16::MAX as usize) as u16;
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), area);
+}
+
+fn draw_reload_scope_popup(f: &mut Frame, state: &AppState) {
+    let area = centered_rect(70, 60, f.area());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::DarkGray));
+
+    let Some(scope) = &state.reload_scope else {
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(t!("reload_scope.none"))
+                .wrap(ratatui::widgets::Wrap { trim: false })
+                .block(block.title(t!("reload_scope.title_empty"))),
+            area,
+        );
+        return;
+    };
+    let block = block.title(t!(
+        "reload_scope.title",
+        time = scope.time,
+        count = scope.library_count()
+    ));
+    if scope.packages.is_empty() {
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(t!("reload_scope.unchanged"))
+                .wrap(ratatui::widgets::Wrap { trim: false })
+                .style(Style::default().fg(theme().log_warn))
+                .block(block),
+            area,
+        );
+        return;
+    }
+
+    let mut lines = Vec::new();
+    for (package, uris) in &scope.packages {
+        lines.push(Line::styled(
+            format!("{} ({})", package, uris.len()),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ));
+        let prefix = format!("package:{}/", package);
+        for uri in uris {
+            lines.push(Line::raw(format!(
+                "  {}",
+                uri.strip_prefix(&prefix).unwrap_or(uri)
+            )));
+        }
+    }
+    let scroll = state
+        .reload_scope_scroll
+        .min(lines.len().saturating_sub(1))
+        .min(u16::MAX as usize) as u16;
+
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), area);
+}
+
+fn draw_rebuild_stats_popup(f: &mut Frame, state: &AppState) {
+    let area = centered_rect(70, 60, f.area());
+    let block = Block::default()
+        .title(t!(
+            "rebuilds.title",
+            sort = state.rebuild_stats_sort.label()
+        ))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::DarkGray));
+
+    f.render_widget(Clear, area);
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let stats = state.rebuild_stats();
+    if stats.is_empty() {
+        let text = if state.rebuild_tracking {
+            t!("rebuilds.none_yet")
+        } else {
+            t!("rebuilds.off")
+        };
+        f.render_widget(Paragraph::new(text), inner_area);
+        return;
+    }
+
+    let items: Vec<ratatui::widgets::ListItem> = stats
+        .iter()
+        .map(|stat| {
+            // file:///.../lib/home.dart:12:5 -> home.dart:12:5
+            let location = stat.key.rsplit('/').next().unwrap_or(&stat.key);
+            ratatui::widgets::ListItem::new(format!(
+                "{:>8} {:>6} {:>8}  {:<24} {}",
+                stat.total,
+                stat.recent,
+                stat.paints,
+                stat.widget.as_deref().unwrap_or("-"),
+                location
+            ))
+        })
+        .collect();
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner_area);
+    f.render_widget(
+        Paragraph::new(format!(
+            "   {:>8} {:>6} {:>8}  {:<24} {}",
+            t!("rebuilds.total"),
+            t!("rebuilds.recent"),
+            t!("rebuilds.paints"),
+            t!("rebuilds.widget"),
+            t!("rebuilds.location")
+        ))
+        .style(Style::default().fg(Color::Cyan)),
+        chunks[0],
+    );
+
+    let list = ratatui::widgets::List::new(items)
+        .highlight_style(selection_style(theme().selection))
+        .highlight_symbol(">> ");
+    let mut list_state = ratatui::widgets::ListState::default();
+    list_state.select(Some(
+        state
+            .rebuild_stats_selected_index
+            .min(stats.len().saturating_sub(1)),
+    ));
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+fn draw_layout_warnings_popup(f: &mut Frame, state: &AppState) {
+    let area = centered_rect(70, 60, f.area());
+    let block = Block::default()
+        .title(t!(
+            "layout_warnings.title",
+            count = state.layout_warnings.len()
+        ))
+        .borders(Borders::ALL)
+        .style(Style::default().bg(Color::DarkGray));
+
+    f.render_widget(Clear, area);
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    if state.layout_warnings.is_empty() {
+        f.render_widget(
+            Paragraph::new(t!("layout_warnings.none")).wrap(ratatui::widgets::Wrap { trim: false }),
+            inner_area,
+        );
+        return;
+    }
+
+    let items: Vec<ratatui::widgets::ListItem> = state
+        .layout_warnings
+        .iter()
+        .map(|warning| {
+            ratatui::widgets::ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<24} ", warning.widget),
+                    Style::default().fg(theme().log_warn),
+                ),
+                Span::raw(warning.message.as_str()),
+            ]))
+        })
+        .collect();
+    let list = ratatui::widgets::List::new(items)
+        .highlight_style(selection_style(theme().selection))
+        .highlight_symbol(">> ");
+    let mut list_state = ratatui::widgets::ListState::default();
+    list_state.select(Some(
+        state
+            .layout_warnings_selected_index
+            .min(state.layout_warnings.len().saturating_sub(1)),
+    ));
+    f.render_stateful_widget(list, inner_area, &mut list_state);
+}
+
+fn draw_logs(f: &mut Frame, log_area: Rect, state: &AppState) {
+    state.logs_area.replace(log_area);
+    let focused = state.focus == crate::app_state::Focus::Logs;
+    let log_block = focus_border(
+        ratatui::widgets::Block::default()
+            .title(
+                if focused && (state.logs_only || state.current_tab == Tab::Logs) {
+                    t!("logs.title_full_screen")
+                } else if focused {
+                    t!("logs.title_focused")
+                } else {
+                    t!("logs.title")
+                },
+            )
+            .title(log_filters_title(state))
+            .borders(ratatui::widgets::Borders::ALL),
+        focused,
+    );
+    state.log_view_height.replace(log_area.height as usize);
+    let log_height = (log_area.height as usize).saturating_sub(2); // -2 for borders
+
+    // Calculate scroll offset: when tailing, start as late as still fits everything below
+    let scroll_offset = if state.log_auto_scroll {
+        let mut offset = state.log_view.len();
+        let mut used = 0;
+        while offset > 0 && used + state.log_entry_height(offset - 1) <= log_height {
+            offset -= 1;
+            used += state.log_entry_height(offset);
+        }
+        offset
+    } else {
+        state.log_scroll_state
+    };
+
+    // Ensure scroll_offset is valid
+    let scroll_offset = scroll_offset.min(state.log_view.len().saturating_sub(1));
+
+    let marked = state.marked_logs();
+    let mut lines = Vec::new();
+    for (index, entry) in state.shown_logs().enumerate().skip(scroll_offset) {
+        if lines.len() >= log_height {
+            break;
+        }
+        let selected = focused && state.log_selected == Some(index);
+        let marked = focused && marked.as_ref().is_some_and(|m| m.contains(&index));
+        let expanded = state.expanded_logs.contains(&index);
+        let level_style = Style::default().fg(match entry.level {
+            log::Level::Error => theme().log_error,
+            log::Level::Warn => theme().log_warn,
+            log::Level::Info => theme().log_info,
+            log::Level::Debug | log::Level::Trace => theme().log_debug,
+        });
+
+        let mut spans = Vec::new();
+        if accessible() {
+            spans.push(ratatui::text::Span::raw(if selected {
+                ">> "
+            } else {
+                "   "
+            }));
+        }
+        if entry.has_details() {
+            spans.push(ratatui::text::Span::raw(expander(true, expanded)));
+        }
+        spans.push(ratatui::text::Span::styled(
+            format!("[{}] ", entry.level),
+            level_style,
+        ));
+        if let Some(logger) = &entry.logger {
+            spans.push(ratatui::text::Span::styled(
+                format!("[{}] ", logger),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        spans.push(ratatui::text::Span::styled(
+            entry.message.as_str(),
+            level_style,
+        ));
+        let mut line = ratatui::text::Line::from(spans);
+        if selected {
+            line = line.style(selection_style(theme().line_selection));
+        } else if marked {
+            line = line.style(mark_style(theme().mark));
+        }
+        lines.push(ratatui::widgets::ListItem::new(line));
+
+        if expanded {
+            if let Some(error) = &entry.error {
+                lines.push(ratatui::widgets::ListItem::new(
+                    ratatui::text::Line::styled(
+                        format!("    {}", t!("common.error", error = error)),
+                        Style::default().fg(Color::Red),
+                    ),
+                ));
+            }
+            for frame in &entry.stack_trace {
+                lines.push(ratatui::widgets::ListItem::new(
+                    ratatui::text::Line::styled(
+                        format!("    {}", frame),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ));
+            }
+        }
+    }
+    lines.truncate(log_height);
+
+    let logs_list = ratatui::widgets::List::new(lines).block(log_block);
+    f.render_widget(logs_list, log_area);
+}
+
+/// Source tabs of the Logs pane, the current one highlighted, then the level filter and
+/// the entries of each level from that source.
+fn log_filters_title(state: &AppState) -> Line<'static> {
+    let mut spans = vec![Span::raw(" ")];
+    for source in crate::app_state::LogSourceFilter::ALL {
+        let style = if source == state.log_source_filter {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        spans.push(Span::styled(source.label(), style));
+        spans.push(Span::raw(" "));
+    }
+    let level = match state.log_level_filter {
+        log::LevelFilter::Trace => t!("logs.level_all").to_string(),
+        level => t!("logs.level_min", level = level),
+    };
+    spans.push(Span::raw(format!("│ {} │", level)));
+    for (level, count) in state.log_level_counts() {
+        let color = match level {
+            log::Level::Error => theme().log_error,
+            log::Level::Warn => theme().log_warn,
+            log::Level::Info => theme().log_info,
+            log::Level::Debug | log::Level::Trace => theme().log_debug,
+        };
+        spans.push(Span::styled(
+            format!(
+                " {} {}",
+                level.as_str().chars().next().unwrap_or(' '),
+                count
+            ),
+            Style::default().fg(color),
+        ));
+    }
+    spans.push(Span::raw(" "));
+    Line::from(spans)
+}
+
+fn draw_app_info_popup(f: &mut Frame, state: &AppState) {
+    let info = &state.app_info;
+    let display = (state.refresh_rate_override.is_some() || state.display_refresh_rate.is_some())
+        .then(|| {
+            t!(
+                "info.display",
+                rate = format!("{:.0}", state.refresh_rate()),
+                budget = format!("{:.1}", state.frame_budget().as_secs_f64() * 1000.0)
+            )
+        });
+    let rows = [
+        (t!("info.package"), &info.package_name),
+        (t!("info.device"), &info.device),
+        (t!("info.mode"), &info.build_mode),
+        (t!("info.flutter"), &info.flutter_version),
+        (t!("info.engine"), &info.engine_revision),
+        (t!("info.dart"), &info.dart_version),
+        (t!("info.target"), &info.vm_target),
+        (t!("info.vm_service"), &info.protocol_version),
+        (t!("info.display_label"), &display),
+    ];
+
+    let mut lines: Vec<ratatui::text::Line> = rows
+        .iter()
+        .map(|(label, value)| {
+            ratatui::text::Line::from(vec![
+                ratatui::text::Span::styled(
+                    format!("{:<12}", label),
+                    Style::default().fg(Color::Cyan),
+                ),
+                ratatui::text::Span::raw(value.as_deref().unwrap_or(t!("common.unknown"))),
+            ])
+        })
+        .collect();
+
+    if !state.subsystems.is_empty() {
+        lines.push(ratatui::text::Line::raw(""));
+        lines.push(ratatui::text::Line::styled(
+            t!("info.subsystems"),
+            Style::default().fg(Color::Cyan),
+        ));
+        for (name, status) in &state.subsystems {
+            let style = match status {
+                crate::supervisor::TaskStatus::Failed(_) => Style::default().fg(Color::Red),
+                crate::supervisor::TaskStatus::Restarting { .. } => {
+                    Style::default().fg(Color::Yellow)
+                }
+                _ => Style::default(),
+            };
+            lines.push(ratatui::text::Line::from(vec![
+                ratatui::text::Span::raw(format!("  {:<18}", name)),
+                ratatui::text::Span::styled(status.to_string(), style),
+            ]));
+        }
+    }
+
+    let area = centered_rect(50, 60, f.area());
+    let block = Block::default()
+        .title(t!("info.title"))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let paragraph = Paragraph::new(lines).block(block);
+    f.render_widget(Clear, area); // Clear background
+    f.render_widget(paragraph, area);
+}
+
+fn draw_isolate_metrics_popup(f: &mut Frame, state: &AppState) {
+    let area = centered_rect(80, 60, f.area());
+    let title = match &state.isolate_metrics_at {
+        Some(at) => t!("metrics.title_as_of", time = at),
+        None => t!("metrics.title").to_string(),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let bytes = |usage: Option<crate::vm_service::MemoryUsage>| match usage {
+        Some(usage) => format!(
+            "{:>10} / {:<10} {:>10}",
+            memory::format_bytes(usage.heap_usage),
+            memory::format_bytes(usage.heap_capacity),
+            memory::format_bytes(usage.external_usage)
+        ),
+        None => format!("{:>10} / {:<10} {:>10}", "?", "?", "?"),
+    };
+
+    let lines: Vec<Line> = match &state.isolate_metrics {
+        None => vec![Line::raw(t!("common.fetching"))],
+        Some(Err(e)) => vec![Line::styled(e.clone(), Style::default().fg(Color::Red))],
+        Some(Ok(groups)) => {
+            let mut lines = vec![Line::styled(
+                format!(
+                    "  {:<30} {:>10} / {:<10} {:>10}  {:<16} {:>5}",
+                    t!("metrics.isolate"),
+                    t!("metrics.heap"),
+                    t!("metrics.capacity"),
+                    t!("metrics.external"),
+                    t!("metrics.state"),
+                    t!("metrics.ports")
+                ),
+                Style::default().fg(Color::Cyan),
+            )];
+            for group in groups {
+                lines.push(Line::styled(
+                    format!("{:<32} {}", group.name, bytes(group.usage)),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+                for isolate in &group.isolates {
+                    let inspected = state
+                        .inspected_isolate
+                        .as_ref()
+                        .is_some_and(|inspected| inspected.id == isolate.id);
+                    let paused = isolate
+                        .pause_event
+                        .as_deref()
+                        .is_some_and(|kind| kind != "Resume" && kind != "None");
+                    lines.push(Line::from(vec![
+                        Span::raw(format!(
+                            "{} {:<30} {}  ",
+                            if inspected { "*" } else { " " },
+                            state.isolate_label(&isolate.id, &isolate.name),
+                            bytes(isolate.usage)
+                        )),
+                        Span::styled(
+                            format!("{:<16}", isolate.pause_event.as_deref().unwrap_or("?")),
+                            if paused {
+                                Style::default().fg(Color::Yellow)
+                            } else {
+                                Style::default()
+                            },
+                        ),
+                        Span::raw(format!(
+                            " {:>5}",
+                            isolate
+                                .live_ports
+                                .map_or("?".to_string(), |ports| ports.to_string())
+                        )),
+                    ]));
+                }
+            }
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                t!("metrics.legend"),
+                Style::default().fg(Color::DarkGray),
+            ));
+            lines
+        }
+    };
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -0,0 +1,219 @@
+use crate::app_state::{AppState, Focus, NetworkTabState};
+use crate::network::HttpProfileRequest;
+use crate::t;
+use crate::ui::memory::format_bytes;
+use crate::ui::theme::theme;
+use crate::ui::{focus_border, selection_style};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::collections::BTreeMap;
+
+/// Body lines shown in the details pane, enough for any response worth reading there.
+const MAX_BODY_LINES: usize = 1000;
+
+pub fn draw(f: &mut Frame, area: Rect, state: &AppState) {
+    let Some(network) = &state.network else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    draw_requests(f, chunks[0], state, network);
+    draw_details(f, chunks[1], state, network);
+}
+
+fn draw_requests(f: &mut Frame, area: Rect, state: &AppState, network: &NetworkTabState) {
+    let block = focus_border(
+        Block::default()
+            .title(t!("network.title", count = network.requests.len()))
+            .borders(Borders::ALL),
+        state.focus == Focus::Network,
+    );
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    if network.requests.is_empty() {
+        f.render_widget(Paragraph::new(t!("network.none")), inner_area);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner_area);
+    f.render_widget(
+        Paragraph::new(format!(
+            "   {:<7}{:<4}{:>8} {:>9}  {}",
+            t!("network.method"),
+            t!("network.code"),
+            t!("network.time"),
+            t!("network.size"),
+            t!("network.url")
+        ))
+        .style(Style::default().fg(Color::Cyan)),
+        chunks[0],
+    );
+    let items: Vec<ListItem> = network
+        .requests
+        .iter()
+        .map(|request| {
+            let (status, style) = status(request);
+            let duration = request
+                .duration()
+                .map_or(String::new(), |d| format!("{}ms", d.as_millis()));
+            let size = request.response_size().map_or(String::new(), format_bytes);
+            let mut spans = vec![
+                Span::raw(format!("{:<7}", request.method)),
+                Span::styled(format!("{:<4}", status), style),
+                Span::raw(format!("{:>8} {:>9}  ", duration, size)),
+                Span::raw(request.uri.clone()),
+            ];
+            if let Some(error) = request.error() {
+                spans.push(Span::styled(
+                    format!("  {}", error),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .highlight_style(selection_style(theme().selection))
+        .highlight_symbol(">> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(network.selected_index));
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+/// Status code, or what became of a request without one, and its color.
+fn status(request: &HttpProfileRequest) -> (String, Style) {
+    match request.status_code() {
+        Some(code @ 200..=399) => (code.to_string(), Style::default().fg(Color::Green)),
+        Some(code) => (code.to_string(), Style::default().fg(Color::Red)),
+        None if request.end_time.is_none() => ("...".to_string(), Style::default()),
+        None => ("ERR".to_string(), Style::default().fg(Color::Red)),
+    }
+}
+
+/// Headers and bodies of the request drilled into.
+fn draw_details(f: &mut Frame, area: Rect, state: &AppState, network: &NetworkTabState) {
+    let block = focus_border(
+        Block::default()
+            .title(t!("network.details"))
+            .borders(Borders::ALL),
+        state.focus == Focus::NetworkDetails,
+    );
+    let lines: Vec<Line> = match network.details.as_ref().map(|d| &d.request) {
+        None => vec![Line::raw(t!("network.select"))],
+        Some(None) => vec![Line::raw(t!("common.loading"))],
+        Some(Some(Err(e))) => vec![Line::styled(e.clone(), Style::default().fg(Color::Red))],
+        Some(Some(Ok(request))) => detail_lines(request),
+    };
+    let height = block.inner(area).height as usize;
+    network
+        .details_max_scroll
+        .replace(lines.len().saturating_sub(height));
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((network.details_scroll.min(u16::MAX as usize) as u16, 0)),
+        area,
+    );
+}
+
+fn detail_lines(request: &HttpProfileRequest) -> Vec<Line<'static>> {
+    let heading = |text: &str| {
+        Line::styled(
+            text.to_string(),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+    };
+    let (status, style) = status(request);
+    let mut lines = vec![
+        Line::raw(format!("{} {}", request.method, request.uri)),
+        Line::from(vec![
+            Span::raw(format!("{} ", t!("network.status"))),
+            Span::styled(status, style),
+            Span::raw(match request.duration() {
+                Some(duration) => format!(
+                    " {}",
+                    t!("network.in_millis", millis = duration.as_millis())
+                ),
+                None => String::new(),
+            }),
+        ]),
+    ];
+    if let Some(error) = request.error() {
+        lines.push(Line::styled(
+            t!("common.error", error = error),
+            Style::default().fg(Color::Red),
+        ));
+    }
+
+    lines.push(Line::raw(""));
+    lines.push(heading(t!("network.request_headers")));
+    lines.extend(header_lines(request.request.as_ref().map(|r| &r.headers)));
+    lines.push(heading(t!("network.request_body")));
+    lines.extend(body_lines(request.request_body.as_deref()));
+    lines.push(Line::raw(""));
+    lines.push(heading(t!("network.response_headers")));
+    lines.extend(header_lines(request.response.as_ref().map(|r| &r.headers)));
+    lines.push(heading(t!("network.response_body")));
+    lines.extend(body_lines(request.response_body.as_deref()));
+    lines
+}
+
+fn header_lines(headers: Option<&BTreeMap<String, Vec<String>>>) -> Vec<Line<'static>> {
+    match headers {
+        Some(headers) if !headers.is_empty() => headers
+            .iter()
+            .map(|(name, values)| Line::raw(format!("  {}: {}", name, values.join(", "))))
+            .collect(),
+        _ => vec![Line::raw(format!("  {}", t!("network.no_headers")))],
+    }
+}
+
+/// The body as text, pretty-printed when it is JSON.
+fn body_lines(body: Option<&[u8]>) -> Vec<Line<'static>> {
+    let body = match body {
+        Some(body) if !body.is_empty() => body,
+        _ => return vec![Line::raw(format!("  {}", t!("network.empty_body")))],
+    };
+    let Ok(text) = std::str::from_utf8(body) else {
+        return vec![Line::raw(format!(
+            "  {}",
+            t!(
+                "network.binary_body",
+                size = format_bytes(body.len() as u64)
+            )
+        ))];
+    };
+    let text = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(json) => serde_json::to_string_pretty(&json).unwrap_or_else(|_| text.to_string()),
+        Err(_) => text.to_string(),
+    };
+    let total = text.lines().count();
+    let mut lines: Vec<Line> = text
+        .lines()
+        .take(MAX_BODY_LINES)
+        .map(|line| Line::raw(format!("  {}", line)))
+        .collect();
+    if total > MAX_BODY_LINES {
+        lines.push(Line::styled(
+            format!(
+                "  {}",
+                t!("network.more_lines", count = total - MAX_BODY_LINES)
+            ),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    lines
+}
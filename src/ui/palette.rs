@@ -0,0 +1,163 @@
+use ratatui::{buffer::Buffer, style::Color};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How many colors the terminal can show, detected once at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    /// No colors at all (`NO_COLOR`, `TERM=dumb`): styles fall back to modifiers.
+    Monochrome,
+    /// The 16 ANSI colors.
+    Ansi16,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// 24-bit RGB (`COLORTERM=truecolor`).
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Reads the conventional environment variables: `NO_COLOR` wins, then `COLORTERM`,
+    /// then `TERM`.
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).unwrap_or_default();
+        if !var("NO_COLOR").is_empty() {
+            return ColorSupport::Monochrome;
+        }
+        let colorterm = var("COLORTERM").to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+        let term = var("TERM").to_lowercase();
+        if term.is_empty() || term == "dumb" {
+            ColorSupport::Monochrome
+        } else if term.contains("256color") || term.contains("direct") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+}
+
+static COLOR_SUPPORT: AtomicU8 = AtomicU8::new(ColorSupport::TrueColor as u8);
+
+pub fn set_color_support(support: ColorSupport) {
+    COLOR_SUPPORT.store(support as u8, Ordering::Relaxed);
+}
+
+pub fn color_support() -> ColorSupport {
+    match COLOR_SUPPORT.load(Ordering::Relaxed) {
+        0 => ColorSupport::Monochrome,
+        1 => ColorSupport::Ansi16,
+        2 => ColorSupport::Ansi256,
+        _ => ColorSupport::TrueColor,
+    }
+}
+
+/// Rewrites the colors of a drawn frame to what the terminal supports. Monochrome drops
+/// colors entirely; selections and focus then rely on the accessible-mode markers.
+pub fn apply(buffer: &mut Buffer) {
+    let support = color_support();
+    if support == ColorSupport::TrueColor {
+        return;
+    }
+    for cell in buffer.content.iter_mut() {
+        if support == ColorSupport::Monochrome {
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+        } else {
+            cell.fg = downsample(cell.fg, support);
+            cell.bg = downsample(cell.bg, support);
+        }
+    }
+}
+
+fn downsample(color: Color, support: ColorSupport) -> Color {
+    match (color, support) {
+        (Color::Rgb(r, g, b), ColorSupport::Ansi256) => Color::Indexed(rgb_to_256(r, g, b)),
+        (Color::Rgb(r, g, b), ColorSupport::Ansi16) => nearest_ansi16(r, g, b),
+        (Color::Indexed(i), ColorSupport::Ansi16) if i >= 16 => {
+            let (r, g, b) = indexed_to_rgb(i);
+            nearest_ansi16(r, g, b)
+        }
+        (Color::Indexed(i), ColorSupport::Ansi16) => ANSI16[i as usize].0,
+        _ => color,
+    }
+}
+
+/// Closest entry of the 6x6x6 cube or the grayscale ramp.
+fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    let cube = |v: u8| -> u8 {
+        if v < 48 {
+            0
+        } else if v < 115 {
+            1
+        } else {
+            (v - 35) / 40
+        }
+    };
+    let (cr, cg, cb) = (cube(r), cube(g), cube(b));
+    let cube_index = 16 + 36 * cr + 6 * cg + cb;
+    let cube_rgb = indexed_to_rgb(cube_index);
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = if average > 238 {
+        23
+    } else {
+        (average.saturating_sub(3) / 10) as u8
+    };
+    let gray_index = 232 + gray_step;
+    let gray_rgb = indexed_to_rgb(gray_index);
+
+    if distance((r, g, b), gray_rgb) < distance((r, g, b), cube_rgb) {
+        gray_index
+    } else {
+        cube_index
+    }
+}
+
+fn indexed_to_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => ANSI16[i as usize].1,
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = i - 16;
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + (i - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// The 16 ANSI colors with their xterm default RGB values, in palette order.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
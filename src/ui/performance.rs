@@ -0,0 +1,258 @@
+use crate::app_state::{AppState, Focus, PerformanceTabState, TimelineRow};
+use crate::t;
+use crate::ui::theme::theme;
+use crate::ui::{focus_border, selection_style};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+use std::time::Duration;
+
+pub fn draw(f: &mut Frame, area: Rect, state: &AppState) {
+    let Some(performance) = &state.performance else {
+        return;
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+    draw_frames(f, chunks[0], state, performance);
+    draw_timeline(f, chunks[1], state, performance);
+}
+
+/// Build and raster time of the frames since the tab was opened.
+fn draw_frames(f: &mut Frame, area: Rect, state: &AppState, performance: &PerformanceTabState) {
+    let budget = state.frame_budget();
+    let block = Block::default()
+        .title(t!(
+            "performance.frames_title",
+            budget = format!("{:.1}", millis(budget))
+        ))
+        .borders(Borders::ALL);
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    if performance.frames.is_empty() {
+        f.render_widget(Paragraph::new(t!("performance.waiting")), inner_area);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(inner_area);
+    f.render_widget(Paragraph::new(stats(performance, budget)), chunks[0]);
+    draw_frame_chart(f, chunks[1], performance, budget);
+}
+
+fn stats(performance: &PerformanceTabState, budget: Duration) -> String {
+    let count = performance.frames.len() as u32;
+    let average = |part: fn(&crate::timeline::FrameTiming) -> Duration| {
+        performance.frames.iter().map(part).sum::<Duration>() / count
+    };
+    let worst = match performance.worst_frame() {
+        Some(frame) => t!(
+            "performance.worst",
+            number = frame.number,
+            work = format!("{:.1}", millis(frame.work())),
+            build = format!("{:.1}", millis(frame.build)),
+            raster = format!("{:.1}", millis(frame.raster))
+        ),
+        None => "-".to_string(),
+    };
+    t!(
+        "performance.stats",
+        fps = performance.fps(),
+        janky = performance.janky_frames(budget),
+        count = count,
+        build = format!("{:.1}", millis(average(|frame| frame.build))),
+        raster = format!("{:.1}", millis(average(|frame| frame.raster))),
+        worst = worst
+    )
+}
+
+/// The newest frames as one column each, right-aligned so the chart scrolls left.
+fn draw_frame_chart(
+    f: &mut Frame,
+    area: Rect,
+    performance: &PerformanceTabState,
+    budget: Duration,
+) {
+    let shown = performance.frames.len().min(area.width as usize);
+    let frames = performance
+        .frames
+        .iter()
+        .skip(performance.frames.len() - shown);
+    let bars: Vec<Bar> = frames
+        .map(|frame| {
+            let color = if frame.work() > budget {
+                Color::Red
+            } else {
+                Color::Green
+            };
+            Bar::default()
+                .value(frame.work().as_micros() as u64)
+                .text_value(String::new())
+                .style(Style::default().fg(color))
+        })
+        .collect();
+    // At least twice the budget high, so the budget stays a visible height
+    let max = performance
+        .frames
+        .iter()
+        .map(|frame| frame.work().as_micros() as u64)
+        .max()
+        .unwrap_or(0)
+        .max(2 * budget.as_micros() as u64);
+
+    let offset = area.width.saturating_sub(shown as u16);
+    let chart_area = Rect {
+        x: area.x + offset,
+        width: area.width - offset,
+        ..area
+    };
+    f.render_widget(
+        BarChart::default()
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(1)
+            .bar_gap(0)
+            .max(max),
+        chart_area,
+    );
+}
+
+/// The events of the last timeline capture, grouped by category, longest first.
+fn draw_timeline(f: &mut Frame, area: Rect, state: &AppState, performance: &PerformanceTabState) {
+    let filtering = state.focus == Focus::TimelineFilter;
+    let show_filter_bar = filtering || !performance.filter.is_empty();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(if show_filter_bar { 3 } else { 0 }),
+        ])
+        .split(area);
+    if show_filter_bar {
+        let block = focus_border(
+            Block::default()
+                .title(if filtering {
+                    t!("performance.filter_editing")
+                } else {
+                    t!("performance.filter")
+                })
+                .borders(Borders::ALL),
+            filtering,
+        );
+        let cursor = if filtering { "_" } else { "" };
+        f.render_widget(
+            Paragraph::new(format!("{}{}", performance.filter, cursor)).block(block),
+            chunks[1],
+        );
+    }
+
+    let title = t!(
+        "performance.timeline_title",
+        category = performance
+            .category
+            .as_deref()
+            .unwrap_or(t!("performance.all_categories")),
+        secs = performance.window_secs()
+    );
+    let block = focus_border(
+        Block::default().title(title).borders(Borders::ALL),
+        state.focus == Focus::Timeline,
+    );
+    let inner_area = block.inner(chunks[0]);
+    f.render_widget(block, chunks[0]);
+
+    let capture = match &performance.timeline {
+        _ if performance.capturing => {
+            f.render_widget(Paragraph::new(t!("performance.fetching")), inner_area);
+            return;
+        }
+        None => {
+            f.render_widget(Paragraph::new(t!("performance.no_capture")), inner_area);
+            return;
+        }
+        Some(Err(e)) => {
+            f.render_widget(
+                Paragraph::new(e.as_str()).style(Style::default().fg(Color::Red)),
+                inner_area,
+            );
+            return;
+        }
+        Some(Ok(capture)) => capture,
+    };
+    if performance.rows.is_empty() {
+        let message = if capture.event_count == 0 {
+            t!("performance.no_events")
+        } else {
+            t!("performance.no_matches")
+        };
+        f.render_widget(Paragraph::new(message), inner_area);
+        return;
+    }
+
+    let items: Vec<ListItem> = performance
+        .rows
+        .iter()
+        .map(|row| match *row {
+            TimelineRow::Category { category, matched } => {
+                let category = &capture.categories[category];
+                ListItem::new(t!(
+                    "performance.category",
+                    name = if category.name.is_empty() {
+                        t!("performance.no_category")
+                    } else {
+                        &category.name
+                    },
+                    count = matched,
+                    total = format!("{:.1}", millis(category.total))
+                ))
+                .style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+            }
+            TimelineRow::Span { category, span } => {
+                let span = &capture.categories[category].spans[span];
+                ListItem::new(format!(
+                    "  {:>9.2}ms  {:<40} {:<16} {}",
+                    millis(span.duration),
+                    span.name,
+                    span.thread,
+                    t!(
+                        "performance.at_offset",
+                        secs = format!("{:.3}", span.offset.as_secs_f64())
+                    )
+                ))
+            }
+        })
+        .collect();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner_area);
+    f.render_widget(
+        Paragraph::new(t!(
+            "performance.captured",
+            count = capture.event_count,
+            secs = capture.window_secs
+        ))
+        .style(Style::default().fg(Color::DarkGray)),
+        chunks[0],
+    );
+    let list = List::new(items)
+        .highlight_style(selection_style(theme().selection))
+        .highlight_symbol(">> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(performance.selected_index));
+    f.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
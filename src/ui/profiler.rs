@@ -0,0 +1,245 @@
+use crate::app_state::{AppState, ProfileView, ProfilerTabState};
+use crate::profiler::{CallNode, CpuProfile};
+use crate::t;
+use crate::ui::theme::theme;
+use crate::ui::{focus_border, selection_style};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Flame graph frames cycle through these so neighbours stand apart.
+const FLAME_COLORS: [Color; 4] = [
+    Color::Red,
+    Color::Yellow,
+    Color::LightRed,
+    Color::LightYellow,
+];
+
+pub fn draw(f: &mut Frame, area: Rect, state: &AppState) {
+    let Some(profiler) = &state.profiler else {
+        return;
+    };
+    let title = t!(
+        "profiler.title",
+        view = profiler.view.label(),
+        secs = profiler.window_secs()
+    );
+    let block = focus_border(
+        Block::default().title(title).borders(Borders::ALL),
+        state.focus == crate::app_state::Focus::Profiler,
+    );
+    let inner_area = block.inner(area);
+    f.render_widget(block, area);
+
+    let profile = match &profiler.profile {
+        _ if profiler.capturing => {
+            f.render_widget(Paragraph::new(t!("profiler.fetching")), inner_area);
+            return;
+        }
+        None => {
+            f.render_widget(Paragraph::new(t!("profiler.no_capture")), inner_area);
+            return;
+        }
+        Some(Err(e)) => {
+            f.render_widget(
+                Paragraph::new(e.as_str()).style(Style::default().fg(Color::Red)),
+                inner_area,
+            );
+            return;
+        }
+        Some(Ok(profile)) => profile,
+    };
+    if profile.sample_count == 0 {
+        f.render_widget(Paragraph::new(t!("profiler.no_samples")), inner_area);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(inner_area);
+    f.render_widget(
+        Paragraph::new(t!(
+            "profiler.summary",
+            count = profile.sample_count,
+            extent = format!("{:.1}", profile.time_extent_micros as f64 / 1_000_000.0),
+            period = profile.sample_period_micros,
+            secs = profile.window_secs
+        ))
+        .style(Style::default().fg(Color::DarkGray)),
+        chunks[0],
+    );
+
+    match profiler.view {
+        ProfileView::TopDown | ProfileView::BottomUp => {
+            draw_call_tree(f, chunks[1], profiler, profile)
+        }
+        ProfileView::Functions => draw_functions(f, chunks[1], profiler, profile),
+        ProfileView::FlameGraph => draw_flame_graph(f, chunks[1], profiler, profile),
+    }
+}
+
+fn draw_call_tree(f: &mut Frame, area: Rect, profiler: &ProfilerTabState, profile: &CpuProfile) {
+    let top_down = profiler.view == ProfileView::TopDown;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    let header = if top_down {
+        format!(
+            "   {:>7} {:>7}  {}",
+            t!("profiler.total"),
+            t!("profiler.self"),
+            t!("profiler.function_callees")
+        )
+    } else {
+        format!(
+            "   {:>7}  {}",
+            t!("profiler.self"),
+            t!("profiler.function_callers")
+        )
+    };
+    f.render_widget(
+        Paragraph::new(header).style(Style::default().fg(Color::Cyan)),
+        chunks[0],
+    );
+
+    let rows = profiler.rows();
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| {
+            let marker = match (row.expandable, row.expanded) {
+                (false, _) => "  ",
+                (true, false) => "▶ ",
+                (true, true) => "▼ ",
+            };
+            let indent = "  ".repeat(row.path.len() - 1);
+            let columns = if top_down {
+                format!(
+                    "{:>6.1}% {:>6.1}%",
+                    profile.percent(row.ticks),
+                    profile.percent(row.self_ticks)
+                )
+            } else {
+                format!("{:>6.1}%", profile.percent(row.ticks))
+            };
+            ListItem::new(format!(
+                "{}  {}{}{}",
+                columns,
+                indent,
+                marker,
+                profile.name(row.function)
+            ))
+        })
+        .collect();
+    draw_list(f, chunks[1], items, profiler.selected_index);
+}
+
+fn draw_functions(f: &mut Frame, area: Rect, profiler: &ProfilerTabState, profile: &CpuProfile) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    f.render_widget(
+        Paragraph::new(format!(
+            "   {:>7} {:>7}  {:<40} {}",
+            t!("profiler.self"),
+            t!("profiler.total"),
+            t!("profiler.function"),
+            t!("profiler.location")
+        ))
+        .style(Style::default().fg(Color::Cyan)),
+        chunks[0],
+    );
+    let items: Vec<ListItem> = profile
+        .functions
+        .iter()
+        .map(|function| {
+            // package:app/src/home.dart -> home.dart
+            let location = function.url.rsplit('/').next().unwrap_or(&function.url);
+            ListItem::new(format!(
+                "{:>6.1}% {:>6.1}%  {:<40} {}",
+                profile.percent(function.self_ticks),
+                profile.percent(function.total_ticks),
+                function.name,
+                location
+            ))
+        })
+        .collect();
+    draw_list(f, chunks[1], items, profiler.selected_index);
+}
+
+fn draw_list(f: &mut Frame, area: Rect, items: Vec<ListItem>, selected: usize) {
+    let count = items.len();
+    let list = List::new(items)
+        .highlight_style(selection_style(theme().selection))
+        .highlight_symbol(">> ");
+    let mut list_state = ListState::default();
+    list_state.select(Some(selected.min(count.saturating_sub(1))));
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// An icicle graph of the top-down tree: callers above their callees, each frame as wide
+/// as its share of the samples.
+fn draw_flame_graph(f: &mut Frame, area: Rect, profiler: &ProfilerTabState, profile: &CpuProfile) {
+    let root = profile
+        .top_down
+        .at(&profiler.flame_root)
+        .unwrap_or(&profile.top_down);
+    let mut rows: Vec<Vec<(u16, u16, usize)>> = vec![Vec::new(); area.height as usize];
+    layout_frames(root, 0, area.width, 0, &mut rows);
+
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .map(|frames| {
+            let mut spans = Vec::new();
+            let mut column = 0;
+            for (x, width, function) in frames {
+                if x > column {
+                    spans.push(Span::raw(" ".repeat((x - column) as usize)));
+                }
+                let name = profile.name(function);
+                // The last column stays blank to set frames apart
+                let name: String = name.chars().take(width as usize - 1).collect();
+                let label = format!("{:<1$}", name, width as usize);
+                let color = match function {
+                    crate::profiler::ROOT_FUNCTION => Color::Gray,
+                    function => FLAME_COLORS[function % FLAME_COLORS.len()],
+                };
+                spans.push(Span::styled(
+                    label,
+                    Style::default().fg(Color::Black).bg(color),
+                ));
+                column = x + width;
+            }
+            Line::from(spans)
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+/// Places `node` at `x` with `width` columns on row `depth` and its callees below it, left
+/// to right. Frames narrower than a column are left out.
+fn layout_frames(
+    node: &CallNode,
+    x: u16,
+    width: u16,
+    depth: usize,
+    rows: &mut [Vec<(u16, u16, usize)>],
+) {
+    if width == 0 || depth >= rows.len() {
+        return;
+    }
+    rows[depth].push((x, width, node.function));
+    let mut before = 0;
+    for child in &node.children {
+        let start = (before * width as u64 / node.ticks) as u16;
+        before += child.ticks;
+        let end = (before * width as u64 / node.ticks) as u16;
+        layout_frames(child, x + start, end - start, depth + 1, rows);
+    }
+}
@@ -0,0 +1,110 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// The built-in themes, picked with `theme = "..."` in the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// Colors of what carries meaning beyond the text itself. Everything else keeps the
+/// terminal's own colors, so it suits any background.
+pub struct Theme {
+    /// Cursor row of lists.
+    pub selection: Style,
+    /// Cursor row of trees.
+    pub tree_selection: Style,
+    /// Cursor line of the logs and the source view, patched over the line's own style.
+    pub line_selection: Style,
+    /// Rows of a Shift+arrow selection besides the cursor's.
+    pub mark: Style,
+    pub focus_border: Color,
+    pub breakpoint: Color,
+    pub logpoint: Color,
+    pub disabled_breakpoint: Color,
+    /// Line the debugger is paused at.
+    pub execution_line: Style,
+    pub log_error: Color,
+    pub log_warn: Color,
+    pub log_info: Color,
+    pub log_debug: Color,
+}
+
+static DARK: Theme = Theme {
+    selection: Style::new().fg(Color::Black).bg(Color::White),
+    tree_selection: Style::new().fg(Color::White).bg(Color::Blue),
+    line_selection: Style::new().bg(Color::DarkGray),
+    mark: Style::new().bg(Color::DarkGray),
+    focus_border: Color::Yellow,
+    breakpoint: Color::Red,
+    logpoint: Color::Magenta,
+    disabled_breakpoint: Color::DarkGray,
+    execution_line: Style::new().fg(Color::Black).bg(Color::Yellow),
+    log_error: Color::Red,
+    log_warn: Color::Yellow,
+    log_info: Color::Reset,
+    log_debug: Color::DarkGray,
+};
+
+static LIGHT: Theme = Theme {
+    selection: Style::new().fg(Color::White).bg(Color::Blue),
+    tree_selection: Style::new().fg(Color::White).bg(Color::Blue),
+    line_selection: Style::new().bg(Color::Rgb(215, 215, 215)),
+    mark: Style::new().fg(Color::Black).bg(Color::Rgb(175, 215, 255)),
+    focus_border: Color::Blue,
+    breakpoint: Color::Rgb(200, 0, 0),
+    logpoint: Color::Rgb(160, 0, 160),
+    disabled_breakpoint: Color::Rgb(150, 150, 150),
+    execution_line: Style::new().fg(Color::Black).bg(Color::Rgb(255, 215, 95)),
+    log_error: Color::Rgb(200, 0, 0),
+    log_warn: Color::Rgb(175, 95, 0),
+    log_info: Color::Reset,
+    log_debug: Color::Rgb(110, 110, 110),
+};
+
+static HIGH_CONTRAST: Theme = Theme {
+    selection: Style::new()
+        .fg(Color::Black)
+        .bg(Color::White)
+        .add_modifier(Modifier::BOLD),
+    tree_selection: Style::new()
+        .fg(Color::Black)
+        .bg(Color::White)
+        .add_modifier(Modifier::BOLD),
+    line_selection: Style::new()
+        .fg(Color::Black)
+        .bg(Color::White)
+        .add_modifier(Modifier::BOLD),
+    mark: Style::new().fg(Color::Black).bg(Color::LightCyan),
+    focus_border: Color::LightYellow,
+    breakpoint: Color::LightRed,
+    logpoint: Color::LightMagenta,
+    disabled_breakpoint: Color::Gray,
+    execution_line: Style::new()
+        .fg(Color::Black)
+        .bg(Color::LightYellow)
+        .add_modifier(Modifier::BOLD),
+    log_error: Color::LightRed,
+    log_warn: Color::LightYellow,
+    log_info: Color::White,
+    log_debug: Color::Gray,
+};
+
+static THEME: AtomicU8 = AtomicU8::new(ThemeName::Dark as u8);
+
+pub fn set_theme(name: ThemeName) {
+    THEME.store(name as u8, Ordering::Relaxed);
+}
+
+pub fn theme() -> &'static Theme {
+    match THEME.load(Ordering::Relaxed) {
+        1 => &LIGHT,
+        2 => &HIGH_CONTRAST,
+        _ => &DARK,
+    }
+}
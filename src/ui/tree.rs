@@ -1,10 +1,17 @@
+use super::theme::theme;
+use crate::t;
 use ratatui::{
     layout::Rect,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     widgets::{Block, Borders},
     Frame,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+
+const SELECTION_MARKER: &str = ">> ";
+const MATCH_MARKER: &str = "*  ";
+const MARK_MARKER: &str = "+  ";
 
 pub trait Treeable: Sized {
     fn children(&self) -> Option<&[Self]>;
@@ -12,136 +19,354 @@ pub trait Treeable: Sized {
     fn render(&self, depth: usize, is_expanded: bool) -> String;
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn draw<T: Treeable>(
     f: &mut Frame,
     area: Rect,
     root_node: Option<&T>,
+    rows: &TreeRows,
     selected_index: usize,
-    expanded_ids: &HashSet<String>,
+    marked: Option<RangeInclusive<usize>>,
+    matches: Option<&HashSet<String>>,
+    annotations: Option<&HashMap<String, String>>,
     scroll_offset: usize,
     horizontal_scroll: usize,
     title: &str,
     is_focused: bool,
 ) -> usize {
-    let mut lines = Vec::new();
-    if let Some(root) = root_node {
-        flatten_tree(root, 0, &mut lines, expanded_ids);
-    }
+    let block = super::focus_border(
+        Block::default().title(title).borders(Borders::ALL),
+        is_focused,
+    );
 
-    let visible_count = lines.len();
-
-    let block = Block::default()
-        .title(title)
-        .borders(Borders::ALL)
-        .border_style(if is_focused {
-            Style::default().fg(Color::Yellow)
-        } else {
-            Style::default()
-        });
-
-    let inner_area = block.inner(area);
+    let mut inner_area = block.inner(area);
     f.render_widget(block, area);
 
-    if lines.is_empty() {
+    // Only the rows in the viewport are rendered
+    let window = match root_node {
+        Some(root) => rows.window(root, matches, scroll_offset, inner_area.height as usize),
+        None => Window::default(),
+    };
+    let visible_count = rows.len();
+
+    if visible_count == 0 {
         if root_node.is_none() {
             f.buffer_mut().set_string(
                 inner_area.x,
                 inner_area.y,
-                "Waiting for data...",
+                t!("tree.waiting"),
+                Style::default().fg(Color::Yellow),
+            );
+        } else if rows.filtered {
+            f.buffer_mut().set_string(
+                inner_area.x,
+                inner_area.y,
+                t!("tree.no_matches"),
                 Style::default().fg(Color::Yellow),
             );
         }
         return 0;
     }
 
-    // Apply scrolling
-    let skip_count = scroll_offset;
-
-    for (i, line) in lines.iter().skip(skip_count).enumerate() {
-        if i >= inner_area.height as usize {
-            break;
-        }
+    if !window.match_rows.is_empty() && inner_area.width > 1 {
+        inner_area.width -= 1;
+        draw_match_strip(
+            f,
+            Rect {
+                x: inner_area.x + inner_area.width,
+                width: 1,
+                ..inner_area
+            },
+            &window.match_rows,
+            visible_count,
+            scroll_offset,
+        );
+    }
 
-        let actual_index = i + skip_count;
-        let style = if actual_index == selected_index {
-            Style::default().bg(Color::Blue).fg(Color::White)
+    for (i, row) in window.rows.iter().enumerate() {
+        let actual_index = i + scroll_offset;
+        let is_selected = actual_index == selected_index;
+        let is_match = matches.is_some_and(|m| row.node.id().is_some_and(|id| m.contains(id)));
+        let is_marked = marked.as_ref().is_some_and(|m| m.contains(&actual_index));
+        let style = if is_selected {
+            super::selection_style(theme().tree_selection)
+        } else if is_marked {
+            super::mark_style(theme().mark)
+        } else if is_match {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
         } else {
             Style::default()
         };
 
-        // Apply horizontal scrolling
-        let line_width = unicode_width::UnicodeWidthStr::width(line.as_str());
-        let visible_width = inner_area.width as usize;
-        let scroll_offset = horizontal_scroll;
+        // Accessible mode marks the selection and matches with text, not only with color
+        let mut x = inner_area.x;
+        if super::accessible() && inner_area.width > SELECTION_MARKER.len() as u16 {
+            let marker = if is_selected {
+                SELECTION_MARKER
+            } else if is_marked {
+                MARK_MARKER
+            } else if is_match {
+                MATCH_MARKER
+            } else {
+                "   "
+            };
+            f.buffer_mut()
+                .set_string(x, inner_area.y + i as u16, marker, style);
+            x += SELECTION_MARKER.len() as u16;
+        }
 
-        let display_line = if scroll_offset >= line_width {
-            ""
-        } else {
-            let mut current_width = 0;
-            let mut start_byte = 0;
-            let mut end_byte = line.len();
-            let mut found_start = false;
-
-            for (i, c) in line.char_indices() {
-                let char_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
-
-                if !found_start {
-                    if current_width + char_width > scroll_offset {
-                        start_byte = i;
-                        found_start = true;
-                        // Reset width to count visible part
-                        current_width = 0;
-                    } else {
-                        current_width += char_width;
-                        continue;
-                    }
-                }
+        let line = row.node.render(row.depth, row.expanded);
+        let visible_width = (inner_area.x + inner_area.width - x) as usize;
+        f.buffer_mut().set_string(
+            x,
+            inner_area.y + i as u16,
+            horizontal_slice(&line, horizontal_scroll, visible_width),
+            style,
+        );
 
-                if found_start {
-                    if current_width + char_width > visible_width {
-                        end_byte = i;
-                        break;
-                    }
-                    current_width += char_width;
+        // Right-aligned extra info for the node, e.g. rebuild activity
+        if let Some(annotation) = row
+            .node
+            .id()
+            .and_then(|id| annotations.and_then(|a| a.get(id)))
+        {
+            let width = unicode_width::UnicodeWidthStr::width(annotation.as_str()) as u16;
+            if width < inner_area.width {
+                f.buffer_mut().set_string(
+                    inner_area.x + inner_area.width - width,
+                    inner_area.y + i as u16,
+                    annotation,
+                    style.fg(Color::Magenta),
+                );
+            }
+        }
+    }
+
+    visible_count
+}
+
+/// A shown row of a tree, not rendered yet.
+struct Row<'a, T> {
+    node: &'a T,
+    depth: usize,
+    expanded: bool,
+}
+
+/// The rows of a tree in the viewport.
+struct Window<'a, T> {
+    rows: Vec<Row<'a, T>>,
+    /// Indices of the shown rows matching the search, all of them.
+    match_rows: Vec<usize>,
+}
+
+impl<T> Default for Window<'_, T> {
+    fn default() -> Self {
+        Self {
+            rows: Vec::new(),
+            match_rows: Vec::new(),
+        }
+    }
+}
+
+/// A shown row as kept by [`TreeRows`]: the node is found again through its parent's row
+/// and its index among the parent's children.
+struct FlatRow {
+    parent: Option<usize>,
+    child: usize,
+    depth: usize,
+    expanded: bool,
+}
+
+/// The shown rows of a tree in order, walked once and kept until the tree, its expansion
+/// or its filter changes, so drawing a tree of tens of thousands of nodes only resolves
+/// the rows that fit the viewport.
+#[derive(Default)]
+pub struct TreeRows {
+    rows: Vec<FlatRow>,
+    /// Row of each node with an id.
+    index: HashMap<String, usize>,
+    /// Whether a filter hid nodes, for telling an empty result from an empty tree.
+    pub filtered: bool,
+}
+
+impl TreeRows {
+    pub fn new<T: Treeable>(
+        root: Option<&T>,
+        expanded_ids: &HashSet<String>,
+        filter: Option<&HashSet<String>>,
+    ) -> Self {
+        fn walk<T: Treeable>(
+            node: &T,
+            parent: Option<usize>,
+            child: usize,
+            depth: usize,
+            expanded_ids: &HashSet<String>,
+            filter: Option<&HashSet<String>>,
+            rows: &mut TreeRows,
+        ) {
+            let id = node.id();
+            if let Some(filter) = filter {
+                if !id.is_some_and(|id| filter.contains(id)) {
+                    return;
                 }
             }
 
-            if !found_start {
-                ""
-            } else {
-                &line[start_byte..end_byte]
+            let is_expanded = match id {
+                Some(id) => expanded_ids.contains(id),
+                None => true, // Default expanded if no ID?
+            };
+            let row = rows.rows.len();
+            if let Some(id) = id {
+                rows.index.insert(id.to_string(), row);
             }
+            rows.rows.push(FlatRow {
+                parent,
+                child,
+                depth,
+                expanded: is_expanded,
+            });
+
+            if is_expanded {
+                for (i, child) in node.children().unwrap_or_default().iter().enumerate() {
+                    walk(child, Some(row), i, depth + 1, expanded_ids, filter, rows);
+                }
+            }
+        }
+
+        let mut rows = TreeRows {
+            filtered: filter.is_some(),
+            ..TreeRows::default()
         };
+        if let Some(root) = root {
+            walk(root, None, 0, 0, expanded_ids, filter, &mut rows);
+        }
+        rows
+    }
 
-        f.buffer_mut()
-            .set_string(inner_area.x, inner_area.y + i as u16, display_line, style);
+    pub fn len(&self) -> usize {
+        self.rows.len()
     }
 
-    visible_count
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Row of the node with `id`, if it is shown.
+    pub fn row_of(&self, id: &str) -> Option<usize> {
+        self.index.get(id).copied()
+    }
+
+    /// The node shown at `row`, found from `root`, the tree these rows were walked from.
+    fn node<'a, T: Treeable>(&self, root: &'a T, row: usize) -> Option<&'a T> {
+        let mut path = Vec::new();
+        let mut current = Some(row);
+        while let Some(row) = current {
+            let flat = self.rows.get(row)?;
+            current = flat.parent;
+            if current.is_some() {
+                path.push(flat.child);
+            }
+        }
+        let mut node = root;
+        for &child in path.iter().rev() {
+            node = node.children()?.get(child)?;
+        }
+        Some(node)
+    }
+
+    /// The `height` rows from `offset` on, and the rows of every node in `matches`.
+    fn window<'a, T: Treeable>(
+        &self,
+        root: &'a T,
+        matches: Option<&HashSet<String>>,
+        offset: usize,
+        height: usize,
+    ) -> Window<'a, T> {
+        let end = (offset + height).min(self.rows.len());
+        let rows = (offset.min(end)..end)
+            .filter_map(|row| {
+                let node = self.node(root, row)?;
+                Some(Row {
+                    node,
+                    depth: self.rows[row].depth,
+                    expanded: self.rows[row].expanded,
+                })
+            })
+            .collect();
+        let mut match_rows: Vec<usize> = matches
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.row_of(id))
+            .collect();
+        match_rows.sort_unstable();
+        Window { rows, match_rows }
+    }
 }
 
-fn flatten_tree<T: Treeable>(
-    node: &T,
-    depth: usize,
-    lines: &mut Vec<String>,
-    expanded_ids: &HashSet<String>,
+/// A scrollbar-like strip showing where the search matches are in the whole tree, denser
+/// marks for more matches, over a track marking the part in view.
+fn draw_match_strip(
+    f: &mut Frame,
+    area: Rect,
+    match_rows: &[usize],
+    count: usize,
+    scroll_offset: usize,
 ) {
-    let has_children = node.children().map(|c| !c.is_empty()).unwrap_or(false);
-    let is_expanded = if let Some(id) = node.id() {
-        expanded_ids.contains(id)
+    let height = area.height as usize;
+    let marks: [&str; 3] = if super::accessible() {
+        ["-", "=", "#"]
     } else {
-        true // Default expanded if no ID?
+        ["▪", "■", "█"]
     };
+    for y in 0..height {
+        // Rows of the tree this cell stands for, at least one
+        let start = y * count / height;
+        let end = ((y + 1) * count / height).max(start + 1);
+        let first = match_rows.partition_point(|&row| row < start);
+        let matches = match_rows[first..].partition_point(|&row| row < end);
+        let in_view = start < scroll_offset + height && end > scroll_offset;
+        let style = if in_view {
+            Style::default().fg(Color::Yellow).bg(Color::DarkGray)
+        } else {
+            Style::default().fg(Color::Yellow)
+        };
+        let mark = match matches {
+            0 => " ",
+            1 => marks[0],
+            2..=4 => marks[1],
+            _ => marks[2],
+        };
+        f.buffer_mut()
+            .set_string(area.x, area.y + y as u16, mark, style);
+    }
+}
 
-    lines.push(node.render(depth, is_expanded));
-
-    if has_children && is_expanded {
-        if let Some(children) = node.children() {
-            for child in children {
-                flatten_tree(child, depth + 1, lines, expanded_ids);
+/// The part of `line` shown `scroll` columns in, at most `width` columns wide.
+fn horizontal_slice(line: &str, scroll: usize, width: usize) -> &str {
+    let mut current_width = 0;
+    let mut start_byte = None;
+    for (i, c) in line.char_indices() {
+        let char_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+        match start_byte {
+            None if current_width + char_width > scroll => {
+                start_byte = Some(i);
+                // Count the visible part from here
+                current_width = 0;
+            }
+            None => {
+                current_width += char_width;
+                continue;
             }
+            Some(_) => {}
+        }
+        if current_width + char_width > width {
+            return &line[start_byte.unwrap_or(i)..i];
         }
+        current_width += char_width;
     }
+    start_byte.map_or("", |start| &line[start..])
 }
 
 // Implement Treeable for RemoteDiagnosticsNode
@@ -167,18 +392,19 @@ impl Treeable for crate::vm_service::RemoteDiagnosticsNode {
             .as_ref()
             .map(|c| !c.is_empty())
             .unwrap_or(false);
+        let unloaded = self.has_unloaded_children();
 
-        let icon = if has_children {
-            if is_expanded {
-                "▼ "
-            } else {
-                "▶ "
-            }
+        let icon = super::expander(has_children || unloaded, is_expanded);
+
+        let loading = if is_expanded && unloaded {
+            format!(" {}", t!("tree.loading"))
         } else {
-            "  "
+            String::new()
         };
-
-        format!("{}{}{}{} ({})", indent, icon, type_name, "", description)
+        format!(
+            "{}{}{}{} ({}){}",
+            indent, icon, type_name, "", description, loading
+        )
     }
 }
 
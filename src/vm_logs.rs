@@ -0,0 +1,80 @@
+use crate::logger::LogEntry;
+use crate::vm_service::InstanceRef;
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// `logRecord` of a `Logging` stream event, posted by `dart:developer`'s `log()`.
+#[derive(Debug, Deserialize)]
+struct LogRecord {
+    message: InstanceRef,
+    level: i64,
+    #[serde(rename = "loggerName")]
+    logger_name: Option<InstanceRef>,
+    error: Option<InstanceRef>,
+    #[serde(rename = "stackTrace")]
+    stack_trace: Option<InstanceRef>,
+}
+
+/// Decodes the base64 `bytes` of a `WriteEvent` from the Stdout or Stderr stream.
+pub fn decode_write_event(data: &Value) -> Option<String> {
+    let bytes = data.get("bytes")?.as_str()?;
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(bytes)
+        .ok()?;
+    Some(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+/// Turns the `logRecord` of a `Logging` event into a log entry.
+///
+/// Levels follow `package:logging`: SEVERE (1000) and above are errors, WARNING (900)
+/// warnings and FINE to CONFIG (below 800) debug. The rest, including the unset level 0
+/// of a plain `log()`, is shown as info.
+pub fn parse_log_record(data: &Value) -> Option<LogEntry> {
+    let record: LogRecord = serde_json::from_value(data.get("logRecord")?.clone()).ok()?;
+
+    let level = match record.level {
+        1000.. => log::Level::Error,
+        900.. => log::Level::Warn,
+        1..800 => log::Level::Debug,
+        _ => log::Level::Info,
+    };
+    Some(LogEntry {
+        level,
+        logger: Some(
+            record
+                .logger_name
+                .as_ref()
+                .and_then(value_of)
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "log".to_string()),
+        ),
+        message: value_of(&record.message).unwrap_or_default(),
+        error: record.error.as_ref().and_then(value_of),
+        stack_trace: record
+            .stack_trace
+            .as_ref()
+            .and_then(value_of)
+            .map(|trace| {
+                trace
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// The string value of a non-null instance, marked when the VM truncated it.
+fn value_of(instance: &InstanceRef) -> Option<String> {
+    if instance.kind == "Null" {
+        return None;
+    }
+    let value = instance.value_as_string.as_deref()?;
+    Some(if instance.value_as_string_is_truncated {
+        format!("{}…", value)
+    } else {
+        value.to_string()
+    })
+}
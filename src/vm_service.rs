@@ -1,11 +1,32 @@
-use anyhow::{Context, Result};
-use futures::{SinkExt, StreamExt};
-use serde::{Deserialize, Serialize};
+use crate::network::HttpProfileRequest;
+use crate::profiler::CpuSamples;
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use futures::{SinkExt, Stream, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 use tokio::net::TcpStream;
 use tokio::sync::{mpsc, oneshot};
-use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async_with_config, MaybeTlsStream, WebSocketStream};
+
+/// Full widget trees of big apps run to tens of megabytes, well past tungstenite's
+/// defaults (64 MiB messages, 16 MiB frames).
+const MAX_MESSAGE_SIZE: usize = 512 << 20;
+const MAX_FRAME_SIZE: usize = 256 << 20;
+/// Messages larger than this are parsed on the blocking pool so the driver keeps
+/// serving other requests and events meanwhile.
+const LARGE_MESSAGE_SIZE: usize = 1 << 20;
+/// Half-open connections (a wireless debugging link dropping, say) never deliver a Close,
+/// so the driver pings and gives up on the socket when nothing comes back in time.
+const PING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+const PONG_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+/// Prefix of the inspector object groups node ids are handed out in. The app keeps the
+/// objects of a group alive until it is disposed.
+const OBJECT_GROUP_PREFIX: &str = "tui_inspector";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VmEvent {
@@ -19,6 +40,7 @@ pub struct VmEvent {
 #[derive(Clone)]
 pub struct VmServiceClient {
     tx_request: mpsc::Sender<RequestMessage>,
+    object_groups: Arc<Mutex<ObjectGroups>>,
     // We might want to support multiple event listeners in the future,
     // but for now a single receiver is enough.
     // Actually, we'll let the user take the receiver.
@@ -30,7 +52,98 @@ struct RequestMessage {
     tx_response: oneshot::Sender<Result<Value>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A text frame from the VM service, decoded by [`VmMessage::decode`].
+#[derive(Debug)]
+pub enum VmMessage {
+    /// The answer to the request with `id`: its result, or the error object the VM sent.
+    Response {
+        id: u64,
+        result: Result<Value, Value>,
+    },
+    /// A `streamNotify` notification.
+    Event(VmEvent),
+    Invalid {
+        error: serde_json::Error,
+        /// The id of the request this was probably a response to.
+        id: Option<u64>,
+    },
+    /// Valid JSON that is neither a response to a request of ours nor an event.
+    Ignored,
+}
+
+impl VmMessage {
+    /// Decodes a frame without acting on it, so the driver loop is left with dispatching.
+    pub fn decode(text: &str) -> Self {
+        match serde_json::from_str::<Value>(text) {
+            Ok(message) => Self::from_json(message),
+            Err(error) => Self::Invalid {
+                error,
+                id: Self::recover_id(text),
+            },
+        }
+    }
+
+    fn from_json(mut message: Value) -> Self {
+        if let Some(id) = message.get("id").and_then(|id| id.as_u64()) {
+            let result = if let Some(result) = message.get_mut("result") {
+                Ok(result.take())
+            } else if let Some(error) = message.get_mut("error") {
+                Err(error.take())
+            } else {
+                Ok(message) // Fallback
+            };
+            return Self::Response { id, result };
+        }
+        if message.get("method").and_then(|m| m.as_str()) != Some("streamNotify") {
+            return Self::Ignored;
+        }
+        let Some(params) = message.get_mut("params") else {
+            return Self::Ignored;
+        };
+        let stream_id = params
+            .get("streamId")
+            .and_then(|s| s.as_str())
+            .unwrap_or("")
+            .to_string();
+        let data = params
+            .get_mut("event")
+            .map(Value::take)
+            .unwrap_or(Value::Null);
+        let event_kind = data
+            .get("kind")
+            .and_then(|s| s.as_str())
+            .unwrap_or("")
+            .to_string();
+        let isolate_id = data
+            .get("isolate")
+            .and_then(|i| i.get("id"))
+            .and_then(|s| s.as_str())
+            .map(|s| s.to_string());
+        let timestamp = data.get("timestamp").and_then(|t| t.as_i64()).unwrap_or(0);
+        Self::Event(VmEvent {
+            stream_id,
+            event_kind,
+            isolate_id,
+            timestamp,
+            data,
+        })
+    }
+
+    /// The VM service writes the response id after the result, so the last `"id":` in a
+    /// message that failed to parse is most likely the top-level one.
+    fn recover_id(text: &str) -> Option<u64> {
+        let (_, rest) = text.rsplit_once("\"id\":")?;
+        let digits: String = rest
+            .trim_start()
+            .trim_start_matches('"')
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        digits.parse().ok()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct RemoteDiagnosticsNode {
     pub description: Option<String>,
     #[serde(rename = "type")]
@@ -47,11 +160,105 @@ pub struct RemoteDiagnosticsNode {
     #[serde(rename = "valueId")]
     pub value_id: Option<String>,
     pub properties: Option<Vec<RemoteDiagnosticsNode>>,
+    #[serde(rename = "creationLocation")]
+    pub creation_location: Option<CreationLocation>,
+    #[serde(rename = "isDiagnosableValue")]
+    pub is_diagnosable_value: Option<bool>,
+    /// DiagnosticLevel name, e.g. "info", "fine", "warning", "hidden".
+    pub level: Option<String>,
+    #[serde(rename = "showName")]
+    pub show_name: Option<bool>,
+    /// Set when the framework cut off the children or properties of this node.
+    pub truncated: Option<bool>,
+    /// The render object of an element, which details subtrees include for the root node.
+    #[serde(rename = "renderObject")]
+    pub render_object: Option<Box<RemoteDiagnosticsNode>>,
+    /// Every other field of the JSON node, kept so nothing the framework sends is lost.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
+}
+
+impl RemoteDiagnosticsNode {
+    /// The framework says there are children but they were cut off the tree;
+    /// [`VmServiceClient::get_children`] fetches them.
+    pub fn has_unloaded_children(&self) -> bool {
+        self.has_children == Some(true) && self.children.as_ref().is_none_or(|c| c.is_empty())
+    }
+
+    /// Layout of the render object behind this element: its description plus the
+    /// constraints, size and parent data properties it reports, in that order.
+    pub fn render_info(&self) -> Option<(&str, Vec<(&str, &str)>)> {
+        let render_object = self.render_object.as_deref()?;
+        let properties = render_object.properties.as_deref().unwrap_or_default();
+        let layout = ["constraints", "size", "parentData"]
+            .iter()
+            .filter_map(|name| {
+                let property = properties
+                    .iter()
+                    .find(|p| p.name.as_deref() == Some(*name))?;
+                Some((*name, property.description.as_deref().unwrap_or("-")))
+            })
+            .collect();
+        Some((
+            render_object
+                .description
+                .as_deref()
+                .unwrap_or("RenderObject"),
+            layout,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreationLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    pub name: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VM {
     pub isolates: Vec<IsolateRef>,
+    /// Missing before isolate groups existed.
+    #[serde(rename = "isolateGroups", default)]
+    pub isolate_groups: Vec<IsolateGroupRef>,
+    pub version: Option<String>,
+    #[serde(rename = "operatingSystem")]
+    pub operating_system: Option<String>,
+    #[serde(rename = "targetCPU")]
+    pub target_cpu: Option<String>,
+}
+
+/// Memory of one isolate, in bytes. External memory is held outside the Dart heap on
+/// behalf of Dart objects, e.g. decoded images.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+pub struct MemoryUsage {
+    #[serde(rename = "heapUsage")]
+    pub heap_usage: u64,
+    #[serde(rename = "heapCapacity")]
+    pub heap_capacity: u64,
+    #[serde(rename = "externalUsage")]
+    pub external_usage: u64,
+}
+
+/// The result of `getAllocationProfile`: instances and bytes per class.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AllocationProfile {
+    #[serde(default)]
+    pub members: Vec<ClassHeapStats>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClassHeapStats {
+    pub class: ClassRef,
+    #[serde(rename = "instancesCurrent", default)]
+    pub instances_current: u64,
+    #[serde(rename = "bytesCurrent", default)]
+    pub bytes_current: u64,
+    /// Instances allocated since the accumulators were last reset.
+    #[serde(rename = "instancesAccumulated", default)]
+    pub instances_accumulated: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -60,17 +267,296 @@ pub struct IsolateRef {
     pub name: String,
 }
 
+/// Picks the isolate running the Flutter UI: the first one exposing the inspector, or else
+/// the one named `main`. Extensions are registered a little after startup, hence the name
+/// fallback.
+pub fn flutter_isolate(
+    isolates: &[IsolateRef],
+    inspector_isolates: &HashSet<String>,
+) -> Option<String> {
+    isolates
+        .iter()
+        .find(|isolate| inspector_isolates.contains(&isolate.id))
+        .or_else(|| isolates.iter().find(|isolate| isolate.name == "main"))
+        .map(|isolate| isolate.id.clone())
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Isolate {
     pub id: String,
     pub name: String,
     #[serde(rename = "extensionRPCs")]
     pub extension_rpcs: Option<Vec<String>>,
+    #[serde(default)]
+    pub libraries: Vec<LibraryRef>,
+    #[serde(rename = "rootLib")]
+    pub root_lib: Option<LibraryRef>,
+    /// The event of the last pause, `Resume` while running.
+    #[serde(rename = "pauseEvent")]
+    pub pause_event: Option<PauseEvent>,
+    #[serde(rename = "livePorts")]
+    pub live_ports: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PauseEvent {
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IsolateGroupRef {
+    pub id: String,
+    pub name: String,
+}
+
+/// Result of `getIsolateGroup`, isolates that share a heap.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IsolateGroup {
+    #[serde(default)]
+    pub isolates: Vec<IsolateRef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryRef {
+    pub id: String,
+    pub uri: String,
+}
+
+/// Result of `getStack`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Stack {
+    #[serde(default)]
+    pub frames: Vec<Frame>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Frame {
+    /// "Regular", "AsyncCausal", "AsyncSuspensionMarker", ...
+    pub kind: Option<String>,
+    pub function: Option<FuncRef>,
+    pub location: Option<SourceLocation>,
+    #[serde(default)]
+    pub vars: Vec<BoundVariable>,
+}
+
+impl Frame {
+    /// The function name, or a placeholder for async gaps, which have no function.
+    pub fn function_name(&self) -> &str {
+        match &self.function {
+            Some(function) if self.kind.as_deref() != Some("AsyncSuspensionMarker") => {
+                &function.name
+            }
+            _ => "<async gap>",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FuncRef {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClassRef {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScriptRef {
+    pub id: String,
+    pub uri: String,
+}
+
+/// Result of `getScripts`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ScriptList {
+    #[serde(default)]
+    pub scripts: Vec<ScriptRef>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourceLocation {
+    pub script: ScriptRef,
+    #[serde(rename = "tokenPos")]
+    pub token_pos: Option<i64>,
+    /// Only sent by newer VMs, otherwise resolved through the script's tokenPosTable.
+    pub line: Option<usize>,
+}
+
+/// Result of `getObject` for a script.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Script {
+    /// Each row is `[line, tokenPos, column, tokenPos, column, ...]`.
+    #[serde(rename = "tokenPosTable", default)]
+    pub token_pos_table: Vec<Vec<i64>>,
+}
+
+impl Script {
+    pub fn line_for_token_pos(&self, token_pos: i64) -> Option<usize> {
+        self.token_pos_table.iter().find_map(|row| {
+            let (line, positions) = row.split_first()?;
+            positions
+                .chunks(2)
+                .any(|pair| pair[0] == token_pos)
+                .then_some(*line as usize)
+        })
+    }
+}
+
+/// Result of `addBreakpoint` and `addBreakpointWithScriptUri`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Breakpoint {
+    pub id: String,
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BoundVariable {
+    pub name: String,
+    pub value: InstanceRefOrSentinel,
+}
+
+/// A value slot in a frame or object. Variables that were optimized out or not yet
+/// initialized come back as Sentinels instead of instances.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum InstanceRefOrSentinel {
+    #[serde(rename = "@Instance")]
+    Instance(InstanceRef),
+    #[serde(rename = "@Sentinel", alias = "Sentinel")]
+    Sentinel(Sentinel),
+    /// Contexts, type arguments and other refs the Locals pane doesn't look into.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InstanceRef {
+    pub id: Option<String>,
+    pub kind: String,
+    pub class: Option<ClassRef>,
+    #[serde(rename = "valueAsString")]
+    pub value_as_string: Option<String>,
+    #[serde(rename = "valueAsStringIsTruncated", default)]
+    pub value_as_string_is_truncated: bool,
+    pub length: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Sentinel {
+    pub kind: String,
+    #[serde(rename = "valueAsString")]
+    pub value_as_string: String,
+}
+
+/// An RPC was answered with a Sentinel instead of the object asked for, typically because
+/// the object was collected or its id expired. Recover it from an `anyhow::Error` with
+/// [`SentinelError::find`].
+#[derive(Debug, Clone)]
+pub struct SentinelError {
+    /// "Collected", "Expired", "NotInitialized", ...
+    pub kind: String,
+}
+
+impl SentinelError {
+    pub fn find(error: &anyhow::Error) -> Option<&Self> {
+        error.downcast_ref()
+    }
+}
+
+impl std::fmt::Display for SentinelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind.as_str() {
+            "Collected" => write!(f, "object was garbage collected"),
+            "Expired" => write!(f, "object expired"),
+            kind => write!(f, "object unavailable ({})", kind),
+        }
+    }
+}
+
+impl std::error::Error for SentinelError {}
+
+/// Unwraps the `_extensionType` envelope of an inspector extension response. The
+/// inspector answers with `null` for ids whose object group was disposed, which is
+/// reported like an expired Sentinel.
+fn inspector_result(result: &Value) -> Result<&Value> {
+    let inner = if result.get("type").and_then(|t| t.as_str()) == Some("_extensionType") {
+        result.get("result").unwrap_or(result)
+    } else {
+        result
+    };
+    match inner.get("type").and_then(|t| t.as_str()) {
+        _ if inner.is_null() => Err(SentinelError {
+            kind: "Expired".to_string(),
+        }
+        .into()),
+        Some("Sentinel") => Err(SentinelError {
+            kind: inner
+                .get("kind")
+                .and_then(|k| k.as_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+        }
+        .into()),
+        _ => Ok(inner),
+    }
+}
+
+/// Result of `getObject` for an instance.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Instance {
+    #[serde(default)]
+    pub fields: Vec<BoundField>,
+    #[serde(default)]
+    pub elements: Vec<InstanceRefOrSentinel>,
+    #[serde(default)]
+    pub associations: Vec<MapAssociation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BoundField {
+    pub decl: Option<FieldRef>,
+    /// Set instead of `decl` for record fields: a string for named fields, a position
+    /// for positional ones.
+    pub name: Option<Value>,
+    pub value: InstanceRefOrSentinel,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldRef {
+    pub name: String,
+}
+
+/// Generations of the inspector's object groups. Like DevTools, every tree is fetched into
+/// a fresh group and the group of the tree it replaces is disposed, so the app doesn't keep
+/// every element the inspector ever looked at alive in long sessions.
+#[derive(Default)]
+struct ObjectGroups {
+    /// The group of the tree on screen.
+    installed: u64,
+    /// The group of the most recent tree fetch.
+    latest: u64,
+}
+
+fn group_name(generation: u64) -> String {
+    format!("{}_{}", OBJECT_GROUP_PREFIX, generation)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MapAssociation {
+    pub key: InstanceRefOrSentinel,
+    pub value: InstanceRefOrSentinel,
 }
 
 impl VmServiceClient {
     pub async fn connect(uri: &str) -> Result<(Self, mpsc::Receiver<VmEvent>)> {
-        let (ws_stream, _) = connect_async(uri)
+        let config = WebSocketConfig {
+            max_message_size: Some(MAX_MESSAGE_SIZE),
+            max_frame_size: Some(MAX_FRAME_SIZE),
+            ..Default::default()
+        };
+        let (ws_stream, _) = connect_async_with_config(uri, Some(config), false)
             .await
             .context("Failed to connect to WebSocket")?;
 
@@ -83,7 +569,39 @@ impl VmServiceClient {
             }
         });
 
-        Ok((Self { tx_request }, rx_event))
+        Ok((
+            Self {
+                tx_request,
+                object_groups: Arc::default(),
+            },
+            rx_event,
+        ))
+    }
+
+    /// The object group of the installed tree, which its details, children and properties
+    /// are fetched into as well.
+    pub fn inspector_group(&self) -> String {
+        group_name(self.object_groups.lock().unwrap().installed)
+    }
+
+    /// A fresh object group to fetch a replacement tree into.
+    pub fn next_inspector_group(&self) -> String {
+        let mut groups = self.object_groups.lock().unwrap();
+        groups.latest += 1;
+        group_name(groups.latest)
+    }
+
+    /// Makes `group` the current one once its tree is fetched, returning the group it
+    /// replaces for disposal. `None` when a newer fetch was started meanwhile, which makes
+    /// the tree of `group` outdated.
+    pub fn install_inspector_group(&self, group: &str) -> Option<String> {
+        let mut groups = self.object_groups.lock().unwrap();
+        if group != group_name(groups.latest) {
+            return None;
+        }
+        let replaced = group_name(groups.installed);
+        groups.installed = groups.latest;
+        Some(replaced)
     }
 
     async fn driver_loop(
@@ -93,9 +611,25 @@ impl VmServiceClient {
     ) -> Result<()> {
         let mut request_id = 0u64;
         let mut pending_requests: HashMap<u64, oneshot::Sender<Result<Value>>> = HashMap::new();
+        // Results of large messages parsed off the driver task
+        let (tx_parsed, mut rx_parsed) = mpsc::unbounded_channel::<VmMessage>();
+        let mut ping =
+            tokio::time::interval_at(tokio::time::Instant::now() + PING_INTERVAL, PING_INTERVAL);
+        let mut last_received = tokio::time::Instant::now();
 
         loop {
             tokio::select! {
+                _ = ping.tick() => {
+                    if last_received.elapsed() > PONG_TIMEOUT {
+                        bail!(
+                            "No response from VM service for {}s, closing connection",
+                            last_received.elapsed().as_secs()
+                        );
+                    }
+                    if let Err(e) = ws_stream.send(Message::Ping(Vec::new())).await {
+                        bail!("Failed to send ping: {}", e);
+                    }
+                }
                 Some(msg) = rx_request.recv() => {
                     request_id += 1;
                     let request_json = json!({
@@ -107,59 +641,39 @@ impl VmServiceClient {
 
                     pending_requests.insert(request_id, msg.tx_response);
 
-                    if let Err(e) = ws_stream.send(tokio_tungstenite::tungstenite::Message::Text(request_json.to_string())).await {
+                    if let Err(e) = ws_stream.send(Message::Text(request_json.to_string())).await {
                         log::error!("Failed to send request: {}", e);
-                        // We should probably remove the pending request and error it out
                         if let Some(tx) = pending_requests.remove(&request_id) {
                             let _ = tx.send(Err(anyhow::anyhow!("Failed to send request: {}", e)));
                         }
                     }
                 }
-                Some(msg) = ws_stream.next() => {
+                Some(parsed) = rx_parsed.recv() => {
+                    Self::dispatch(parsed, &mut pending_requests, &tx_event).await;
+                }
+                msg = ws_stream.next() => {
+                    // Pongs, and any other traffic, show the connection is alive
+                    last_received = tokio::time::Instant::now();
                     match msg {
-                        Ok(tokio_tungstenite::tungstenite::Message::Text(text)) => {
-                             if let Ok(response) = serde_json::from_str::<Value>(&text) {
-                                // Check if it's a response or event
-                                if let Some(id) = response.get("id").and_then(|id| id.as_u64()) {
-                                    // It's a response
-                                    if let Some(tx) = pending_requests.remove(&id) {
-                                        if let Some(result) = response.get("result") {
-                                            let _ = tx.send(Ok(result.clone()));
-                                        } else if let Some(error) = response.get("error") {
-                                            let _ = tx.send(Err(anyhow::anyhow!("RPC Error: {:?}", error)));
-                                        } else {
-                                             let _ = tx.send(Ok(response.clone())); // Fallback
-                                        }
-                                    }
-                                } else if let Some(method) = response.get("method").and_then(|s| s.as_str()) {
-                                    if method == "streamNotify" {
-                                        // It's an event
-                                        if let Some(params) = response.get("params") {
-                                            let stream_id = params.get("streamId").and_then(|s| s.as_str()).unwrap_or("").to_string();
-                                            let event_kind = params.get("event").and_then(|e| e.get("kind")).and_then(|s| s.as_str()).unwrap_or("").to_string();
-                                            let isolate_id = params.get("event").and_then(|e| e.get("isolate")).and_then(|i| i.get("id")).and_then(|s| s.as_str()).map(|s| s.to_string());
-                                            let timestamp = params.get("event").and_then(|e| e.get("timestamp")).and_then(|t| t.as_i64()).unwrap_or(0);
-                                            let data = params.get("event").cloned().unwrap_or(Value::Null);
-
-                                            let event = VmEvent {
-                                                stream_id,
-                                                event_kind,
-                                                isolate_id,
-                                                timestamp,
-                                                data,
-                                            };
-                                            let _ = tx_event.send(event).await;
-                                        }
-                                    }
-                                }
+                        Some(Ok(Message::Text(text))) => {
+                            if text.len() > LARGE_MESSAGE_SIZE {
+                                // Events from big messages may overtake smaller ones sent
+                                // after them, responses are matched by id either way
+                                let tx_parsed = tx_parsed.clone();
+                                tokio::task::spawn_blocking(move || {
+                                    let _ = tx_parsed.send(VmMessage::decode(&text));
+                                });
+                            } else {
+                                let parsed = VmMessage::decode(&text);
+                                Self::dispatch(parsed, &mut pending_requests, &tx_event).await;
                             }
                         }
-                        Ok(tokio_tungstenite::tungstenite::Message::Close(_)) => break,
-                        Err(e) => {
+                        Some(Ok(Message::Close(_))) | None => break,
+                        Some(Err(e)) => {
                             log::error!("WebSocket error: {}", e);
                             break;
                         }
-                        _ => {}
+                        Some(Ok(_)) => {}
                     }
                 }
                 else => break,
@@ -168,6 +682,34 @@ impl VmServiceClient {
         Ok(())
     }
 
+    async fn dispatch(
+        message: VmMessage,
+        pending_requests: &mut HashMap<u64, oneshot::Sender<Result<Value>>>,
+        tx_event: &mpsc::Sender<VmEvent>,
+    ) {
+        match message {
+            VmMessage::Response { id, result } => {
+                if let Some(tx) = pending_requests.remove(&id) {
+                    let _ =
+                        tx.send(result.map_err(|error| anyhow::anyhow!("RPC Error: {:?}", error)));
+                }
+            }
+            VmMessage::Event(event) => {
+                let _ = tx_event.send(event).await;
+            }
+            VmMessage::Invalid { error, id } => {
+                // Fail the request the message answered, if its id could be recovered
+                match id.and_then(|id| pending_requests.remove(&id)) {
+                    Some(tx) => {
+                        let _ = tx.send(Err(anyhow::anyhow!("Invalid response: {}", error)));
+                    }
+                    None => log::error!("Dropping unparseable VM service message: {}", error),
+                }
+            }
+            VmMessage::Ignored => {}
+        }
+    }
+
     async fn send_request(&self, method: &str, params: Value) -> Result<Value> {
         let (tx, rx) = oneshot::channel();
         let msg = RequestMessage {
@@ -184,6 +726,25 @@ impl VmServiceClient {
         rx.await.context("Failed to receive response from driver")?
     }
 
+    /// Sends a request whose result is a typed object. A Sentinel result (an expired or
+    /// collected object, for example) becomes an error rather than a misparsed object.
+    async fn send_typed_request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T> {
+        let result = self.send_request(method, params).await?;
+        if result.get("type").and_then(|t| t.as_str()) == Some("Sentinel") {
+            let sentinel: Sentinel = serde_json::from_value(result)?;
+            log::info!("{} returned {}", method, sentinel.value_as_string);
+            return Err(SentinelError {
+                kind: sentinel.kind,
+            }
+            .into());
+        }
+        serde_json::from_value(result).with_context(|| format!("Unexpected {} response", method))
+    }
+
     pub async fn stream_listen(&self, stream_id: &str) -> Result<()> {
         self.send_request("streamListen", json!({ "streamId": stream_id }))
             .await?;
@@ -196,6 +757,153 @@ impl VmServiceClient {
         Ok(vm)
     }
 
+    /// Returns the VM service protocol version as "major.minor".
+    pub async fn get_version(&self) -> Result<String> {
+        let result = self.send_request("getVersion", json!({})).await?;
+        let major = result.get("major").and_then(|v| v.as_i64()).unwrap_or(0);
+        let minor = result.get("minor").and_then(|v| v.as_i64()).unwrap_or(0);
+        Ok(format!("{}.{}", major, minor))
+    }
+
+    pub async fn set_vm_flag(&self, name: &str, value: &str) -> Result<()> {
+        let result = self
+            .send_request("setVMFlag", json!({ "name": name, "value": value }))
+            .await?;
+        if result.get("type").and_then(|t| t.as_str()) == Some("Error") {
+            bail!(
+                "Failed to set {}: {}",
+                name,
+                result.get("message").and_then(|m| m.as_str()).unwrap_or("")
+            );
+        }
+        Ok(())
+    }
+
+    /// Picks the timeline streams the VM records, e.g. `Dart`, `Embedder` and `GC`.
+    pub async fn set_vm_timeline_flags(&self, recorded_streams: &[&str]) -> Result<()> {
+        self.send_request(
+            "setVMTimelineFlags",
+            json!({ "recordedStreams": recorded_streams }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// The recorded timeline events in the window starting at `time_origin_micros`, in
+    /// Chrome's trace event format.
+    pub async fn get_vm_timeline(
+        &self,
+        time_origin_micros: i64,
+        time_extent_micros: i64,
+    ) -> Result<Vec<Value>> {
+        let result = self
+            .send_request(
+                "getVMTimeline",
+                json!({
+                    "timeOriginMicros": time_origin_micros,
+                    "timeExtentMicros": time_extent_micros
+                }),
+            )
+            .await?;
+        match result.get("traceEvents") {
+            Some(Value::Array(events)) => Ok(events.clone()),
+            _ => bail!("No trace events in response"),
+        }
+    }
+
+    /// The current time on the clock of timeline events and CPU samples, in microseconds.
+    pub async fn get_vm_timeline_micros(&self) -> Result<i64> {
+        let result = self.send_request("getVMTimelineMicros", json!({})).await?;
+        result
+            .get("timestamp")
+            .and_then(|t| t.as_i64())
+            .context("No timestamp in response")
+    }
+
+    /// The CPU samples the isolate's profiler took in the window starting at
+    /// `time_origin_micros`.
+    pub async fn get_cpu_samples(
+        &self,
+        isolate_id: &str,
+        time_origin_micros: i64,
+        time_extent_micros: i64,
+    ) -> Result<CpuSamples> {
+        self.send_typed_request(
+            "getCpuSamples",
+            json!({
+                "isolateId": isolate_id,
+                "timeOriginMicros": time_origin_micros,
+                "timeExtentMicros": time_extent_micros
+            }),
+        )
+        .await
+    }
+
+    /// Heap usage per class. With `gc` the VM collects garbage first, so only live
+    /// objects are counted.
+    pub async fn get_allocation_profile(
+        &self,
+        isolate_id: &str,
+        gc: bool,
+    ) -> Result<AllocationProfile> {
+        let mut params = json!({ "isolateId": isolate_id });
+        if gc {
+            params["gc"] = json!(true);
+        }
+        self.send_typed_request("getAllocationProfile", params)
+            .await
+    }
+
+    pub async fn get_memory_usage(&self, isolate_id: &str) -> Result<MemoryUsage> {
+        let result = self
+            .send_request("getMemoryUsage", json!({ "isolateId": isolate_id }))
+            .await?;
+        Ok(serde_json::from_value(result)?)
+    }
+
+    /// Refresh rate in Hz of the display showing the app's first Flutter view, from the
+    /// services the engine registers.
+    pub async fn get_display_refresh_rate(&self) -> Result<f64> {
+        let views = self.send_request("_flutter.listViews", json!({})).await?;
+        let view_id = views
+            .get("views")
+            .and_then(|v| v.as_array())
+            .and_then(|views| views.first())
+            .and_then(|view| view.get("id"))
+            .and_then(|id| id.as_str())
+            .context("No Flutter views")?;
+        let result = self
+            .send_request(
+                "_flutter.getDisplayRefreshRate",
+                json!({ "viewId": view_id }),
+            )
+            .await?;
+        result
+            .get("fps")
+            .and_then(|fps| fps.as_f64())
+            .context("No refresh rate in response")
+    }
+
+    pub async fn get_isolate_group(&self, isolate_group_id: &str) -> Result<IsolateGroup> {
+        self.send_typed_request(
+            "getIsolateGroup",
+            json!({ "isolateGroupId": isolate_group_id }),
+        )
+        .await
+    }
+
+    /// Memory of the heap the isolates of the group share.
+    pub async fn get_isolate_group_memory_usage(
+        &self,
+        isolate_group_id: &str,
+    ) -> Result<MemoryUsage> {
+        self.send_typed_request(
+            "getIsolateGroupMemoryUsage",
+            json!({ "isolateGroupId": isolate_group_id }),
+        )
+        .await
+    }
+
     pub async fn get_isolate(&self, isolate_id: &str) -> Result<Isolate> {
         let result = self
             .send_request(
@@ -209,6 +917,23 @@ impl VmServiceClient {
         Ok(isolate)
     }
 
+    /// Ids of the isolates exposing the Flutter inspector, probing all of them at once.
+    pub async fn find_inspector_isolates(&self, isolates: &[IsolateRef]) -> HashSet<String> {
+        let probes = isolates.iter().map(|isolate_ref| async move {
+            let isolate = self.get_isolate(&isolate_ref.id).await.ok()?;
+            isolate
+                .extension_rpcs?
+                .iter()
+                .any(|rpc| rpc.starts_with("ext.flutter.inspector."))
+                .then(|| isolate_ref.id.clone())
+        });
+        futures::future::join_all(probes)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
     pub async fn get_root_widget_summary_tree(
         &self,
         group: &str,
@@ -229,51 +954,395 @@ impl VmServiceClient {
         //     serde_json::to_string_pretty(&result).unwrap()
         // );
 
-        let node_json = if result.get("type").and_then(|t| t.as_str()) == Some("_extensionType") {
-            result.get("result").unwrap_or(&result)
-        } else {
-            &result
-        };
+        let node_json = inspector_result(&result)?;
 
         let node: RemoteDiagnosticsNode = serde_json::from_value(node_json.clone())?;
         Ok(node)
     }
 
+    /// The whole widget tree, including the framework's own widgets between the user's
+    /// (`Padding`, `Semantics`, `RenderObjectToWidgetAdapter`, ...).
+    pub async fn get_root_widget_tree(
+        &self,
+        group: &str,
+        isolate_id: &str,
+    ) -> Result<RemoteDiagnosticsNode> {
+        let result = match self
+            .send_request(
+                "ext.flutter.inspector.getRootWidgetTree",
+                json!({
+                    "isolateId": isolate_id,
+                    "groupName": group,
+                    "isSummaryTree": "false",
+                    "withPreviews": "true"
+                }),
+            )
+            .await
+        {
+            Ok(result) => result,
+            // Older frameworks only have the original full tree call
+            Err(_) => {
+                self.send_request(
+                    "ext.flutter.inspector.getRootWidget",
+                    json!({
+                        "isolateId": isolate_id,
+                        "objectGroup": group
+                    }),
+                )
+                .await?
+            }
+        };
+
+        let node_json = inspector_result(&result)?;
+        Ok(serde_json::from_value(node_json.clone())?)
+    }
+
+    /// The direct children of a node, for subtrees deeper than the root tree goes. `summary`
+    /// selects the summary or the full tree's notion of children.
+    pub async fn get_children(
+        &self,
+        isolate_id: &str,
+        object_id: &str,
+        summary: bool,
+    ) -> Result<Vec<RemoteDiagnosticsNode>> {
+        let method = if summary {
+            "ext.flutter.inspector.getChildrenSummaryTree"
+        } else {
+            "ext.flutter.inspector.getChildrenDetailsSubtree"
+        };
+        let result = self
+            .send_request(
+                method,
+                json!({
+                    "isolateId": isolate_id,
+                    "objectGroup": self.inspector_group(),
+                    "arg": object_id
+                }),
+            )
+            .await?;
+
+        let nodes_json = inspector_result(&result)?;
+        Ok(serde_json::from_value(nodes_json.clone())?)
+    }
+
+    /// Returns the parsed details along with the raw JSON, which keeps fields the typed model
+    /// doesn't know about.
     pub async fn get_details_subtree(
         &self,
         isolate_id: &str,
         object_id: &str,
         subtree_depth: i32,
-    ) -> Result<RemoteDiagnosticsNode> {
+    ) -> Result<(RemoteDiagnosticsNode, Value)> {
         let result = self
             .send_request(
                 "ext.flutter.inspector.getDetailsSubtree",
                 json!({
                     "isolateId": isolate_id,
-                    "objectGroup": "tui_inspector",
+                    "objectGroup": self.inspector_group(),
                     "arg": object_id,
                     "subtreeDepth": subtree_depth
                 }),
             )
             .await?;
 
-        let node_json = if result.get("type").and_then(|t| t.as_str()) == Some("_extensionType") {
-            result.get("result").unwrap_or(&result)
-        } else {
-            &result
-        };
+        let node_json = inspector_result(&result)?;
 
         let node: RemoteDiagnosticsNode = serde_json::from_value(node_json.clone())?;
-        Ok(node)
+        Ok((node, node_json.clone()))
+    }
+
+    /// Fetches the details of several nodes, keeping up to `concurrency` requests in
+    /// flight on the socket at once. Results are yielded in completion order.
+    pub fn get_details_subtrees(
+        &self,
+        isolate_id: &str,
+        object_ids: Vec<String>,
+        subtree_depth: i32,
+        concurrency: usize,
+    ) -> impl Stream<Item = (String, Result<(RemoteDiagnosticsNode, Value)>)> {
+        let client = self.clone();
+        let isolate_id = isolate_id.to_string();
+        futures::stream::iter(object_ids)
+            .map(move |object_id| {
+                let client = client.clone();
+                let isolate_id = isolate_id.clone();
+                async move {
+                    let result = client
+                        .get_details_subtree(&isolate_id, &object_id, subtree_depth)
+                        .await;
+                    (object_id, result)
+                }
+            })
+            .buffer_unordered(concurrency)
+    }
+
+    pub async fn get_properties(
+        &self,
+        isolate_id: &str,
+        object_id: &str,
+    ) -> Result<Vec<RemoteDiagnosticsNode>> {
+        let result = self
+            .send_request(
+                "ext.flutter.inspector.getProperties",
+                json!({
+                    "isolateId": isolate_id,
+                    "objectGroup": self.inspector_group(),
+                    "arg": object_id
+                }),
+            )
+            .await?;
+
+        let nodes_json = inspector_result(&result)?;
+
+        let nodes: Vec<RemoteDiagnosticsNode> = serde_json::from_value(nodes_json.clone())?;
+        Ok(nodes)
+    }
+
+    /// The app's FocusNode tree as text, with the nodes on the path to the primary focus
+    /// marked "IN FOCUS PATH" and the focused one "PRIMARY FOCUS".
+    pub async fn dump_focus_tree(&self, isolate_id: &str) -> Result<String> {
+        let result = self
+            .send_request(
+                "ext.flutter.debugDumpFocusTree",
+                json!({ "isolateId": isolate_id }),
+            )
+            .await?;
+        // Older frameworks print the dump to the app's console and return nothing
+        result
+            .get("data")
+            .and_then(|d| d.as_str())
+            .map(str::to_string)
+            .context("The app printed the focus tree to its log instead of returning it")
+    }
+
+    /// PNG of a widget as the app renders it, scaled down to fit `width` x `height`.
+    pub async fn screenshot(
+        &self,
+        isolate_id: &str,
+        object_id: &str,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>> {
+        let result = self
+            .send_request(
+                "ext.flutter.inspector.screenshot",
+                json!({
+                    "isolateId": isolate_id,
+                    "id": object_id,
+                    "width": width.to_string(),
+                    "height": height.to_string(),
+                    "margin": "0",
+                    "maxPixelRatio": "1",
+                    "debugPaint": "false"
+                }),
+            )
+            .await?;
+        let encoded = result
+            .get("result")
+            .and_then(|r| r.as_str())
+            .context("The widget has nothing painted to screenshot")?;
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .context("Invalid screenshot data")
+    }
+
+    /// Lets the app release the objects of `group`. Ids handed out for it stop working.
+    pub async fn dispose_group(&self, isolate_id: &str, group: &str) -> Result<Value> {
+        self.send_request(
+            "ext.flutter.inspector.disposeGroup",
+            json!({
+                "isolateId": isolate_id,
+                "objectGroup": group
+            }),
+        )
+        .await
     }
 
+    /// The widget selected on the device, as a node of the summary tree.
+    pub async fn get_selected_summary_widget(
+        &self,
+        isolate_id: &str,
+    ) -> Result<RemoteDiagnosticsNode> {
+        let result = self
+            .send_request(
+                "ext.flutter.inspector.getSelectedSummaryWidget",
+                json!({
+                    "isolateId": isolate_id,
+                    "objectGroup": self.inspector_group()
+                }),
+            )
+            .await?;
+
+        let node_json = inspector_result(&result)?;
+        Ok(serde_json::from_value(node_json.clone())?)
+    }
+
+    /// Highlights the widget with `object_id` on the device while select mode is on.
+    pub async fn set_selection_by_id(&self, isolate_id: &str, object_id: &str) -> Result<Value> {
+        self.send_request(
+            "ext.flutter.inspector.setSelectionById",
+            json!({
+                "isolateId": isolate_id,
+                "objectGroup": self.inspector_group(),
+                "arg": object_id
+            }),
+        )
+        .await
+    }
+
+    /// Makes the framework post a `Flutter.RebuildDirtyWidgets` event with per-location
+    /// rebuild counts after every frame.
+    pub async fn track_rebuild_dirty_widgets(
+        &self,
+        isolate_id: &str,
+        enabled: bool,
+    ) -> Result<Value> {
+        self.send_request(
+            "ext.flutter.inspector.trackRebuildDirtyWidgets",
+            json!({
+                "isolateId": isolate_id,
+                "enabled": enabled.to_string()
+            }),
+        )
+        .await
+    }
+
+    /// Makes the framework post a `Flutter.RepaintWidgets` event with per-location
+    /// repaint counts after every frame.
+    pub async fn track_repaint_widgets(&self, isolate_id: &str, enabled: bool) -> Result<Value> {
+        self.send_request(
+            "ext.flutter.inspector.trackRepaintWidgets",
+            json!({
+                "isolateId": isolate_id,
+                "enabled": enabled.to_string()
+            }),
+        )
+        .await
+    }
+
+    pub async fn reload_sources(&self, isolate_id: &str) -> Result<Value> {
+        let result = self
+            .send_request(
+                "reloadSources",
+                json!({
+                    "isolateId": isolate_id
+                }),
+            )
+            .await?;
+        // A ReloadReport with success=false is still a successful RPC
+        if result.get("success").and_then(|s| s.as_bool()) == Some(false) {
+            bail!("reload rejected: {}", result);
+        }
+        Ok(result)
+    }
+
+    /// Calls a service another client registered with the VM service (e.g. the `hotRestart`
+    /// of a running flutter tool) through the method alias from its ServiceRegistered event.
+    pub async fn call_registered_service(&self, method: &str, isolate_id: &str) -> Result<Value> {
+        self.send_request(
+            method,
+            json!({
+                "isolateId": isolate_id
+            }),
+        )
+        .await
+    }
+
+    /// Reads or, with `enabled` set, changes a boolean service extension such as
+    /// `ext.flutter.debugPaint`. Returns the state the app reports back.
+    pub async fn bool_extension(
+        &self,
+        isolate_id: &str,
+        method: &str,
+        enabled: Option<bool>,
+    ) -> Result<bool> {
+        let mut params = json!({
+            "isolateId": isolate_id
+        });
+        if let Some(enabled) = enabled {
+            // Extension parameters are always strings
+            params["enabled"] = json!(enabled.to_string());
+        }
+        let result = self.send_request(method, params).await?;
+        match result.get("enabled") {
+            Some(Value::String(s)) => Ok(s == "true"),
+            Some(Value::Bool(b)) => Ok(*b),
+            _ => bail!("{} returned no state", method),
+        }
+    }
+
+    /// Reads or, with `value` set, changes a service extension that takes its state as the
+    /// string parameter `parameter`, such as `ext.flutter.platformOverride`. Returns the
+    /// value the app reports back.
+    pub async fn value_extension(
+        &self,
+        isolate_id: &str,
+        method: &str,
+        parameter: &str,
+        value: Option<&str>,
+    ) -> Result<String> {
+        let mut params = json!({
+            "isolateId": isolate_id
+        });
+        if let Some(value) = value {
+            params[parameter] = json!(value);
+        }
+        let result = self.send_request(method, params).await?;
+        match result.get(parameter) {
+            Some(Value::String(s)) => Ok(s.clone()),
+            Some(Value::Null) | None => bail!("{} returned no state", method),
+            Some(value) => Ok(value.to_string()),
+        }
+    }
+
+    /// HTTP requests `dart:io` recorded since logging was enabled with
+    /// `ext.dart.io.httpEnableTimelineLogging`, without their bodies.
+    pub async fn get_http_profile(&self, isolate_id: &str) -> Result<Vec<HttpProfileRequest>> {
+        let result = self
+            .send_request(
+                "ext.dart.io.getHttpProfile",
+                json!({ "isolateId": isolate_id }),
+            )
+            .await?;
+        let requests = result.get("requests").cloned().unwrap_or_default();
+        Ok(serde_json::from_value(requests)?)
+    }
+
+    /// A recorded HTTP request with its request and response bodies.
+    pub async fn get_http_profile_request(
+        &self,
+        isolate_id: &str,
+        id: &str,
+    ) -> Result<HttpProfileRequest> {
+        self.send_typed_request(
+            "ext.dart.io.getHttpProfileRequest",
+            json!({
+                "isolateId": isolate_id,
+                "id": id
+            }),
+        )
+        .await
+    }
+
+    /// Rebuilds the whole widget tree without reloading any code.
+    pub async fn reassemble(&self, isolate_id: &str) -> Result<Value> {
+        self.send_request(
+            "ext.flutter.reassemble",
+            json!({
+                "isolateId": isolate_id
+            }),
+        )
+        .await
+    }
+
+    #[allow(dead_code)]
     pub async fn add_breakpoint(
         &self,
         isolate_id: &str,
         script_id: &str,
         line: usize,
-    ) -> Result<Value> {
-        self.send_request(
+    ) -> Result<Breakpoint> {
+        self.send_typed_request(
             "addBreakpoint",
             json!({
                 "isolateId": isolate_id,
@@ -289,8 +1358,8 @@ impl VmServiceClient {
         isolate_id: &str,
         script_uri: &str,
         line: usize,
-    ) -> Result<Value> {
-        self.send_request(
+    ) -> Result<Breakpoint> {
+        self.send_typed_request(
             "addBreakpointWithScriptUri",
             json!({
                 "isolateId": isolate_id,
@@ -335,8 +1404,22 @@ impl VmServiceClient {
         .await
     }
 
-    pub async fn get_stack(&self, isolate_id: &str) -> Result<Value> {
-        self.send_request(
+    /// Every script loaded in the isolate. The VM puts the load time into script ids, so a
+    /// library that a hot reload recompiled comes back with a new id.
+    pub async fn get_scripts(&self, isolate_id: &str) -> Result<Vec<ScriptRef>> {
+        let list: ScriptList = self
+            .send_typed_request(
+                "getScripts",
+                json!({
+                    "isolateId": isolate_id
+                }),
+            )
+            .await?;
+        Ok(list.scripts)
+    }
+
+    pub async fn get_stack(&self, isolate_id: &str) -> Result<Stack> {
+        self.send_typed_request(
             "getStack",
             json!({
                 "isolateId": isolate_id
@@ -345,8 +1428,125 @@ impl VmServiceClient {
         .await
     }
 
-    pub async fn get_object(&self, isolate_id: &str, object_id: &str) -> Result<Value> {
+    /// Reads or, with `value` set, assigns a top-level bool of a library. For debug flags
+    /// such as `debugPrintGestureArenaDiagnostics` that no service extension exposes.
+    pub async fn library_bool(
+        &self,
+        isolate_id: &str,
+        library_uri: &str,
+        name: &str,
+        value: Option<bool>,
+    ) -> Result<bool> {
+        let expression = match value {
+            Some(value) => format!("{} = {}", name, value),
+            None => name.to_string(),
+        };
+        match self
+            .evaluate_in_library(isolate_id, Some(library_uri), &expression)
+            .await?
+            .as_str()
+        {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => bail!("{} is not a bool", name),
+        }
+    }
+
+    /// Evaluates an expression in a library, the isolate's root library by default, and
+    /// returns the result as a string: the value of simple instances, `toString()` of
+    /// everything else.
+    pub async fn evaluate_in_library(
+        &self,
+        isolate_id: &str,
+        library_uri: Option<&str>,
+        expression: &str,
+    ) -> Result<String> {
+        let isolate = self.get_isolate(isolate_id).await?;
+        let library = match library_uri {
+            Some(uri) => isolate
+                .libraries
+                .iter()
+                .find(|library| library.uri == uri)
+                .with_context(|| format!("{} is not loaded", uri))?,
+            None => isolate
+                .root_lib
+                .as_ref()
+                .context("Isolate has no root library")?,
+        };
+        let result = self.evaluate(isolate_id, &library.id, expression).await?;
+        if let Some(value) = result.get("valueAsString").and_then(|v| v.as_str()) {
+            let truncated = result
+                .get("valueAsStringIsTruncated")
+                .and_then(|t| t.as_bool())
+                .unwrap_or(false);
+            return Ok(if truncated {
+                format!("{}…", value)
+            } else {
+                value.to_string()
+            });
+        }
+        let id = result
+            .get("id")
+            .and_then(|id| id.as_str())
+            .context("Result has no id")?;
+        let described = self.evaluate(isolate_id, id, "toString()").await?;
+        described
+            .get("valueAsString")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .context("toString() did not return a string")
+    }
+
+    /// `evaluate` against a library or instance, failing on compile errors and exceptions.
+    async fn evaluate(&self, isolate_id: &str, target_id: &str, expression: &str) -> Result<Value> {
+        let result = self
+            .send_request(
+                "evaluate",
+                json!({
+                    "isolateId": isolate_id,
+                    "targetId": target_id,
+                    "expression": expression,
+                    "disableBreakpoints": true
+                }),
+            )
+            .await?;
+        if result.get("type").and_then(|t| t.as_str()) == Some("@Error") {
+            bail!(
+                "{}",
+                result
+                    .get("message")
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("evaluation failed")
+            );
+        }
+        Ok(result)
+    }
+
+    pub async fn evaluate_in_frame(
+        &self,
+        isolate_id: &str,
+        frame_index: usize,
+        expression: &str,
+    ) -> Result<Value> {
         self.send_request(
+            "evaluateInFrame",
+            json!({
+                "isolateId": isolate_id,
+                "frameIndex": frame_index,
+                "expression": expression,
+                "disableBreakpoints": true
+            }),
+        )
+        .await
+    }
+
+    /// Fetches an object as `T`, e.g. [`Instance`] or [`Script`].
+    pub async fn get_object<T: DeserializeOwned>(
+        &self,
+        isolate_id: &str,
+        object_id: &str,
+    ) -> Result<T> {
+        self.send_typed_request(
             "getObject",
             json!({
                 "isolateId": isolate_id,
@@ -355,4 +1555,67 @@ impl VmServiceClient {
         )
         .await
     }
+
+    pub async fn lookup_resolved_package_uris(
+        &self,
+        isolate_id: &str,
+        uris: &[String],
+    ) -> Result<Vec<Option<String>>> {
+        let result = self
+            .send_request(
+                "lookupResolvedPackageUris",
+                json!({
+                    "isolateId": isolate_id,
+                    "uris": uris
+                }),
+            )
+            .await?;
+
+        Ok(result
+            .get("uris")
+            .and_then(|u| u.as_array())
+            .map(|uris| {
+                uris.iter()
+                    .map(|u| u.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Resolves the `location` of a stack frame to a `file://` URI and a 1-based line.
+    ///
+    /// Newer VMs include the line in the SourceLocation directly; otherwise the script is
+    /// fetched with `getObject` and the token position is looked up in its tokenPosTable.
+    pub async fn resolve_frame_location(
+        &self,
+        isolate_id: &str,
+        frame: &Frame,
+    ) -> Result<(String, usize)> {
+        let location = frame.location.as_ref().context("Frame has no location")?;
+        let script_uri = location.script.uri.clone();
+
+        let line = match location.line {
+            Some(line) => line,
+            None => {
+                let token_pos = location.token_pos.context("Location has no tokenPos")?;
+                let script: Script = self.get_object(isolate_id, &location.script.id).await?;
+                script
+                    .line_for_token_pos(token_pos)
+                    .context("Token position not found in script")?
+            }
+        };
+
+        let file_uri = if script_uri.starts_with("file://") {
+            script_uri
+        } else {
+            self.lookup_resolved_package_uris(isolate_id, std::slice::from_ref(&script_uri))
+                .await?
+                .into_iter()
+                .next()
+                .flatten()
+                .unwrap_or(script_uri)
+        };
+
+        Ok((file_uri, line))
+    }
 }
@@ -0,0 +1,189 @@
+//! End-to-end flows of the VM service client against the mock VM service.
+
+use flutter_tui_tools::app_state::{AppState, DebugState, PauseControl};
+use flutter_tui_tools::mock_vm_service::{MockBreakpoint, MockIsolate, MockVmService};
+use flutter_tui_tools::synthetic_tree::TreeShape;
+use flutter_tui_tools::vm_service::{flutter_isolate, VmEvent, VmServiceClient};
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+async fn next_event(rx_event: &mut mpsc::Receiver<VmEvent>) -> VmEvent {
+    tokio::time::timeout(Duration::from_secs(5), rx_event.recv())
+        .await
+        .expect("no event within 5s")
+        .expect("event channel closed")
+}
+
+#[tokio::test]
+async fn selects_the_flutter_isolate() {
+    let mock = MockVmService::with_isolates(vec![
+        MockIsolate::plain("isolates/1", "worker"),
+        MockIsolate::flutter("isolates/2", TreeShape::new(10)),
+    ])
+    .await
+    .unwrap();
+    let (client, _rx_event) = VmServiceClient::connect(&mock.uri()).await.unwrap();
+
+    let vm = client.get_vm().await.unwrap();
+    assert_eq!(vm.isolates.len(), 2);
+    let inspector_isolates = client.find_inspector_isolates(&vm.isolates).await;
+    assert_eq!(
+        flutter_isolate(&vm.isolates, &inspector_isolates).as_deref(),
+        Some("isolates/2")
+    );
+
+    let isolate = client.get_isolate("isolates/2").await.unwrap();
+    assert_eq!(isolate.name, "main");
+    assert_eq!(isolate.pause_event.unwrap().kind, "Resume");
+}
+
+#[tokio::test]
+async fn fetches_the_widget_tree() {
+    let mock = MockVmService::start().await.unwrap();
+    let (client, _rx_event) = VmServiceClient::connect(&mock.uri()).await.unwrap();
+    let group = client.next_inspector_group();
+
+    let root = client
+        .get_root_widget_summary_tree(&group, "isolates/1")
+        .await
+        .unwrap();
+    assert_eq!(root.value_id.as_deref(), Some("inspector-0"));
+    let expected = TreeShape::new(50).generate();
+    assert_eq!(root.description, expected.description);
+    assert_eq!(
+        root.children.as_ref().map(Vec::len),
+        expected.children.as_ref().map(Vec::len)
+    );
+
+    let children = client
+        .get_children("isolates/1", "inspector-0", true)
+        .await
+        .unwrap();
+    assert_eq!(children[0].value_id.as_deref(), Some("inspector-1"));
+
+    let (details, _) = client
+        .get_details_subtree("isolates/1", "inspector-0", 1)
+        .await
+        .unwrap();
+    assert!(details
+        .children
+        .unwrap()
+        .iter()
+        .all(|child| child.children.as_ref().is_none_or(|c| c.is_empty())));
+
+    client
+        .set_selection_by_id("isolates/1", "inspector-1")
+        .await
+        .unwrap();
+    assert_eq!(mock.selected().as_deref(), Some("inspector-1"));
+}
+
+#[tokio::test]
+async fn sets_and_hits_breakpoints() {
+    let mock = MockVmService::start().await.unwrap();
+    let (client, mut rx_event) = VmServiceClient::connect(&mock.uri()).await.unwrap();
+    client.stream_listen("Debug").await.unwrap();
+
+    let breakpoint = client
+        .add_breakpoint_with_script_uri("isolates/1", "package:app/main.dart", 12)
+        .await
+        .unwrap();
+    assert!(breakpoint.resolved);
+    assert_eq!(
+        mock.breakpoints(),
+        vec![MockBreakpoint {
+            id: breakpoint.id.clone(),
+            isolate_id: "isolates/1".to_string(),
+            script_uri: "package:app/main.dart".to_string(),
+            line: 12,
+        }]
+    );
+
+    mock.hit_breakpoint("isolates/1");
+    let event = next_event(&mut rx_event).await;
+    assert_eq!(event.stream_id, "Debug");
+    assert_eq!(event.event_kind, "PauseBreakpoint");
+    assert_eq!(event.isolate_id.as_deref(), Some("isolates/1"));
+    let isolate = client.get_isolate("isolates/1").await.unwrap();
+    assert_eq!(isolate.pause_event.unwrap().kind, "PauseBreakpoint");
+
+    client.resume("isolates/1", None).await.unwrap();
+    client
+        .remove_breakpoint("isolates/1", &breakpoint.id)
+        .await
+        .unwrap();
+    assert!(mock.breakpoints().is_empty());
+}
+
+#[tokio::test]
+async fn forwards_only_listened_streams() {
+    let mock = MockVmService::start().await.unwrap();
+    let (client, mut rx_event) = VmServiceClient::connect(&mock.uri()).await.unwrap();
+    client.stream_listen("Isolate").await.unwrap();
+    assert!(client.stream_listen("Isolate").await.is_err());
+
+    mock.send_event("Extension", json!({ "kind": "Extension" }));
+    mock.start_isolate(MockIsolate::plain("isolates/2", "worker"));
+    assert_eq!(next_event(&mut rx_event).await.event_kind, "IsolateStart");
+    assert_eq!(
+        next_event(&mut rx_event).await.event_kind,
+        "IsolateRunnable"
+    );
+    assert_eq!(client.get_vm().await.unwrap().isolates.len(), 2);
+
+    mock.exit_isolate("isolates/2");
+    let event = next_event(&mut rx_event).await;
+    assert_eq!(event.event_kind, "IsolateExit");
+    assert_eq!(event.isolate_id.as_deref(), Some("isolates/2"));
+}
+
+#[tokio::test]
+async fn unknown_methods_and_collected_isolates_fail() {
+    let mock = MockVmService::start().await.unwrap();
+    let (client, _rx_event) = VmServiceClient::connect(&mock.uri()).await.unwrap();
+
+    assert!(client.get_stack("isolates/9").await.is_err());
+    assert!(client.dump_focus_tree("isolates/1").await.is_err());
+    let methods: Vec<_> = mock.requests().into_iter().map(|(m, _)| m).collect();
+    assert_eq!(methods, ["getStack", "ext.flutter.debugDumpFocusTree"]);
+}
+
+#[tokio::test]
+async fn starts_an_app_paused_at_its_start() {
+    let mut isolate = MockIsolate::flutter("isolates/1", TreeShape::new(10));
+    isolate.pause_kind = Some("PauseStart".to_string());
+    let mock = MockVmService::with_isolates(vec![isolate]).await.unwrap();
+    let (client, _rx_event) = VmServiceClient::connect(&mock.uri()).await.unwrap();
+    let mut state = AppState::new(std::env::temp_dir());
+    assert_eq!(state.pause_control(), PauseControl::Pause);
+
+    // The pause from before attaching, as the VM task reports it
+    let isolate = client.get_isolate("isolates/1").await.unwrap();
+    let reason = isolate.pause_event.unwrap().kind;
+    state.set_debug_state(
+        DebugState::Paused {
+            isolate_id: "isolates/1".to_string(),
+            reason,
+            breakpoint_id: None,
+        },
+        None,
+    );
+    assert!(state.paused_at_start());
+    assert_eq!(state.pause_control(), PauseControl::Start);
+    client
+        .add_breakpoint_with_script_uri("isolates/1", "package:app/main.dart", 3)
+        .await
+        .unwrap();
+
+    for isolate_id in state.isolates_paused_at_start() {
+        client.resume(&isolate_id, None).await.unwrap();
+    }
+    let isolate = client.get_isolate("isolates/1").await.unwrap();
+    assert_eq!(isolate.pause_event.unwrap().kind, "Resume");
+    assert_eq!(mock.breakpoints().len(), 1);
+
+    state.isolate_resumed("isolates/1");
+    assert!(!state.paused_at_start());
+    assert_eq!(state.pause_control(), PauseControl::Pause);
+}
@@ -0,0 +1,195 @@
+//! Properties of the decoding of VM service frames, `VmMessage::decode`.
+
+use flutter_tui_tools::vm_service::VmMessage;
+use proptest::prelude::*;
+use serde_json::{json, Value};
+
+/// Arbitrary JSON, a few levels deep. Without floats, which serde_json only parses back
+/// to the closest float and not always the one written.
+fn json_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        any::<i64>().prop_map(Value::from),
+        ".*".prop_map(Value::from),
+    ];
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(Value::from),
+            prop::collection::btree_map(".*", inner, 0..8)
+                .prop_map(|map| Value::Object(map.into_iter().collect())),
+        ]
+    })
+}
+
+fn response(id: u64, result: &Value) -> String {
+    json!({"jsonrpc": "2.0", "result": result, "id": id}).to_string()
+}
+
+fn event(stream: &str, kind: &str, isolate: &str, timestamp: i64) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "streamNotify",
+        "params": {
+            "streamId": stream,
+            "event": {
+                "type": "Event",
+                "kind": kind,
+                "isolate": {"type": "@Isolate", "id": isolate},
+                "timestamp": timestamp,
+            },
+        },
+    })
+    .to_string()
+}
+
+#[derive(Debug, Clone)]
+enum Frame {
+    Response(u64),
+    Event(String),
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_text_never_panics(text in ".*") {
+        let _ = VmMessage::decode(&text);
+    }
+
+    #[test]
+    fn arbitrary_json_never_panics(value in json_value()) {
+        let _ = VmMessage::decode(&value.to_string());
+    }
+
+    #[test]
+    fn responses_carry_their_id_and_result(id in any::<u64>(), result in json_value()) {
+        match VmMessage::decode(&response(id, &result)) {
+            VmMessage::Response { id: decoded, result: Ok(decoded_result) } => {
+                prop_assert_eq!(decoded, id);
+                prop_assert_eq!(decoded_result, result);
+            }
+            other => prop_assert!(false, "decoded as {:?}", other),
+        }
+    }
+
+    #[test]
+    fn error_responses_fail(id in any::<u64>(), message in ".*") {
+        let frame = json!({
+            "jsonrpc": "2.0",
+            "error": {"code": -32000, "message": message},
+            "id": id,
+        });
+        match VmMessage::decode(&frame.to_string()) {
+            VmMessage::Response { id: decoded, result: Err(error) } => {
+                prop_assert_eq!(decoded, id);
+                prop_assert_eq!(&error["message"], &Value::from(message));
+            }
+            other => prop_assert!(false, "decoded as {:?}", other),
+        }
+    }
+
+    #[test]
+    fn messages_without_a_numeric_id_are_not_responses(
+        id in prop_oneof![Just(Value::Null), ".*".prop_map(Value::from), Just(json!(-1))],
+        result in json_value(),
+    ) {
+        let mut frame = json!({"jsonrpc": "2.0", "result": result});
+        if !id.is_null() {
+            frame["id"] = id;
+        }
+        let decoded = VmMessage::decode(&frame.to_string());
+        prop_assert!(matches!(decoded, VmMessage::Ignored), "decoded as {:?}", decoded);
+    }
+
+    #[test]
+    fn events_keep_their_fields(
+        stream in "[A-Za-z]{1,12}",
+        kind in "[A-Za-z]{1,20}",
+        isolate in "isolates/[0-9]{1,12}",
+        timestamp in any::<i64>(),
+    ) {
+        match VmMessage::decode(&event(&stream, &kind, &isolate, timestamp)) {
+            VmMessage::Event(decoded) => {
+                prop_assert_eq!(decoded.stream_id, stream);
+                prop_assert_eq!(decoded.event_kind, kind);
+                prop_assert_eq!(decoded.isolate_id, Some(isolate));
+                prop_assert_eq!(decoded.timestamp, timestamp);
+                prop_assert_eq!(&decoded.data["type"], &Value::from("Event"));
+            }
+            other => prop_assert!(false, "decoded as {:?}", other),
+        }
+    }
+
+    #[test]
+    fn truncated_frames_are_invalid(id in any::<u64>(), result in json_value(), cut in any::<prop::sample::Index>()) {
+        let frame = response(id, &result);
+        // Cut on a character boundary, leaving at least the opening brace out of the rest
+        let boundaries: Vec<usize> = frame.char_indices().map(|(i, _)| i).skip(1).collect();
+        let end = boundaries[cut.index(boundaries.len())];
+        let decoded = VmMessage::decode(&frame[..end]);
+        prop_assert!(matches!(decoded, VmMessage::Invalid { .. }), "decoded as {:?}", decoded);
+    }
+
+    #[test]
+    fn truncated_responses_still_name_their_request(id in any::<u64>(), result in json_value()) {
+        // The id comes last, so a response cut off at the very end still fails its request
+        let frame = response(id, &result);
+        match VmMessage::decode(&frame[..frame.len() - 1]) {
+            VmMessage::Invalid { id: recovered, .. } => prop_assert_eq!(recovered, Some(id)),
+            other => prop_assert!(false, "decoded as {:?}", other),
+        }
+    }
+
+    #[test]
+    fn interleaved_frames_decode_in_order(
+        frames in prop::collection::vec(
+            prop_oneof![
+                any::<u64>().prop_map(Frame::Response),
+                "[A-Za-z]{1,12}".prop_map(Frame::Event),
+            ],
+            0..32,
+        ),
+    ) {
+        for frame in &frames {
+            let text = match frame {
+                Frame::Response(id) => response(*id, &json!({"type": "Success"})),
+                Frame::Event(kind) => event("Debug", kind, "isolates/1", 0),
+            };
+            match (frame, VmMessage::decode(&text)) {
+                (Frame::Response(id), VmMessage::Response { id: decoded, result: Ok(_) }) => {
+                    prop_assert_eq!(decoded, *id);
+                }
+                (Frame::Event(kind), VmMessage::Event(decoded)) => {
+                    prop_assert_eq!(&decoded.event_kind, kind);
+                }
+                (frame, decoded) => prop_assert!(false, "{:?} decoded as {:?}", frame, decoded),
+            }
+        }
+    }
+}
+
+#[test]
+fn huge_strings_survive() {
+    let description = "x".repeat(64 << 20);
+    let frame = response(7, &json!({"description": description}));
+    match VmMessage::decode(&frame) {
+        VmMessage::Response {
+            id: 7,
+            result: Ok(result),
+        } => assert_eq!(result["description"].as_str().map(str::len), Some(64 << 20)),
+        other => panic!("decoded as {:?}", other),
+    }
+}
+
+#[test]
+fn deep_nesting_is_rejected_not_overflowed() {
+    let depth = 100_000;
+    let frame = format!(
+        "{{\"result\":{}{},\"id\":3}}",
+        "[".repeat(depth),
+        "]".repeat(depth)
+    );
+    match VmMessage::decode(&frame) {
+        VmMessage::Invalid { id, .. } => assert_eq!(id, Some(3)),
+        other => panic!("decoded as {:?}", other),
+    }
+}